@@ -0,0 +1,299 @@
+//! A non-blocking counterpart to `RpcClient`.  Each method returns a future that must be run on
+//! a tokio 0.1 runtime, and unlike `RpcClient` failed requests are not retried.
+
+use crate::async_rpc_client_request::AsyncRpcClientRequest;
+use crate::client_error::ClientError;
+use crate::generic_rpc_client_request::{GenericAsyncRpcClientRequest, RpcFuture};
+use crate::mock_async_rpc_client_request::MockAsyncRpcClientRequest;
+use crate::rpc_client::get_rpc_request_str;
+use crate::rpc_request::RpcRequest;
+use bincode::serialize;
+use futures::Future;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use solana_sdk::account::Account;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::fee_calculator::FeeCalculator;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::{self, Transaction};
+use std::io;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+pub struct AsyncRpcClient {
+    client: Box<GenericAsyncRpcClientRequest + Send + Sync>,
+}
+
+impl AsyncRpcClient {
+    pub fn new(url: String) -> Self {
+        Self {
+            client: Box::new(AsyncRpcClientRequest::new(url)),
+        }
+    }
+
+    pub fn new_mock(url: String) -> Self {
+        Self {
+            client: Box::new(MockAsyncRpcClientRequest::new(url)),
+        }
+    }
+
+    pub fn new_socket(addr: SocketAddr) -> Self {
+        Self::new(get_rpc_request_str(addr, false))
+    }
+
+    pub fn new_socket_with_timeout(addr: SocketAddr, timeout: Duration) -> Self {
+        let url = get_rpc_request_str(addr, false);
+        Self {
+            client: Box::new(AsyncRpcClientRequest::new_with_timeout(url, timeout)),
+        }
+    }
+
+    /// Send a single request and resolve to its raw `result`
+    pub fn send(&self, request: &RpcRequest, params: Option<Value>) -> RpcFuture<Value> {
+        self.client.send(request, params)
+    }
+
+    /// Send `requests` as one JSON-RPC 2.0 batch.  The future fails only if the batch as a whole
+    /// fails; otherwise it resolves to each request's raw `result`, in the order of `requests`
+    pub fn send_batch(
+        &self,
+        requests: Vec<(RpcRequest, Option<Value>)>,
+    ) -> RpcFuture<Vec<Result<Value, ClientError>>> {
+        self.client.send_batch(requests)
+    }
+
+    pub fn send_transaction(&self, transaction: &Transaction) -> RpcFuture<Signature> {
+        let serialized = serialize(transaction).unwrap();
+        let params = json!([serialized]);
+        Box::new(
+            self.client
+                .send(&RpcRequest::SendTransaction, Some(params))
+                .and_then(|response| parse_from_str(response, "SendTransaction")),
+        )
+    }
+
+    pub fn get_signature_status(
+        &self,
+        signature: &Signature,
+    ) -> RpcFuture<Option<transaction::Result<()>>> {
+        let params = json!([signature.to_string()]);
+        Box::new(
+            self.client
+                .send(&RpcRequest::GetSignatureStatus, Some(params))
+                .and_then(parse),
+        )
+    }
+
+    pub fn get_slot(&self) -> RpcFuture<u64> {
+        self.get_slot_with_commitment(CommitmentConfig::default())
+    }
+
+    pub fn get_slot_with_commitment(&self, commitment: CommitmentConfig) -> RpcFuture<u64> {
+        let params = json!([commitment]);
+        Box::new(
+            self.client
+                .send(&RpcRequest::GetSlot, Some(params))
+                .and_then(parse),
+        )
+    }
+
+    pub fn get_account(&self, pubkey: &Pubkey) -> RpcFuture<Account> {
+        self.get_account_with_commitment(pubkey, CommitmentConfig::default())
+    }
+
+    pub fn get_account_with_commitment(
+        &self,
+        pubkey: &Pubkey,
+        commitment: CommitmentConfig,
+    ) -> RpcFuture<Account> {
+        let params = json!([pubkey.to_string(), commitment]);
+        Box::new(
+            self.client
+                .send(&RpcRequest::GetAccountInfo, Some(params))
+                .and_then(parse),
+        )
+    }
+
+    /// Request a batch of accounts. All accounts are read from the same bank, and any account
+    /// that does not exist is returned as `None`.
+    pub fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> RpcFuture<Vec<Option<Account>>> {
+        let pubkeys: Vec<String> = pubkeys.iter().map(ToString::to_string).collect();
+        let params = json!([pubkeys]);
+        Box::new(
+            self.client
+                .send(&RpcRequest::GetMultipleAccounts, Some(params))
+                .and_then(parse),
+        )
+    }
+
+    pub fn get_balance(&self, pubkey: &Pubkey) -> RpcFuture<u64> {
+        self.get_balance_with_commitment(pubkey, CommitmentConfig::default())
+    }
+
+    pub fn get_balance_with_commitment(
+        &self,
+        pubkey: &Pubkey,
+        commitment: CommitmentConfig,
+    ) -> RpcFuture<u64> {
+        let params = json!([pubkey.to_string(), commitment]);
+        Box::new(
+            self.client
+                .send(&RpcRequest::GetBalance, Some(params))
+                .and_then(parse),
+        )
+    }
+
+    pub fn get_transaction_count(&self) -> RpcFuture<u64> {
+        self.get_transaction_count_with_commitment(CommitmentConfig::default())
+    }
+
+    pub fn get_transaction_count_with_commitment(
+        &self,
+        commitment: CommitmentConfig,
+    ) -> RpcFuture<u64> {
+        let params = json!([commitment]);
+        Box::new(
+            self.client
+                .send(&RpcRequest::GetTransactionCount, Some(params))
+                .and_then(parse),
+        )
+    }
+
+    pub fn get_recent_blockhash(&self) -> RpcFuture<(Hash, FeeCalculator)> {
+        self.get_recent_blockhash_with_commitment(CommitmentConfig::default())
+    }
+
+    pub fn get_recent_blockhash_with_commitment(
+        &self,
+        commitment: CommitmentConfig,
+    ) -> RpcFuture<(Hash, FeeCalculator)> {
+        let params = json!([commitment]);
+        Box::new(
+            self.client
+                .send(&RpcRequest::GetRecentBlockhash, Some(params))
+                .and_then(parse)
+                .and_then(|(blockhash, fee_calculator): (Value, FeeCalculator)| {
+                    let blockhash = parse_from_str(blockhash, "GetRecentBlockhash")?;
+                    Ok((blockhash, fee_calculator))
+                }),
+        )
+    }
+}
+
+fn parse<T: DeserializeOwned>(response: Value) -> Result<T, ClientError> {
+    Ok(serde_json::from_value(response)?)
+}
+
+/// Parse a base-58 encoded string response, such as a `Hash` or `Signature`
+fn parse_from_str<T>(response: Value, request: &str) -> Result<T, ClientError>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Debug,
+{
+    let string: String = parse(response)?;
+    string.parse().map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::Other,
+            format!("{} parse failure: {:?}", request, err),
+        )
+        .into()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_rpc_client_request::{PUBKEY, SIGNATURE};
+    use jsonrpc_core::{Error, IoHandler, Params};
+    use jsonrpc_http_server::{AccessControlAllowOrigin, DomainsValidation, ServerBuilder};
+    use serde_json::Number;
+    use solana_sdk::signature::{Keypair, KeypairUtil};
+    use solana_sdk::system_transaction;
+    use std::sync::mpsc::channel;
+    use std::thread;
+    use tokio::runtime::Runtime;
+
+    #[test]
+    fn test_async_rpc_client_send_batch() {
+        let (sender, receiver) = channel();
+        thread::spawn(move || {
+            let rpc_addr = "0.0.0.0:0".parse().unwrap();
+            let mut io = IoHandler::default();
+            io.add_method("getBalance", |_params: Params| {
+                Ok(Value::Number(Number::from(50)))
+            });
+            io.add_method("getSlot", |_params: Params| Err(Error::invalid_request()));
+
+            let server = ServerBuilder::new(io)
+                .threads(1)
+                .cors(DomainsValidation::AllowOnly(vec![
+                    AccessControlAllowOrigin::Any,
+                ]))
+                .start_http(&rpc_addr)
+                .expect("Unable to start RPC server");
+            sender.send(*server.address()).unwrap();
+            server.wait();
+        });
+
+        let rpc_addr = receiver.recv().unwrap();
+        let rpc_client = AsyncRpcClient::new_socket(rpc_addr);
+        let mut runtime = Runtime::new().unwrap();
+
+        let balance = runtime.block_on(rpc_client.get_balance(&Pubkey::new_rand()));
+        assert_eq!(balance.unwrap(), 50);
+
+        let slot = runtime.block_on(rpc_client.get_slot());
+        assert!(slot.is_err());
+
+        let results = runtime
+            .block_on(rpc_client.send_batch(vec![
+                (RpcRequest::GetSlot, None),
+                (
+                    RpcRequest::GetBalance,
+                    Some(json!([Pubkey::new_rand().to_string()])),
+                ),
+            ]))
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_err());
+        assert_eq!(results[1].as_ref().unwrap(), &json!(50));
+    }
+
+    #[test]
+    fn test_async_rpc_client_mock() {
+        let rpc_client = AsyncRpcClient::new_mock("succeeds".to_string());
+
+        assert_eq!(
+            rpc_client.get_balance(&Pubkey::new_rand()).wait().unwrap(),
+            50
+        );
+        assert_eq!(rpc_client.get_transaction_count().wait().unwrap(), 1234);
+        let (blockhash, _fee_calculator) = rpc_client.get_recent_blockhash().wait().unwrap();
+        assert_eq!(blockhash, PUBKEY.parse().unwrap());
+
+        let key = Keypair::new();
+        let tx = system_transaction::create_user_account(&key, &Pubkey::new_rand(), 50, blockhash);
+        let signature = rpc_client.send_transaction(&tx).wait().unwrap();
+        assert_eq!(signature, SIGNATURE.parse().unwrap());
+        assert_eq!(
+            rpc_client.get_signature_status(&signature).wait().unwrap(),
+            Some(Ok(()))
+        );
+
+        let results = rpc_client
+            .send_batch(vec![
+                (RpcRequest::GetSlot, None),
+                (RpcRequest::GetTransactionCount, None),
+            ])
+            .wait()
+            .unwrap();
+        assert_eq!(results[0].as_ref().unwrap(), &json!(0));
+        assert_eq!(results[1].as_ref().unwrap(), &json!(1234));
+
+        let rpc_client = AsyncRpcClient::new_mock("fails".to_string());
+        assert!(rpc_client.get_balance(&Pubkey::new_rand()).wait().is_err());
+        assert!(rpc_client.get_recent_blockhash().wait().is_err());
+    }
+}
@@ -0,0 +1,147 @@
+use crate::client_error::ClientError;
+use crate::generic_rpc_client_request::{GenericAsyncRpcClientRequest, RpcFuture};
+use crate::rpc_request::{RpcError, RpcRequest};
+use futures::Future;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::r#async::Client;
+use serde_json::Value;
+use std::time::Duration;
+
+pub struct AsyncRpcClientRequest {
+    client: Client,
+    url: String,
+}
+
+impl AsyncRpcClientRequest {
+    pub fn new(url: String) -> Self {
+        Self {
+            client: Client::new(),
+            url,
+        }
+    }
+
+    pub fn new_with_timeout(url: String, timeout: Duration) -> Self {
+        let client = Client::builder()
+            .timeout(timeout)
+            .build()
+            .expect("build rpc client");
+
+        Self { client, url }
+    }
+
+    fn post(&self, request_json: Value) -> impl Future<Item = Value, Error = ClientError> {
+        self.client
+            .post(&self.url)
+            .header(CONTENT_TYPE, "application/json")
+            .body(request_json.to_string())
+            .send()
+            .and_then(|mut response| response.json::<Value>())
+            .map_err(ClientError::from)
+    }
+}
+
+impl GenericAsyncRpcClientRequest for AsyncRpcClientRequest {
+    fn send(&self, request: &RpcRequest, params: Option<Value>) -> RpcFuture<Value> {
+        // Each request gets its own response, so the same request id is used for all requests
+        let request_json = request.build_request_json(1, params);
+        Box::new(
+            self.post(request_json)
+                .and_then(|json| parse_rpc_response(&json)),
+        )
+    }
+
+    fn send_batch(
+        &self,
+        requests: Vec<(RpcRequest, Option<Value>)>,
+    ) -> RpcFuture<Vec<Result<Value, ClientError>>> {
+        let len = requests.len();
+        let request_json = Value::Array(
+            requests
+                .into_iter()
+                .enumerate()
+                .map(|(id, (request, params))| request.build_request_json(id as u64, params))
+                .collect(),
+        );
+        Box::new(
+            self.post(request_json)
+                .and_then(move |json| parse_rpc_batch_response(json, len)),
+        )
+    }
+}
+
+fn parse_rpc_response(json: &Value) -> Result<Value, ClientError> {
+    if json["error"].is_object() {
+        Err(RpcError::RpcRequestError(format!(
+            "RPC Error response: {}",
+            serde_json::to_string(&json["error"]).unwrap()
+        )))?
+    }
+    Ok(json["result"].clone())
+}
+
+/// Match the responses of a batch back to their requests by id.  A batch that fails as a
+/// whole is answered with a single error object rather than an array
+fn parse_rpc_batch_response(
+    json: Value,
+    len: usize,
+) -> Result<Vec<Result<Value, ClientError>>, ClientError> {
+    let responses = match json {
+        Value::Array(responses) => responses,
+        json => {
+            parse_rpc_response(&json)?;
+            Err(RpcError::RpcRequestError(format!(
+                "RPC batch response is not an array: {}",
+                json
+            )))?
+        }
+    };
+
+    let mut results: Vec<Option<Result<Value, ClientError>>> = (0..len).map(|_| None).collect();
+    for response in responses {
+        let id = response["id"].as_u64().map(|id| id as usize);
+        match id {
+            Some(id) if id < len => results[id] = Some(parse_rpc_response(&response)),
+            _ => Err(RpcError::RpcRequestError(format!(
+                "RPC batch response has an unexpected id: {}",
+                response["id"]
+            )))?,
+        }
+    }
+    Ok(results
+        .into_iter()
+        .enumerate()
+        .map(|(id, result)| {
+            result.unwrap_or_else(|| {
+                Err(ClientError::RpcError(RpcError::RpcRequestError(format!(
+                    "RPC batch response is missing request {}",
+                    id
+                ))))
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_rpc_batch_response() {
+        let json = json!([
+            {"jsonrpc": "2.0", "result": 7, "id": 1},
+            {"jsonrpc": "2.0", "error": {"code": -32600, "message": "Invalid request"}, "id": 0},
+        ]);
+        let results = parse_rpc_batch_response(json, 3).unwrap();
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_err());
+        assert_eq!(results[1].as_ref().unwrap(), &json!(7));
+        assert!(results[2].is_err());
+
+        let json = json!({"jsonrpc": "2.0", "error": {"code": -32600, "message": "Invalid request"}, "id": null});
+        assert!(parse_rpc_batch_response(json, 1).is_err());
+
+        let json = json!([{"jsonrpc": "2.0", "result": 7, "id": 5}]);
+        assert!(parse_rpc_batch_response(json, 1).is_err());
+    }
+}
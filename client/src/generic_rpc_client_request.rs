@@ -1,5 +1,6 @@
 use crate::client_error::ClientError;
 use crate::rpc_request::RpcRequest;
+use futures::Future;
 
 pub(crate) trait GenericRpcClientRequest {
     fn send(
@@ -9,3 +10,20 @@ pub(crate) trait GenericRpcClientRequest {
         retries: usize,
     ) -> Result<serde_json::Value, ClientError>;
 }
+
+pub type RpcFuture<T> = Box<dyn Future<Item = T, Error = ClientError> + Send>;
+
+pub(crate) trait GenericAsyncRpcClientRequest {
+    fn send(
+        &self,
+        request: &RpcRequest,
+        params: Option<serde_json::Value>,
+    ) -> RpcFuture<serde_json::Value>;
+
+    /// Send `requests` as a single JSON-RPC 2.0 batch.  The results are in the
+    /// same order as `requests`
+    fn send_batch(
+        &self,
+        requests: Vec<(RpcRequest, Option<serde_json::Value>)>,
+    ) -> RpcFuture<Vec<Result<serde_json::Value, ClientError>>>;
+}
@@ -1,8 +1,15 @@
+pub mod async_rpc_client;
+pub mod async_rpc_client_request;
 pub mod client_error;
 mod generic_rpc_client_request;
+pub mod mock_async_rpc_client_request;
 pub mod mock_rpc_client_request;
 pub mod perf_utils;
+pub mod pubsub_client;
 pub mod rpc_client;
 pub mod rpc_client_request;
+pub mod rpc_filter;
 pub mod rpc_request;
 pub mod thin_client;
+
+pub use generic_rpc_client_request::RpcFuture;
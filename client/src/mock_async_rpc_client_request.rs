@@ -0,0 +1,38 @@
+use crate::client_error::ClientError;
+use crate::generic_rpc_client_request::{
+    GenericAsyncRpcClientRequest, GenericRpcClientRequest, RpcFuture,
+};
+use crate::mock_rpc_client_request::MockRpcClientRequest;
+use crate::rpc_request::RpcRequest;
+use futures::future;
+use serde_json::Value;
+
+/// Answers requests immediately with the same canned responses as `MockRpcClientRequest`
+pub struct MockAsyncRpcClientRequest {
+    mock: MockRpcClientRequest,
+}
+
+impl MockAsyncRpcClientRequest {
+    pub fn new(url: String) -> Self {
+        Self {
+            mock: MockRpcClientRequest::new(url),
+        }
+    }
+}
+
+impl GenericAsyncRpcClientRequest for MockAsyncRpcClientRequest {
+    fn send(&self, request: &RpcRequest, params: Option<Value>) -> RpcFuture<Value> {
+        Box::new(future::result(self.mock.send(request, params, 0)))
+    }
+
+    fn send_batch(
+        &self,
+        requests: Vec<(RpcRequest, Option<Value>)>,
+    ) -> RpcFuture<Vec<Result<Value, ClientError>>> {
+        let results = requests
+            .into_iter()
+            .map(|(request, params)| self.mock.send(&request, params, 0))
+            .collect();
+        Box::new(future::ok(results))
+    }
+}
@@ -1,8 +1,10 @@
 use crate::client_error::ClientError;
 use crate::generic_rpc_client_request::GenericRpcClientRequest;
 use crate::rpc_request::RpcRequest;
-use serde_json::{Number, Value};
+use serde_json::{json, Number, Value};
+use solana_sdk::account::Account;
 use solana_sdk::fee_calculator::FeeCalculator;
+use solana_sdk::pubkey::Pubkey;
 use solana_sdk::transaction::{self, TransactionError};
 
 pub const PUBKEY: &str = "7RoSF9fUmdphVCpabEoefH81WwrW7orsWonXWqTXkKV8";
@@ -45,10 +47,31 @@ impl GenericRpcClientRequest for MockRpcClientRequest {
                 let n = if self.url == "airdrop" { 0 } else { 50 };
                 Value::Number(Number::from(n))
             }
+            RpcRequest::GetMultipleAccounts => {
+                // Only `PUBKEY` exists
+                let accounts: Vec<Option<Account>> = match params {
+                    Some(Value::Array(param_array)) => match &param_array[0] {
+                        Value::Array(pubkeys) => pubkeys
+                            .iter()
+                            .map(|pubkey| {
+                                if pubkey.as_str() == Some(PUBKEY) {
+                                    Some(Account::new(50, 0, &Pubkey::default()))
+                                } else {
+                                    None
+                                }
+                            })
+                            .collect(),
+                        _ => vec![],
+                    },
+                    _ => vec![],
+                };
+                serde_json::to_value(accounts).unwrap()
+            }
             RpcRequest::GetRecentBlockhash => Value::Array(vec![
                 Value::String(PUBKEY.to_string()),
                 serde_json::to_value(FeeCalculator::default()).unwrap(),
             ]),
+            RpcRequest::GetSignaturesForAddress => Value::Array(vec![json!([SIGNATURE, 1])]),
             RpcRequest::GetSignatureStatus => {
                 let response: Option<transaction::Result<()>> = if self.url == "account_in_use" {
                     Some(Err(TransactionError::AccountInUse))
@@ -61,7 +84,13 @@ impl GenericRpcClientRequest for MockRpcClientRequest {
             }
             RpcRequest::GetTransactionCount => Value::Number(Number::from(1234)),
             RpcRequest::GetSlot => Value::Number(Number::from(0)),
+            RpcRequest::GetMinimumBalanceForRentExemption => Value::Number(Number::from(1280)),
             RpcRequest::SendTransaction => Value::String(SIGNATURE.to_string()),
+            RpcRequest::SimulateTransaction => json!({
+                "result": { "Ok": null },
+                "logs": ["log"],
+                "accounts": [[PUBKEY, Account::new(50, 0, &Pubkey::default())]],
+            }),
             _ => Value::Null,
         };
         Ok(val)
@@ -0,0 +1,459 @@
+//! The `pubsub_client` module implements a client for the websocket subscription API.
+//! Subscriptions are re-established whenever the connection is, and are cancelled when their
+//! `PubsubSubscription` is dropped.
+
+use futures::sync::mpsc::{unbounded, UnboundedReceiver};
+use futures::{Poll, Stream};
+use log::*;
+use serde::de::DeserializeOwned;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{sleep, Builder, JoinHandle};
+use std::time::Duration;
+use ws::{CloseCode, Handler, Handshake, Message};
+
+/// How long to wait before reconnecting after the connection is lost
+const RECONNECT_DELAY_MS: u64 = 1000;
+
+/// A slot notification, sent as each new bank is created
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct SlotInfo {
+    /// The slot of the new bank
+    pub slot: u64,
+
+    /// The slot of the new bank's parent
+    pub parent: u64,
+
+    /// The current root slot
+    pub root: u64,
+}
+
+struct Subscription {
+    subscribe_method: &'static str,
+    unsubscribe_method: &'static str,
+    params: Value,
+    /// The id the server assigned to this subscription on the current connection
+    server_id: Option<u64>,
+    /// Signature subscriptions are cancelled by the server after their first notification
+    one_shot: bool,
+    /// Forwards a notification's result, returning false once nobody is listening
+    notify: Box<dyn FnMut(Value) -> bool + Send>,
+}
+
+#[derive(Default)]
+struct PubsubState {
+    sender: Option<ws::Sender>,
+    next_subscription_id: u64,
+    next_request_id: u64,
+    subscriptions: HashMap<u64, Subscription>,
+    /// Subscribe requests awaiting a response, by request id
+    pending: HashMap<u64, (u64, &'static str)>,
+}
+
+impl PubsubState {
+    fn send(&mut self, method: &str, params: Value) -> Option<u64> {
+        let sender = self.sender.as_ref()?;
+        let request_id = self.next_request_id;
+        self.next_request_id += 1;
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": request_id,
+            "method": method,
+            "params": params,
+        });
+        if let Err(err) = sender.send(request.to_string()) {
+            warn!("pubsub {} request failed: {:?}", method, err);
+            return None;
+        }
+        Some(request_id)
+    }
+
+    fn subscribe(&mut self, id: u64) {
+        let (method, unsubscribe_method, params) = {
+            let subscription = &self.subscriptions[&id];
+            (
+                subscription.subscribe_method,
+                subscription.unsubscribe_method,
+                subscription.params.clone(),
+            )
+        };
+        if let Some(request_id) = self.send(method, params) {
+            self.pending.insert(request_id, (id, unsubscribe_method));
+        }
+    }
+
+    fn unsubscribe(&mut self, id: u64) {
+        if let Some(subscription) = self.subscriptions.remove(&id) {
+            if let Some(server_id) = subscription.server_id {
+                self.send(subscription.unsubscribe_method, json!([server_id]));
+            }
+        }
+    }
+
+    fn on_open(&mut self, sender: ws::Sender) {
+        self.sender = Some(sender);
+        self.pending.clear();
+        let ids: Vec<_> = self.subscriptions.keys().cloned().collect();
+        for id in ids {
+            self.subscriptions.get_mut(&id).unwrap().server_id = None;
+            self.subscribe(id);
+        }
+    }
+
+    fn on_close(&mut self) {
+        self.sender = None;
+        self.pending.clear();
+        for subscription in self.subscriptions.values_mut() {
+            subscription.server_id = None;
+        }
+    }
+
+    fn on_message(&mut self, message: &str) {
+        let json: Value = match serde_json::from_str(message) {
+            Ok(json) => json,
+            Err(err) => {
+                warn!("pubsub message parse failure: {:?}", err);
+                return;
+            }
+        };
+
+        if json["method"].is_string() {
+            self.on_notification(&json["params"]);
+        } else if let Some(request_id) = json["id"].as_u64() {
+            if let Some((id, unsubscribe_method)) = self.pending.remove(&request_id) {
+                match (json["result"].as_u64(), self.subscriptions.get_mut(&id)) {
+                    (Some(server_id), Some(subscription)) => {
+                        subscription.server_id = Some(server_id);
+                    }
+                    (Some(server_id), None) => {
+                        // Dropped before the server acknowledged it
+                        self.send(unsubscribe_method, json!([server_id]));
+                    }
+                    _ => warn!("pubsub subscribe failed: {}", json["error"]),
+                }
+            }
+        }
+    }
+
+    fn on_notification(&mut self, params: &Value) {
+        let server_id = params["subscription"].as_u64();
+        let id = self
+            .subscriptions
+            .iter()
+            .find(|(_, subscription)| server_id.is_some() && subscription.server_id == server_id)
+            .map(|(id, _)| *id);
+        if let Some(id) = id {
+            let (listening, one_shot) = {
+                let subscription = self.subscriptions.get_mut(&id).unwrap();
+                (
+                    (subscription.notify)(params["result"].clone()),
+                    subscription.one_shot,
+                )
+            };
+            if one_shot {
+                self.subscriptions.remove(&id);
+            } else if !listening {
+                self.unsubscribe(id);
+            }
+        }
+    }
+}
+
+struct PubsubHandler {
+    sender: ws::Sender,
+    state: Arc<Mutex<PubsubState>>,
+    exit: Arc<AtomicBool>,
+}
+
+impl Handler for PubsubHandler {
+    fn on_open(&mut self, _: Handshake) -> ws::Result<()> {
+        info!("pubsub connection opened");
+        let mut state = self.state.lock().unwrap();
+        // A client dropped while connecting must not leave this connection open, so `exit` is
+        // checked under the same lock its drop takes the sender under
+        if self.exit.load(Ordering::Relaxed) {
+            return self.sender.shutdown();
+        }
+        state.on_open(self.sender.clone());
+        Ok(())
+    }
+
+    fn on_message(&mut self, message: Message) -> ws::Result<()> {
+        if let Message::Text(text) = message {
+            self.state.lock().unwrap().on_message(&text);
+        }
+        Ok(())
+    }
+
+    fn on_close(&mut self, code: CloseCode, reason: &str) {
+        info!("pubsub connection closed: {:?} {}", code, reason);
+        self.state.lock().unwrap().on_close();
+    }
+
+    fn on_error(&mut self, err: ws::Error) {
+        warn!("pubsub connection error: {:?}", err);
+    }
+}
+
+/// A stream of the notifications for one subscription
+pub struct PubsubSubscription<T> {
+    id: u64,
+    state: Arc<Mutex<PubsubState>>,
+    receiver: UnboundedReceiver<T>,
+}
+
+impl<T> PubsubSubscription<T> {
+    /// Whether the server has acknowledged this subscription on the current connection
+    pub fn is_subscribed(&self) -> bool {
+        self.state
+            .lock()
+            .unwrap()
+            .subscriptions
+            .get(&self.id)
+            .map_or(false, |subscription| subscription.server_id.is_some())
+    }
+}
+
+impl<T> Stream for PubsubSubscription<T> {
+    type Item = T;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Option<T>, ()> {
+        self.receiver.poll()
+    }
+}
+
+impl<T> Drop for PubsubSubscription<T> {
+    fn drop(&mut self) {
+        self.state.lock().unwrap().unsubscribe(self.id);
+    }
+}
+
+pub struct PubsubClient {
+    state: Arc<Mutex<PubsubState>>,
+    exit: Arc<AtomicBool>,
+    thread_hdl: Option<JoinHandle<()>>,
+}
+
+impl PubsubClient {
+    /// Connect to the websocket at `url`, such as "ws://127.0.0.1:8900", reconnecting whenever
+    /// the connection is lost until the client is dropped
+    pub fn new(url: String) -> Self {
+        let state = Arc::new(Mutex::new(PubsubState::default()));
+        let exit = Arc::new(AtomicBool::new(false));
+        let state_ = state.clone();
+        let exit_ = exit.clone();
+        let thread_hdl = Builder::new()
+            .name("solana-pubsub-client".to_string())
+            .spawn(move || {
+                while !exit_.load(Ordering::Relaxed) {
+                    let result = ws::connect(url.as_str(), |sender| PubsubHandler {
+                        sender,
+                        state: state_.clone(),
+                        exit: exit_.clone(),
+                    });
+                    if let Err(err) = result {
+                        warn!("pubsub connection to {} failed: {:?}", url, err);
+                    }
+                    state_.lock().unwrap().on_close();
+
+                    let mut waited = 0;
+                    while waited < RECONNECT_DELAY_MS && !exit_.load(Ordering::Relaxed) {
+                        sleep(Duration::from_millis(100));
+                        waited += 100;
+                    }
+                }
+            })
+            .unwrap();
+        Self {
+            state,
+            exit,
+            thread_hdl: Some(thread_hdl),
+        }
+    }
+
+    /// Whether the client is currently connected
+    pub fn is_connected(&self) -> bool {
+        self.state.lock().unwrap().sender.is_some()
+    }
+
+    /// Subscribe to changes of the account at `pubkey`, notified once `confirmations` blocks
+    /// have been confirmed on top of the change
+    pub fn account_subscribe(
+        &self,
+        pubkey: &Pubkey,
+        confirmations: Option<usize>,
+    ) -> PubsubSubscription<Account> {
+        self.subscribe(
+            "accountSubscribe",
+            "accountUnsubscribe",
+            json!([pubkey.to_string(), confirmations]),
+            false,
+            parse::<Account>,
+        )
+    }
+
+    /// Subscribe to changes of the accounts owned by `program_id`
+    pub fn program_subscribe(
+        &self,
+        program_id: &Pubkey,
+        confirmations: Option<usize>,
+    ) -> PubsubSubscription<(Pubkey, Account)> {
+        self.subscribe(
+            "programSubscribe",
+            "programUnsubscribe",
+            json!([program_id.to_string(), confirmations]),
+            false,
+            |result| {
+                let (pubkey, account) = parse::<(String, Account)>(result)?;
+                Some((pubkey.parse().ok()?, account))
+            },
+        )
+    }
+
+    /// Subscribe to the status of the transaction with `signature`.  The subscription ends after
+    /// its first notification
+    pub fn signature_subscribe(
+        &self,
+        signature: &Signature,
+        confirmations: Option<usize>,
+    ) -> PubsubSubscription<transaction::Result<()>> {
+        self.subscribe(
+            "signatureSubscribe",
+            "signatureUnsubscribe",
+            json!([signature.to_string(), confirmations]),
+            true,
+            parse::<transaction::Result<()>>,
+        )
+    }
+
+    /// Subscribe to the slot, parent and root of each new bank
+    pub fn slot_subscribe(&self) -> PubsubSubscription<SlotInfo> {
+        self.subscribe(
+            "slotSubscribe",
+            "slotUnsubscribe",
+            json!([]),
+            false,
+            parse::<SlotInfo>,
+        )
+    }
+
+    /// Subscribe to the slot of each new root
+    pub fn root_subscribe(&self) -> PubsubSubscription<u64> {
+        self.subscribe(
+            "rootSubscribe",
+            "rootUnsubscribe",
+            json!([]),
+            false,
+            parse::<u64>,
+        )
+    }
+
+    fn subscribe<T, F>(
+        &self,
+        subscribe_method: &'static str,
+        unsubscribe_method: &'static str,
+        params: Value,
+        one_shot: bool,
+        parse: F,
+    ) -> PubsubSubscription<T>
+    where
+        T: Send + 'static,
+        F: Fn(Value) -> Option<T> + Send + 'static,
+    {
+        let (sender, receiver) = unbounded();
+        let notify = move |result: Value| match parse(result.clone()) {
+            Some(item) => sender.unbounded_send(item).is_ok(),
+            None => {
+                warn!("pubsub notification parse failure: {}", result);
+                true
+            }
+        };
+
+        let mut state = self.state.lock().unwrap();
+        let id = state.next_subscription_id;
+        state.next_subscription_id += 1;
+        state.subscriptions.insert(
+            id,
+            Subscription {
+                subscribe_method,
+                unsubscribe_method,
+                params,
+                server_id: None,
+                one_shot,
+                notify: Box::new(notify),
+            },
+        );
+        state.subscribe(id);
+        PubsubSubscription {
+            id,
+            state: self.state.clone(),
+            receiver,
+        }
+    }
+}
+
+impl Drop for PubsubClient {
+    fn drop(&mut self) {
+        self.exit.store(true, Ordering::Relaxed);
+        if let Some(sender) = self.state.lock().unwrap().sender.take() {
+            let _ = sender.shutdown();
+        }
+        if let Some(thread_hdl) = self.thread_hdl.take() {
+            let _ = thread_hdl.join();
+        }
+    }
+}
+
+fn parse<T: DeserializeOwned>(result: Value) -> Option<T> {
+    serde_json::from_value(result).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn test_pubsub_state_on_message() {
+        let mut state = PubsubState::default();
+        let (sender, receiver) = channel();
+        state.subscriptions.insert(
+            7,
+            Subscription {
+                subscribe_method: "rootSubscribe",
+                unsubscribe_method: "rootUnsubscribe",
+                params: json!([]),
+                server_id: None,
+                one_shot: true,
+                notify: Box::new(move |result: Value| {
+                    sender.send(result.as_u64().unwrap()).is_ok()
+                }),
+            },
+        );
+        state.pending.insert(3, (7, "rootUnsubscribe"));
+
+        // Notifications for an unknown server id are ignored
+        state.on_message(
+            r#"{"jsonrpc":"2.0","method":"rootNotification","params":{"result":1,"subscription":4}}"#,
+        );
+        assert!(receiver.try_recv().is_err());
+
+        state.on_message(r#"{"jsonrpc":"2.0","result":4,"id":3}"#);
+        assert_eq!(state.subscriptions[&7].server_id, Some(4));
+        assert!(state.pending.is_empty());
+
+        state.on_message(
+            r#"{"jsonrpc":"2.0","method":"rootNotification","params":{"result":2,"subscription":4}}"#,
+        );
+        assert_eq!(receiver.try_recv(), Ok(2));
+        assert!(state.subscriptions.is_empty());
+    }
+}
@@ -2,11 +2,13 @@ use crate::client_error::ClientError;
 use crate::generic_rpc_client_request::GenericRpcClientRequest;
 use crate::mock_rpc_client_request::MockRpcClientRequest;
 use crate::rpc_client_request::RpcClientRequest;
+use crate::rpc_filter::RpcProgramAccountsConfig;
 use crate::rpc_request::RpcRequest;
 use bincode::serialize;
 use log::*;
 use serde_json::{json, Value};
 use solana_sdk::account::Account;
+use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::fee_calculator::FeeCalculator;
 use solana_sdk::hash::Hash;
 use solana_sdk::pubkey::Pubkey;
@@ -62,6 +64,53 @@ impl RpcClient {
         Ok(signature.as_str().unwrap().to_string())
     }
 
+    /// Execute `transaction` against the node's working bank without committing it,
+    /// returning its result, the messages logged by its programs, and the post-execution
+    /// state of the accounts it may write
+    #[allow(clippy::type_complexity)]
+    pub fn simulate_transaction(
+        &self,
+        transaction: &Transaction,
+    ) -> io::Result<(transaction::Result<()>, Vec<String>, Vec<(Pubkey, Account)>)> {
+        let serialized = serialize(transaction).unwrap();
+        let params = json!([serialized]);
+        let response = self
+            .client
+            .send(&RpcRequest::SimulateTransaction, Some(params), 0)
+            .map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("SimulateTransaction request failure: {:?}", err),
+                )
+            })?;
+
+        let parse_error = |err| {
+            io::Error::new(
+                io::ErrorKind::Other,
+                format!("SimulateTransaction parse failure: {:?}", err),
+            )
+        };
+        let result: transaction::Result<()> =
+            serde_json::from_value(response["result"].clone()).map_err(parse_error)?;
+        let logs: Vec<String> =
+            serde_json::from_value(response["logs"].clone()).map_err(parse_error)?;
+        let accounts: Vec<(String, Account)> =
+            serde_json::from_value(response["accounts"].clone()).map_err(parse_error)?;
+        let accounts = accounts
+            .into_iter()
+            .map(|(pubkey, account)| {
+                let pubkey = pubkey.parse().map_err(|err| {
+                    io::Error::new(
+                        io::ErrorKind::Other,
+                        format!("SimulateTransaction parse failure: {:?}", err),
+                    )
+                })?;
+                Ok((pubkey, account))
+            })
+            .collect::<io::Result<_>>()?;
+        Ok((result, logs, accounts))
+    }
+
     pub fn get_signature_status(
         &self,
         signature: &str,
@@ -76,9 +125,14 @@ impl RpcClient {
     }
 
     pub fn get_slot(&self) -> io::Result<u64> {
+        self.get_slot_with_commitment(CommitmentConfig::default())
+    }
+
+    pub fn get_slot_with_commitment(&self, commitment: CommitmentConfig) -> io::Result<u64> {
+        let params = json!([commitment]);
         let response = self
             .client
-            .send(&RpcRequest::GetSlot, None, 0)
+            .send(&RpcRequest::GetSlot, Some(params), 0)
             .map_err(|err| {
                 io::Error::new(
                     io::ErrorKind::Other,
@@ -243,7 +297,15 @@ impl RpcClient {
     }
 
     pub fn get_account(&self, pubkey: &Pubkey) -> io::Result<Account> {
-        let params = json!([format!("{}", pubkey)]);
+        self.get_account_with_commitment(pubkey, CommitmentConfig::default())
+    }
+
+    pub fn get_account_with_commitment(
+        &self,
+        pubkey: &Pubkey,
+        commitment: CommitmentConfig,
+    ) -> io::Result<Account> {
+        let params = json!([format!("{}", pubkey), commitment]);
         let response = self
             .client
             .send(&RpcRequest::GetAccountInfo, Some(params), 0);
@@ -267,19 +329,144 @@ impl RpcClient {
         self.get_account(pubkey).map(|account| account.data)
     }
 
+    /// Request a batch of accounts. All accounts are read from the same bank, and any account
+    /// that does not exist is returned as `None`.
+    pub fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> io::Result<Vec<Option<Account>>> {
+        let pubkeys: Vec<String> = pubkeys.iter().map(ToString::to_string).collect();
+        let params = json!([pubkeys]);
+        let response = self
+            .client
+            .send(&RpcRequest::GetMultipleAccounts, Some(params), 0)
+            .map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("GetMultipleAccounts request failure: {:?}", err),
+                )
+            })?;
+
+        serde_json::from_value(response).map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::Other,
+                format!("GetMultipleAccounts parse failure: {:?}", err),
+            )
+        })
+    }
+
+    pub fn get_program_accounts(&self, program_id: &Pubkey) -> io::Result<Vec<(Pubkey, Account)>> {
+        self.get_program_accounts_with_config(program_id, &RpcProgramAccountsConfig::default())
+    }
+
+    pub fn get_program_accounts_with_config(
+        &self,
+        program_id: &Pubkey,
+        config: &RpcProgramAccountsConfig,
+    ) -> io::Result<Vec<(Pubkey, Account)>> {
+        let params = json!([format!("{}", program_id), config]);
+        let response = self
+            .client
+            .send(&RpcRequest::GetProgramAccounts, Some(params), 0)
+            .map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("GetProgramAccounts request failure: {:?}", err),
+                )
+            })?;
+
+        let accounts: Vec<(String, Account)> = serde_json::from_value(response).map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::Other,
+                format!("GetProgramAccounts parse failure: {:?}", err),
+            )
+        })?;
+
+        accounts
+            .into_iter()
+            .map(|(pubkey, account)| {
+                let pubkey = pubkey.parse().map_err(|err| {
+                    io::Error::new(
+                        io::ErrorKind::Other,
+                        format!("GetProgramAccounts parse failure: {:?}", err),
+                    )
+                })?;
+                Ok((pubkey, account))
+            })
+            .collect()
+    }
+
+    /// Request the signatures of rooted transactions that reference `address`, newest first,
+    /// along with the slot each was recorded in
+    pub fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before: Option<Signature>,
+        limit: Option<usize>,
+    ) -> io::Result<Vec<(Signature, u64)>> {
+        let params = json!([
+            format!("{}", address),
+            before.map(|signature| signature.to_string()),
+            limit
+        ]);
+        let response = self
+            .client
+            .send(&RpcRequest::GetSignaturesForAddress, Some(params), 0)
+            .map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("GetSignaturesForAddress request failure: {:?}", err),
+                )
+            })?;
+
+        let signatures: Vec<(String, u64)> = serde_json::from_value(response).map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::Other,
+                format!("GetSignaturesForAddress parse failure: {:?}", err),
+            )
+        })?;
+
+        signatures
+            .into_iter()
+            .map(|(signature, slot)| {
+                let signature = signature.parse().map_err(|err| {
+                    io::Error::new(
+                        io::ErrorKind::Other,
+                        format!("GetSignaturesForAddress parse failure: {:?}", err),
+                    )
+                })?;
+                Ok((signature, slot))
+            })
+            .collect()
+    }
+
     /// Request the balance of the user holding `pubkey`. This method blocks
     /// until the server sends a response. If the response packet is dropped
     /// by the network, this method will hang indefinitely.
     pub fn get_balance(&self, pubkey: &Pubkey) -> io::Result<u64> {
-        self.get_account(pubkey).map(|account| account.lamports)
+        self.get_balance_with_commitment(pubkey, CommitmentConfig::default())
+    }
+
+    pub fn get_balance_with_commitment(
+        &self,
+        pubkey: &Pubkey,
+        commitment: CommitmentConfig,
+    ) -> io::Result<u64> {
+        self.get_account_with_commitment(pubkey, commitment)
+            .map(|account| account.lamports)
     }
 
     /// Request the transaction count.  If the response packet is dropped by the network,
     /// this method will try again 5 times.
     pub fn get_transaction_count(&self) -> io::Result<u64> {
+        self.get_transaction_count_with_commitment(CommitmentConfig::default())
+    }
+
+    pub fn get_transaction_count_with_commitment(
+        &self,
+        commitment: CommitmentConfig,
+    ) -> io::Result<u64> {
+        let params = json!([commitment]);
         let response = self
             .client
-            .send(&RpcRequest::GetTransactionCount, None, 0)
+            .send(&RpcRequest::GetTransactionCount, Some(params), 0)
             .map_err(|err| {
                 io::Error::new(
                     io::ErrorKind::Other,
@@ -295,10 +482,47 @@ impl RpcClient {
         })
     }
 
+    /// Request the minimum balance an account holding `data_len` bytes of data needs to be
+    /// exempt from rent
+    pub fn get_minimum_balance_for_rent_exemption(&self, data_len: usize) -> io::Result<u64> {
+        let params = json!([data_len]);
+        let response = self
+            .client
+            .send(
+                &RpcRequest::GetMinimumBalanceForRentExemption,
+                Some(params),
+                0,
+            )
+            .map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::Other,
+                    format!(
+                        "GetMinimumBalanceForRentExemption request failure: {:?}",
+                        err
+                    ),
+                )
+            })?;
+
+        serde_json::from_value(response).map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::Other,
+                format!("GetMinimumBalanceForRentExemption parse failure: {}", err),
+            )
+        })
+    }
+
     pub fn get_recent_blockhash(&self) -> io::Result<(Hash, FeeCalculator)> {
+        self.get_recent_blockhash_with_commitment(CommitmentConfig::default())
+    }
+
+    pub fn get_recent_blockhash_with_commitment(
+        &self,
+        commitment: CommitmentConfig,
+    ) -> io::Result<(Hash, FeeCalculator)> {
+        let params = json!([commitment]);
         let response = self
             .client
-            .send(&RpcRequest::GetRecentBlockhash, None, 0)
+            .send(&RpcRequest::GetRecentBlockhash, Some(params), 0)
             .map_err(|err| {
                 io::Error::new(
                     io::ErrorKind::Other,
@@ -670,6 +894,46 @@ mod tests {
         let signature = rpc_client.send_transaction(&tx);
         assert!(signature.is_err());
     }
+    #[test]
+    fn test_simulate_transaction() {
+        let rpc_client = RpcClient::new_mock("succeeds".to_string());
+
+        let key = Keypair::new();
+        let to = Pubkey::new_rand();
+        let blockhash = Hash::default();
+        let tx = system_transaction::create_user_account(&key, &to, 50, blockhash);
+
+        let (result, logs, accounts) = rpc_client.simulate_transaction(&tx).unwrap();
+        assert_eq!(result, Ok(()));
+        assert_eq!(logs, vec!["log".to_string()]);
+        assert_eq!(
+            accounts,
+            vec![(
+                PUBKEY.parse().unwrap(),
+                Account::new(50, 0, &Pubkey::default())
+            )]
+        );
+
+        let rpc_client = RpcClient::new_mock("fails".to_string());
+        assert!(rpc_client.simulate_transaction(&tx).is_err());
+    }
+
+    #[test]
+    fn test_get_minimum_balance_for_rent_exemption() {
+        let rpc_client = RpcClient::new_mock("succeeds".to_string());
+        assert_eq!(
+            rpc_client
+                .get_minimum_balance_for_rent_exemption(0)
+                .unwrap(),
+            1280
+        );
+
+        let rpc_client = RpcClient::new_mock("fails".to_string());
+        assert!(rpc_client
+            .get_minimum_balance_for_rent_exemption(0)
+            .is_err());
+    }
+
     #[test]
     fn test_get_recent_blockhash() {
         let rpc_client = RpcClient::new_mock("succeeds".to_string());
@@ -702,6 +966,33 @@ mod tests {
         assert_eq!(status, Some(Err(TransactionError::AccountInUse)));
     }
 
+    #[test]
+    fn test_get_multiple_accounts() {
+        let rpc_client = RpcClient::new_mock("succeeds".to_string());
+        let pubkeys = vec![PUBKEY.parse().unwrap(), Pubkey::new_rand()];
+        let accounts = rpc_client.get_multiple_accounts(&pubkeys).unwrap();
+        assert_eq!(accounts.len(), 2);
+        assert_eq!(accounts[0].as_ref().unwrap().lamports, 50);
+        assert!(accounts[1].is_none());
+
+        let rpc_client = RpcClient::new_mock("fails".to_string());
+        assert!(rpc_client.get_multiple_accounts(&pubkeys).is_err());
+    }
+
+    #[test]
+    fn test_get_signatures_for_address() {
+        let rpc_client = RpcClient::new_mock("succeeds".to_string());
+        let signatures = rpc_client
+            .get_signatures_for_address(&Pubkey::new_rand(), None, Some(10))
+            .unwrap();
+        assert_eq!(signatures, vec![(SIGNATURE.parse().unwrap(), 1)]);
+
+        let rpc_client = RpcClient::new_mock("fails".to_string());
+        assert!(rpc_client
+            .get_signatures_for_address(&Pubkey::new_rand(), None, None)
+            .is_err());
+    }
+
     #[test]
     fn test_send_and_confirm_transaction() {
         let rpc_client = RpcClient::new_mock("succeeds".to_string());
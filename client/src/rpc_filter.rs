@@ -0,0 +1,162 @@
+use serde_derive::{Deserialize, Serialize};
+
+/// Upper bound on the decoded length of a `Memcmp` filter
+pub const MAX_MEMCMP_BYTES: usize = 128;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcFilterType {
+    /// Match accounts whose data is exactly this many bytes long
+    DataSize(u64),
+    /// Match accounts whose data contains `bytes` at `offset`
+    Memcmp(Memcmp),
+}
+
+impl RpcFilterType {
+    pub fn verify(&self) -> Result<(), RpcFilterError> {
+        match self {
+            RpcFilterType::DataSize(_) => Ok(()),
+            RpcFilterType::Memcmp(compare) => {
+                let bytes = compare.bytes()?;
+                if bytes.len() > MAX_MEMCMP_BYTES {
+                    Err(RpcFilterError::DataTooLarge)
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    pub fn allows(&self, data: &[u8]) -> bool {
+        match self {
+            RpcFilterType::DataSize(size) => data.len() as u64 == *size,
+            RpcFilterType::Memcmp(compare) => compare.bytes_match(data),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RpcFilterError {
+    DataTooLarge,
+    Base58DecodeError,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Memcmp {
+    /// Data offset to begin match
+    pub offset: usize,
+    /// Bytes to match, as a base-58 encoded string
+    pub bytes: String,
+}
+
+impl Memcmp {
+    pub fn new(offset: usize, bytes: &[u8]) -> Self {
+        Self {
+            offset,
+            bytes: bs58::encode(bytes).into_string(),
+        }
+    }
+
+    pub fn bytes(&self) -> Result<Vec<u8>, RpcFilterError> {
+        // A base-58 string never decodes to more bytes than it has characters
+        let mut bytes = vec![0; self.bytes.len()];
+        let len = bs58::decode(&self.bytes)
+            .into(&mut bytes)
+            .map_err(|_| RpcFilterError::Base58DecodeError)?;
+        bytes.truncate(len);
+        Ok(bytes)
+    }
+
+    pub fn bytes_match(&self, data: &[u8]) -> bool {
+        match self.bytes() {
+            Ok(bytes) => {
+                if self.offset > data.len() || data[self.offset..].len() < bytes.len() {
+                    return false;
+                }
+                data[self.offset..self.offset + bytes.len()] == bytes[..]
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RpcDataSlice {
+    pub offset: usize,
+    pub length: usize,
+}
+
+impl RpcDataSlice {
+    /// Return the portion of `data` covered by this slice, truncated to the data length
+    pub fn slice<'a>(&self, data: &'a [u8]) -> &'a [u8] {
+        if self.offset >= data.len() {
+            &[]
+        } else {
+            let end = self.offset.saturating_add(self.length).min(data.len());
+            &data[self.offset..end]
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcProgramAccountsConfig {
+    pub filters: Option<Vec<RpcFilterType>>,
+    pub data_slice: Option<RpcDataSlice>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memcmp_bytes_match() {
+        let data = vec![1, 2, 3, 4, 5];
+
+        assert!(Memcmp::new(0, &[1, 2, 3, 4, 5]).bytes_match(&data));
+        assert!(Memcmp::new(0, &[1, 2]).bytes_match(&data));
+        assert!(Memcmp::new(2, &[3, 4]).bytes_match(&data));
+        assert!(Memcmp::new(5, &[]).bytes_match(&data));
+        assert!(!Memcmp::new(0, &[2]).bytes_match(&data));
+        assert!(!Memcmp::new(4, &[5, 6]).bytes_match(&data));
+        assert!(!Memcmp::new(6, &[]).bytes_match(&data));
+
+        let bad = Memcmp {
+            offset: 0,
+            bytes: "0OIl".to_string(),
+        };
+        assert!(!bad.bytes_match(&data));
+    }
+
+    #[test]
+    fn test_filter_verify() {
+        assert_eq!(RpcFilterType::DataSize(1).verify(), Ok(()));
+        assert_eq!(
+            RpcFilterType::Memcmp(Memcmp::new(0, &[0; MAX_MEMCMP_BYTES])).verify(),
+            Ok(())
+        );
+        assert_eq!(
+            RpcFilterType::Memcmp(Memcmp::new(0, &[0; MAX_MEMCMP_BYTES + 1])).verify(),
+            Err(RpcFilterError::DataTooLarge)
+        );
+        assert_eq!(
+            RpcFilterType::Memcmp(Memcmp {
+                offset: 0,
+                bytes: "0OIl".to_string(),
+            })
+            .verify(),
+            Err(RpcFilterError::Base58DecodeError)
+        );
+    }
+
+    #[test]
+    fn test_data_slice() {
+        let data = vec![1, 2, 3, 4, 5];
+        let slice = |offset, length| RpcDataSlice { offset, length };
+        assert_eq!(slice(0, 5).slice(&data), &data[..]);
+        assert_eq!(slice(1, 2).slice(&data), &[2, 3]);
+        assert_eq!(slice(3, 10).slice(&data), &[4, 5]);
+        assert_eq!(slice(5, 1).slice(&data), &[] as &[u8]);
+        assert_eq!(slice(0, 0).slice(&data), &[] as &[u8]);
+    }
+}
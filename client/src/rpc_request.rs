@@ -10,11 +10,15 @@ pub enum RpcRequest {
     GetBalance,
     GetClusterNodes,
     GetNumBlocksSinceSignatureConfirmation,
+    GetProgramAccounts,
     GetRecentBlockhash,
+    GetSignaturesForAddress,
     GetSignatureStatus,
     GetSlot,
     GetSlotLeader,
     GetEpochVoteAccounts,
+    GetMultipleAccounts,
+    GetMinimumBalanceForRentExemption,
     GetStorageBlockhash,
     GetStorageSlot,
     GetStoragePubkeysForSlot,
@@ -23,6 +27,7 @@ pub enum RpcRequest {
     RequestAirdrop,
     SendTransaction,
     SignVote,
+    SimulateTransaction,
 }
 
 impl RpcRequest {
@@ -38,11 +43,15 @@ impl RpcRequest {
             RpcRequest::GetNumBlocksSinceSignatureConfirmation => {
                 "getNumBlocksSinceSignatureConfirmation"
             }
+            RpcRequest::GetProgramAccounts => "getProgramAccounts",
             RpcRequest::GetRecentBlockhash => "getRecentBlockhash",
+            RpcRequest::GetSignaturesForAddress => "getSignaturesForAddress",
             RpcRequest::GetSignatureStatus => "getSignatureStatus",
             RpcRequest::GetSlot => "getSlot",
             RpcRequest::GetSlotLeader => "getSlotLeader",
             RpcRequest::GetEpochVoteAccounts => "getEpochVoteAccounts",
+            RpcRequest::GetMultipleAccounts => "getMultipleAccounts",
+            RpcRequest::GetMinimumBalanceForRentExemption => "getMinimumBalanceForRentExemption",
             RpcRequest::GetStorageBlockhash => "getStorageBlockhash",
             RpcRequest::GetStorageSlot => "getStorageSlot",
             RpcRequest::GetStoragePubkeysForSlot => "getStoragePubkeysForSlot",
@@ -51,6 +60,7 @@ impl RpcRequest {
             RpcRequest::RequestAirdrop => "requestAirdrop",
             RpcRequest::SendTransaction => "sendTransaction",
             RpcRequest::SignVote => "signVote",
+            RpcRequest::SimulateTransaction => "simulateTransaction",
         };
         let mut request = json!({
            "jsonrpc": jsonrpc,
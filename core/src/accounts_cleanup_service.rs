@@ -0,0 +1,129 @@
+//! The `accounts_cleanup_service` periodically reclaims the account storage held by old roots
+//! as `BankForks` moves its root forward.
+
+use crate::bank_forks::BankForks;
+use crate::service::Service;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread::{self, sleep, Builder, JoinHandle};
+use std::time::Duration;
+
+/// How often the root bank is checked for roots to clean
+const CLEANUP_INTERVAL_MS: u64 = 1000;
+
+pub struct AccountsCleanupService {
+    t_cleanup: JoinHandle<()>,
+}
+
+impl AccountsCleanupService {
+    pub fn new(bank_forks: &Arc<RwLock<BankForks>>, exit: &Arc<AtomicBool>) -> Self {
+        let bank_forks = bank_forks.clone();
+        let exit = exit.clone();
+        let t_cleanup = Builder::new()
+            .name("solana-accounts-cleanup".to_string())
+            .spawn(move || loop {
+                if exit.load(Ordering::Relaxed) {
+                    break;
+                }
+                Self::clean(&bank_forks);
+                sleep(Duration::from_millis(CLEANUP_INTERVAL_MS));
+            })
+            .unwrap();
+        Self { t_cleanup }
+    }
+
+    /// Clean the accounts of the current root bank, a batch of roots at a time, until every
+    /// root has been cleaned or a pending snapshot holds cleaning off
+    fn clean(bank_forks: &RwLock<BankForks>) {
+        let root_bank = bank_forks.read().unwrap().root_bank();
+        let mut num_uncleaned_roots = root_bank.num_uncleaned_roots();
+        while num_uncleaned_roots > 0 {
+            let reclaimed_bytes = root_bank.clean_accounts();
+            debug!(
+                "cleaned accounts at root {}: {} bytes reclaimed",
+                root_bank.slot(),
+                reclaimed_bytes
+            );
+            let remaining = root_bank.num_uncleaned_roots();
+            if remaining >= num_uncleaned_roots {
+                break;
+            }
+            num_uncleaned_roots = remaining;
+        }
+    }
+}
+
+impl Service for AccountsCleanupService {
+    type JoinReturnType = ();
+
+    fn join(self) -> thread::Result<()> {
+        self.t_cleanup.join()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::genesis_utils::{create_genesis_block, GenesisBlockInfo};
+    use solana_runtime::bank::Bank;
+    use solana_sdk::pubkey::Pubkey;
+
+    #[test]
+    fn test_accounts_cleanup_service_exit() {
+        let GenesisBlockInfo { genesis_block, .. } = create_genesis_block(10_000);
+        let bank = Bank::new(&genesis_block);
+        let bank_forks = Arc::new(RwLock::new(BankForks::new(0, bank)));
+        let exit = Arc::new(AtomicBool::new(false));
+        let accounts_cleanup_service = AccountsCleanupService::new(&bank_forks, &exit);
+        exit.store(true, Ordering::Relaxed);
+        accounts_cleanup_service.join().unwrap();
+    }
+
+    #[test]
+    fn test_accounts_cleanup_service_clean() {
+        let GenesisBlockInfo {
+            genesis_block,
+            mint_keypair,
+            ..
+        } = create_genesis_block(10_000);
+        let bank0 = Bank::new(&genesis_block);
+        let mut bank_forks = BankForks::new(0, bank0);
+        let bank0 = bank_forks[0].clone();
+        let pubkeys: Vec<_> = (0..10).map(|_| Pubkey::new_rand()).collect();
+        for pubkey in &pubkeys {
+            bank0.transfer(1, &mint_keypair, pubkey).unwrap();
+        }
+        bank_forks.set_root(0);
+
+        // Overwrite every account in many newer roots, more than are cleaned in one batch,
+        // so the storage of the older roots is all but dead
+        let num_roots = 20;
+        for slot in 1..=num_roots {
+            let parent = bank_forks[slot - 1].clone();
+            let bank = Bank::new_from_parent(&parent, &Pubkey::default(), slot);
+            for pubkey in &pubkeys {
+                bank.transfer(1, &mint_keypair, pubkey).unwrap();
+            }
+            bank_forks.insert(bank);
+            bank_forks.set_root(slot);
+        }
+
+        let root_bank = bank_forks.root_bank();
+        assert!(root_bank.num_uncleaned_roots() > 0);
+        let bank_forks = RwLock::new(bank_forks);
+
+        // Cleaning is held off, without spinning, while a snapshot is pending
+        let snapshot = root_bank.accounts().accounts_db.snapshot(root_bank.slot());
+        AccountsCleanupService::clean(&bank_forks);
+        assert!(root_bank.num_uncleaned_roots() > 0);
+        drop(snapshot);
+
+        // Every root is cleaned in one pass, however many batches that takes
+        AccountsCleanupService::clean(&bank_forks);
+        assert_eq!(root_bank.num_uncleaned_roots(), 0);
+        assert_eq!(root_bank.clean_accounts(), 0);
+        for pubkey in &pubkeys {
+            assert_eq!(root_bank.get_balance(pubkey), num_roots + 1);
+        }
+    }
+}
@@ -1,5 +1,7 @@
 //! The `bank_forks` module implments BankForks a DAG of checkpointed Banks
 
+use crate::snapshot_packager_service::SnapshotPackageSender;
+use crate::snapshot_utils;
 use bincode::{deserialize_from, serialize_into};
 use solana_metrics::inc_new_counter_info;
 use solana_runtime::bank::{Bank, BankRc, StatusCacheRc};
@@ -11,15 +13,20 @@ use std::fs::File;
 use std::io::{BufReader, BufWriter, Error, ErrorKind};
 use std::ops::Index;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
 pub struct BankForks {
     banks: HashMap<u64, Arc<Bank>>,
     working_bank: Arc<Bank>,
     root: u64,
+    confirmed_slot: u64,
     slots: HashSet<u64>,
     snapshot_path: Option<String>,
+    // Held in a mutex so BankForks stays shareable across the threads that read it
+    snapshot_package_sender: Option<Mutex<SnapshotPackageSender>>,
+    snapshot_interval_slots: u64,
+    last_snapshot_slot: Option<u64>,
 }
 
 impl Index<u64> for BankForks {
@@ -38,8 +45,12 @@ impl BankForks {
             banks,
             working_bank,
             root: 0,
+            confirmed_slot: 0,
             slots: HashSet::new(),
             snapshot_path: None,
+            snapshot_package_sender: None,
+            snapshot_interval_slots: snapshot_utils::DEFAULT_SNAPSHOT_INTERVAL_SLOTS,
+            last_snapshot_slot: None,
         }
     }
 
@@ -100,10 +111,14 @@ impl BankForks {
         }
         Self {
             root,
+            confirmed_slot: root,
             banks,
             working_bank,
             slots: HashSet::new(),
             snapshot_path: None,
+            snapshot_package_sender: None,
+            snapshot_interval_slots: snapshot_utils::DEFAULT_SNAPSHOT_INTERVAL_SLOTS,
+            last_snapshot_slot: None,
         }
     }
 
@@ -134,7 +149,9 @@ impl BankForks {
             .unwrap_or(0);
         root_bank.squash();
         let new_tx_count = root_bank.transaction_count();
+        let root_bank = root_bank.clone();
         self.prune_non_root(root);
+        self.send_snapshot_package(&root_bank);
 
         inc_new_counter_info!(
             "bank-forks_set_root_ms",
@@ -150,6 +167,77 @@ impl BankForks {
         self.root
     }
 
+    /// Record that a supermajority of the cluster stake has voted on `slot`
+    pub fn set_confirmed_slot(&mut self, slot: u64) {
+        if slot > self.confirmed_slot {
+            self.confirmed_slot = slot;
+        }
+    }
+
+    /// The highest slot known to be confirmed by a supermajority of the cluster stake
+    pub fn confirmed_slot(&self) -> u64 {
+        self.confirmed_slot
+    }
+
+    pub fn root_bank(&self) -> Arc<Bank> {
+        self.find_bank(self.root)
+            .unwrap_or_else(|| self.working_bank())
+    }
+
+    /// The bank for the highest confirmed slot, or the root bank if that fork has been pruned
+    pub fn confirmed_bank(&self) -> Arc<Bank> {
+        self.find_bank(self.confirmed_slot)
+            .unwrap_or_else(|| self.root_bank())
+    }
+
+    // Pruning only keeps descendants of the root, so older banks are found through their children
+    fn find_bank(&self, slot: u64) -> Option<Arc<Bank>> {
+        self.banks.get(&slot).cloned().or_else(|| {
+            self.banks.values().find_map(|bank| {
+                bank.parents()
+                    .into_iter()
+                    .find(|parent| parent.slot() == slot)
+            })
+        })
+    }
+
+    /// Have a snapshot archive written for every `snapshot_interval_slots` the root advances
+    pub fn set_snapshot_package_sender(
+        &mut self,
+        snapshot_package_sender: SnapshotPackageSender,
+        snapshot_interval_slots: u64,
+    ) {
+        self.snapshot_package_sender = Some(Mutex::new(snapshot_package_sender));
+        self.snapshot_interval_slots = snapshot_interval_slots;
+    }
+
+    fn send_snapshot_package(&mut self, root_bank: &Arc<Bank>) {
+        let sender = match &self.snapshot_package_sender {
+            Some(sender) => sender,
+            None => return,
+        };
+        if let Some(last_snapshot_slot) = self.last_snapshot_slot {
+            if root_bank.slot() < last_snapshot_slot + self.snapshot_interval_slots {
+                return;
+            }
+        }
+        match snapshot_utils::package_snapshot(root_bank) {
+            Ok(package) => {
+                if sender.lock().unwrap().send(package).is_err() {
+                    warn!("snapshot packager is gone, no longer packaging snapshots");
+                    self.snapshot_package_sender = None;
+                    return;
+                }
+                self.last_snapshot_slot = Some(root_bank.slot());
+            }
+            Err(e) => warn!(
+                "Failed to package snapshot for {}: {:?}",
+                root_bank.slot(),
+                e
+            ),
+        }
+    }
+
     fn prune_non_root(&mut self, root: u64) {
         let slots: HashSet<u64> = self
             .banks
@@ -232,6 +320,7 @@ impl BankForks {
         snapshot_path: &Option<String>,
     ) -> Option<u64> {
         let path = BankForks::get_snapshot_path(snapshot_path);
+        let account_paths = bank0.rc.account_paths();
         let mut bank_root: Option<u64> = None;
 
         for bank_slot in names.iter().rev() {
@@ -254,7 +343,12 @@ impl BankForks {
                 .map_err(|_| BankForks::get_io_error("deserialize root error"));
             let status_cache: Result<StatusCacheRc, std::io::Error> = deserialize_from(&mut stream)
                 .map_err(|_| BankForks::get_io_error("deserialize bank status cache error"));
-            if bank_root.is_none() && bank0.rc.update_from_stream(&mut stream).is_ok() {
+            if bank_root.is_none()
+                && bank0
+                    .rc
+                    .update_from_stream(&account_paths, &mut stream)
+                    .is_ok()
+            {
                 bank_root = Some(root.unwrap());
             }
             if bank_root.is_some() {
@@ -321,7 +415,7 @@ impl BankForks {
                 entry.ok().and_then(|e| {
                     e.path()
                         .file_name()
-                        .and_then(|n| n.to_str().map(|s| s.parse::<u64>().unwrap()))
+                        .and_then(|n| n.to_str().and_then(|s| s.parse::<u64>().ok()))
                 })
             })
             .collect::<Vec<u64>>();
@@ -352,8 +446,12 @@ impl BankForks {
             banks,
             working_bank,
             root,
+            confirmed_slot: root,
             slots,
             snapshot_path: snapshot_path.clone(),
+            snapshot_package_sender: None,
+            snapshot_interval_slots: snapshot_utils::DEFAULT_SNAPSHOT_INTERVAL_SLOTS,
+            last_snapshot_slot: None,
         })
     }
 }
@@ -381,6 +479,35 @@ mod tests {
         assert_eq!(bank_forks.working_bank().tick_height(), 1);
     }
 
+    #[test]
+    fn test_bank_forks_confirmed_bank() {
+        let GenesisBlockInfo { genesis_block, .. } = create_genesis_block(10_000);
+        let bank = Bank::new(&genesis_block);
+        let mut bank_forks = BankForks::new(0, bank);
+        let bank0 = bank_forks[0].clone();
+        let bank = Bank::new_from_parent(&bank0, &Pubkey::default(), 1);
+        bank_forks.insert(bank);
+        let bank = Bank::new_from_parent(&bank0, &Pubkey::default(), 2);
+        bank_forks.insert(bank);
+        let bank = Bank::new_from_parent(&bank_forks[1], &Pubkey::default(), 3);
+        bank_forks.insert(bank);
+        assert_eq!(bank_forks.confirmed_bank().slot(), 0);
+
+        bank_forks.set_confirmed_slot(2);
+        bank_forks.set_confirmed_slot(1);
+        assert_eq!(bank_forks.confirmed_slot(), 2);
+        assert_eq!(bank_forks.confirmed_bank().slot(), 2);
+        assert_eq!(bank_forks.root_bank().slot(), 0);
+
+        // Rooting the other fork prunes the confirmed bank
+        bank_forks.set_root(1);
+        assert_eq!(bank_forks.root_bank().slot(), 1);
+        assert_eq!(bank_forks.confirmed_bank().slot(), 1);
+
+        bank_forks.set_confirmed_slot(3);
+        assert_eq!(bank_forks.confirmed_bank().slot(), 3);
+    }
+
     #[test]
     fn test_bank_forks_descendants() {
         let GenesisBlockInfo { genesis_block, .. } = create_genesis_block(10_000);
@@ -422,7 +422,7 @@ impl BankingStage {
         // the likelihood of any single thread getting starved and processing old ids.
         // TODO: Banking stage threads should be prioritized to complete faster then this queue
         // expires.
-        let (loaded_accounts, results) =
+        let (loaded_accounts, results, log_messages, pre_balances, compute_units_consumed) =
             bank.load_and_execute_transactions(txs, lock_results, MAX_PROCESSING_AGE);
         let load_execute_time = now.elapsed();
 
@@ -436,7 +436,14 @@ impl BankingStage {
 
         let commit_time = {
             let now = Instant::now();
-            bank.commit_transactions(txs, &loaded_accounts, &results);
+            bank.commit_transactions(
+                txs,
+                &loaded_accounts,
+                &results,
+                &log_messages,
+                &pre_balances,
+                &compute_units_consumed,
+            );
             now.elapsed()
         };
 
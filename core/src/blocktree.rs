@@ -6,28 +6,32 @@ use crate::erasure::{self, Session};
 use crate::packet::{Blob, SharedBlob, BLOB_HEADER_SIZE};
 use crate::result::{Error, Result};
 
-#[cfg(feature = "kvstore")]
 use solana_kvstore as kvstore;
 
 use bincode::deserialize;
 
 use std::collections::HashMap;
 
-#[cfg(not(feature = "kvstore"))]
 use rocksdb;
 
 use solana_metrics::{datapoint_error, datapoint_info};
 
 use solana_sdk::genesis_block::GenesisBlock;
 use solana_sdk::hash::Hash;
-use solana_sdk::signature::{Keypair, KeypairUtil};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, KeypairUtil, Signature};
+use solana_sdk::transaction::Transaction;
+use solana_sdk::transaction_status::TransactionStatusMeta;
 
 use std::borrow::{Borrow, Cow};
 use std::cell::RefCell;
 use std::cmp;
+use std::env;
 use std::fs;
 use std::io;
+use std::path::Path;
 use std::rc::Rc;
+use std::str::FromStr;
 use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError};
 use std::sync::{Arc, RwLock};
 
@@ -35,38 +39,32 @@ pub use self::meta::*;
 pub use self::rooted_slot_iterator::*;
 
 mod db;
+mod dispatch;
+mod kvs;
 mod meta;
+mod rocks;
 mod rooted_slot_iterator;
 
-macro_rules! db_imports {
-    { $mod:ident, $db:ident, $db_path:expr } => {
-        mod $mod;
+use db::columns as cf;
+use dispatch::Dispatch;
 
-        use $mod::$db;
-        use db::columns as cf;
+pub use db::columns;
+pub use db::{Column, TypedColumn};
 
-        pub use db::columns;
+pub type Database = db::Database<Dispatch>;
+pub type Cursor<C> = db::Cursor<Dispatch, C>;
+pub type LedgerColumn<C> = db::LedgerColumn<Dispatch, C>;
+pub type WriteBatch = db::WriteBatch<Dispatch>;
+type BatchProcessor = db::BatchProcessor<Dispatch>;
 
-        pub type Database = db::Database<$db>;
-        pub type Cursor<C> = db::Cursor<$db, C>;
-        pub type LedgerColumn<C> = db::LedgerColumn<$db, C>;
-        pub type WriteBatch = db::WriteBatch<$db>;
-        type BatchProcessor = db::BatchProcessor<$db>;
-
-        pub trait Column: db::Column<$db> {}
-        impl<C: db::Column<$db>> Column for C {}
-
-        pub const BLOCKTREE_DIRECTORY: &str = $db_path;
-    };
-}
-
-#[cfg(not(feature = "kvstore"))]
-db_imports! {rocks, Rocks, "rocksdb"}
-#[cfg(feature = "kvstore")]
-db_imports! {kvs, Kvs, "kvstore"}
+/// Environment variable naming the backend of ledgers that don't have one yet
+pub const BLOCKTREE_BACKEND_ENV: &str = "SOLANA_BLOCKTREE_BACKEND";
 
 pub const MAX_COMPLETED_SLOTS_IN_CHANNEL: usize = 100_000;
 
+/// Number of slots `purge_slots` deletes per write batch
+const PURGE_BATCH_SLOTS: usize = 32;
+
 pub type CompletedSlotsReceiver = Receiver<Vec<u64>>;
 
 #[derive(Debug)]
@@ -74,11 +72,59 @@ pub enum BlocktreeError {
     BlobForIndexExists,
     InvalidBlobData,
     RocksDb(rocksdb::Error),
-    #[cfg(feature = "kvstore")]
     KvsDb(kvstore::Error),
     SlotNotRooted,
 }
 
+/// The database a ledger's `Blocktree` is stored in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlocktreeBackend {
+    RocksDb,
+    KvStore,
+}
+
+impl BlocktreeBackend {
+    /// The directory of the ledger the backend keeps its files in
+    pub fn directory(self) -> &'static str {
+        match self {
+            BlocktreeBackend::RocksDb => "rocksdb",
+            BlocktreeBackend::KvStore => "kvstore",
+        }
+    }
+
+    /// The backend of the `Blocktree` already in `ledger_path`, if there is one
+    pub fn detect(ledger_path: &str) -> Option<BlocktreeBackend> {
+        [BlocktreeBackend::RocksDb, BlocktreeBackend::KvStore]
+            .iter()
+            .cloned()
+            .find(|backend| Path::new(ledger_path).join(backend.directory()).exists())
+    }
+}
+
+/// Taken from `BLOCKTREE_BACKEND_ENV`, or RocksDB if it isn't set
+impl Default for BlocktreeBackend {
+    fn default() -> Self {
+        match env::var(BLOCKTREE_BACKEND_ENV) {
+            Ok(backend) => backend
+                .parse()
+                .unwrap_or_else(|err| panic!("{}: {}", BLOCKTREE_BACKEND_ENV, err)),
+            Err(_) => BlocktreeBackend::RocksDb,
+        }
+    }
+}
+
+impl FromStr for BlocktreeBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "rocksdb" => Ok(BlocktreeBackend::RocksDb),
+            "kvstore" => Ok(BlocktreeBackend::KvStore),
+            _ => Err(format!("unknown blocktree backend: {}", s)),
+        }
+    }
+}
+
 // ledger window
 pub struct Blocktree {
     db: Arc<Database>,
@@ -107,17 +153,27 @@ pub const ERASURE_META_CF: &str = "erasure_meta";
 pub const ORPHANS_CF: &str = "orphans";
 // Column family for root data
 pub const ROOT_CF: &str = "root";
+// Column family for the rooted slot of each transaction signature
+pub const SIGNATURE_SLOT_CF: &str = "signature_slot";
+// Column family for the rooted transaction signatures of each address
+pub const ADDRESS_SIGNATURES_CF: &str = "address_signatures";
+// Column family for the status meta of each rooted transaction
+pub const TRANSACTION_STATUS_CF: &str = "transaction_status";
 
 impl Blocktree {
-    /// Opens a Ledger in directory, provides "infinite" window of blobs
+    /// Opens a Ledger in directory, provides "infinite" window of blobs. A new ledger uses
+    /// the default backend
     pub fn open(ledger_path: &str) -> Result<Blocktree> {
-        use std::path::Path;
+        let backend = BlocktreeBackend::detect(ledger_path).unwrap_or_default();
+        Self::open_with_backend(ledger_path, backend)
+    }
 
+    pub fn open_with_backend(ledger_path: &str, backend: BlocktreeBackend) -> Result<Blocktree> {
         fs::create_dir_all(&ledger_path)?;
-        let ledger_path = Path::new(&ledger_path).join(BLOCKTREE_DIRECTORY);
+        let ledger_path = Path::new(&ledger_path).join(backend.directory());
 
         // Open the database
-        let db = Database::open(&ledger_path)?;
+        let db = Database::new(Dispatch::open(&ledger_path, backend)?);
 
         let batch_processor = unsafe { Arc::new(RwLock::new(db.batch_processor())) };
 
@@ -176,8 +232,13 @@ impl Blocktree {
     pub fn destroy(ledger_path: &str) -> Result<()> {
         // Database::destroy() fails is the path doesn't exist
         fs::create_dir_all(ledger_path)?;
-        let path = std::path::Path::new(ledger_path).join(BLOCKTREE_DIRECTORY);
-        Database::destroy(&path)
+        for backend in &[BlocktreeBackend::RocksDb, BlocktreeBackend::KvStore] {
+            let path = Path::new(ledger_path).join(backend.directory());
+            if path.exists() {
+                Dispatch::destroy(&path, *backend)?;
+            }
+        }
+        Ok(())
     }
 
     pub fn meta(&self, slot: u64) -> Result<Option<SlotMeta>> {
@@ -857,6 +918,216 @@ impl Blocktree {
         Ok(())
     }
 
+    /// Indexes the transactions of `entries`, replayed in `slot`, by signature and by
+    /// address.  Lookups only answer for the slots that are roots, so a slot's transactions
+    /// are indexed as it is replayed rather than read back from the ledger once it is rooted
+    pub fn index_transactions<'a, I>(&self, slot: u64, entries: I) -> Result<()>
+    where
+        I: IntoIterator<Item = &'a Entry>,
+    {
+        unsafe {
+            let mut batch_processor = self.db.batch_processor();
+            let mut write_batch = batch_processor.batch()?;
+            for transaction in entries.into_iter().flat_map(|entry| &entry.transactions) {
+                if let Some(signature) = transaction.signatures.first() {
+                    write_batch.put::<cf::SignatureSlot>((*signature, slot), &true)?;
+                    for address in &transaction.message.account_keys {
+                        write_batch
+                            .put::<cf::AddressSignatures>((*address, slot, *signature), &true)?;
+                    }
+                }
+            }
+
+            batch_processor.write(write_batch)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the entries of `slot`, which must be a root
+    pub fn get_rooted_slot_entries(&self, slot: u64) -> Result<Vec<Entry>> {
+        if !self.is_root(slot) {
+            return Err(Error::BlocktreeError(BlocktreeError::SlotNotRooted));
+        }
+        self.get_slot_entries(slot, 0, None)
+    }
+
+    /// Returns the rooted slot containing the transaction with `signature`
+    pub fn get_transaction_slot(&self, signature: &Signature) -> Result<Option<u64>> {
+        let mut cursor = self.db.cursor::<cf::SignatureSlot>()?;
+        cursor.seek((*signature, 0));
+        while cursor.valid() {
+            match cursor.key() {
+                Some((key_signature, slot)) if key_signature == *signature => {
+                    if self.is_root(slot) {
+                        return Ok(Some(slot));
+                    }
+                }
+                _ => break,
+            }
+            cursor.next();
+        }
+        Ok(None)
+    }
+
+    /// Returns a rooted transaction and the slot it was recorded in
+    pub fn get_confirmed_transaction(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<(u64, Transaction)>> {
+        if let Some(slot) = self.get_transaction_slot(signature)? {
+            let transaction = self
+                .get_rooted_slot_entries(slot)?
+                .into_iter()
+                .flat_map(|entry| entry.transactions)
+                .find(|transaction| transaction.signatures.first() == Some(signature));
+            Ok(transaction.map(|transaction| (slot, transaction)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Records the status metas of the transactions `slot`, a root, committed
+    pub fn write_transaction_status_metas(
+        &self,
+        slot: u64,
+        metas: &[(Signature, TransactionStatusMeta)],
+    ) -> Result<()> {
+        unsafe {
+            let mut batch_processor = self.db.batch_processor();
+            let mut write_batch = batch_processor.batch()?;
+            for (signature, meta) in metas {
+                write_batch.put::<cf::TransactionStatus>((slot, *signature), meta)?;
+            }
+            batch_processor.write(write_batch)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the status meta of the rooted transaction with `signature`
+    pub fn get_transaction_status_meta(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<TransactionStatusMeta>> {
+        if let Some(slot) = self.get_transaction_slot(signature)? {
+            self.db.get::<cf::TransactionStatus>((slot, *signature))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Returns up to `limit` (slot, signature) pairs of rooted transactions that reference
+    /// `address`, newest first, starting after the transaction with signature `before`
+    pub fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before: Option<Signature>,
+        limit: usize,
+    ) -> Result<Vec<(u64, Signature)>> {
+        let mut cursor = self.db.cursor::<cf::AddressSignatures>()?;
+        match before {
+            Some(before) => {
+                let slot = match self.get_transaction_slot(&before)? {
+                    Some(slot) => slot,
+                    None => return Ok(vec![]),
+                };
+                cursor.seek((*address, slot, before));
+                if cursor.key() == Some((*address, slot, before)) {
+                    cursor.next();
+                }
+            }
+            None => cursor.seek((*address, u64::max_value(), Signature::default())),
+        }
+
+        // Transactions of slots that were replayed but never rooted are skipped over
+        let mut signatures = vec![];
+        let mut rooted_slot = None;
+        while cursor.valid() && signatures.len() < limit {
+            match cursor.key() {
+                Some((key_address, slot, signature)) if key_address == *address => {
+                    let is_root = match rooted_slot {
+                        Some((last_slot, is_root)) if last_slot == slot => is_root,
+                        _ => self.is_root(slot),
+                    };
+                    rooted_slot = Some((slot, is_root));
+                    if is_root {
+                        signatures.push((slot, signature));
+                    }
+                }
+                _ => break,
+            }
+            cursor.next();
+        }
+        Ok(signatures)
+    }
+
+    /// Deletes everything stored for the slots `from_slot` through `to_slot`, inclusive,
+    /// including the signature and address indexes of their transactions
+    pub fn purge_slots(&self, from_slot: u64, to_slot: u64) -> Result<()> {
+        let slots: Vec<u64> = self
+            .slot_meta_iterator(from_slot)?
+            .map(|(slot, _)| slot)
+            .take_while(|slot| *slot <= to_slot)
+            .collect();
+        if slots.is_empty() {
+            return self.purge_slot_batch(from_slot, to_slot, &[]);
+        }
+
+        // Purge a bounded batch of slots at a time so writers are never held up for long
+        let mut batch_start = from_slot;
+        let mut batches = slots.chunks(PURGE_BATCH_SLOTS).peekable();
+        while let Some(batch) = batches.next() {
+            let batch_end = if batches.peek().is_some() {
+                batch[batch.len() - 1]
+            } else {
+                to_slot
+            };
+            self.purge_slot_batch(batch_start, batch_end, batch)?;
+            batch_start = batch_end.saturating_add(1);
+        }
+        Ok(())
+    }
+
+    // Purges the slots `from_slot` through `to_slot`, of which `slots` are the ones with a
+    // `SlotMeta`.  Their entries are read before the batch processor is locked
+    fn purge_slot_batch(&self, from_slot: u64, to_slot: u64, slots: &[u64]) -> Result<()> {
+        let mut transactions = vec![];
+        for slot in slots {
+            let entries = self.get_slot_entries(*slot, 0, None)?;
+            for entry in entries {
+                for transaction in entry.transactions {
+                    if let Some(signature) = transaction.signatures.first() {
+                        transactions.push((*slot, *signature, transaction.message.account_keys));
+                    }
+                }
+            }
+        }
+
+        let mut batch_processor = self.batch_processor.write().unwrap();
+        let mut write_batch = batch_processor.batch()?;
+        for (slot, signature, account_keys) in transactions {
+            write_batch.delete::<cf::SignatureSlot>((signature, slot))?;
+            for address in account_keys {
+                write_batch.delete::<cf::AddressSignatures>((address, slot, signature))?;
+            }
+        }
+
+        // The ranges end before the first key of the slot after `to_slot`
+        let end_slot = to_slot.saturating_add(1);
+        write_batch.delete_range::<cf::SlotMeta>(from_slot, end_slot)?;
+        write_batch.delete_range::<cf::Orphans>(from_slot, end_slot)?;
+        write_batch.delete_range::<cf::DeadSlots>(from_slot, end_slot)?;
+        write_batch.delete_range::<cf::Root>(from_slot, end_slot)?;
+        write_batch.delete_range::<cf::Data>((from_slot, 0), (end_slot, 0))?;
+        write_batch.delete_range::<cf::Coding>((from_slot, 0), (end_slot, 0))?;
+        write_batch.delete_range::<cf::ErasureMeta>((from_slot, 0), (end_slot, 0))?;
+        write_batch.delete_range::<cf::TransactionStatus>(
+            (from_slot, Signature::default()),
+            (end_slot, Signature::default()),
+        )?;
+
+        batch_processor.write(write_batch)
+    }
+
     pub fn is_dead(&self, slot: u64) -> bool {
         if let Some(true) = self
             .db
@@ -1558,12 +1829,20 @@ fn slot_has_updates(slot_meta: &SlotMeta, slot_meta_backup: &Option<SlotMeta>) -
 //
 // Returns the blockhash that can be used to append entries with.
 pub fn create_new_ledger(ledger_path: &str, genesis_block: &GenesisBlock) -> Result<Hash> {
+    create_new_ledger_with_backend(ledger_path, genesis_block, BlocktreeBackend::default())
+}
+
+pub fn create_new_ledger_with_backend(
+    ledger_path: &str,
+    genesis_block: &GenesisBlock,
+    backend: BlocktreeBackend,
+) -> Result<Hash> {
     let ticks_per_slot = genesis_block.ticks_per_slot;
     Blocktree::destroy(ledger_path)?;
     genesis_block.write(&ledger_path)?;
 
     // Fill slot 0 with ticks that link back to the genesis_block to bootstrap the ledger.
-    let blocktree = Blocktree::open(ledger_path)?;
+    let blocktree = Blocktree::open_with_backend(ledger_path, backend)?;
     let entries = crate::entry::create_ticks(ticks_per_slot, genesis_block.hash());
     blocktree.write_entries(0, 0, 0, ticks_per_slot, &entries)?;
 
@@ -1608,7 +1887,6 @@ macro_rules! get_tmp_ledger_path {
 }
 
 pub fn get_tmp_ledger_path(name: &str) -> String {
-    use std::env;
     let out_dir = env::var("OUT_DIR").unwrap_or_else(|_| "target".to_string());
     let keypair = Keypair::new();
 
@@ -1672,6 +1950,7 @@ pub mod tests {
     use rand::Rng;
     use solana_sdk::hash::Hash;
     use solana_sdk::pubkey::Pubkey;
+    use solana_sdk::system_transaction;
     use std::cmp::min;
     use std::collections::HashSet;
     use std::iter::once;
@@ -3179,6 +3458,294 @@ pub mod tests {
         Blocktree::destroy(&blocktree_path).expect("Expected successful database destruction");
     }
 
+    #[test]
+    fn test_get_confirmed_transaction() {
+        let blocktree_path = get_tmp_ledger_path!();
+        let blocktree = Blocktree::open(&blocktree_path).unwrap();
+        let (blobs, entries) = make_slot_entries(1, 0, 5);
+        blocktree.write_blobs(blobs).unwrap();
+        let signature = entries[2].transactions[0].signatures[0];
+
+        // The transactions are replayed in slot 1 and in a fork, slot 2, but nothing is found
+        // until a slot holding them is rooted
+        blocktree.index_transactions(1, &entries).unwrap();
+        blocktree.index_transactions(2, &entries).unwrap();
+        assert!(blocktree.get_rooted_slot_entries(1).is_err());
+        assert_eq!(blocktree.get_transaction_slot(&signature).unwrap(), None);
+        assert_eq!(
+            blocktree.get_confirmed_transaction(&signature).unwrap(),
+            None
+        );
+
+        blocktree.set_roots(&[0, 1]).unwrap();
+        assert_eq!(blocktree.get_rooted_slot_entries(1).unwrap(), entries);
+        for entry in &entries {
+            let transaction = &entry.transactions[0];
+            assert_eq!(
+                blocktree
+                    .get_confirmed_transaction(&transaction.signatures[0])
+                    .unwrap(),
+                Some((1, transaction.clone()))
+            );
+        }
+        assert_eq!(
+            blocktree
+                .get_confirmed_transaction(&Signature::default())
+                .unwrap(),
+            None
+        );
+
+        drop(blocktree);
+        Blocktree::destroy(&blocktree_path).expect("Expected successful database destruction");
+    }
+
+    #[test]
+    fn test_get_signatures_for_address() {
+        // The address index is kept by every backend
+        for backend in &[BlocktreeBackend::RocksDb, BlocktreeBackend::KvStore] {
+            let blocktree_path = get_tmp_ledger_path!();
+            let blocktree = Blocktree::open_with_backend(&blocktree_path, *backend).unwrap();
+            let keypair = Keypair::new();
+            let address = Pubkey::new_rand();
+
+            let mut expected = vec![];
+            for slot in 1..4 {
+                let mut entries = vec![];
+                for i in 0..3 {
+                    let to = if i == 2 { Pubkey::new_rand() } else { address };
+                    let lamports = slot * 10 + i;
+                    let tx = system_transaction::transfer(&keypair, &to, lamports, Hash::default());
+                    if to == address {
+                        expected.push((slot, tx.signatures[0]));
+                    }
+                    entries.push(Entry::new(&Hash::default(), 1, vec![tx]));
+                }
+                let blobs = entries_to_blobs(&entries, slot, slot - 1, true);
+                blocktree.write_blobs(blobs).unwrap();
+                blocktree.index_transactions(slot, &entries).unwrap();
+            }
+            // A fork that is never rooted
+            let tx = system_transaction::transfer(&keypair, &address, 1, Hash::default());
+            let entries = vec![Entry::new(&Hash::default(), 1, vec![tx])];
+            blocktree.index_transactions(4, &entries).unwrap();
+            // Newest slot first, then in key order within a slot
+            expected.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.as_ref().cmp(b.1.as_ref())));
+
+            assert!(blocktree
+                .get_signatures_for_address(&address, None, 10)
+                .unwrap()
+                .is_empty());
+
+            blocktree.set_roots(&[1, 2, 3]).unwrap();
+            assert_eq!(
+                blocktree
+                    .get_signatures_for_address(&address, None, 10)
+                    .unwrap(),
+                expected
+            );
+            assert_eq!(
+                blocktree
+                    .get_signatures_for_address(&keypair.pubkey(), None, 10)
+                    .unwrap()
+                    .len(),
+                9
+            );
+
+            // Page through the history
+            let first_page = blocktree
+                .get_signatures_for_address(&address, None, 4)
+                .unwrap();
+            assert_eq!(first_page, expected[..4].to_vec());
+            let second_page = blocktree
+                .get_signatures_for_address(&address, Some(first_page[3].1), 4)
+                .unwrap();
+            assert_eq!(second_page, expected[4..].to_vec());
+            assert!(blocktree
+                .get_signatures_for_address(&address, Some(Signature::new(&[1; 64])), 4)
+                .unwrap()
+                .is_empty());
+
+            drop(blocktree);
+            Blocktree::destroy(&blocktree_path).expect("Expected successful database destruction");
+        }
+    }
+
+    #[test]
+    fn test_transaction_status_metas() {
+        let blocktree_path = get_tmp_ledger_path!();
+        let blocktree = Blocktree::open(&blocktree_path).unwrap();
+        let keypair = Keypair::new();
+
+        let tx = system_transaction::transfer(&keypair, &Pubkey::new_rand(), 1, Hash::default());
+        let signature = tx.signatures[0];
+        let entries = vec![Entry::new(&Hash::default(), 1, vec![tx])];
+        let blobs = entries_to_blobs(&entries, 1, 0, true);
+        blocktree.write_blobs(blobs).unwrap();
+        blocktree.index_transactions(1, &entries).unwrap();
+
+        let meta = TransactionStatusMeta {
+            status: Ok(()),
+            fee: 2,
+            pre_balances: vec![10, 0, 1],
+            post_balances: vec![7, 1, 1],
+            log_messages: vec!["logged".to_string()],
+            compute_units_consumed: 1_000,
+        };
+        blocktree
+            .write_transaction_status_metas(1, &[(signature, meta.clone())])
+            .unwrap();
+        // Metas are found through the signature index of rooted slots
+        assert_eq!(
+            blocktree.get_transaction_status_meta(&signature).unwrap(),
+            None
+        );
+
+        blocktree.set_roots(&[1]).unwrap();
+        assert_eq!(
+            blocktree.get_transaction_status_meta(&signature).unwrap(),
+            Some(meta)
+        );
+        assert_eq!(
+            blocktree
+                .get_transaction_status_meta(&Signature::new(&[1; 64]))
+                .unwrap(),
+            None
+        );
+
+        drop(blocktree);
+        Blocktree::destroy(&blocktree_path).expect("Expected successful database destruction");
+    }
+
+    #[test]
+    fn test_purge_slots() {
+        let blocktree_path = get_tmp_ledger_path!();
+        let blocktree = Blocktree::open(&blocktree_path).unwrap();
+        let keypair = Keypair::new();
+        let address = Pubkey::new_rand();
+
+        let mut signatures = vec![];
+        for slot in 1..5 {
+            let tx = system_transaction::transfer(&keypair, &address, slot, Hash::default());
+            signatures.push(tx.signatures[0]);
+            let entries = vec![Entry::new(&Hash::default(), 1, vec![tx])];
+            let blobs = entries_to_blobs(&entries, slot, slot - 1, true);
+            blocktree.write_blobs(blobs).unwrap();
+            blocktree.index_transactions(slot, &entries).unwrap();
+            blocktree
+                .put_coding_blob_bytes_raw(slot, 0, &[0; BLOB_HEADER_SIZE])
+                .unwrap();
+            blocktree
+                .erasure_meta_cf
+                .put((slot, 0), &ErasureMeta::new(0))
+                .unwrap();
+            blocktree.orphans_cf.put(slot, &true).unwrap();
+            blocktree.set_dead_slot(slot).unwrap();
+            let meta = TransactionStatusMeta {
+                status: Ok(()),
+                fee: 0,
+                pre_balances: vec![],
+                post_balances: vec![],
+                log_messages: vec![],
+                compute_units_consumed: 0,
+            };
+            blocktree
+                .write_transaction_status_metas(slot, &[(signatures[slot as usize - 1], meta)])
+                .unwrap();
+        }
+        blocktree.set_roots(&[1, 2, 3, 4]).unwrap();
+
+        blocktree.purge_slots(1, 2).unwrap();
+        for slot in 1..5 {
+            let purged = slot <= 2;
+            let signature = &signatures[slot as usize - 1];
+            assert_eq!(blocktree.meta(slot).unwrap().is_none(), purged);
+            assert_eq!(
+                blocktree
+                    .get_slot_entries(slot, 0, None)
+                    .unwrap()
+                    .is_empty(),
+                purged
+            );
+            assert_eq!(
+                blocktree.get_coding_blob_bytes(slot, 0).unwrap().is_none(),
+                purged
+            );
+            assert_eq!(blocktree.erasure_meta(slot, 0).unwrap().is_none(), purged);
+            assert_eq!(blocktree.orphan(slot).unwrap().is_none(), purged);
+            assert_eq!(blocktree.is_dead(slot), !purged);
+            assert_eq!(blocktree.is_root(slot), !purged);
+            assert_eq!(
+                blocktree.get_transaction_slot(signature).unwrap().is_none(),
+                purged
+            );
+            assert_eq!(
+                blocktree
+                    .db
+                    .get::<cf::SignatureSlot>((*signature, slot))
+                    .unwrap()
+                    .is_none(),
+                purged
+            );
+            assert_eq!(
+                blocktree
+                    .db
+                    .get::<cf::TransactionStatus>((slot, *signature))
+                    .unwrap()
+                    .is_none(),
+                purged
+            );
+        }
+        assert_eq!(
+            blocktree
+                .get_signatures_for_address(&address, None, 10)
+                .unwrap(),
+            vec![(4, signatures[3]), (3, signatures[2])]
+        );
+
+        // Purging an empty range is a no-op
+        blocktree.purge_slots(10, 20).unwrap();
+        assert!(blocktree.meta(4).unwrap().is_some());
+
+        // Ranges spanning several batches are purged batch by batch
+        let num_slots = 3 * PURGE_BATCH_SLOTS as u64;
+        let (blobs, _) = make_many_slot_entries(10, num_slots, 1);
+        blocktree.write_blobs(blobs).unwrap();
+        blocktree.purge_slots(5, num_slots).unwrap();
+        assert!(blocktree.meta(4).unwrap().is_some());
+        for slot in 10..10 + num_slots {
+            assert_eq!(blocktree.meta(slot).unwrap().is_none(), slot <= num_slots);
+        }
+
+        drop(blocktree);
+        Blocktree::destroy(&blocktree_path).expect("Expected successful database destruction");
+    }
+
+    #[test]
+    fn test_open_detects_backend() {
+        let blocktree_path = get_tmp_ledger_path!();
+        assert_eq!(BlocktreeBackend::detect(&blocktree_path), None);
+        assert_eq!("kvstore".parse(), Ok(BlocktreeBackend::KvStore));
+        assert!("leveldb".parse::<BlocktreeBackend>().is_err());
+
+        // A ledger is re-opened with the backend it was created with, whatever the default
+        {
+            let blocktree =
+                Blocktree::open_with_backend(&blocktree_path, BlocktreeBackend::KvStore).unwrap();
+            blocktree.set_roots(&[1]).unwrap();
+        }
+        assert_eq!(
+            BlocktreeBackend::detect(&blocktree_path),
+            Some(BlocktreeBackend::KvStore)
+        );
+        let blocktree = Blocktree::open(&blocktree_path).unwrap();
+        assert!(blocktree.is_root(1));
+        assert!(!Path::new(&blocktree_path).join("rocksdb").exists());
+
+        drop(blocktree);
+        Blocktree::destroy(&blocktree_path).expect("Expected successful database destruction");
+        assert_eq!(BlocktreeBackend::detect(&blocktree_path), None);
+    }
+
     mod erasure {
         use super::*;
         use crate::blocktree::meta::ErasureMetaStatus;
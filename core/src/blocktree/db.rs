@@ -1,5 +1,11 @@
 use crate::result::{Error, Result};
 
+use byteorder::{BigEndian, ByteOrder};
+
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction_status::TransactionStatusMeta;
+
 use bincode::{deserialize, serialize};
 
 use serde::de::DeserializeOwned;
@@ -8,7 +14,6 @@ use serde::Serialize;
 use std::borrow::Borrow;
 use std::collections::HashMap;
 use std::marker::PhantomData;
-use std::path::Path;
 use std::sync::Arc;
 
 pub mod columns {
@@ -39,49 +44,72 @@ pub mod columns {
     #[derive(Debug)]
     /// The root column
     pub struct Root;
+
+    #[derive(Debug)]
+    /// The transaction signature to replayed slots column
+    pub struct SignatureSlot;
+
+    #[derive(Debug)]
+    /// The address to replayed transaction signatures column
+    pub struct AddressSignatures;
+
+    #[derive(Debug)]
+    /// The rooted transaction status meta column
+    pub struct TransactionStatus;
 }
 
 pub trait Backend: Sized + Send + Sync {
-    type Key: ?Sized + ToOwned<Owned = Self::OwnedKey>;
-    type OwnedKey: Borrow<Self::Key>;
     type ColumnFamily: Clone;
     type Cursor: DbCursor<Self>;
-    type Iter: Iterator<Item = (Box<Self::Key>, Box<[u8]>)>;
+    type Iter: Iterator<Item = (Box<[u8]>, Box<[u8]>)>;
     type WriteBatch: IWriteBatch<Self>;
     type Error: Into<Error>;
 
-    fn open(path: &Path) -> Result<Self>;
-
     fn columns(&self) -> Vec<&'static str>;
 
-    fn destroy(path: &Path) -> Result<()>;
-
     fn cf_handle(&self, cf: &str) -> Self::ColumnFamily;
 
-    fn get_cf(&self, cf: Self::ColumnFamily, key: &Self::Key) -> Result<Option<Vec<u8>>>;
+    fn get_cf(&self, cf: Self::ColumnFamily, key: &[u8]) -> Result<Option<Vec<u8>>>;
 
-    fn put_cf(&self, cf: Self::ColumnFamily, key: &Self::Key, value: &[u8]) -> Result<()>;
+    fn put_cf(&self, cf: Self::ColumnFamily, key: &[u8], value: &[u8]) -> Result<()>;
 
-    fn delete_cf(&self, cf: Self::ColumnFamily, key: &Self::Key) -> Result<()>;
+    fn delete_cf(&self, cf: Self::ColumnFamily, key: &[u8]) -> Result<()>;
 
-    fn iterator_cf(&self, cf: Self::ColumnFamily, from: Option<&Self::Key>) -> Result<Self::Iter>;
+    fn iterator_cf(&self, cf: Self::ColumnFamily, from: Option<&[u8]>) -> Result<Self::Iter>;
 
     fn raw_iterator_cf(&self, cf: Self::ColumnFamily) -> Result<Self::Cursor>;
 
     fn write(&self, batch: Self::WriteBatch) -> Result<()>;
 
     fn batch(&self) -> Result<Self::WriteBatch>;
+
+    /// Queues the deletion of every key of `cf` from `from` up to, but not including, `to`
+    fn delete_range_cf(
+        &self,
+        batch: &mut Self::WriteBatch,
+        cf: Self::ColumnFamily,
+        from: &[u8],
+        to: &[u8],
+    ) -> Result<()> {
+        let mut cursor = self.raw_iterator_cf(cf.clone())?;
+        cursor.seek(from);
+        while cursor.valid() {
+            match cursor.key() {
+                Some(ref key) if key.as_slice() < to => batch.delete_cf(cf.clone(), key)?,
+                _ => break,
+            }
+            cursor.next();
+        }
+        Ok(())
+    }
 }
 
-pub trait Column<B>
-where
-    B: Backend,
-{
+pub trait Column {
     const NAME: &'static str;
     type Index;
 
-    fn key(index: Self::Index) -> B::OwnedKey;
-    fn index(key: &B::Key) -> Self::Index;
+    fn key(index: Self::Index) -> Vec<u8>;
+    fn index(key: &[u8]) -> Self::Index;
 }
 
 pub trait DbCursor<B>
@@ -90,13 +118,13 @@ where
 {
     fn valid(&self) -> bool;
 
-    fn seek(&mut self, key: &B::Key);
+    fn seek(&mut self, key: &[u8]);
 
     fn seek_to_first(&mut self);
 
     fn next(&mut self);
 
-    fn key(&self) -> Option<B::OwnedKey>;
+    fn key(&self) -> Option<Vec<u8>>;
 
     fn value(&self) -> Option<Vec<u8>>;
 }
@@ -105,14 +133,11 @@ pub trait IWriteBatch<B>
 where
     B: Backend,
 {
-    fn put_cf(&mut self, cf: B::ColumnFamily, key: &B::Key, value: &[u8]) -> Result<()>;
-    fn delete_cf(&mut self, cf: B::ColumnFamily, key: &B::Key) -> Result<()>;
+    fn put_cf(&mut self, cf: B::ColumnFamily, key: &[u8], value: &[u8]) -> Result<()>;
+    fn delete_cf(&mut self, cf: B::ColumnFamily, key: &[u8]) -> Result<()>;
 }
 
-pub trait TypedColumn<B>: Column<B>
-where
-    B: Backend,
-{
+pub trait TypedColumn: Column {
     type Type: Serialize + DeserializeOwned;
 }
 
@@ -136,7 +161,7 @@ where
 pub struct Cursor<B, C>
 where
     B: Backend,
-    C: Column<B>,
+    C: Column,
 {
     db_cursor: B::Cursor,
     column: PhantomData<C>,
@@ -147,7 +172,7 @@ where
 pub struct LedgerColumn<B, C>
 where
     B: Backend,
-    C: Column<B>,
+    C: Column,
 {
     backend: Arc<B>,
     column: PhantomData<C>,
@@ -159,7 +184,7 @@ where
     B: Backend,
 {
     write_batch: B::WriteBatch,
-    backend: PhantomData<B>,
+    backend: Arc<B>,
     map: HashMap<&'static str, B::ColumnFamily>,
 }
 
@@ -167,21 +192,15 @@ impl<B> Database<B>
 where
     B: Backend,
 {
-    pub fn open(path: &Path) -> Result<Self> {
-        let backend = Arc::new(B::open(path)?);
-
-        Ok(Database { backend })
-    }
-
-    pub fn destroy(path: &Path) -> Result<()> {
-        B::destroy(path)?;
-
-        Ok(())
+    pub fn new(backend: B) -> Self {
+        Database {
+            backend: Arc::new(backend),
+        }
     }
 
     pub fn get_bytes<C>(&self, key: C::Index) -> Result<Option<Vec<u8>>>
     where
-        C: Column<B>,
+        C: Column,
     {
         self.backend
             .get_cf(self.cf_handle::<C>(), C::key(key).borrow())
@@ -189,7 +208,7 @@ where
 
     pub fn put_bytes<C>(&self, key: C::Index, data: &[u8]) -> Result<()>
     where
-        C: Column<B>,
+        C: Column,
     {
         self.backend
             .put_cf(self.cf_handle::<C>(), C::key(key).borrow(), data)
@@ -197,7 +216,7 @@ where
 
     pub fn delete<C>(&self, key: C::Index) -> Result<()>
     where
-        C: Column<B>,
+        C: Column,
     {
         self.backend
             .delete_cf(self.cf_handle::<C>(), C::key(key).borrow())
@@ -205,7 +224,7 @@ where
 
     pub fn get<C>(&self, key: C::Index) -> Result<Option<C::Type>>
     where
-        C: TypedColumn<B>,
+        C: TypedColumn,
     {
         if let Some(serialized_value) = self
             .backend
@@ -221,7 +240,7 @@ where
 
     pub fn put<C>(&self, key: C::Index, value: &C::Type) -> Result<()>
     where
-        C: TypedColumn<B>,
+        C: TypedColumn,
     {
         let serialized_value = serialize(value)?;
 
@@ -234,7 +253,7 @@ where
 
     pub fn cursor<C>(&self) -> Result<Cursor<B, C>>
     where
-        C: Column<B>,
+        C: Column,
     {
         let db_cursor = self.backend.raw_iterator_cf(self.cf_handle::<C>())?;
 
@@ -250,7 +269,7 @@ where
         start_from: Option<C::Index>,
     ) -> Result<impl Iterator<Item = (C::Index, Box<[u8]>)>>
     where
-        C: Column<B>,
+        C: Column,
     {
         let iter = {
             if let Some(index) = start_from {
@@ -268,14 +287,14 @@ where
     #[inline]
     pub fn cf_handle<C>(&self) -> B::ColumnFamily
     where
-        C: Column<B>,
+        C: Column,
     {
         self.backend.cf_handle(C::NAME).clone()
     }
 
     pub fn column<C>(&self) -> LedgerColumn<B, C>
     where
-        C: Column<B>,
+        C: Column,
     {
         LedgerColumn {
             backend: Arc::clone(&self.backend),
@@ -310,7 +329,7 @@ where
 
         Ok(WriteBatch {
             write_batch: db_write_batch,
-            backend: PhantomData,
+            backend: Arc::clone(&self.backend),
             map,
         })
     }
@@ -323,7 +342,7 @@ where
 impl<B, C> Cursor<B, C>
 where
     B: Backend,
-    C: Column<B>,
+    C: Column,
 {
     pub fn valid(&self) -> bool {
         self.db_cursor.valid()
@@ -357,7 +376,7 @@ where
 impl<B, C> Cursor<B, C>
 where
     B: Backend,
-    C: TypedColumn<B>,
+    C: TypedColumn,
 {
     pub fn value(&self) -> Option<C::Type> {
         if let Some(bytes) = self.db_cursor.value() {
@@ -372,7 +391,7 @@ where
 impl<B, C> LedgerColumn<B, C>
 where
     B: Backend,
-    C: Column<B>,
+    C: Column,
 {
     pub fn get_bytes(&self, key: C::Index) -> Result<Option<Vec<u8>>> {
         self.backend.get_cf(self.handle(), C::key(key).borrow())
@@ -429,7 +448,7 @@ where
 impl<B, C> LedgerColumn<B, C>
 where
     B: Backend,
-    C: TypedColumn<B>,
+    C: TypedColumn,
 {
     pub fn get(&self, key: C::Index) -> Result<Option<C::Type>> {
         if let Some(serialized_value) = self.backend.get_cf(self.handle(), C::key(key).borrow())? {
@@ -453,24 +472,234 @@ impl<B> WriteBatch<B>
 where
     B: Backend,
 {
-    pub fn put_bytes<C: Column<B>>(&mut self, key: C::Index, bytes: &[u8]) -> Result<()> {
+    pub fn put_bytes<C: Column>(&mut self, key: C::Index, bytes: &[u8]) -> Result<()> {
         self.write_batch
             .put_cf(self.get_cf::<C>(), C::key(key).borrow(), bytes)
     }
 
-    pub fn delete<C: Column<B>>(&mut self, key: C::Index) -> Result<()> {
+    pub fn delete<C: Column>(&mut self, key: C::Index) -> Result<()> {
         self.write_batch
             .delete_cf(self.get_cf::<C>(), C::key(key).borrow())
     }
 
-    pub fn put<C: TypedColumn<B>>(&mut self, key: C::Index, value: &C::Type) -> Result<()> {
+    pub fn put<C: TypedColumn>(&mut self, key: C::Index, value: &C::Type) -> Result<()> {
         let serialized_value = serialize(&value)?;
         self.write_batch
             .put_cf(self.get_cf::<C>(), C::key(key).borrow(), &serialized_value)
     }
 
+    /// Deletes the keys of column `C` from `from` up to, but not including, `to`
+    pub fn delete_range<C: Column>(&mut self, from: C::Index, to: C::Index) -> Result<()> {
+        let cf = self.get_cf::<C>();
+        self.backend.delete_range_cf(
+            &mut self.write_batch,
+            cf,
+            C::key(from).borrow(),
+            C::key(to).borrow(),
+        )
+    }
+
     #[inline]
-    fn get_cf<C: Column<B>>(&self) -> B::ColumnFamily {
+    fn get_cf<C: Column>(&self) -> B::ColumnFamily {
         self.map[C::NAME].clone()
     }
 }
+
+impl Column for columns::Coding {
+    const NAME: &'static str = super::ERASURE_CF;
+    type Index = (u64, u64);
+
+    fn key(index: (u64, u64)) -> Vec<u8> {
+        columns::Data::key(index)
+    }
+
+    fn index(key: &[u8]) -> (u64, u64) {
+        columns::Data::index(key)
+    }
+}
+
+impl Column for columns::Data {
+    const NAME: &'static str = super::DATA_CF;
+    type Index = (u64, u64);
+
+    fn key((slot, index): (u64, u64)) -> Vec<u8> {
+        let mut key = vec![0; 16];
+        BigEndian::write_u64(&mut key[..8], slot);
+        BigEndian::write_u64(&mut key[8..16], index);
+        key
+    }
+
+    fn index(key: &[u8]) -> (u64, u64) {
+        let slot = BigEndian::read_u64(&key[..8]);
+        let index = BigEndian::read_u64(&key[8..16]);
+        (slot, index)
+    }
+}
+
+impl Column for columns::DeadSlots {
+    const NAME: &'static str = super::DEAD_SLOTS_CF;
+    type Index = u64;
+
+    fn key(slot: u64) -> Vec<u8> {
+        let mut key = vec![0; 8];
+        BigEndian::write_u64(&mut key[..], slot);
+        key
+    }
+
+    fn index(key: &[u8]) -> u64 {
+        BigEndian::read_u64(&key[..8])
+    }
+}
+
+impl TypedColumn for columns::DeadSlots {
+    type Type = bool;
+}
+
+impl Column for columns::Orphans {
+    const NAME: &'static str = super::ORPHANS_CF;
+    type Index = u64;
+
+    fn key(slot: u64) -> Vec<u8> {
+        let mut key = vec![0; 8];
+        BigEndian::write_u64(&mut key[..], slot);
+        key
+    }
+
+    fn index(key: &[u8]) -> u64 {
+        BigEndian::read_u64(&key[..8])
+    }
+}
+
+impl TypedColumn for columns::Orphans {
+    type Type = bool;
+}
+
+impl Column for columns::Root {
+    const NAME: &'static str = super::ROOT_CF;
+    type Index = u64;
+
+    fn key(slot: u64) -> Vec<u8> {
+        let mut key = vec![0; 8];
+        BigEndian::write_u64(&mut key[..], slot);
+        key
+    }
+
+    fn index(key: &[u8]) -> u64 {
+        BigEndian::read_u64(&key[..8])
+    }
+}
+
+impl TypedColumn for columns::Root {
+    type Type = bool;
+}
+
+impl Column for columns::SignatureSlot {
+    const NAME: &'static str = super::SIGNATURE_SLOT_CF;
+    type Index = (Signature, u64);
+
+    fn key((signature, slot): (Signature, u64)) -> Vec<u8> {
+        let mut key = vec![0; 64 + 8];
+        key[..64].copy_from_slice(signature.as_ref());
+        BigEndian::write_u64(&mut key[64..], slot);
+        key
+    }
+
+    fn index(key: &[u8]) -> (Signature, u64) {
+        let signature = Signature::new(&key[..64]);
+        let slot = BigEndian::read_u64(&key[64..72]);
+        (signature, slot)
+    }
+}
+
+impl TypedColumn for columns::SignatureSlot {
+    type Type = bool;
+}
+
+impl Column for columns::AddressSignatures {
+    const NAME: &'static str = super::ADDRESS_SIGNATURES_CF;
+    type Index = (Pubkey, u64, Signature);
+
+    // Slots are stored inverted so that iteration returns the newest slots first
+    fn key((address, slot, signature): (Pubkey, u64, Signature)) -> Vec<u8> {
+        let mut key = vec![0; 32 + 8 + 64];
+        key[..32].copy_from_slice(address.as_ref());
+        BigEndian::write_u64(&mut key[32..40], !slot);
+        key[40..].copy_from_slice(signature.as_ref());
+        key
+    }
+
+    fn index(key: &[u8]) -> (Pubkey, u64, Signature) {
+        let address = Pubkey::new(&key[..32]);
+        let slot = !BigEndian::read_u64(&key[32..40]);
+        let signature = Signature::new(&key[40..104]);
+        (address, slot, signature)
+    }
+}
+
+impl TypedColumn for columns::AddressSignatures {
+    type Type = bool;
+}
+
+impl Column for columns::TransactionStatus {
+    const NAME: &'static str = super::TRANSACTION_STATUS_CF;
+    type Index = (u64, Signature);
+
+    fn key((slot, signature): (u64, Signature)) -> Vec<u8> {
+        let mut key = vec![0; 8 + 64];
+        BigEndian::write_u64(&mut key[..8], slot);
+        key[8..].copy_from_slice(signature.as_ref());
+        key
+    }
+
+    fn index(key: &[u8]) -> (u64, Signature) {
+        let slot = BigEndian::read_u64(&key[..8]);
+        let signature = Signature::new(&key[8..72]);
+        (slot, signature)
+    }
+}
+
+impl TypedColumn for columns::TransactionStatus {
+    type Type = TransactionStatusMeta;
+}
+
+impl Column for columns::SlotMeta {
+    const NAME: &'static str = super::META_CF;
+    type Index = u64;
+
+    fn key(slot: u64) -> Vec<u8> {
+        let mut key = vec![0; 8];
+        BigEndian::write_u64(&mut key[..], slot);
+        key
+    }
+
+    fn index(key: &[u8]) -> u64 {
+        BigEndian::read_u64(&key[..8])
+    }
+}
+
+impl TypedColumn for columns::SlotMeta {
+    type Type = super::SlotMeta;
+}
+
+impl Column for columns::ErasureMeta {
+    const NAME: &'static str = super::ERASURE_META_CF;
+    type Index = (u64, u64);
+
+    fn index(key: &[u8]) -> (u64, u64) {
+        let slot = BigEndian::read_u64(&key[..8]);
+        let set_index = BigEndian::read_u64(&key[8..]);
+
+        (slot, set_index)
+    }
+
+    fn key((slot, set_index): (u64, u64)) -> Vec<u8> {
+        let mut key = vec![0; 16];
+        BigEndian::write_u64(&mut key[..8], slot);
+        BigEndian::write_u64(&mut key[8..], set_index);
+        key
+    }
+}
+
+impl TypedColumn for columns::ErasureMeta {
+    type Type = super::ErasureMeta;
+}
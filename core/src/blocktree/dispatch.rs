@@ -0,0 +1,245 @@
+use crate::blocktree::db::{Backend, DbCursor, IWriteBatch};
+use crate::blocktree::kvs::{self, Kvs, KvsCursor, KvsIter, KvsWriteBatch};
+use crate::blocktree::rocks::Rocks;
+use crate::blocktree::BlocktreeBackend;
+use crate::result::Result;
+
+use rocksdb::{self, DBIterator, DBRawIterator, WriteBatch as RWriteBatch};
+
+use std::path::Path;
+
+/// `Backend` over whichever database a ledger was opened with.
+///
+/// Every handle, cursor and batch comes from the backend it is used with, so the arms of
+/// each match below only ever pair up values of the same backend.
+#[derive(Debug)]
+pub enum Dispatch {
+    Rocks(Rocks),
+    Kvs(Kvs),
+}
+
+#[derive(Clone)]
+pub enum ColumnFamily {
+    Rocks(rocksdb::ColumnFamily),
+    Kvs(kvs::ColumnFamily),
+}
+
+pub enum Cursor {
+    Rocks(DBRawIterator),
+    Kvs(KvsCursor),
+}
+
+pub enum Iter {
+    Rocks(DBIterator),
+    Kvs(KvsIter),
+}
+
+pub enum WriteBatch {
+    Rocks(RWriteBatch),
+    Kvs(KvsWriteBatch),
+}
+
+impl Dispatch {
+    pub fn open(path: &Path, backend: BlocktreeBackend) -> Result<Dispatch> {
+        match backend {
+            BlocktreeBackend::RocksDb => Ok(Dispatch::Rocks(Rocks::open(path)?)),
+            BlocktreeBackend::KvStore => Ok(Dispatch::Kvs(Kvs::open(path)?)),
+        }
+    }
+
+    pub fn destroy(path: &Path, backend: BlocktreeBackend) -> Result<()> {
+        match backend {
+            BlocktreeBackend::RocksDb => Rocks::destroy(path),
+            BlocktreeBackend::KvStore => Kvs::destroy(path),
+        }
+    }
+}
+
+impl Backend for Dispatch {
+    type ColumnFamily = ColumnFamily;
+    type Cursor = Cursor;
+    type Iter = Iter;
+    type WriteBatch = WriteBatch;
+    type Error = crate::result::Error;
+
+    fn columns(&self) -> Vec<&'static str> {
+        match self {
+            Dispatch::Rocks(rocks) => rocks.columns(),
+            Dispatch::Kvs(kvs) => kvs.columns(),
+        }
+    }
+
+    fn cf_handle(&self, cf: &str) -> ColumnFamily {
+        match self {
+            Dispatch::Rocks(rocks) => ColumnFamily::Rocks(rocks.cf_handle(cf)),
+            Dispatch::Kvs(kvs) => ColumnFamily::Kvs(kvs.cf_handle(cf)),
+        }
+    }
+
+    fn get_cf(&self, cf: ColumnFamily, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        match (self, cf) {
+            (Dispatch::Rocks(rocks), ColumnFamily::Rocks(cf)) => rocks.get_cf(cf, key),
+            (Dispatch::Kvs(kvs), ColumnFamily::Kvs(cf)) => kvs.get_cf(cf, key),
+            _ => unreachable!(),
+        }
+    }
+
+    fn put_cf(&self, cf: ColumnFamily, key: &[u8], value: &[u8]) -> Result<()> {
+        match (self, cf) {
+            (Dispatch::Rocks(rocks), ColumnFamily::Rocks(cf)) => rocks.put_cf(cf, key, value),
+            (Dispatch::Kvs(kvs), ColumnFamily::Kvs(cf)) => kvs.put_cf(cf, key, value),
+            _ => unreachable!(),
+        }
+    }
+
+    fn delete_cf(&self, cf: ColumnFamily, key: &[u8]) -> Result<()> {
+        match (self, cf) {
+            (Dispatch::Rocks(rocks), ColumnFamily::Rocks(cf)) => rocks.delete_cf(cf, key),
+            (Dispatch::Kvs(kvs), ColumnFamily::Kvs(cf)) => kvs.delete_cf(cf, key),
+            _ => unreachable!(),
+        }
+    }
+
+    fn iterator_cf(&self, cf: ColumnFamily, from: Option<&[u8]>) -> Result<Iter> {
+        match (self, cf) {
+            (Dispatch::Rocks(rocks), ColumnFamily::Rocks(cf)) => {
+                Ok(Iter::Rocks(rocks.iterator_cf(cf, from)?))
+            }
+            (Dispatch::Kvs(kvs), ColumnFamily::Kvs(cf)) => {
+                Ok(Iter::Kvs(kvs.iterator_cf(cf, from)?))
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn raw_iterator_cf(&self, cf: ColumnFamily) -> Result<Cursor> {
+        match (self, cf) {
+            (Dispatch::Rocks(rocks), ColumnFamily::Rocks(cf)) => {
+                Ok(Cursor::Rocks(rocks.raw_iterator_cf(cf)?))
+            }
+            (Dispatch::Kvs(kvs), ColumnFamily::Kvs(cf)) => {
+                Ok(Cursor::Kvs(kvs.raw_iterator_cf(cf)?))
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn write(&self, batch: WriteBatch) -> Result<()> {
+        match batch {
+            WriteBatch::Rocks(batch) => match self {
+                Dispatch::Rocks(rocks) => rocks.write(batch),
+                _ => unreachable!(),
+            },
+            WriteBatch::Kvs(batch) => match self {
+                Dispatch::Kvs(kvs) => kvs.write(batch),
+                _ => unreachable!(),
+            },
+        }
+    }
+
+    fn batch(&self) -> Result<WriteBatch> {
+        match self {
+            Dispatch::Rocks(rocks) => Ok(WriteBatch::Rocks(rocks.batch()?)),
+            Dispatch::Kvs(kvs) => Ok(WriteBatch::Kvs(kvs.batch()?)),
+        }
+    }
+
+    fn delete_range_cf(
+        &self,
+        batch: &mut WriteBatch,
+        cf: ColumnFamily,
+        from: &[u8],
+        to: &[u8],
+    ) -> Result<()> {
+        match (self, batch, cf) {
+            (Dispatch::Rocks(rocks), WriteBatch::Rocks(batch), ColumnFamily::Rocks(cf)) => {
+                rocks.delete_range_cf(batch, cf, from, to)
+            }
+            (Dispatch::Kvs(kvs), WriteBatch::Kvs(batch), ColumnFamily::Kvs(cf)) => {
+                kvs.delete_range_cf(batch, cf, from, to)
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl DbCursor<Dispatch> for Cursor {
+    fn valid(&self) -> bool {
+        match self {
+            Cursor::Rocks(cursor) => DbCursor::<Rocks>::valid(cursor),
+            Cursor::Kvs(cursor) => DbCursor::<Kvs>::valid(cursor),
+        }
+    }
+
+    fn seek(&mut self, key: &[u8]) {
+        match self {
+            Cursor::Rocks(cursor) => DbCursor::<Rocks>::seek(cursor, key),
+            Cursor::Kvs(cursor) => DbCursor::<Kvs>::seek(cursor, key),
+        }
+    }
+
+    fn seek_to_first(&mut self) {
+        match self {
+            Cursor::Rocks(cursor) => DbCursor::<Rocks>::seek_to_first(cursor),
+            Cursor::Kvs(cursor) => DbCursor::<Kvs>::seek_to_first(cursor),
+        }
+    }
+
+    fn next(&mut self) {
+        match self {
+            Cursor::Rocks(cursor) => DbCursor::<Rocks>::next(cursor),
+            Cursor::Kvs(cursor) => DbCursor::<Kvs>::next(cursor),
+        }
+    }
+
+    fn key(&self) -> Option<Vec<u8>> {
+        match self {
+            Cursor::Rocks(cursor) => DbCursor::<Rocks>::key(cursor),
+            Cursor::Kvs(cursor) => DbCursor::<Kvs>::key(cursor),
+        }
+    }
+
+    fn value(&self) -> Option<Vec<u8>> {
+        match self {
+            Cursor::Rocks(cursor) => DbCursor::<Rocks>::value(cursor),
+            Cursor::Kvs(cursor) => DbCursor::<Kvs>::value(cursor),
+        }
+    }
+}
+
+impl Iterator for Iter {
+    type Item = (Box<[u8]>, Box<[u8]>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Iter::Rocks(iter) => iter.next(),
+            Iter::Kvs(iter) => iter.next(),
+        }
+    }
+}
+
+impl IWriteBatch<Dispatch> for WriteBatch {
+    fn put_cf(&mut self, cf: ColumnFamily, key: &[u8], value: &[u8]) -> Result<()> {
+        match (self, cf) {
+            (WriteBatch::Rocks(batch), ColumnFamily::Rocks(cf)) => {
+                IWriteBatch::<Rocks>::put_cf(batch, cf, key, value)
+            }
+            (WriteBatch::Kvs(batch), ColumnFamily::Kvs(cf)) => {
+                IWriteBatch::<Kvs>::put_cf(batch, cf, key, value)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn delete_cf(&mut self, cf: ColumnFamily, key: &[u8]) -> Result<()> {
+        match (self, cf) {
+            (WriteBatch::Rocks(batch), ColumnFamily::Rocks(cf)) => {
+                IWriteBatch::<Rocks>::delete_cf(batch, cf, key)
+            }
+            (WriteBatch::Kvs(batch), ColumnFamily::Kvs(cf)) => {
+                IWriteBatch::<Kvs>::delete_cf(batch, cf, key)
+            }
+            _ => unreachable!(),
+        }
+    }
+}
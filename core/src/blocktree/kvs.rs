@@ -1,264 +1,582 @@
-use crate::blocktree::db::columns as cf;
-use crate::blocktree::db::{Backend, Column, DbCursor, IWriteBatch, TypedColumn};
+use crate::blocktree::db::{Backend, DbCursor, IWriteBatch};
 use crate::blocktree::BlocktreeError;
 use crate::result::{Error, Result};
 use byteorder::{BigEndian, ByteOrder};
-use solana_kvstore::{self as kvstore, Key, KvStore};
+use solana_kvstore::{self as kvstore, Key, KvStore, WriteBatchConfig};
+use std::cmp;
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, VecDeque};
 use std::path::Path;
+use std::sync::Arc;
+
+/// Index of a column in `COLUMNS`, stored in the first byte of each of its keys
+pub type ColumnFamily = u8;
+
+const KEY_LEN: usize = 24;
+/// Bytes of a column's key that fit in a store key after the column byte
+const KEY_MATERIAL_LEN: usize = KEY_LEN - 1;
+
+/// How the keys of a column map onto the store's fixed-size keys
+struct ColumnLayout {
+    name: &'static str,
+    /// For keys too long to be stored whole, the length of each of the key's fields and how
+    /// many of its leading bytes are kept. Fields are shortened rather than the key
+    /// truncated so that a key's later fields still order its entries
+    fields: &'static [(usize, usize)],
+    /// Length of the key prefix a cursor reads at a time, e.g. a slot
+    window: usize,
+}
 
-type ColumnFamily = u64;
-
+/// The columns, in the order their indexes are stored. New columns must be appended
+const COLUMNS: &[ColumnLayout] = &[
+    ColumnLayout {
+        name: super::META_CF,
+        fields: &[],
+        window: 8,
+    },
+    ColumnLayout {
+        name: super::DATA_CF,
+        fields: &[],
+        window: 8,
+    },
+    ColumnLayout {
+        name: super::DEAD_SLOTS_CF,
+        fields: &[],
+        window: 8,
+    },
+    ColumnLayout {
+        name: super::ERASURE_CF,
+        fields: &[],
+        window: 8,
+    },
+    ColumnLayout {
+        name: super::ERASURE_META_CF,
+        fields: &[],
+        window: 8,
+    },
+    ColumnLayout {
+        name: super::ORPHANS_CF,
+        fields: &[],
+        window: 8,
+    },
+    ColumnLayout {
+        name: super::ROOT_CF,
+        fields: &[],
+        window: 8,
+    },
+    ColumnLayout {
+        name: super::SIGNATURE_SLOT_CF,
+        fields: &[(64, 15), (8, 8)],
+        window: 15,
+    },
+    ColumnLayout {
+        name: super::ADDRESS_SIGNATURES_CF,
+        fields: &[(32, 12), (8, 8), (64, 3)],
+        window: 20,
+    },
+    ColumnLayout {
+        name: super::TRANSACTION_STATUS_CF,
+        fields: &[(8, 8), (64, 15)],
+        window: 8,
+    },
+];
+
+/// A key and its value
+type Row = (Vec<u8>, Vec<u8>);
+
+/// `Backend` over a `KvStore`, which holds every column in one keyspace.
+///
+/// Keys that don't fit in a store key are shortened, so several keys may share one. Each
+/// store value is therefore a bucket of the full keys stored under it and their values,
+/// sorted by key; in practice a bucket holds a single row.
 #[derive(Debug)]
-pub struct Kvs(KvStore);
+pub struct Kvs(Arc<KvStore>);
+
+pub struct KvsCursor {
+    store: Arc<KvStore>,
+    cf: ColumnFamily,
+    /// The rows of the current window from the cursor on
+    rows: VecDeque<Row>,
+    /// Where the next window starts, or `None` past the end of the column
+    next_window: Option<Key>,
+}
 
-/// Dummy struct for now
-#[derive(Debug, Clone, Copy)]
-pub struct Dummy;
+pub struct KvsIter(KvsCursor);
 
-impl Backend for Kvs {
-    type Key = Key;
-    type OwnedKey = Key;
-    type ColumnFamily = ColumnFamily;
-    type Cursor = Dummy;
-    type Iter = Dummy;
-    type WriteBatch = Dummy;
-    type Error = kvstore::Error;
+pub struct KvsWriteBatch {
+    store: Arc<KvStore>,
+    /// The buckets the batch writes; an empty bucket deletes its key
+    buckets: BTreeMap<Key, Vec<Row>>,
+}
 
-    fn open(_path: &Path) -> Result<Kvs> {
-        unimplemented!()
+impl Kvs {
+    pub fn open(path: &Path) -> Result<Kvs> {
+        Ok(Kvs(Arc::new(KvStore::open_default(path)?)))
     }
 
-    fn columns(&self) -> Vec<&'static str> {
-        unimplemented!()
-    }
+    pub fn destroy(path: &Path) -> Result<()> {
+        KvStore::destroy(path)?;
 
-    fn destroy(_path: &Path) -> Result<()> {
-        unimplemented!()
+        Ok(())
     }
+}
 
-    fn cf_handle(&self, _cf: &str) -> ColumnFamily {
-        unimplemented!()
+impl Backend for Kvs {
+    type ColumnFamily = ColumnFamily;
+    type Cursor = KvsCursor;
+    type Iter = KvsIter;
+    type WriteBatch = KvsWriteBatch;
+    type Error = kvstore::Error;
+
+    fn columns(&self) -> Vec<&'static str> {
+        COLUMNS.iter().map(|layout| layout.name).collect()
+    }
+
+    fn cf_handle(&self, cf: &str) -> ColumnFamily {
+        COLUMNS
+            .iter()
+            .position(|layout| layout.name == cf)
+            .expect("should never get an unknown column") as ColumnFamily
+    }
+
+    fn get_cf(&self, cf: ColumnFamily, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        let bucket = read_bucket(&self.0, &store_key(cf, key))?;
+        Ok(bucket
+            .into_iter()
+            .find(|(row_key, _)| row_key.as_slice() == key)
+            .map(|(_, value)| value))
+    }
+
+    fn put_cf(&self, cf: ColumnFamily, key: &[u8], value: &[u8]) -> Result<()> {
+        let store_key = store_key(cf, key);
+        let mut bucket = if is_shortened(key) {
+            read_bucket(&self.0, &store_key)?
+        } else {
+            vec![]
+        };
+        insert_row(&mut bucket, key, value);
+        self.0.put(&store_key, &serialize_bucket(&bucket))?;
+        Ok(())
+    }
+
+    fn delete_cf(&self, cf: ColumnFamily, key: &[u8]) -> Result<()> {
+        let store_key = store_key(cf, key);
+        if !is_shortened(key) {
+            self.0.delete(&store_key)?;
+            return Ok(());
+        }
+        let mut bucket = read_bucket(&self.0, &store_key)?;
+        remove_row(&mut bucket, key);
+        if bucket.is_empty() {
+            self.0.delete(&store_key)?;
+        } else {
+            self.0.put(&store_key, &serialize_bucket(&bucket))?;
+        }
+        Ok(())
+    }
+
+    fn iterator_cf(&self, cf: ColumnFamily, start_from: Option<&[u8]>) -> Result<KvsIter> {
+        let mut cursor = self.raw_iterator_cf(cf)?;
+        match start_from {
+            Some(key) => cursor.seek(key),
+            None => cursor.seek_to_first(),
+        }
+        Ok(KvsIter(cursor))
+    }
+
+    fn raw_iterator_cf(&self, cf: ColumnFamily) -> Result<KvsCursor> {
+        Ok(KvsCursor {
+            store: Arc::clone(&self.0),
+            cf,
+            rows: VecDeque::new(),
+            next_window: None,
+        })
+    }
+
+    fn batch(&self) -> Result<KvsWriteBatch> {
+        Ok(KvsWriteBatch {
+            store: Arc::clone(&self.0),
+            buckets: BTreeMap::new(),
+        })
+    }
+
+    fn write(&self, batch: KvsWriteBatch) -> Result<()> {
+        let mut store_batch = self.0.batch(WriteBatchConfig {
+            log_writes: true,
+            max_size: usize::max_value(),
+        });
+        for (store_key, bucket) in batch.buckets {
+            if bucket.is_empty() {
+                store_batch.delete(&store_key);
+            } else {
+                store_batch.put(&store_key, &serialize_bucket(&bucket))?;
+            }
+        }
+        self.0.commit(store_batch)?;
+        Ok(())
     }
+}
 
-    fn get_cf(&self, _cf: ColumnFamily, _key: &Key) -> Result<Option<Vec<u8>>> {
-        unimplemented!()
+impl KvsCursor {
+    /// Reads the rows of the windows from `start` on until one isn't empty, leaving out those
+    /// keyed before `seek_key`. Windows with nothing in them are skipped over in ever larger
+    /// steps, so a sparse column doesn't cost a read per missing prefix
+    fn load(&mut self, mut start: Key, seek_key: Option<&[u8]>) {
+        let window = COLUMNS[self.cf as usize].window;
+        let mut step_bits = 0;
+        self.rows.clear();
+        loop {
+            let mut next_window = start;
+            for byte in &mut next_window.0[window + 1..] {
+                *byte = 0;
+            }
+            let (end, next_window) = if add_power_of_two(&mut next_window.0[1..=window], step_bits)
+            {
+                let mut end = next_window;
+                decrement(&mut end.0[1..]);
+                (end, Some(next_window))
+            } else {
+                (column_end(self.cf), None)
+            };
+
+            let buckets = match self.store.range(start..=end) {
+                Ok(buckets) => buckets,
+                Err(err) => {
+                    warn!("kvstore cursor read failed: {:?}", err);
+                    self.next_window = None;
+                    return;
+                }
+            };
+            for (_, bytes) in buckets {
+                match deserialize_bucket(&bytes) {
+                    Ok(bucket) => self.rows.extend(bucket.into_iter().filter(|(key, _)| {
+                        seek_key.map_or(true, |seek_key| key.as_slice() >= seek_key)
+                    })),
+                    Err(err) => warn!("kvstore cursor read a corrupt bucket: {:?}", err),
+                }
+            }
+
+            self.next_window = next_window;
+            match next_window {
+                Some(next_window) if self.rows.is_empty() => {
+                    start = next_window;
+                    step_bits += 1;
+                }
+                _ => return,
+            }
+        }
     }
+}
 
-    fn put_cf(&self, _cf: ColumnFamily, _key: &Key, _value: &[u8]) -> Result<()> {
-        unimplemented!()
+impl DbCursor<Kvs> for KvsCursor {
+    fn valid(&self) -> bool {
+        !self.rows.is_empty()
     }
 
-    fn delete_cf(&self, _cf: ColumnFamily, _key: &Key) -> Result<()> {
-        unimplemented!()
+    fn seek(&mut self, key: &[u8]) {
+        self.load(store_key(self.cf, key), Some(key));
     }
 
-    fn iterator_cf(&self, _cf: ColumnFamily) -> Result<Dummy> {
-        unimplemented!()
+    fn seek_to_first(&mut self) {
+        self.load(column_start(self.cf), None);
     }
 
-    fn raw_iterator_cf(&self, _cf: ColumnFamily) -> Result<Dummy> {
-        unimplemented!()
+    fn next(&mut self) {
+        self.rows.pop_front();
+        if self.rows.is_empty() {
+            if let Some(next_window) = self.next_window.take() {
+                self.load(next_window, None);
+            }
+        }
     }
 
-    fn batch(&self) -> Result<Dummy> {
-        unimplemented!()
+    fn key(&self) -> Option<Vec<u8>> {
+        self.rows.front().map(|(key, _)| key.clone())
     }
 
-    fn write(&self, _batch: Dummy) -> Result<()> {
-        unimplemented!()
+    fn value(&self) -> Option<Vec<u8>> {
+        self.rows.front().map(|(_, value)| value.clone())
     }
 }
 
-impl Column<Kvs> for cf::Coding {
-    const NAME: &'static str = super::ERASURE_CF;
-    type Index = (u64, u64);
+impl Iterator for KvsIter {
+    type Item = (Box<[u8]>, Box<[u8]>);
 
-    fn key(index: (u64, u64)) -> Key {
-        cf::Data::key(index)
+    fn next(&mut self) -> Option<Self::Item> {
+        let row = self.0.rows.front().cloned();
+        if row.is_some() {
+            self.0.next();
+        }
+        row.map(|(key, value)| (key.into_boxed_slice(), value.into_boxed_slice()))
     }
+}
 
-    fn index(key: &Key) -> (u64, u64) {
-        cf::Data::index(key)
+impl KvsWriteBatch {
+    fn bucket(&mut self, store_key: Key, key: &[u8]) -> Result<&mut Vec<Row>> {
+        match self.buckets.entry(store_key) {
+            Entry::Occupied(entry) => Ok(entry.into_mut()),
+            Entry::Vacant(entry) => {
+                let bucket = if is_shortened(key) {
+                    read_bucket(&self.store, &store_key)?
+                } else {
+                    vec![]
+                };
+                Ok(entry.insert(bucket))
+            }
+        }
     }
 }
 
-impl Column<Kvs> for cf::Data {
-    const NAME: &'static str = super::DATA_CF;
-    type Index = (u64, u64);
-
-    fn key((slot, index): (u64, u64)) -> Key {
-        let mut key = Key::default();
-        BigEndian::write_u64(&mut key.0[8..16], slot);
-        BigEndian::write_u64(&mut key.0[16..], index);
-        key
+impl IWriteBatch<Kvs> for KvsWriteBatch {
+    fn put_cf(&mut self, cf: ColumnFamily, key: &[u8], value: &[u8]) -> Result<()> {
+        let bucket = self.bucket(store_key(cf, key), key)?;
+        insert_row(bucket, key, value);
+        Ok(())
     }
 
-    fn index(key: &Key) -> (u64, u64) {
-        let slot = BigEndian::read_u64(&key.0[8..16]);
-        let index = BigEndian::read_u64(&key.0[16..]);
-        (slot, index)
+    fn delete_cf(&mut self, cf: ColumnFamily, key: &[u8]) -> Result<()> {
+        let bucket = self.bucket(store_key(cf, key), key)?;
+        remove_row(bucket, key);
+        Ok(())
     }
 }
 
-impl Column<Kvs> for cf::DeadSlots {
-    const NAME: &'static str = super::DEAD_SLOTS;
-    type Index = u64;
-
-    fn key(slot: u64) -> Key {
-        let mut key = Key::default();
-        BigEndian::write_u64(&mut key.0[8..16], slot);
-        key
-    }
-
-    fn index(key: &Key) -> u64 {
-        BigEndian::read_u64(&key.0[8..16])
+impl std::convert::From<kvstore::Error> for Error {
+    fn from(e: kvstore::Error) -> Error {
+        Error::BlocktreeError(BlocktreeError::KvsDb(e))
     }
 }
 
-impl TypedColumn<Kvs> for cf::Root {
-    type Type = bool;
+fn is_shortened(key: &[u8]) -> bool {
+    key.len() > KEY_MATERIAL_LEN
 }
 
-impl Column<Kvs> for cf::Orphans {
-    const NAME: &'static str = super::ORPHANS_CF;
-    type Index = u64;
-
-    fn key(slot: u64) -> Key {
-        let mut key = Key::default();
-        BigEndian::write_u64(&mut key.0[8..16], slot);
-        key
-    }
-
-    fn index(key: &Key) -> u64 {
-        BigEndian::read_u64(&key.0[8..16])
-    }
+/// The store key of `key` in column `cf`
+fn store_key(cf: ColumnFamily, key: &[u8]) -> Key {
+    let mut store_key = Key::default();
+    store_key.0[0] = cf;
+    let material = &mut store_key.0[1..];
+    let fields = COLUMNS[cf as usize].fields;
+    if is_shortened(key) && !fields.is_empty() {
+        let (mut from, mut to) = (0, 0);
+        for (field_len, kept) in fields {
+            let kept = cmp::min(*kept, key.len().saturating_sub(from));
+            material[to..to + kept].copy_from_slice(&key[from..from + kept]);
+            from += field_len;
+            to += kept;
+        }
+    } else {
+        let len = cmp::min(key.len(), KEY_MATERIAL_LEN);
+        material[..len].copy_from_slice(&key[..len]);
+    }
+    store_key
 }
 
-impl TypedColumn<Kvs> for cf::Orphans {
-    type Type = bool;
+fn column_start(cf: ColumnFamily) -> Key {
+    let mut key = Key::default();
+    key.0[0] = cf;
+    key
 }
 
-impl Column<Kvs> for cf::Root {
-    const NAME: &'static str = super::ROOT_CF;
-    type Index = u64;
-
-    fn key(slot: u64) -> Key {
-        let mut key = Key::default();
-        BigEndian::write_u64(&mut key.0[8..16], slot);
-        key
-    }
-
-    fn index(key: &Key) -> u64 {
-        BigEndian::read_u64(&key.0[8..16])
-    }
+fn column_end(cf: ColumnFamily) -> Key {
+    let mut key = Key([0xff; KEY_LEN]);
+    key.0[0] = cf;
+    key
 }
 
-impl TypedColumn<Kvs> for cf::Root {
-    type Type = bool;
+/// Adds `2^bit` to `prefix`, read as a big-endian number, returning false if it overflows
+fn add_power_of_two(prefix: &mut [u8], bit: usize) -> bool {
+    let len = prefix.len();
+    if bit >= len * 8 {
+        return false;
+    }
+    let mut carry = 1u16 << (bit % 8);
+    for byte in prefix[..len - bit / 8].iter_mut().rev() {
+        let sum = u16::from(*byte) + carry;
+        *byte = sum as u8;
+        carry = sum >> 8;
+        if carry == 0 {
+            return true;
+        }
+    }
+    false
 }
 
-impl Column<Kvs> for cf::SlotMeta {
-    const NAME: &'static str = super::META_CF;
-    type Index = u64;
-
-    fn key(slot: u64) -> Key {
-        let mut key = Key::default();
-        BigEndian::write_u64(&mut key.0[8..16], slot);
-        key
-    }
-
-    fn index(key: &Key) -> u64 {
-        BigEndian::read_u64(&key.0[8..16])
+/// Subtracts one from `bytes`, read as a big-endian number that isn't zero
+fn decrement(bytes: &mut [u8]) {
+    for byte in bytes.iter_mut().rev() {
+        let (difference, borrow) = byte.overflowing_sub(1);
+        *byte = difference;
+        if !borrow {
+            return;
+        }
     }
 }
 
-impl Column<Kvs> for cf::SlotMeta {
-    const NAME: &'static str = super::META_CF;
-    type Index = u64;
-
-    fn key(slot: u64) -> Key {
-        let mut key = Key::default();
-        BigEndian::write_u64(&mut key.0[8..16], slot);
-        key
-    }
-
-    fn index(key: &Key) -> u64 {
-        BigEndian::read_u64(&key.0[8..16])
+fn read_bucket(store: &KvStore, store_key: &Key) -> Result<Vec<Row>> {
+    match store.get(store_key)? {
+        Some(bytes) => deserialize_bucket(&bytes),
+        None => Ok(vec![]),
     }
 }
 
-impl TypedColumn<Kvs> for cf::SlotMeta {
-    type Type = super::SlotMeta;
-}
-
-impl Column<Kvs> for cf::ErasureMeta {
-    const NAME: &'static str = super::ERASURE_META_CF;
-    type Index = (u64, u64);
-
-    fn key((slot, set_index): (u64, u64)) -> Key {
-        let mut key = Key::default();
-        BigEndian::write_u64(&mut key.0[8..16], slot);
-        BigEndian::write_u64(&mut key.0[16..], set_index);
-        key
-    }
-
-    fn index(key: &Key) -> (u64, u64) {
-        let slot = BigEndian::read_u64(&key.0[8..16]);
-        let set_index = BigEndian::read_u64(&key.0[16..]);
-        (slot, set_index)
+fn insert_row(bucket: &mut Vec<Row>, key: &[u8], value: &[u8]) {
+    match bucket.binary_search_by(|(row_key, _)| row_key.as_slice().cmp(key)) {
+        Ok(i) => bucket[i].1 = value.to_vec(),
+        Err(i) => bucket.insert(i, (key.to_vec(), value.to_vec())),
     }
 }
 
-impl TypedColumn<Kvs> for cf::ErasureMeta {
-    type Type = super::ErasureMeta;
-}
-
-impl DbCursor<Kvs> for Dummy {
-    fn valid(&self) -> bool {
-        unimplemented!()
-    }
-
-    fn seek(&mut self, _key: &Key) {
-        unimplemented!()
-    }
-
-    fn seek_to_first(&mut self) {
-        unimplemented!()
-    }
-
-    fn next(&mut self) {
-        unimplemented!()
-    }
-
-    fn key(&self) -> Option<Key> {
-        unimplemented!()
-    }
-
-    fn value(&self) -> Option<Vec<u8>> {
-        unimplemented!()
+fn remove_row(bucket: &mut Vec<Row>, key: &[u8]) {
+    if let Ok(i) = bucket.binary_search_by(|(row_key, _)| row_key.as_slice().cmp(key)) {
+        bucket.remove(i);
     }
 }
 
-impl IWriteBatch<Kvs> for Dummy {
-    fn put_cf(&mut self, _cf: ColumnFamily, _key: &Key, _value: &[u8]) -> Result<()> {
-        unimplemented!()
-    }
-
-    fn delete_cf(&mut self, _cf: ColumnFamily, _key: &Key) -> Result<()> {
-        unimplemented!()
-    }
+/// Each row is written as the length of its key, the key, the length of its value and the
+/// value, with big-endian `u32` lengths
+fn serialize_bucket(bucket: &[Row]) -> Vec<u8> {
+    let len = bucket
+        .iter()
+        .map(|(key, value)| 8 + key.len() + value.len())
+        .sum();
+    let mut bytes = Vec::with_capacity(len);
+    for (key, value) in bucket {
+        for field in &[key, value] {
+            let mut field_len = [0; 4];
+            BigEndian::write_u32(&mut field_len, field.len() as u32);
+            bytes.extend_from_slice(&field_len);
+            bytes.extend_from_slice(field);
+        }
+    }
+    bytes
 }
 
-impl Iterator for Dummy {
-    type Item = (Box<Key>, Box<[u8]>);
-
-    fn next(&mut self) -> Option<Self::Item> {
-        unimplemented!()
-    }
+fn deserialize_bucket(mut bytes: &[u8]) -> Result<Vec<Row>> {
+    let next_field = |bytes: &mut &[u8]| -> Result<Vec<u8>> {
+        if bytes.len() < 4 {
+            return Err(Error::BlocktreeError(BlocktreeError::InvalidBlobData));
+        }
+        let len = BigEndian::read_u32(&bytes[..4]) as usize;
+        if bytes.len() < 4 + len {
+            return Err(Error::BlocktreeError(BlocktreeError::InvalidBlobData));
+        }
+        let field = bytes[4..4 + len].to_vec();
+        *bytes = &bytes[4 + len..];
+        Ok(field)
+    };
+    let mut bucket = vec![];
+    while !bytes.is_empty() {
+        let key = next_field(&mut bytes)?;
+        let value = next_field(&mut bytes)?;
+        bucket.push((key, value));
+    }
+    Ok(bucket)
 }
 
-impl std::convert::From<kvstore::Error> for Error {
-    fn from(e: kvstore::Error) -> Error {
-        Error::BlocktreeError(BlocktreeError::KvsDb(e))
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocktree::get_tmp_ledger_path;
+
+    #[test]
+    fn test_store_key_order() {
+        let cf = 8; // Address signatures
+        let key = |address: u8, slot: u64, signature: u8| {
+            let mut key = vec![address; 32];
+            let mut slot_bytes = [0; 8];
+            BigEndian::write_u64(&mut slot_bytes, slot);
+            key.extend_from_slice(&slot_bytes);
+            key.extend_from_slice(&[signature; 64]);
+            key
+        };
+        assert!(store_key(cf, &key(1, 2, 3)) < store_key(cf, &key(1, 2, 4)));
+        assert!(store_key(cf, &key(1, 2, 9)) < store_key(cf, &key(1, 3, 0)));
+        assert!(store_key(cf, &key(1, 9, 9)) < store_key(cf, &key(2, 0, 0)));
+        assert!(store_key(cf - 1, &key(9, 9, 9)) < store_key(cf, &key(0, 0, 0)));
+    }
+
+    #[test]
+    fn test_add_power_of_two() {
+        let mut prefix = [0, 0xff];
+        assert!(add_power_of_two(&mut prefix, 0));
+        assert_eq!(prefix, [1, 0]);
+        assert!(add_power_of_two(&mut prefix, 9));
+        assert_eq!(prefix, [3, 0]);
+        assert!(!add_power_of_two(&mut [0xff, 0xff], 3));
+        assert!(!add_power_of_two(&mut [0, 0], 16));
+
+        let mut bytes = [1, 0, 0];
+        decrement(&mut bytes);
+        assert_eq!(bytes, [0, 0xff, 0xff]);
+    }
+
+    #[test]
+    fn test_shared_store_keys() {
+        let path = get_tmp_ledger_path("kvs");
+        let kvs = Kvs::open(Path::new(&path)).unwrap();
+        let cf = kvs.cf_handle(super::super::SIGNATURE_SLOT_CF);
+
+        // Signatures that only differ past the bytes the store keys keep share a bucket
+        let keys: Vec<Vec<u8>> = (0..3u8)
+            .map(|i| {
+                let mut key = vec![7; 64];
+                key[63] = i;
+                key
+            })
+            .collect();
+        assert_eq!(store_key(cf, &keys[0]), store_key(cf, &keys[2]));
+        kvs.put_cf(cf, &keys[2], &[2]).unwrap();
+        kvs.put_cf(cf, &keys[0], &[0]).unwrap();
+        let mut batch = kvs.batch().unwrap();
+        batch.put_cf(cf, &keys[1], &[1]).unwrap();
+        kvs.write(batch).unwrap();
+        for (i, key) in keys.iter().enumerate() {
+            assert_eq!(kvs.get_cf(cf, key).unwrap(), Some(vec![i as u8]));
+        }
+        let rows: Vec<_> = kvs.iterator_cf(cf, None).unwrap().collect();
+        assert_eq!(rows.len(), 3);
+        assert!(rows.windows(2).all(|pair| pair[0].0 < pair[1].0));
+
+        kvs.delete_cf(cf, &keys[1]).unwrap();
+        assert_eq!(kvs.get_cf(cf, &keys[1]).unwrap(), None);
+        assert_eq!(kvs.get_cf(cf, &keys[2]).unwrap(), Some(vec![2]));
+
+        drop(kvs);
+        Kvs::destroy(Path::new(&path)).unwrap();
+    }
+
+    #[test]
+    fn test_cursor_skips_sparse_windows() {
+        let path = get_tmp_ledger_path("kvs");
+        let kvs = Kvs::open(Path::new(&path)).unwrap();
+        let cf = kvs.cf_handle(super::super::META_CF);
+        let slots = [0, 1, 1000, 1 << 40, u64::max_value()];
+        for slot in &slots {
+            let mut key = [0; 8];
+            BigEndian::write_u64(&mut key, *slot);
+            kvs.put_cf(cf, &key, &[1]).unwrap();
+        }
+
+        let mut cursor = kvs.raw_iterator_cf(cf).unwrap();
+        cursor.seek(&[0, 0, 0, 0, 0, 0, 0, 2]);
+        let mut found = vec![];
+        while cursor.valid() {
+            found.push(BigEndian::read_u64(&cursor.key().unwrap()));
+            cursor.next();
+        }
+        assert_eq!(found, &slots[2..]);
+
+        // Seeking past the last key of a column with a wide window ends the cursor
+        let cf = kvs.cf_handle(super::super::ADDRESS_SIGNATURES_CF);
+        kvs.put_cf(cf, &[1; 104], &[1]).unwrap();
+        cursor = kvs.raw_iterator_cf(cf).unwrap();
+        cursor.seek(&[2; 104]);
+        assert!(!cursor.valid());
+        cursor.seek(&[0; 104]);
+        assert_eq!(cursor.key(), Some(vec![1; 104]));
+
+        drop(kvs);
+        Kvs::destroy(Path::new(&path)).unwrap();
     }
 }
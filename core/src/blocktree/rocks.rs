@@ -1,10 +1,7 @@
-use crate::blocktree::db::columns as cf;
-use crate::blocktree::db::{Backend, Column, DbCursor, IWriteBatch, TypedColumn};
+use crate::blocktree::db::{Backend, Column, DbCursor, IWriteBatch};
 use crate::blocktree::BlocktreeError;
 use crate::result::{Error, Result};
 
-use byteorder::{BigEndian, ByteOrder};
-
 use rocksdb::{
     self, ColumnFamily, ColumnFamilyDescriptor, DBIterator, DBRawIterator, Direction, IteratorMode,
     Options, WriteBatch as RWriteBatch, DB,
@@ -20,17 +17,12 @@ const MAX_WRITE_BUFFER_SIZE: usize = 512 * 1024 * 1024;
 #[derive(Debug)]
 pub struct Rocks(rocksdb::DB);
 
-impl Backend for Rocks {
-    type Key = [u8];
-    type OwnedKey = Vec<u8>;
-    type ColumnFamily = ColumnFamily;
-    type Cursor = DBRawIterator;
-    type Iter = DBIterator;
-    type WriteBatch = RWriteBatch;
-    type Error = rocksdb::Error;
-
-    fn open(path: &Path) -> Result<Rocks> {
-        use crate::blocktree::db::columns::{Coding, Data, DeadSlots, ErasureMeta, Orphans, Root, SlotMeta};
+impl Rocks {
+    pub fn open(path: &Path) -> Result<Rocks> {
+        use crate::blocktree::db::columns::{
+            AddressSignatures, Coding, Data, DeadSlots, ErasureMeta, Orphans, Root, SignatureSlot,
+            SlotMeta, TransactionStatus,
+        };
 
         fs::create_dir_all(&path)?;
 
@@ -40,12 +32,19 @@ impl Backend for Rocks {
         // Column family names
         let meta_cf_descriptor = ColumnFamilyDescriptor::new(SlotMeta::NAME, get_cf_options());
         let data_cf_descriptor = ColumnFamilyDescriptor::new(Data::NAME, get_cf_options());
-        let dead_slots_cf_descriptor = ColumnFamilyDescriptor::new(DeadSlots::NAME, get_cf_options());
+        let dead_slots_cf_descriptor =
+            ColumnFamilyDescriptor::new(DeadSlots::NAME, get_cf_options());
         let erasure_cf_descriptor = ColumnFamilyDescriptor::new(Coding::NAME, get_cf_options());
         let erasure_meta_cf_descriptor =
             ColumnFamilyDescriptor::new(ErasureMeta::NAME, get_cf_options());
         let orphans_cf_descriptor = ColumnFamilyDescriptor::new(Orphans::NAME, get_cf_options());
         let root_cf_descriptor = ColumnFamilyDescriptor::new(Root::NAME, get_cf_options());
+        let signature_slot_cf_descriptor =
+            ColumnFamilyDescriptor::new(SignatureSlot::NAME, get_cf_options());
+        let address_signatures_cf_descriptor =
+            ColumnFamilyDescriptor::new(AddressSignatures::NAME, get_cf_options());
+        let transaction_status_cf_descriptor =
+            ColumnFamilyDescriptor::new(TransactionStatus::NAME, get_cf_options());
 
         let cfs = vec![
             meta_cf_descriptor,
@@ -55,6 +54,9 @@ impl Backend for Rocks {
             erasure_meta_cf_descriptor,
             orphans_cf_descriptor,
             root_cf_descriptor,
+            signature_slot_cf_descriptor,
+            address_signatures_cf_descriptor,
+            transaction_status_cf_descriptor,
         ];
 
         // Open the database
@@ -63,26 +65,40 @@ impl Backend for Rocks {
         Ok(db)
     }
 
+    pub fn destroy(path: &Path) -> Result<()> {
+        DB::destroy(&Options::default(), path)?;
+
+        Ok(())
+    }
+}
+
+impl Backend for Rocks {
+    type ColumnFamily = ColumnFamily;
+    type Cursor = DBRawIterator;
+    type Iter = DBIterator;
+    type WriteBatch = RWriteBatch;
+    type Error = rocksdb::Error;
+
     fn columns(&self) -> Vec<&'static str> {
-        use crate::blocktree::db::columns::{Coding, Data, DeadSlots, ErasureMeta, Orphans, Root, SlotMeta};
+        use crate::blocktree::db::columns::{
+            AddressSignatures, Coding, Data, DeadSlots, ErasureMeta, Orphans, Root, SignatureSlot,
+            SlotMeta, TransactionStatus,
+        };
 
         vec![
+            AddressSignatures::NAME,
             Coding::NAME,
             ErasureMeta::NAME,
             DeadSlots::NAME,
             Data::NAME,
             Orphans::NAME,
             Root::NAME,
+            SignatureSlot::NAME,
             SlotMeta::NAME,
+            TransactionStatus::NAME,
         ]
     }
 
-    fn destroy(path: &Path) -> Result<()> {
-        DB::destroy(&Options::default(), path)?;
-
-        Ok(())
-    }
-
     fn cf_handle(&self, cf: &str) -> ColumnFamily {
         self.0
             .cf_handle(cf)
@@ -133,136 +149,6 @@ impl Backend for Rocks {
     }
 }
 
-impl Column<Rocks> for cf::Coding {
-    const NAME: &'static str = super::ERASURE_CF;
-    type Index = (u64, u64);
-
-    fn key(index: (u64, u64)) -> Vec<u8> {
-        cf::Data::key(index)
-    }
-
-    fn index(key: &[u8]) -> (u64, u64) {
-        cf::Data::index(key)
-    }
-}
-
-impl Column<Rocks> for cf::Data {
-    const NAME: &'static str = super::DATA_CF;
-    type Index = (u64, u64);
-
-    fn key((slot, index): (u64, u64)) -> Vec<u8> {
-        let mut key = vec![0; 16];
-        BigEndian::write_u64(&mut key[..8], slot);
-        BigEndian::write_u64(&mut key[8..16], index);
-        key
-    }
-
-    fn index(key: &[u8]) -> (u64, u64) {
-        let slot = BigEndian::read_u64(&key[..8]);
-        let index = BigEndian::read_u64(&key[8..16]);
-        (slot, index)
-    }
-}
-
-impl Column<Rocks> for cf::DeadSlots {
-    const NAME: &'static str = super::DEAD_SLOTS_CF;
-    type Index = u64;
-
-    fn key(slot: u64) -> Vec<u8> {
-        let mut key = vec![0; 8];
-        BigEndian::write_u64(&mut key[..], slot);
-        key
-    }
-
-    fn index(key: &[u8]) -> u64 {
-        BigEndian::read_u64(&key[..8])
-    }
-}
-
-impl TypedColumn<Rocks> for cf::DeadSlots {
-    type Type = bool;
-}
-
-impl Column<Rocks> for cf::Orphans {
-    const NAME: &'static str = super::ORPHANS_CF;
-    type Index = u64;
-
-    fn key(slot: u64) -> Vec<u8> {
-        let mut key = vec![0; 8];
-        BigEndian::write_u64(&mut key[..], slot);
-        key
-    }
-
-    fn index(key: &[u8]) -> u64 {
-        BigEndian::read_u64(&key[..8])
-    }
-}
-
-impl TypedColumn<Rocks> for cf::Orphans {
-    type Type = bool;
-}
-
-impl Column<Rocks> for cf::Root {
-    const NAME: &'static str = super::ROOT_CF;
-    type Index = u64;
-
-    fn key(slot: u64) -> Vec<u8> {
-        let mut key = vec![0; 8];
-        BigEndian::write_u64(&mut key[..], slot);
-        key
-    }
-
-    fn index(key: &[u8]) -> u64 {
-        BigEndian::read_u64(&key[..8])
-    }
-}
-
-impl TypedColumn<Rocks> for cf::Root {
-    type Type = bool;
-}
-
-impl Column<Rocks> for cf::SlotMeta {
-    const NAME: &'static str = super::META_CF;
-    type Index = u64;
-
-    fn key(slot: u64) -> Vec<u8> {
-        let mut key = vec![0; 8];
-        BigEndian::write_u64(&mut key[..], slot);
-        key
-    }
-
-    fn index(key: &[u8]) -> u64 {
-        BigEndian::read_u64(&key[..8])
-    }
-}
-
-impl TypedColumn<Rocks> for cf::SlotMeta {
-    type Type = super::SlotMeta;
-}
-
-impl Column<Rocks> for cf::ErasureMeta {
-    const NAME: &'static str = super::ERASURE_META_CF;
-    type Index = (u64, u64);
-
-    fn index(key: &[u8]) -> (u64, u64) {
-        let slot = BigEndian::read_u64(&key[..8]);
-        let set_index = BigEndian::read_u64(&key[8..]);
-
-        (slot, set_index)
-    }
-
-    fn key((slot, set_index): (u64, u64)) -> Vec<u8> {
-        let mut key = vec![0; 16];
-        BigEndian::write_u64(&mut key[..8], slot);
-        BigEndian::write_u64(&mut key[8..], set_index);
-        key
-    }
-}
-
-impl TypedColumn<Rocks> for cf::ErasureMeta {
-    type Type = super::ErasureMeta;
-}
-
 impl DbCursor<Rocks> for DBRawIterator {
     fn valid(&self) -> bool {
         DBRawIterator::valid(self)
@@ -142,35 +142,88 @@ pub fn process_blocktree(
     genesis_block: &GenesisBlock,
     blocktree: &Blocktree,
     account_paths: Option<String>,
+) -> result::Result<(BankForks, Vec<BankForksInfo>, LeaderScheduleCache), BlocktreeProcessorError> {
+    process_blocktree_with_callback(genesis_block, blocktree, account_paths, |_| ())
+}
+
+/// Like `process_blocktree`, but calls `on_frozen` with every bank once it is frozen, before
+/// squashing a root can drop it
+pub fn process_blocktree_with_callback<F: FnMut(&Bank)>(
+    genesis_block: &GenesisBlock,
+    blocktree: &Blocktree,
+    account_paths: Option<String>,
+    on_frozen: F,
+) -> result::Result<(BankForks, Vec<BankForksInfo>, LeaderScheduleCache), BlocktreeProcessorError> {
+    info!("processing ledger from genesis...");
+    let bank0 = Arc::new(Bank::new_with_paths(&genesis_block, account_paths));
+    process_blocktree_from_root_with_callback(blocktree, bank0, on_frozen)
+}
+
+/// Replay every full slot in `blocktree` descending from `bank`, which becomes the first
+/// root. A frozen `bank`, such as one loaded from a snapshot, is taken as is and only its
+/// descendants are replayed
+pub fn process_blocktree_from_root(
+    blocktree: &Blocktree,
+    bank: Arc<Bank>,
+) -> result::Result<(BankForks, Vec<BankForksInfo>, LeaderScheduleCache), BlocktreeProcessorError> {
+    process_blocktree_from_root_with_callback(blocktree, bank, |_| ())
+}
+
+fn process_blocktree_from_root_with_callback<F: FnMut(&Bank)>(
+    blocktree: &Blocktree,
+    bank: Arc<Bank>,
+    mut on_frozen: F,
 ) -> result::Result<(BankForks, Vec<BankForksInfo>, LeaderScheduleCache), BlocktreeProcessorError> {
     let now = Instant::now();
-    info!("processing ledger...");
-    // Setup bank for slot 0
+    let start_slot = bank.slot();
+    info!("processing ledger from slot {}...", start_slot);
+    // Setup bank for the starting slot
     let mut pending_slots = {
-        let slot = 0;
-        let bank = Arc::new(Bank::new_with_paths(&genesis_block, account_paths));
-        let entry_height = 0;
+        let entry_height = if bank.is_frozen() {
+            bank.tick_height()
+        } else {
+            0
+        };
         let last_entry_hash = bank.last_blockhash();
 
         // Load the metadata for this slot
-        let meta = blocktree
-            .meta(slot)
-            .map_err(|err| {
-                warn!("Failed to load meta for slot {}: {:?}", slot, err);
-                BlocktreeProcessorError::LedgerVerificationFailed
-            })?
-            .unwrap();
+        let meta = blocktree.meta(start_slot).map_err(|err| {
+            warn!("Failed to load meta for slot {}: {:?}", start_slot, err);
+            BlocktreeProcessorError::LedgerVerificationFailed
+        })?;
 
-        vec![(slot, meta, bank, entry_height, last_entry_hash)]
+        match meta {
+            Some(meta) => vec![(start_slot, meta, bank, entry_height, last_entry_hash)],
+            None if bank.is_frozen() => {
+                // Nothing in the ledger builds on this bank yet
+                on_frozen(&bank);
+                blocktree
+                    .set_roots(&[start_slot])
+                    .expect("Couldn't set first root");
+                let leader_schedule_cache = LeaderScheduleCache::new_from_bank(&bank);
+                let bank_forks = BankForks::new_from_banks(&[bank], start_slot);
+                let bank_forks_info = vec![BankForksInfo {
+                    bank_slot: start_slot,
+                    entry_height,
+                }];
+                return Ok((bank_forks, bank_forks_info, leader_schedule_cache));
+            }
+            None => {
+                warn!("Missing meta for slot {}", start_slot);
+                return Err(BlocktreeProcessorError::LedgerVerificationFailed);
+            }
+        }
     };
 
-    blocktree.set_roots(&[0]).expect("Couldn't set first root");
+    blocktree
+        .set_roots(&[start_slot])
+        .expect("Couldn't set first root");
 
-    let leader_schedule_cache = LeaderScheduleCache::new(*pending_slots[0].2.epoch_schedule(), 0);
+    let leader_schedule_cache = LeaderScheduleCache::new_from_bank(&pending_slots[0].2);
 
     let mut fork_info = vec![];
     let mut last_status_report = Instant::now();
-    let mut root = 0;
+    let mut root = start_slot;
     while !pending_slots.is_empty() {
         let (slot, meta, bank, mut entry_height, mut last_entry_hash) =
             pending_slots.pop().unwrap();
@@ -180,13 +233,17 @@ pub fn process_blocktree(
             last_status_report = Instant::now();
         }
 
-        // Fetch all entries for this slot
-        let mut entries = blocktree.get_slot_entries(slot, 0, None).map_err(|err| {
-            warn!("Failed to load entries for slot {}: {:?}", slot, err);
-            BlocktreeProcessorError::LedgerVerificationFailed
-        })?;
+        // Fetch all entries for this slot, unless its bank has already been frozen
+        let mut entries = if bank.is_frozen() {
+            vec![]
+        } else {
+            blocktree.get_slot_entries(slot, 0, None).map_err(|err| {
+                warn!("Failed to load entries for slot {}: {:?}", slot, err);
+                BlocktreeProcessorError::LedgerVerificationFailed
+            })?
+        };
 
-        if slot == 0 {
+        if slot == 0 && !bank.is_frozen() {
             // The first entry in the ledger is a pseudo-tick used only to ensure the number of ticks
             // in slot 0 is the same as the number of ticks in all subsequent slots.  It is not
             // processed by the bank, skip over it.
@@ -212,6 +269,12 @@ pub fn process_blocktree(
                 return Err(BlocktreeProcessorError::LedgerVerificationFailed);
             }
 
+            blocktree
+                .index_transactions(slot, &entries)
+                .map_err(|err| {
+                    warn!("Failed to index entries for slot {}: {:?}", slot, err);
+                    BlocktreeProcessorError::LedgerVerificationFailed
+                })?;
             process_entries(&bank, &entries).map_err(|err| {
                 warn!("Failed to process entries for slot {}: {:?}", slot, err);
                 BlocktreeProcessorError::LedgerVerificationFailed
@@ -222,6 +285,7 @@ pub fn process_blocktree(
         }
 
         bank.freeze(); // all banks handled by this routine are created from complete slots
+        on_frozen(&bank);
 
         if blocktree.is_root(slot) {
             root = slot;
@@ -304,7 +368,7 @@ pub mod tests {
     use crate::genesis_utils::{
         create_genesis_block, create_genesis_block_with_leader, GenesisBlockInfo,
     };
-    use solana_runtime::epoch_schedule::EpochSchedule;
+    use solana_sdk::epoch_schedule::EpochSchedule;
     use solana_sdk::hash::Hash;
     use solana_sdk::instruction::InstructionError;
     use solana_sdk::pubkey::Pubkey;
@@ -385,6 +449,54 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_process_blocktree_from_root() {
+        solana_logger::setup();
+
+        let GenesisBlockInfo { genesis_block, .. } = create_genesis_block(10_000);
+        let ticks_per_slot = genesis_block.ticks_per_slot;
+
+        // slot 0 <- slot 1 <- slot 2, all full of ticks
+        let (ledger_path, mut blockhash) = create_new_tmp_ledger!(&genesis_block);
+        let blocktree =
+            Blocktree::open(&ledger_path).expect("Expected to successfully open database ledger");
+        blockhash = fill_blocktree_slot_with_ticks(&blocktree, ticks_per_slot, 1, 0, blockhash);
+        fill_blocktree_slot_with_ticks(&blocktree, ticks_per_slot, 2, 1, blockhash);
+
+        let (bank_forks, _, _) = process_blocktree(&genesis_block, &blocktree, None).unwrap();
+        let bank2 = bank_forks[2].clone();
+        let bank1 = bank2.parent().unwrap();
+
+        // Starting from the frozen bank for slot 1 only replays slot 2
+        let (bank_forks, bank_forks_info, _) =
+            process_blocktree_from_root(&blocktree, bank1.clone()).unwrap();
+        assert_eq!(bank_forks.root(), 1);
+        assert_eq!(
+            bank_forks_info,
+            vec![BankForksInfo {
+                bank_slot: 2,
+                entry_height: bank2.tick_height(),
+            }]
+        );
+        assert_eq!(bank_forks[2].hash(), bank2.hash());
+        assert!(blocktree.is_root(1));
+
+        // A frozen bank with nothing built on it in the ledger is taken on its own
+        let bank5 = Arc::new(Bank::new_from_parent(&bank2, &Pubkey::default(), 5));
+        bank5.freeze();
+        let (bank_forks, bank_forks_info, _) =
+            process_blocktree_from_root(&blocktree, bank5.clone()).unwrap();
+        assert_eq!(bank_forks.root(), 5);
+        assert_eq!(
+            bank_forks_info,
+            vec![BankForksInfo {
+                bank_slot: 5,
+                entry_height: bank5.tick_height(),
+            }]
+        );
+        assert_eq!(bank_forks.working_bank().hash(), bank5.hash());
+    }
+
     #[test]
     fn test_process_blocktree_with_two_forks_and_squash() {
         solana_logger::setup();
@@ -549,6 +661,33 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_process_blocktree_with_callback() {
+        let GenesisBlockInfo { genesis_block, .. } = create_genesis_block(10_000);
+        let ticks_per_slot = genesis_block.ticks_per_slot;
+        let (ledger_path, blockhash) = create_new_tmp_ledger!(&genesis_block);
+        let blocktree =
+            Blocktree::open(&ledger_path).expect("Expected to successfully open database ledger");
+        let last_entry_hash =
+            fill_blocktree_slot_with_ticks(&blocktree, ticks_per_slot, 1, 0, blockhash);
+        fill_blocktree_slot_with_ticks(&blocktree, ticks_per_slot, 2, 1, last_entry_hash);
+        blocktree.set_roots(&[0, 1]).unwrap();
+
+        // Every replayed bank is seen, including the roots squashing drops
+        let mut frozen = vec![];
+        let (bank_forks, _, _) =
+            process_blocktree_with_callback(&genesis_block, &blocktree, None, |bank| {
+                assert!(bank.is_frozen());
+                frozen.push((bank.slot(), bank.hash()));
+            })
+            .unwrap();
+        assert_eq!(
+            frozen.iter().map(|(slot, _)| *slot).collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+        assert_eq!(frozen[2].1, bank_forks[2].hash());
+    }
+
     #[test]
     fn test_process_blocktree_epoch_boundary_root() {
         solana_logger::setup();
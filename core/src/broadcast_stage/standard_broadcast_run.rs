@@ -63,6 +63,16 @@ impl BroadcastRun for StandardBroadcastRun {
         let last_tick = receive_results.last_tick;
         inc_new_counter_info!("broadcast_service-entries_received", num_entries);
 
+        // The leader doesn't replay its own slots, so their transactions are indexed here
+        blocktree.index_transactions(
+            bank.slot(),
+            receive_results
+                .ventries
+                .iter()
+                .flatten()
+                .map(|(entry, _)| entry),
+        )?;
+
         // 2) Convert entries to blobs + generate coding blobs
         let to_blobs_start = Instant::now();
         let keypair = &cluster_info.read().unwrap().keypair.clone();
@@ -10,8 +10,8 @@ use crate::entry::{Entry, EntrySlice};
 use crate::gossip_service::discover_cluster;
 use hashbrown::HashSet;
 use solana_client::thin_client::create_client;
-use solana_runtime::epoch_schedule::MINIMUM_SLOTS_PER_EPOCH;
 use solana_sdk::client::SyncClient;
+use solana_sdk::epoch_schedule::MINIMUM_SLOTS_PER_EPOCH;
 use solana_sdk::hash::Hash;
 use solana_sdk::poh_config::PohConfig;
 use solana_sdk::pubkey::Pubkey;
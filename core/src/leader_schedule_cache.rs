@@ -2,7 +2,7 @@ use crate::blocktree::Blocktree;
 use crate::leader_schedule::LeaderSchedule;
 use crate::leader_schedule_utils;
 use solana_runtime::bank::Bank;
-use solana_runtime::epoch_schedule::EpochSchedule;
+use solana_sdk::epoch_schedule::EpochSchedule;
 use solana_sdk::pubkey::Pubkey;
 use std::collections::hash_map::Entry;
 use std::collections::{HashMap, VecDeque};
@@ -172,7 +172,7 @@ mod tests {
     };
     use crate::staking_utils::tests::setup_vote_and_stake_accounts;
     use solana_runtime::bank::Bank;
-    use solana_runtime::epoch_schedule::{EpochSchedule, MINIMUM_SLOTS_PER_EPOCH};
+    use solana_sdk::epoch_schedule::{EpochSchedule, MINIMUM_SLOTS_PER_EPOCH};
     use std::sync::mpsc::channel;
     use std::sync::Arc;
     use std::thread::Builder;
@@ -0,0 +1,171 @@
+//! The `ledger_cleanup_service` caps the size of the ledger by purging the slots that fall
+//! too far behind the root.  The cap is a number of slots rather than bytes: the space taken
+//! by the ledger only shrinks once the database compacts the purged keys, so its size on disk
+//! can't tell how much is left to purge.  Slots are never purged past the latest snapshot
+//! archive, which a restart replays the ledger from.
+
+use crate::bank_forks::BankForks;
+use crate::blocktree::Blocktree;
+use crate::result::Result;
+use crate::service::Service;
+use crate::snapshot_utils;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread::{self, sleep, Builder, JoinHandle};
+use std::time::{Duration, SystemTime};
+
+/// Number of slots kept behind the root when no other limit is given
+pub const DEFAULT_MAX_LEDGER_SLOTS: u64 = 6400;
+
+/// How often the root is checked for progress
+const CLEANUP_INTERVAL_MS: u64 = 1000;
+
+pub struct LedgerCleanupService {
+    t_cleanup: JoinHandle<()>,
+}
+
+impl LedgerCleanupService {
+    pub fn new(
+        blocktree: &Arc<Blocktree>,
+        bank_forks: &Arc<RwLock<BankForks>>,
+        max_ledger_slots: u64,
+        snapshot_path: &str,
+        exit: &Arc<AtomicBool>,
+    ) -> Self {
+        info!(
+            "LedgerCleanupService active. Max Ledger Slots {}",
+            max_ledger_slots
+        );
+        let blocktree = blocktree.clone();
+        let bank_forks = bank_forks.clone();
+        let archive_path = snapshot_utils::get_snapshot_archive_path(snapshot_path);
+        let exit = exit.clone();
+        let t_cleanup = Builder::new()
+            .name("solana-ledger-cleanup".to_string())
+            .spawn(move || {
+                let mut next_purge_slot = 0;
+                let mut archive = None;
+                loop {
+                    if exit.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    archive = Self::archived_snapshot_slot(&archive_path, archive);
+                    if let Some((_, snapshot_slot)) = archive {
+                        let root = bank_forks.read().unwrap().root();
+                        match Self::cleanup_ledger(
+                            &blocktree,
+                            root,
+                            snapshot_slot,
+                            max_ledger_slots,
+                            next_purge_slot,
+                        ) {
+                            Ok(slot) => next_purge_slot = slot,
+                            Err(e) => warn!("Failed to purge ledger: {:?}", e),
+                        }
+                    }
+                    sleep(Duration::from_millis(CLEANUP_INTERVAL_MS));
+                }
+            })
+            .unwrap();
+        Self { t_cleanup }
+    }
+
+    /// The modification time and slot of the archive at `archive_path`, whose manifest is
+    /// only read again once the archive has been replaced since `cached`
+    fn archived_snapshot_slot(
+        archive_path: &Path,
+        cached: Option<(SystemTime, u64)>,
+    ) -> Option<(SystemTime, u64)> {
+        let modified = fs::metadata(archive_path)
+            .and_then(|metadata| metadata.modified())
+            .ok()?;
+        match cached {
+            Some((cached_modified, _)) if cached_modified == modified => cached,
+            _ => snapshot_utils::read_snapshot_manifest(archive_path)
+                .map(|manifest| (modified, manifest.slot))
+                .ok(),
+        }
+    }
+
+    /// Purge every slot from `next_purge_slot` up to `max_ledger_slots` behind `root`, but
+    /// none from `snapshot_slot` on, returning the first slot that remains
+    fn cleanup_ledger(
+        blocktree: &Blocktree,
+        root: u64,
+        snapshot_slot: u64,
+        max_ledger_slots: u64,
+        next_purge_slot: u64,
+    ) -> Result<u64> {
+        if root < max_ledger_slots {
+            return Ok(next_purge_slot);
+        }
+        let first_kept_slot = (root - max_ledger_slots + 1).min(snapshot_slot);
+        if first_kept_slot <= next_purge_slot {
+            return Ok(next_purge_slot);
+        }
+        let last_purge_slot = first_kept_slot - 1;
+        blocktree.purge_slots(next_purge_slot, last_purge_slot)?;
+        debug!(
+            "purged ledger slots {} through {} behind root {}",
+            next_purge_slot, last_purge_slot, root
+        );
+        Ok(first_kept_slot)
+    }
+}
+
+impl Service for LedgerCleanupService {
+    type JoinReturnType = ();
+
+    fn join(self) -> thread::Result<()> {
+        self.t_cleanup.join()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocktree::get_tmp_ledger_path;
+    use crate::blocktree::tests::make_many_slot_entries;
+
+    #[test]
+    fn test_cleanup_ledger() {
+        let blocktree_path = get_tmp_ledger_path!();
+        let blocktree = Blocktree::open(&blocktree_path).unwrap();
+        let (blobs, _) = make_many_slot_entries(0, 50, 5);
+        blocktree.write_blobs(blobs).unwrap();
+
+        // Nothing falls far enough behind the root yet
+        assert_eq!(
+            LedgerCleanupService::cleanup_ledger(&blocktree, 9, 9, 10, 0).unwrap(),
+            0
+        );
+        assert!(blocktree.meta(0).unwrap().is_some());
+
+        // Nothing from the snapshot slot on is purged
+        assert_eq!(
+            LedgerCleanupService::cleanup_ledger(&blocktree, 40, 20, 10, 0).unwrap(),
+            20
+        );
+        for slot in 0..50 {
+            assert_eq!(blocktree.meta(slot).unwrap().is_some(), slot >= 20);
+        }
+
+        // Only the last 10 slots behind the root are kept
+        assert_eq!(
+            LedgerCleanupService::cleanup_ledger(&blocktree, 40, 40, 10, 20).unwrap(),
+            31
+        );
+        for slot in 0..50 {
+            assert_eq!(blocktree.meta(slot).unwrap().is_some(), slot > 30);
+        }
+        assert_eq!(
+            LedgerCleanupService::cleanup_ledger(&blocktree, 40, 40, 10, 31).unwrap(),
+            31
+        );
+
+        drop(blocktree);
+        Blocktree::destroy(&blocktree_path).expect("Expected successful database destruction");
+    }
+}
@@ -0,0 +1,296 @@
+//! The `ledger_export` module writes the rooted part of a ledger to a single stream and
+//! rebuilds a `Blocktree` from one, so a ledger can be moved between machines or kept
+//! around after the node that produced it has purged it.
+//!
+//! A stream starts with `LEDGER_EXPORT_MAGIC` and the format version as a little-endian
+//! `u32`, followed by records. Each record is its length as a little-endian `u64`, the
+//! bincode-serialized `LedgerRecord`, and the hash of the serialized record. The first record
+//! is always the genesis block and the last is `LedgerRecord::End`, so a truncated stream is
+//! rejected rather than imported partially.
+
+use crate::blocktree::Blocktree;
+use crate::entry::Entry;
+use crate::packet::Blob;
+use bincode::{deserialize, serialize};
+use byteorder::{ByteOrder, LittleEndian};
+use solana_sdk::genesis_block::GenesisBlock;
+use solana_sdk::hash::{hash, Hash};
+use std::fs;
+use std::io::{Error, ErrorKind, Read, Result, Write};
+use std::path::Path;
+
+pub const LEDGER_EXPORT_MAGIC: &[u8; 8] = b"SOLLEDGR";
+pub const LEDGER_EXPORT_VERSION: u32 = 1;
+
+/// Upper bound on a single record, so a corrupt length can't exhaust memory
+const MAX_RECORD_SIZE: u64 = 1 << 30;
+
+#[derive(Serialize, Deserialize, Debug)]
+enum LedgerRecord {
+    Genesis(Box<GenesisBlock>),
+    Slot {
+        slot: u64,
+        parent_slot: u64,
+        entries: Vec<Entry>,
+    },
+    Roots(Vec<u64>),
+    End {
+        num_slots: u64,
+    },
+}
+
+fn get_io_error(error: &str) -> Error {
+    warn!("ledger export error: {:?}", error);
+    Error::new(ErrorKind::Other, error)
+}
+
+fn write_record<W: Write>(writer: &mut W, record: &LedgerRecord) -> Result<()> {
+    let data = serialize(record).map_err(|err| get_io_error(&err.to_string()))?;
+    let mut len = [0u8; 8];
+    LittleEndian::write_u64(&mut len, data.len() as u64);
+    writer.write_all(&len)?;
+    writer.write_all(&data)?;
+    writer.write_all(hash(&data).as_ref())
+}
+
+fn read_record<R: Read>(reader: &mut R) -> Result<LedgerRecord> {
+    let mut len = [0u8; 8];
+    reader.read_exact(&mut len)?;
+    let len = LittleEndian::read_u64(&len);
+    if len > MAX_RECORD_SIZE {
+        return Err(get_io_error(&format!("record too large: {} bytes", len)));
+    }
+    let mut data = vec![0u8; len as usize];
+    reader.read_exact(&mut data)?;
+    let mut checksum = [0u8; 32];
+    reader.read_exact(&mut checksum)?;
+    if hash(&data) != Hash::new(&checksum) {
+        return Err(get_io_error("record checksum mismatch"));
+    }
+    deserialize(&data).map_err(|err| get_io_error(&err.to_string()))
+}
+
+/// Write the genesis block, the entries of every rooted slot and the roots to `writer`,
+/// returning the number of slots exported
+pub fn export_ledger<W: Write>(
+    genesis_block: &GenesisBlock,
+    blocktree: &Blocktree,
+    mut writer: W,
+) -> Result<u64> {
+    let mut version = [0u8; 4];
+    LittleEndian::write_u32(&mut version, LEDGER_EXPORT_VERSION);
+    writer.write_all(LEDGER_EXPORT_MAGIC)?;
+    writer.write_all(&version)?;
+    write_record(
+        &mut writer,
+        &LedgerRecord::Genesis(Box::new(genesis_block.clone())),
+    )?;
+
+    let slot_metas = blocktree
+        .slot_meta_iterator(0)
+        .map_err(|err| get_io_error(&format!("{:?}", err)))?;
+    let mut roots = vec![];
+    for (slot, meta) in slot_metas {
+        if !blocktree.is_root(slot) {
+            continue;
+        }
+        if !meta.is_full() {
+            return Err(get_io_error(&format!("rooted slot {} is incomplete", slot)));
+        }
+        let entries = blocktree
+            .get_slot_entries(slot, 0, None)
+            .map_err(|err| get_io_error(&format!("{:?}", err)))?;
+        write_record(
+            &mut writer,
+            &LedgerRecord::Slot {
+                slot,
+                parent_slot: meta.parent_slot,
+                entries,
+            },
+        )?;
+        roots.push(slot);
+    }
+
+    let num_slots = roots.len() as u64;
+    write_record(&mut writer, &LedgerRecord::Roots(roots))?;
+    write_record(&mut writer, &LedgerRecord::End { num_slots })?;
+    writer.flush()?;
+    Ok(num_slots)
+}
+
+/// Create a new ledger at `ledger_path` from a stream written by `export_ledger`, returning
+/// the number of slots imported. Nothing is left at `ledger_path` if the import fails
+pub fn import_ledger<R: Read>(reader: R, ledger_path: &str) -> Result<u64> {
+    if Path::new(ledger_path).exists() {
+        return Err(Error::new(
+            ErrorKind::AlreadyExists,
+            format!("{} already exists", ledger_path),
+        ));
+    }
+    let result = import_ledger_records(reader, ledger_path);
+    if result.is_err() {
+        let _ = fs::remove_dir_all(ledger_path);
+    }
+    result
+}
+
+fn import_ledger_records<R: Read>(mut reader: R, ledger_path: &str) -> Result<u64> {
+    let mut magic = [0u8; 8];
+    reader.read_exact(&mut magic)?;
+    if magic != *LEDGER_EXPORT_MAGIC {
+        return Err(get_io_error("not a ledger export stream"));
+    }
+    let mut version = [0u8; 4];
+    reader.read_exact(&mut version)?;
+    let version = LittleEndian::read_u32(&version);
+    if version != LEDGER_EXPORT_VERSION {
+        return Err(get_io_error(&format!(
+            "unsupported ledger export version {}",
+            version
+        )));
+    }
+
+    match read_record(&mut reader)? {
+        LedgerRecord::Genesis(genesis_block) => genesis_block.write(ledger_path)?,
+        _ => return Err(get_io_error("stream does not start with a genesis block")),
+    }
+    let blocktree =
+        Blocktree::open(ledger_path).map_err(|err| get_io_error(&format!("{:?}", err)))?;
+
+    let mut num_slots = 0;
+    loop {
+        match read_record(&mut reader)? {
+            LedgerRecord::Slot {
+                slot,
+                parent_slot,
+                entries,
+            } => {
+                let blobs = slot_blobs(slot, parent_slot, &entries);
+                blocktree
+                    .write_blobs(&blobs)
+                    .map_err(|err| get_io_error(&format!("{:?}", err)))?;
+                blocktree
+                    .index_transactions(slot, &entries)
+                    .map_err(|err| get_io_error(&format!("{:?}", err)))?;
+                num_slots += 1;
+            }
+            LedgerRecord::Roots(roots) => blocktree
+                .set_roots(&roots)
+                .map_err(|err| get_io_error(&format!("{:?}", err)))?,
+            LedgerRecord::End {
+                num_slots: expected_num_slots,
+            } => {
+                if num_slots != expected_num_slots {
+                    return Err(get_io_error(&format!(
+                        "expected {} slots, found {}",
+                        expected_num_slots, num_slots
+                    )));
+                }
+                return Ok(num_slots);
+            }
+            LedgerRecord::Genesis(_) => return Err(get_io_error("unexpected genesis block")),
+        }
+    }
+}
+
+/// The blobs of one full slot
+fn slot_blobs(slot: u64, parent_slot: u64, entries: &[Entry]) -> Vec<Blob> {
+    let mut blobs: Vec<Blob> = entries
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let mut blob = entry.to_blob();
+            blob.set_index(index as u64);
+            blob.set_slot(slot);
+            blob.set_parent(parent_slot);
+            blob
+        })
+        .collect();
+    if let Some(blob) = blobs.last_mut() {
+        blob.set_is_last_in_slot();
+    }
+    blobs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocktree::{create_new_tmp_ledger, get_tmp_ledger_path};
+    use crate::blocktree_processor::tests::fill_blocktree_slot_with_ticks;
+    use crate::genesis_utils::create_genesis_block;
+
+    #[test]
+    fn test_ledger_export_round_trip() {
+        let genesis_block = create_genesis_block(10_000).genesis_block;
+        let ticks_per_slot = genesis_block.ticks_per_slot;
+        let (ledger_path, blockhash) = create_new_tmp_ledger!(&genesis_block);
+        let blocktree = Blocktree::open(&ledger_path).unwrap();
+
+        // Slots 0 and 1 are rooted, slot 2 is not and stays behind
+        let last_entry_hash =
+            fill_blocktree_slot_with_ticks(&blocktree, ticks_per_slot, 1, 0, blockhash);
+        fill_blocktree_slot_with_ticks(&blocktree, ticks_per_slot, 2, 1, last_entry_hash);
+        blocktree.set_roots(&[0, 1]).unwrap();
+
+        let mut stream = vec![];
+        assert_eq!(
+            export_ledger(&genesis_block, &blocktree, &mut stream).unwrap(),
+            2
+        );
+
+        let import_path = get_tmp_ledger_path!();
+        assert_eq!(import_ledger(&stream[..], &import_path).unwrap(), 2);
+        assert_eq!(
+            GenesisBlock::load(&import_path).unwrap().hash(),
+            genesis_block.hash()
+        );
+        let imported = Blocktree::open(&import_path).unwrap();
+        for slot in 0..2 {
+            assert!(imported.is_root(slot));
+            assert!(imported.meta(slot).unwrap().unwrap().is_full());
+            assert_eq!(
+                imported.get_slot_entries(slot, 0, None).unwrap(),
+                blocktree.get_slot_entries(slot, 0, None).unwrap()
+            );
+        }
+        assert_eq!(imported.meta(1).unwrap().unwrap().parent_slot, 0);
+        assert!(imported.meta(2).unwrap().is_none());
+
+        // Importing over an existing ledger is refused
+        assert_eq!(
+            import_ledger(&stream[..], &import_path).unwrap_err().kind(),
+            ErrorKind::AlreadyExists
+        );
+
+        drop(blocktree);
+        drop(imported);
+        Blocktree::destroy(&ledger_path).unwrap();
+        Blocktree::destroy(&import_path).unwrap();
+    }
+
+    #[test]
+    fn test_ledger_export_corrupt() {
+        let genesis_block = create_genesis_block(10_000).genesis_block;
+        let (ledger_path, _) = create_new_tmp_ledger!(&genesis_block);
+        let blocktree = Blocktree::open(&ledger_path).unwrap();
+        blocktree.set_roots(&[0]).unwrap();
+        let mut stream = vec![];
+        export_ledger(&genesis_block, &blocktree, &mut stream).unwrap();
+
+        // A flipped bit fails the record checksum
+        let mut corrupt = stream.clone();
+        let middle = corrupt.len() / 2;
+        corrupt[middle] ^= 1;
+        let import_path = get_tmp_ledger_path!();
+        assert!(import_ledger(&corrupt[..], &import_path).is_err());
+        assert!(!Path::new(&import_path).exists());
+
+        // A truncated stream is missing its end record
+        let truncated = &stream[..stream.len() - 1];
+        assert!(import_ledger(truncated, &import_path).is_err());
+        assert!(!Path::new(&import_path).exists());
+
+        drop(blocktree);
+        Blocktree::destroy(&ledger_path).unwrap();
+    }
+}
@@ -5,6 +5,7 @@
 //! command-line tools to spin up fullnodes and a Rust library
 //!
 
+pub mod accounts_cleanup_service;
 pub mod bank_forks;
 pub mod banking_stage;
 pub mod blob_fetch_stage;
@@ -40,6 +41,8 @@ pub mod gossip_service;
 pub mod leader_schedule;
 pub mod leader_schedule_cache;
 pub mod leader_schedule_utils;
+pub mod ledger_cleanup_service;
+pub mod ledger_export;
 pub mod local_cluster;
 pub mod local_vote_signer_service;
 pub mod packet;
@@ -53,6 +56,7 @@ pub mod replicator;
 pub mod result;
 pub mod retransmit_stage;
 pub mod rpc;
+pub mod rpc_access_control;
 pub mod rpc_pubsub;
 pub mod rpc_pubsub_service;
 pub mod rpc_service;
@@ -60,6 +64,8 @@ pub mod rpc_subscriptions;
 pub mod service;
 pub mod sigverify;
 pub mod sigverify_stage;
+pub mod snapshot_packager_service;
+pub mod snapshot_utils;
 pub mod staking_utils;
 pub mod storage_stage;
 pub mod streamer;
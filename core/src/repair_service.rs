@@ -8,7 +8,7 @@ use crate::cluster_info_repair_listener::ClusterInfoRepairListener;
 use crate::result::Result;
 use crate::service::Service;
 use solana_metrics::datapoint_info;
-use solana_runtime::epoch_schedule::EpochSchedule;
+use solana_sdk::epoch_schedule::EpochSchedule;
 use solana_sdk::pubkey::Pubkey;
 use std::collections::BTreeSet;
 use std::net::UdpSocket;
@@ -11,7 +11,7 @@ use crate::leader_schedule_utils;
 use crate::packet::BlobError;
 use crate::poh_recorder::PohRecorder;
 use crate::result::{Error, Result};
-use crate::rpc_subscriptions::RpcSubscriptions;
+use crate::rpc_subscriptions::{RpcSubscriptions, SlotInfo};
 use crate::service::Service;
 use solana_metrics::{datapoint_warn, inc_new_counter_info};
 use solana_runtime::bank::Bank;
@@ -119,11 +119,15 @@ impl ReplayStage {
                         break;
                     }
 
-                    Self::generate_new_bank_forks(
+                    let new_slots = Self::generate_new_bank_forks(
                         &blocktree,
                         &mut bank_forks.write().unwrap(),
                         &leader_schedule_cache,
                     );
+                    // Subscribers are notified only once the bank forks are unlocked
+                    for SlotInfo { slot, parent, root } in new_slots {
+                        subscriptions.notify_slot(slot, parent, root);
+                    }
 
                     let mut is_tpu_bank_active = poh_recorder.lock().unwrap().bank().is_some();
 
@@ -158,6 +162,7 @@ impl ReplayStage {
                             &blocktree,
                             &leader_schedule_cache,
                             &root_bank_sender,
+                            &subscriptions,
                         )?;
 
                         Self::reset_poh_recorder(
@@ -195,6 +200,7 @@ impl ReplayStage {
                             reached_leader_tick,
                             grace_ticks,
                             &leader_schedule_cache,
+                            &subscriptions,
                         );
                     }
 
@@ -224,6 +230,7 @@ impl ReplayStage {
         reached_leader_tick: bool,
         grace_ticks: u64,
         leader_schedule_cache: &Arc<LeaderScheduleCache>,
+        subscriptions: &Arc<RpcSubscriptions>,
     ) {
         trace!("{} checking poh slot {}", my_pubkey, poh_slot);
         if bank_forks.read().unwrap().get(poh_slot).is_none() {
@@ -250,7 +257,12 @@ impl ReplayStage {
                             ("count", poh_slot, i64),
                             ("grace", grace_ticks, i64));
                         let tpu_bank = Bank::new_from_parent(&parent, my_pubkey, poh_slot);
-                        bank_forks.write().unwrap().insert(tpu_bank);
+                        let root = {
+                            let mut bank_forks = bank_forks.write().unwrap();
+                            bank_forks.insert(tpu_bank);
+                            bank_forks.root()
+                        };
+                        subscriptions.notify_slot(poh_slot, parent_slot, root);
                         if let Some(tpu_bank) = bank_forks.read().unwrap().get(poh_slot).cloned() {
                             assert_eq!(
                                 bank_forks.read().unwrap().working_bank().slot(),
@@ -293,6 +305,7 @@ impl ReplayStage {
         progress: &mut HashMap<u64, ForkProgress>,
     ) -> Result<()> {
         let (entries, num) = Self::load_blocktree_entries(bank, blocktree, progress)?;
+        blocktree.index_transactions(bank.slot(), &entries)?;
         let result = Self::replay_entries_into_bank(bank, entries, progress, num);
 
         if Self::is_replay_result_fatal(&result) {
@@ -325,6 +338,7 @@ impl ReplayStage {
         blocktree: &Arc<Blocktree>,
         leader_schedule_cache: &Arc<LeaderScheduleCache>,
         root_bank_sender: &Sender<Vec<Arc<Bank>>>,
+        subscriptions: &Arc<RpcSubscriptions>,
     ) -> Result<()>
     where
         T: 'static + KeypairUtil + Send + Sync,
@@ -343,11 +357,20 @@ impl ReplayStage {
             blocktree
                 .set_roots(&rooted_slots)
                 .expect("Ledger set roots failed");
+            for rooted_bank in &rooted_banks {
+                blocktree
+                    .write_transaction_status_metas(
+                        rooted_bank.slot(),
+                        &rooted_bank.transaction_status_metas(),
+                    )
+                    .expect("Ledger write transaction status metas failed");
+            }
             // Set root first in leader schedule_cache before bank_forks because bank_forks.root
             // is consumed by repair_service to update gossip, so we don't want to get blobs for
             // repair on gossip before we update leader schedule, otherwise they may get dropped.
             leader_schedule_cache.set_root(new_root);
             bank_forks.write().unwrap().set_root(new_root);
+            subscriptions.notify_root(new_root);
             Self::handle_new_root(&bank_forks, progress);
             root_bank_sender.send(rooted_banks)?;
         }
@@ -524,6 +547,7 @@ impl ReplayStage {
                     .unwrap_or(true)
             {
                 info!("validator fork confirmed {} {}", *slot, duration);
+                bank_forks.write().unwrap().set_confirmed_slot(*slot);
                 datapoint_warn!("validator-confirmation", ("duration_ms", duration, i64));
                 false
             } else {
@@ -614,11 +638,14 @@ impl ReplayStage {
         }
     }
 
+    /// Create banks for the slots that chain to a frozen bank, returning the new slots so
+    /// subscribers can be told about them
     fn generate_new_bank_forks(
         blocktree: &Blocktree,
         forks: &mut BankForks,
         leader_schedule_cache: &Arc<LeaderScheduleCache>,
-    ) {
+    ) -> Vec<SlotInfo> {
+        let mut new_slots = vec![];
         // Find the next slot that chains to the old slot
         let frozen_banks = forks.frozen_banks();
         let frozen_bank_slots: Vec<u64> = frozen_banks.keys().cloned().collect();
@@ -643,8 +670,14 @@ impl ReplayStage {
                     .unwrap();
                 info!("new fork:{} parent:{}", child_id, parent_id);
                 forks.insert(Bank::new_from_parent(&parent_bank, &leader, child_id));
+                new_slots.push(SlotInfo {
+                    slot: child_id,
+                    parent: parent_id,
+                    root: forks.root(),
+                });
             }
         }
+        new_slots
     }
 }
 
@@ -693,12 +726,20 @@ mod test {
             blob_slot_1.set_parent(0);
             blocktree.insert_data_blobs(&vec![blob_slot_1]).unwrap();
             assert!(bank_forks.get(1).is_none());
-            ReplayStage::generate_new_bank_forks(
+            let new_slots = ReplayStage::generate_new_bank_forks(
                 &blocktree,
                 &mut bank_forks,
                 &leader_schedule_cache,
             );
             assert!(bank_forks.get(1).is_some());
+            assert_eq!(
+                new_slots,
+                vec![SlotInfo {
+                    slot: 1,
+                    parent: 0,
+                    root: 0,
+                }]
+            );
 
             // Insert blob for slot 3, generate new forks, check result
             let mut blob_slot_2 = Blob::default();
@@ -13,7 +13,7 @@ use crate::window_service::{should_retransmit_and_persist, WindowService};
 use rand::SeedableRng;
 use rand_chacha::ChaChaRng;
 use solana_metrics::{datapoint_info, inc_new_counter_error};
-use solana_runtime::epoch_schedule::EpochSchedule;
+use solana_sdk::epoch_schedule::EpochSchedule;
 use std::net::UdpSocket;
 use std::sync::atomic::AtomicBool;
 use std::sync::mpsc::channel;
@@ -1,6 +1,7 @@
 //! The `rpc` module implements the Solana RPC interface.
 
 use crate::bank_forks::BankForks;
+use crate::blocktree::Blocktree;
 use crate::cluster_info::ClusterInfo;
 use crate::contact_info::ContactInfo;
 use crate::packet::PACKET_DATA_SIZE;
@@ -8,24 +9,39 @@ use crate::storage_stage::StorageState;
 use bincode::{deserialize, serialize};
 use jsonrpc_core::{Error, Metadata, Result};
 use jsonrpc_derive::rpc;
+use solana_client::rpc_filter::{RpcFilterType, RpcProgramAccountsConfig};
 use solana_drone::drone::request_airdrop_transaction;
 use solana_runtime::bank::Bank;
 use solana_sdk::account::Account;
+use solana_sdk::commitment_config::{CommitmentConfig, CommitmentLevel};
 use solana_sdk::fee_calculator::FeeCalculator;
+use solana_sdk::hash::Hash;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::Signature;
 use solana_sdk::transaction::{self, Transaction};
+use solana_sdk::transaction_status::TransactionStatusMeta;
 use solana_vote_api::vote_state::VoteState;
-use std::net::{SocketAddr, UdpSocket};
+use std::collections::HashSet;
+use std::net::{IpAddr, SocketAddr, UdpSocket};
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, RwLock};
 use std::thread::sleep;
 use std::time::{Duration, Instant};
 
+pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+pub const MAX_GET_SIGNATURES_FOR_ADDRESS_LIMIT: usize = 1000;
+
 #[derive(Debug, Clone)]
 pub struct JsonRpcConfig {
     pub enable_fullnode_exit: bool, // Enable the 'fullnodeExit' command
     pub drone_addr: Option<SocketAddr>,
+    pub max_requests_per_second_per_ip: Option<u64>, // Per-client quota on the public listener
+    pub allowed_methods: Option<HashSet<String>>,    // Only these methods are served publicly
+    pub denied_methods: HashSet<String>,             // These methods are never served publicly
+    pub admin_rpc_path: Option<PathBuf>, // Unix socket serving every method, even 'fullnodeExit'
+    pub trusted_proxies: HashSet<IpAddr>, // Proxies skipped in X-Forwarded-For to find the client
+    pub enable_snapshot_download: bool,  // Serve the latest snapshot archive over HTTP
 }
 
 impl Default for JsonRpcConfig {
@@ -33,6 +49,12 @@ impl Default for JsonRpcConfig {
         Self {
             enable_fullnode_exit: false,
             drone_addr: None,
+            max_requests_per_second_per_ip: None,
+            allowed_methods: None,
+            denied_methods: HashSet::new(),
+            admin_rpc_path: None,
+            trusted_proxies: HashSet::new(),
+            enable_snapshot_download: false,
         }
     }
 }
@@ -40,42 +62,111 @@ impl Default for JsonRpcConfig {
 #[derive(Clone)]
 pub struct JsonRpcRequestProcessor {
     bank_forks: Arc<RwLock<BankForks>>,
+    blocktree: Arc<Blocktree>,
     storage_state: StorageState,
     config: JsonRpcConfig,
     fullnode_exit: Arc<AtomicBool>,
 }
 
 impl JsonRpcRequestProcessor {
-    fn bank(&self) -> Arc<Bank> {
-        self.bank_forks.read().unwrap().working_bank()
+    fn bank(&self, commitment: Option<CommitmentConfig>) -> Arc<Bank> {
+        let bank_forks = self.bank_forks.read().unwrap();
+        match commitment.unwrap_or_default().commitment {
+            CommitmentLevel::Recent => bank_forks.working_bank(),
+            CommitmentLevel::MaxConfirmed => bank_forks.confirmed_bank(),
+            CommitmentLevel::Root => bank_forks.root_bank(),
+        }
     }
 
     pub fn new(
         storage_state: StorageState,
         config: JsonRpcConfig,
         bank_forks: Arc<RwLock<BankForks>>,
+        blocktree: Arc<Blocktree>,
         fullnode_exit: &Arc<AtomicBool>,
     ) -> Self {
         JsonRpcRequestProcessor {
             bank_forks,
+            blocktree,
             storage_state,
             config,
             fullnode_exit: fullnode_exit.clone(),
         }
     }
 
-    pub fn get_account_info(&self, pubkey: &Pubkey) -> Result<Account> {
-        self.bank()
+    pub fn get_account_info(
+        &self,
+        pubkey: &Pubkey,
+        commitment: Option<CommitmentConfig>,
+    ) -> Result<Account> {
+        self.bank(commitment)
             .get_account(&pubkey)
             .ok_or_else(Error::invalid_request)
     }
 
-    pub fn get_balance(&self, pubkey: &Pubkey) -> u64 {
-        self.bank().get_balance(&pubkey)
+    /// Load every requested account from the same bank, so the results are mutually consistent
+    pub fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> Result<Vec<Option<Account>>> {
+        if pubkeys.len() > MAX_MULTIPLE_ACCOUNTS {
+            info!(
+                "get_multiple_accounts: too many pubkeys requested: {} (max: {})",
+                pubkeys.len(),
+                MAX_MULTIPLE_ACCOUNTS
+            );
+            return Err(Error::invalid_request());
+        }
+        let bank = self.bank(None);
+        Ok(pubkeys
+            .iter()
+            .map(|pubkey| bank.get_account(pubkey))
+            .collect())
     }
 
-    fn get_recent_blockhash(&self) -> (String, FeeCalculator) {
-        let (blockhash, fee_calculator) = self.bank().confirmed_last_blockhash();
+    pub fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        config: RpcProgramAccountsConfig,
+    ) -> Result<Vec<(String, Account)>> {
+        let RpcProgramAccountsConfig {
+            filters,
+            data_slice,
+        } = config;
+        let filters = filters.unwrap_or_default();
+        for filter in filters.iter() {
+            filter.verify().map_err(|err| {
+                info!(
+                    "get_program_accounts: invalid filter {:?}: {:?}",
+                    filter, err
+                );
+                Error::invalid_request()
+            })?;
+        }
+        Ok(self
+            .bank(None)
+            .get_program_accounts(&program_id)
+            .into_iter()
+            .filter(|(_, account)| {
+                filters
+                    .iter()
+                    .all(|filter: &RpcFilterType| filter.allows(&account.data))
+            })
+            .map(|(pubkey, mut account)| {
+                if let Some(data_slice) = data_slice {
+                    account.data = data_slice.slice(&account.data).to_vec();
+                }
+                (pubkey.to_string(), account)
+            })
+            .collect())
+    }
+
+    pub fn get_balance(&self, pubkey: &Pubkey, commitment: Option<CommitmentConfig>) -> u64 {
+        self.bank(commitment).get_balance(&pubkey)
+    }
+
+    fn get_recent_blockhash(
+        &self,
+        commitment: Option<CommitmentConfig>,
+    ) -> (String, FeeCalculator) {
+        let (blockhash, fee_calculator) = self.bank(commitment).confirmed_last_blockhash();
         (blockhash.to_string(), fee_calculator)
     }
 
@@ -84,6 +175,24 @@ impl JsonRpcRequestProcessor {
             .map(|x| x.1)
     }
 
+    /// What was recorded about the transaction with `signature`, by the banks that still hold
+    /// it or else by the ledger once its slot is rooted
+    pub fn get_transaction_status_meta(
+        &self,
+        signature: Signature,
+    ) -> Option<TransactionStatusMeta> {
+        self.bank(None)
+            .get_transaction_status_meta(&signature)
+            .or_else(|| {
+                self.blocktree
+                    .get_transaction_status_meta(&signature)
+                    .unwrap_or_else(|err| {
+                        info!("get_transaction_status_meta: blocktree error: {:?}", err);
+                        None
+                    })
+            })
+    }
+
     pub fn get_signature_confirmations(&self, signature: Signature) -> Option<usize> {
         self.get_signature_confirmation_status(signature)
             .map(|x| x.0)
@@ -93,23 +202,44 @@ impl JsonRpcRequestProcessor {
         &self,
         signature: Signature,
     ) -> Option<(usize, transaction::Result<()>)> {
-        self.bank().get_signature_confirmation_status(&signature)
+        self.bank(None)
+            .get_signature_confirmation_status(&signature)
     }
 
-    fn get_slot(&self) -> Result<u64> {
-        Ok(self.bank().slot())
+    fn get_slot(&self, commitment: Option<CommitmentConfig>) -> Result<u64> {
+        Ok(self.bank(commitment).slot())
     }
 
-    fn get_transaction_count(&self) -> Result<u64> {
-        Ok(self.bank().transaction_count() as u64)
+    fn get_transaction_count(&self, commitment: Option<CommitmentConfig>) -> Result<u64> {
+        Ok(self.bank(commitment).transaction_count() as u64)
     }
 
     fn get_total_supply(&self) -> Result<u64> {
-        Ok(self.bank().capitalization())
+        Ok(self.bank(None).capitalization())
+    }
+
+    fn get_accounts_hash(&self) -> Result<Option<RpcAccountsHash>> {
+        Ok(self
+            .bank(None)
+            .get_accounts_hash()
+            .map(|(slot, hash)| RpcAccountsHash {
+                slot,
+                hash: hash.to_string(),
+            }))
+    }
+
+    fn get_minimum_balance_for_rent_exemption(
+        &self,
+        data_len: usize,
+        commitment: Option<CommitmentConfig>,
+    ) -> Result<u64> {
+        Ok(self
+            .bank(commitment)
+            .get_minimum_balance_for_rent_exemption(data_len))
     }
 
     fn get_epoch_vote_accounts(&self) -> Result<Vec<RpcVoteAccountInfo>> {
-        let bank = self.bank();
+        let bank = self.bank(None);
         Ok(bank
             .epoch_vote_accounts(bank.get_epoch_and_slot_index(bank.slot()).0)
             .ok_or_else(Error::invalid_request)?
@@ -126,6 +256,117 @@ impl JsonRpcRequestProcessor {
             .collect::<Vec<_>>())
     }
 
+    pub fn get_confirmed_block(&self, slot: u64) -> Result<Option<RpcConfirmedBlock>> {
+        if !self.blocktree.is_root(slot) {
+            return Ok(None);
+        }
+        let map_blocktree_err = |err| {
+            info!("get_confirmed_block: blocktree error: {:?}", err);
+            Error::invalid_request()
+        };
+        let entries = self
+            .blocktree
+            .get_rooted_slot_entries(slot)
+            .map_err(map_blocktree_err)?;
+        let parent_slot = self
+            .blocktree
+            .meta(slot)
+            .map_err(map_blocktree_err)?
+            .map(|meta| meta.parent_slot)
+            .unwrap_or(0);
+        let previous_blockhash = if slot == 0 {
+            Hash::default()
+        } else {
+            self.blocktree
+                .get_slot_entries(parent_slot, 0, None)
+                .map_err(map_blocktree_err)?
+                .last()
+                .map(|entry| entry.hash)
+                .unwrap_or_default()
+        };
+
+        Ok(Some(RpcConfirmedBlock {
+            blockhash: entries
+                .last()
+                .map(|entry| entry.hash)
+                .unwrap_or_default()
+                .to_string(),
+            previous_blockhash: previous_blockhash.to_string(),
+            parent_slot,
+            transactions: entries
+                .into_iter()
+                .flat_map(|entry| entry.transactions)
+                .map(|transaction| {
+                    let status = transaction
+                        .signatures
+                        .first()
+                        .and_then(|signature| self.get_transaction_status_meta(*signature))
+                        .map(|meta| meta.status);
+                    (transaction, status)
+                })
+                .collect(),
+        }))
+    }
+
+    pub fn get_confirmed_transaction(
+        &self,
+        signature: Signature,
+    ) -> Result<Option<RpcConfirmedTransaction>> {
+        let confirmed_transaction = self
+            .blocktree
+            .get_confirmed_transaction(&signature)
+            .map_err(|err| {
+                info!("get_confirmed_transaction: blocktree error: {:?}", err);
+                Error::invalid_request()
+            })?;
+        Ok(confirmed_transaction.map(|(slot, transaction)| {
+            let meta = self.get_transaction_status_meta(signature);
+            RpcConfirmedTransaction {
+                slot,
+                transaction,
+                status: meta.as_ref().map(|meta| meta.status.clone()),
+                meta,
+            }
+        }))
+    }
+
+    pub fn simulate_transaction(&self, transaction: &Transaction) -> RpcSimulatedTransaction {
+        let (result, logs, accounts) = Bank::simulate_transaction(&self.bank(None), transaction);
+        RpcSimulatedTransaction {
+            result,
+            logs,
+            accounts: accounts
+                .into_iter()
+                .map(|(pubkey, account)| (pubkey.to_string(), account))
+                .collect(),
+        }
+    }
+
+    pub fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before: Option<Signature>,
+        limit: usize,
+    ) -> Result<Vec<(String, u64)>> {
+        if limit > MAX_GET_SIGNATURES_FOR_ADDRESS_LIMIT {
+            info!(
+                "get_signatures_for_address: limit too large: {} (max: {})",
+                limit, MAX_GET_SIGNATURES_FOR_ADDRESS_LIMIT
+            );
+            return Err(Error::invalid_request());
+        }
+        Ok(self
+            .blocktree
+            .get_signatures_for_address(address, before, limit)
+            .map_err(|err| {
+                info!("get_signatures_for_address: blocktree error: {:?}", err);
+                Error::invalid_request()
+            })?
+            .into_iter()
+            .map(|(slot, signature)| (signature.to_string(), slot))
+            .collect())
+    }
+
     fn get_storage_blockhash(&self) -> Result<String> {
         Ok(self.storage_state.get_storage_blockhash().to_string())
     }
@@ -169,6 +410,7 @@ fn verify_signature(input: &str) -> Result<Signature> {
 pub struct Meta {
     pub request_processor: Arc<RwLock<JsonRpcRequestProcessor>>,
     pub cluster_info: Arc<RwLock<ClusterInfo>>,
+    pub client_ip: Option<IpAddr>, // None for calls that did not arrive over HTTP
 }
 impl Metadata for Meta {}
 
@@ -200,6 +442,71 @@ pub struct RpcVoteAccountInfo {
     pub commission: u32,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountsHash {
+    /// The rooted slot the hash covers
+    pub slot: u64,
+
+    /// Merkle root over every account in the slot, as base-58 encoded string
+    pub hash: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcConfirmedBlock {
+    /// Hash of the last entry in the block, as base-58 encoded string
+    pub blockhash: String,
+
+    /// Hash of the last entry in the parent block, as base-58 encoded string
+    pub previous_blockhash: String,
+
+    /// The slot of the parent block
+    pub parent_slot: u64,
+
+    /// Transactions in ledger order, with the status recorded when they were committed
+    pub transactions: Vec<(Transaction, Option<transaction::Result<()>>)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RpcConfirmedTransaction {
+    /// The rooted slot the transaction was recorded in
+    pub slot: u64,
+
+    /// The transaction itself
+    pub transaction: Transaction,
+
+    /// The transaction status recorded when it was committed
+    pub status: Option<transaction::Result<()>>,
+
+    /// What was recorded about the transaction when it was committed
+    pub meta: Option<TransactionStatusMeta>,
+}
+
+/// The status of a signature, along with what was recorded about its transaction if that
+/// was requested
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum RpcSignatureStatus {
+    Status(transaction::Result<()>),
+    StatusWithMeta {
+        status: transaction::Result<()>,
+        meta: Option<TransactionStatusMeta>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RpcSimulatedTransaction {
+    /// The result of executing the transaction
+    pub result: transaction::Result<()>,
+
+    /// Messages logged by the transaction's programs
+    pub logs: Vec<String>,
+
+    /// Post-execution state of the writable accounts, empty if the transaction failed
+    pub accounts: Vec<(String, Account)>,
+}
+
 #[rpc(server)]
 pub trait RpcSol {
     type Metadata;
@@ -208,45 +515,108 @@ pub trait RpcSol {
     fn confirm_transaction(&self, _: Self::Metadata, _: String) -> Result<bool>;
 
     #[rpc(meta, name = "getAccountInfo")]
-    fn get_account_info(&self, _: Self::Metadata, _: String) -> Result<Account>;
+    fn get_account_info(
+        &self,
+        _: Self::Metadata,
+        _: String,
+        _: Option<CommitmentConfig>,
+    ) -> Result<Account>;
+
+    #[rpc(meta, name = "getMultipleAccounts")]
+    fn get_multiple_accounts(
+        &self,
+        _: Self::Metadata,
+        _: Vec<String>,
+    ) -> Result<Vec<Option<Account>>>;
+
+    #[rpc(meta, name = "getProgramAccounts")]
+    fn get_program_accounts(
+        &self,
+        _: Self::Metadata,
+        _: String,
+        _: Option<RpcProgramAccountsConfig>,
+    ) -> Result<Vec<(String, Account)>>;
 
     #[rpc(meta, name = "getBalance")]
-    fn get_balance(&self, _: Self::Metadata, _: String) -> Result<u64>;
+    fn get_balance(&self, _: Self::Metadata, _: String, _: Option<CommitmentConfig>)
+        -> Result<u64>;
 
     #[rpc(meta, name = "getClusterNodes")]
     fn get_cluster_nodes(&self, _: Self::Metadata) -> Result<Vec<RpcContactInfo>>;
 
     #[rpc(meta, name = "getRecentBlockhash")]
-    fn get_recent_blockhash(&self, _: Self::Metadata) -> Result<(String, FeeCalculator)>;
+    fn get_recent_blockhash(
+        &self,
+        _: Self::Metadata,
+        _: Option<CommitmentConfig>,
+    ) -> Result<(String, FeeCalculator)>;
 
     #[rpc(meta, name = "getSignatureStatus")]
     fn get_signature_status(
         &self,
         _: Self::Metadata,
         _: String,
-    ) -> Result<Option<transaction::Result<()>>>;
+        _: Option<bool>,
+    ) -> Result<Option<RpcSignatureStatus>>;
 
     #[rpc(meta, name = "getSlot")]
-    fn get_slot(&self, _: Self::Metadata) -> Result<u64>;
+    fn get_slot(&self, _: Self::Metadata, _: Option<CommitmentConfig>) -> Result<u64>;
 
     #[rpc(meta, name = "getTransactionCount")]
-    fn get_transaction_count(&self, _: Self::Metadata) -> Result<u64>;
+    fn get_transaction_count(&self, _: Self::Metadata, _: Option<CommitmentConfig>) -> Result<u64>;
 
     #[rpc(meta, name = "getTotalSupply")]
     fn get_total_supply(&self, _: Self::Metadata) -> Result<u64>;
 
+    #[rpc(meta, name = "getAccountsHash")]
+    fn get_accounts_hash(&self, _: Self::Metadata) -> Result<Option<RpcAccountsHash>>;
+
+    #[rpc(meta, name = "getMinimumBalanceForRentExemption")]
+    fn get_minimum_balance_for_rent_exemption(
+        &self,
+        _: Self::Metadata,
+        _: usize,
+        _: Option<CommitmentConfig>,
+    ) -> Result<u64>;
+
     #[rpc(meta, name = "requestAirdrop")]
     fn request_airdrop(&self, _: Self::Metadata, _: String, _: u64) -> Result<String>;
 
     #[rpc(meta, name = "sendTransaction")]
     fn send_transaction(&self, _: Self::Metadata, _: Vec<u8>) -> Result<String>;
 
+    #[rpc(meta, name = "simulateTransaction")]
+    fn simulate_transaction(
+        &self,
+        _: Self::Metadata,
+        _: Vec<u8>,
+    ) -> Result<RpcSimulatedTransaction>;
+
     #[rpc(meta, name = "getSlotLeader")]
     fn get_slot_leader(&self, _: Self::Metadata) -> Result<String>;
 
     #[rpc(meta, name = "getEpochVoteAccounts")]
     fn get_epoch_vote_accounts(&self, _: Self::Metadata) -> Result<Vec<RpcVoteAccountInfo>>;
 
+    #[rpc(meta, name = "getConfirmedBlock")]
+    fn get_confirmed_block(&self, _: Self::Metadata, _: u64) -> Result<Option<RpcConfirmedBlock>>;
+
+    #[rpc(meta, name = "getConfirmedTransaction")]
+    fn get_confirmed_transaction(
+        &self,
+        _: Self::Metadata,
+        _: String,
+    ) -> Result<Option<RpcConfirmedTransaction>>;
+
+    #[rpc(meta, name = "getSignaturesForAddress")]
+    fn get_signatures_for_address(
+        &self,
+        _: Self::Metadata,
+        _: String,
+        _: Option<String>,
+        _: Option<usize>,
+    ) -> Result<Vec<(String, u64)>>;
+
     #[rpc(meta, name = "getStorageBlockhash")]
     fn get_storage_blockhash(&self, _: Self::Metadata) -> Result<String>;
 
@@ -280,27 +650,75 @@ impl RpcSol for RpcSolImpl {
 
     fn confirm_transaction(&self, meta: Self::Metadata, id: String) -> Result<bool> {
         debug!("confirm_transaction rpc request received: {:?}", id);
-        self.get_signature_status(meta, id).map(|status_option| {
-            if status_option.is_none() {
-                return false;
-            }
-            status_option.unwrap().is_ok()
-        })
+        self.get_signature_confirmation(meta, id)
+            .map(|status_option| {
+                if status_option.is_none() {
+                    return false;
+                }
+                status_option.unwrap().1.is_ok()
+            })
     }
 
-    fn get_account_info(&self, meta: Self::Metadata, id: String) -> Result<Account> {
+    fn get_account_info(
+        &self,
+        meta: Self::Metadata,
+        id: String,
+        commitment: Option<CommitmentConfig>,
+    ) -> Result<Account> {
         debug!("get_account_info rpc request received: {:?}", id);
         let pubkey = verify_pubkey(id)?;
         meta.request_processor
             .read()
             .unwrap()
-            .get_account_info(&pubkey)
+            .get_account_info(&pubkey, commitment)
     }
 
-    fn get_balance(&self, meta: Self::Metadata, id: String) -> Result<u64> {
+    fn get_multiple_accounts(
+        &self,
+        meta: Self::Metadata,
+        ids: Vec<String>,
+    ) -> Result<Vec<Option<Account>>> {
+        debug!(
+            "get_multiple_accounts rpc request received: {:?}",
+            ids.len()
+        );
+        let pubkeys = ids
+            .into_iter()
+            .map(verify_pubkey)
+            .collect::<Result<Vec<_>>>()?;
+        meta.request_processor
+            .read()
+            .unwrap()
+            .get_multiple_accounts(&pubkeys)
+    }
+
+    fn get_program_accounts(
+        &self,
+        meta: Self::Metadata,
+        id: String,
+        config: Option<RpcProgramAccountsConfig>,
+    ) -> Result<Vec<(String, Account)>> {
+        debug!("get_program_accounts rpc request received: {:?}", id);
+        let program_id = verify_pubkey(id)?;
+        meta.request_processor
+            .read()
+            .unwrap()
+            .get_program_accounts(&program_id, config.unwrap_or_default())
+    }
+
+    fn get_balance(
+        &self,
+        meta: Self::Metadata,
+        id: String,
+        commitment: Option<CommitmentConfig>,
+    ) -> Result<u64> {
         debug!("get_balance rpc request received: {:?}", id);
         let pubkey = verify_pubkey(id)?;
-        Ok(meta.request_processor.read().unwrap().get_balance(&pubkey))
+        Ok(meta
+            .request_processor
+            .read()
+            .unwrap()
+            .get_balance(&pubkey, commitment))
     }
 
     fn get_cluster_nodes(&self, meta: Self::Metadata) -> Result<Vec<RpcContactInfo>> {
@@ -330,26 +748,44 @@ impl RpcSol for RpcSolImpl {
             .collect())
     }
 
-    fn get_recent_blockhash(&self, meta: Self::Metadata) -> Result<(String, FeeCalculator)> {
+    fn get_recent_blockhash(
+        &self,
+        meta: Self::Metadata,
+        commitment: Option<CommitmentConfig>,
+    ) -> Result<(String, FeeCalculator)> {
         debug!("get_recent_blockhash rpc request received");
         Ok(meta
             .request_processor
             .read()
             .unwrap()
-            .get_recent_blockhash())
+            .get_recent_blockhash(commitment))
     }
 
     fn get_signature_status(
         &self,
         meta: Self::Metadata,
         id: String,
-    ) -> Result<Option<transaction::Result<()>>> {
-        self.get_signature_confirmation(meta, id)
-            .map(|res| res.map(|x| x.1))
+        with_meta: Option<bool>,
+    ) -> Result<Option<RpcSignatureStatus>> {
+        let request_processor = meta.request_processor.clone();
+        let status = self
+            .get_signature_confirmation(meta, id.clone())?
+            .map(|x| x.1);
+        if !with_meta.unwrap_or(false) {
+            return Ok(status.map(RpcSignatureStatus::Status));
+        }
+        let signature = verify_signature(&id)?;
+        Ok(status.map(|status| RpcSignatureStatus::StatusWithMeta {
+            status,
+            meta: request_processor
+                .read()
+                .unwrap()
+                .get_transaction_status_meta(signature),
+        }))
     }
 
-    fn get_slot(&self, meta: Self::Metadata) -> Result<u64> {
-        meta.request_processor.read().unwrap().get_slot()
+    fn get_slot(&self, meta: Self::Metadata, commitment: Option<CommitmentConfig>) -> Result<u64> {
+        meta.request_processor.read().unwrap().get_slot(commitment)
     }
 
     fn get_num_blocks_since_signature_confirmation(
@@ -375,12 +811,16 @@ impl RpcSol for RpcSolImpl {
             .get_signature_confirmation_status(signature))
     }
 
-    fn get_transaction_count(&self, meta: Self::Metadata) -> Result<u64> {
+    fn get_transaction_count(
+        &self,
+        meta: Self::Metadata,
+        commitment: Option<CommitmentConfig>,
+    ) -> Result<u64> {
         debug!("get_transaction_count rpc request received");
         meta.request_processor
             .read()
             .unwrap()
-            .get_transaction_count()
+            .get_transaction_count(commitment)
     }
 
     fn get_total_supply(&self, meta: Self::Metadata) -> Result<u64> {
@@ -388,6 +828,27 @@ impl RpcSol for RpcSolImpl {
         meta.request_processor.read().unwrap().get_total_supply()
     }
 
+    fn get_accounts_hash(&self, meta: Self::Metadata) -> Result<Option<RpcAccountsHash>> {
+        debug!("get_accounts_hash rpc request received");
+        meta.request_processor.read().unwrap().get_accounts_hash()
+    }
+
+    fn get_minimum_balance_for_rent_exemption(
+        &self,
+        meta: Self::Metadata,
+        data_len: usize,
+        commitment: Option<CommitmentConfig>,
+    ) -> Result<u64> {
+        debug!(
+            "get_minimum_balance_for_rent_exemption rpc request received: {:?}",
+            data_len
+        );
+        meta.request_processor
+            .read()
+            .unwrap()
+            .get_minimum_balance_for_rent_exemption(data_len, commitment)
+    }
+
     fn request_airdrop(&self, meta: Self::Metadata, id: String, lamports: u64) -> Result<String> {
         trace!("request_airdrop id={} lamports={}", id, lamports);
 
@@ -404,7 +865,7 @@ impl RpcSol for RpcSolImpl {
             .request_processor
             .read()
             .unwrap()
-            .bank()
+            .bank(None)
             .confirmed_last_blockhash()
             .0;
         let transaction = request_airdrop_transaction(&drone_addr, &pubkey, lamports, blockhash)
@@ -479,6 +940,31 @@ impl RpcSol for RpcSolImpl {
         Ok(signature)
     }
 
+    fn simulate_transaction(
+        &self,
+        meta: Self::Metadata,
+        data: Vec<u8>,
+    ) -> Result<RpcSimulatedTransaction> {
+        debug!("simulate_transaction rpc request received");
+        let tx: Transaction = deserialize(&data).map_err(|err| {
+            info!("simulate_transaction: deserialize error: {:?}", err);
+            Error::invalid_request()
+        })?;
+        if data.len() >= PACKET_DATA_SIZE {
+            info!(
+                "simulate_transaction: transaction too large: {} bytes (max: {} bytes)",
+                data.len(),
+                PACKET_DATA_SIZE
+            );
+            return Err(Error::invalid_request());
+        }
+        Ok(meta
+            .request_processor
+            .read()
+            .unwrap()
+            .simulate_transaction(&tx))
+    }
+
     fn get_slot_leader(&self, meta: Self::Metadata) -> Result<String> {
         let cluster_info = meta.cluster_info.read().unwrap();
         let leader_data_option = cluster_info.leader_data();
@@ -495,6 +981,54 @@ impl RpcSol for RpcSolImpl {
             .get_epoch_vote_accounts()
     }
 
+    fn get_confirmed_block(
+        &self,
+        meta: Self::Metadata,
+        slot: u64,
+    ) -> Result<Option<RpcConfirmedBlock>> {
+        debug!("get_confirmed_block rpc request received: {:?}", slot);
+        meta.request_processor
+            .read()
+            .unwrap()
+            .get_confirmed_block(slot)
+    }
+
+    fn get_confirmed_transaction(
+        &self,
+        meta: Self::Metadata,
+        id: String,
+    ) -> Result<Option<RpcConfirmedTransaction>> {
+        debug!("get_confirmed_transaction rpc request received: {:?}", id);
+        let signature = verify_signature(&id)?;
+        meta.request_processor
+            .read()
+            .unwrap()
+            .get_confirmed_transaction(signature)
+    }
+
+    fn get_signatures_for_address(
+        &self,
+        meta: Self::Metadata,
+        id: String,
+        before: Option<String>,
+        limit: Option<usize>,
+    ) -> Result<Vec<(String, u64)>> {
+        debug!("get_signatures_for_address rpc request received: {:?}", id);
+        let address = verify_pubkey(id)?;
+        let before = match before {
+            Some(before) => Some(verify_signature(&before)?),
+            None => None,
+        };
+        meta.request_processor
+            .read()
+            .unwrap()
+            .get_signatures_for_address(
+                &address,
+                before,
+                limit.unwrap_or(MAX_GET_SIGNATURES_FOR_ADDRESS_LIMIT),
+            )
+    }
+
     fn get_storage_blockhash(&self, meta: Self::Metadata) -> Result<String> {
         meta.request_processor
             .read()
@@ -521,9 +1055,12 @@ impl RpcSol for RpcSolImpl {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::blocktree::get_tmp_ledger_path;
     use crate::contact_info::ContactInfo;
+    use crate::entry::next_entry;
     use crate::genesis_utils::{create_genesis_block, GenesisBlockInfo};
     use jsonrpc_core::{MetaIoHandler, Output, Response, Value};
+    use solana_client::rpc_filter::{Memcmp, RpcDataSlice};
     use solana_sdk::hash::{hash, Hash};
     use solana_sdk::instruction::InstructionError;
     use solana_sdk::signature::{Keypair, KeypairUtil};
@@ -535,22 +1072,32 @@ mod tests {
 
     fn start_rpc_handler_with_tx(
         pubkey: &Pubkey,
-    ) -> (MetaIoHandler<Meta>, Meta, Hash, Keypair, Pubkey) {
+    ) -> (TmpLedger, MetaIoHandler<Meta>, Meta, Hash, Keypair, Pubkey) {
         let (bank_forks, alice) = new_bank_forks();
         let bank = bank_forks.read().unwrap().working_bank();
         let exit = Arc::new(AtomicBool::new(false));
 
         let blockhash = bank.confirmed_last_blockhash().0;
-        let tx = system_transaction::transfer(&alice, pubkey, 20, blockhash);
-        bank.process_transaction(&tx).expect("process transaction");
+        let tx0 = system_transaction::transfer(&alice, pubkey, 20, blockhash);
+        bank.process_transaction(&tx0).expect("process transaction");
 
-        let tx = system_transaction::transfer(&alice, &alice.pubkey(), 20, blockhash);
-        let _ = bank.process_transaction(&tx);
+        let tx1 = system_transaction::transfer(&alice, &alice.pubkey(), 20, blockhash);
+        let _ = bank.process_transaction(&tx1);
+
+        // Record both transactions in the rooted slot 0
+        let (blocktree, ledger) = new_blocktree();
+        let entries = vec![next_entry(&blockhash, 1, vec![tx0, tx1])];
+        blocktree
+            .write_entries(0, 0, 0, bank.ticks_per_slot(), &entries)
+            .unwrap();
+        blocktree.index_transactions(0, &entries).unwrap();
+        blocktree.set_roots(&[0]).unwrap();
 
         let request_processor = Arc::new(RwLock::new(JsonRpcRequestProcessor::new(
             StorageState::default(),
             JsonRpcConfig::default(),
             bank_forks,
+            blocktree,
             &exit,
         )));
         let cluster_info = Arc::new(RwLock::new(ClusterInfo::new_with_invalid_keypair(
@@ -566,20 +1113,23 @@ mod tests {
         let meta = Meta {
             request_processor,
             cluster_info,
+            client_ip: None,
         };
-        (io, meta, blockhash, alice, leader.id)
+        (ledger, io, meta, blockhash, alice, leader.id)
     }
 
     #[test]
     fn test_rpc_request_processor_new() {
         let bob_pubkey = Pubkey::new_rand();
         let exit = Arc::new(AtomicBool::new(false));
+        let (blocktree, _ledger) = new_blocktree();
         let (bank_forks, alice) = new_bank_forks();
         let bank = bank_forks.read().unwrap().working_bank();
         let request_processor = JsonRpcRequestProcessor::new(
             StorageState::default(),
             JsonRpcConfig::default(),
             bank_forks,
+            blocktree,
             &exit,
         );
         thread::spawn(move || {
@@ -589,13 +1139,14 @@ mod tests {
         })
         .join()
         .unwrap();
-        assert_eq!(request_processor.get_transaction_count().unwrap(), 1);
+        assert_eq!(request_processor.get_transaction_count(None).unwrap(), 1);
     }
 
     #[test]
     fn test_rpc_get_balance() {
         let bob_pubkey = Pubkey::new_rand();
-        let (io, meta, _blockhash, _alice, _leader_pubkey) = start_rpc_handler_with_tx(&bob_pubkey);
+        let (_ledger, io, meta, _blockhash, _alice, _leader_pubkey) =
+            start_rpc_handler_with_tx(&bob_pubkey);
 
         let req = format!(
             r#"{{"jsonrpc":"2.0","id":1,"method":"getBalance","params":["{}"]}}"#,
@@ -610,10 +1161,93 @@ mod tests {
         assert_eq!(expected, result);
     }
 
+    #[test]
+    fn test_rpc_get_balance_with_commitment() {
+        let bob_pubkey = Pubkey::new_rand();
+        let (bank_forks, alice) = new_bank_forks();
+        let bank0 = bank_forks.read().unwrap()[0].clone();
+        bank0.freeze();
+        let bank1 = Bank::new_from_parent(&bank0, &Pubkey::default(), 1);
+        let tx = system_transaction::transfer(&alice, &bob_pubkey, 20, bank1.last_blockhash());
+        bank1.process_transaction(&tx).expect("process transaction");
+        bank_forks.write().unwrap().insert(bank1);
+
+        let exit = Arc::new(AtomicBool::new(false));
+        let (blocktree, _ledger) = new_blocktree();
+        let request_processor = JsonRpcRequestProcessor::new(
+            StorageState::default(),
+            JsonRpcConfig::default(),
+            bank_forks.clone(),
+            blocktree,
+            &exit,
+        );
+        let mut io = MetaIoHandler::default();
+        io.extend_with(RpcSolImpl.to_delegate());
+        let meta = Meta {
+            request_processor: Arc::new(RwLock::new(request_processor)),
+            cluster_info: Arc::new(RwLock::new(ClusterInfo::new_with_invalid_keypair(
+                ContactInfo::default(),
+            ))),
+            client_ip: None,
+        };
+
+        let request = |method: &str, params: Value| {
+            let req = json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": method,
+                "params": params,
+            });
+            let res = io.handle_request_sync(&req.to_string(), meta.clone());
+            let result: Value = serde_json::from_str(&res.expect("actual response"))
+                .expect("actual response deserialization");
+            result["result"].clone()
+        };
+        let get_balance = |commitment: &str| {
+            request(
+                "getBalance",
+                json!([bob_pubkey.to_string(), { "commitment": commitment }]),
+            )
+        };
+        let get_slot = |commitment: &str| request("getSlot", json!([{ "commitment": commitment }]));
+
+        assert_eq!(request("getBalance", json!([bob_pubkey.to_string()])), 20);
+        assert_eq!(get_balance("recent"), 20);
+        assert_eq!(get_balance("root"), 0);
+        assert_eq!(get_balance("max-confirmed"), 0);
+        assert_eq!(get_slot("recent"), 1);
+        assert_eq!(get_slot("root"), 0);
+        assert_eq!(get_slot("max-confirmed"), 0);
+        assert_eq!(
+            request(
+                "getAccountInfo",
+                json!([bob_pubkey.to_string(), {"commitment": "root"}])
+            ),
+            Value::Null
+        );
+
+        bank_forks.write().unwrap().set_confirmed_slot(1);
+        assert_eq!(get_balance("max-confirmed"), 20);
+        assert_eq!(get_slot("max-confirmed"), 1);
+        assert_eq!(get_balance("root"), 0);
+        assert_eq!(
+            request(
+                "getTransactionCount",
+                json!([{"commitment": "max-confirmed"}])
+            ),
+            1
+        );
+        assert_eq!(
+            request("getTransactionCount", json!([{"commitment": "root"}])),
+            0
+        );
+    }
+
     #[test]
     fn test_rpc_get_cluster_nodes() {
         let bob_pubkey = Pubkey::new_rand();
-        let (io, meta, _blockhash, _alice, leader_pubkey) = start_rpc_handler_with_tx(&bob_pubkey);
+        let (_ledger, io, meta, _blockhash, _alice, leader_pubkey) =
+            start_rpc_handler_with_tx(&bob_pubkey);
 
         let req = format!(r#"{{"jsonrpc":"2.0","id":1,"method":"getClusterNodes"}}"#);
         let res = io.handle_request_sync(&req, meta);
@@ -633,7 +1267,8 @@ mod tests {
     #[test]
     fn test_rpc_get_slot_leader() {
         let bob_pubkey = Pubkey::new_rand();
-        let (io, meta, _blockhash, _alice, _leader_pubkey) = start_rpc_handler_with_tx(&bob_pubkey);
+        let (_ledger, io, meta, _blockhash, _alice, _leader_pubkey) =
+            start_rpc_handler_with_tx(&bob_pubkey);
 
         let req = format!(r#"{{"jsonrpc":"2.0","id":1,"method":"getSlotLeader"}}"#);
         let res = io.handle_request_sync(&req, meta);
@@ -646,10 +1281,32 @@ mod tests {
         assert_eq!(expected, result);
     }
 
+    #[test]
+    fn test_rpc_get_minimum_balance_for_rent_exemption() {
+        let bob_pubkey = Pubkey::new_rand();
+        let data_len = 50;
+        let (_ledger, io, meta, _blockhash, _alice, _leader_pubkey) =
+            start_rpc_handler_with_tx(&bob_pubkey);
+        let bank = meta.request_processor.read().unwrap().bank(None);
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getMinimumBalanceForRentExemption","params":[{}]}}"#,
+            data_len
+        );
+        let res = io.handle_request_sync(&req, meta);
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        let minimum_balance: u64 = serde_json::from_value(json["result"].clone()).unwrap();
+        assert_eq!(
+            minimum_balance,
+            bank.get_minimum_balance_for_rent_exemption(data_len)
+        );
+    }
+
     #[test]
     fn test_rpc_get_tx_count() {
         let bob_pubkey = Pubkey::new_rand();
-        let (io, meta, _blockhash, _alice, _leader_pubkey) = start_rpc_handler_with_tx(&bob_pubkey);
+        let (_ledger, io, meta, _blockhash, _alice, _leader_pubkey) =
+            start_rpc_handler_with_tx(&bob_pubkey);
 
         let req = format!(r#"{{"jsonrpc":"2.0","id":1,"method":"getTransactionCount"}}"#);
         let res = io.handle_request_sync(&req, meta);
@@ -661,10 +1318,45 @@ mod tests {
         assert_eq!(expected, result);
     }
 
+    #[test]
+    fn test_rpc_get_accounts_hash() {
+        let bob_pubkey = Pubkey::new_rand();
+        let (_ledger, io, meta, _blockhash, _alice, _leader_pubkey) =
+            start_rpc_handler_with_tx(&bob_pubkey);
+
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"getAccountsHash"}"#;
+        let res = io.handle_request_sync(&req, meta.clone());
+        let expected = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+        let expected: Response =
+            serde_json::from_str(&expected).expect("expected response deserialization");
+        let result: Response = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert_eq!(expected, result);
+
+        // Squashing a child roots slot 0
+        let bank = meta.request_processor.read().unwrap().bank(None);
+        Bank::new_from_parent(&bank, &Pubkey::default(), 1).squash();
+        let (slot, hash) = bank.get_accounts_hash().unwrap();
+        assert_eq!(slot, 0);
+
+        let res = io.handle_request_sync(&req, meta);
+        let expected = json!({
+            "jsonrpc": "2.0",
+            "result": { "slot": 0, "hash": hash.to_string() },
+            "id": 1,
+        });
+        let expected: Response =
+            serde_json::from_value(expected).expect("expected response deserialization");
+        let result: Response = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert_eq!(expected, result);
+    }
+
     #[test]
     fn test_rpc_get_total_supply() {
         let bob_pubkey = Pubkey::new_rand();
-        let (io, meta, _blockhash, _alice, _leader_pubkey) = start_rpc_handler_with_tx(&bob_pubkey);
+        let (_ledger, io, meta, _blockhash, _alice, _leader_pubkey) =
+            start_rpc_handler_with_tx(&bob_pubkey);
 
         let req = format!(r#"{{"jsonrpc":"2.0","id":1,"method":"getTotalSupply"}}"#);
         let rep = io.handle_request_sync(&req, meta);
@@ -689,7 +1381,8 @@ mod tests {
     #[test]
     fn test_rpc_get_account_info() {
         let bob_pubkey = Pubkey::new_rand();
-        let (io, meta, _blockhash, _alice, _leader_pubkey) = start_rpc_handler_with_tx(&bob_pubkey);
+        let (_ledger, io, meta, _blockhash, _alice, _leader_pubkey) =
+            start_rpc_handler_with_tx(&bob_pubkey);
 
         let req = format!(
             r#"{{"jsonrpc":"2.0","id":1,"method":"getAccountInfo","params":["{}"]}}"#,
@@ -702,7 +1395,8 @@ mod tests {
                 "owner": [0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0],
                 "lamports": 20,
                 "data": [],
-                "executable": false
+                "executable": false,
+                "rentEpoch": 0
             },
             "id":1}
         "#;
@@ -713,10 +1407,302 @@ mod tests {
         assert_eq!(expected, result);
     }
 
+    #[test]
+    fn test_rpc_get_multiple_accounts() {
+        let bob_pubkey = Pubkey::new_rand();
+        let (_ledger, io, meta, _blockhash, _alice, _leader_pubkey) =
+            start_rpc_handler_with_tx(&bob_pubkey);
+        let missing_pubkey = Pubkey::new_rand();
+
+        let req = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "getMultipleAccounts",
+            "params": [[bob_pubkey.to_string(), missing_pubkey.to_string()]],
+        });
+        let res = io.handle_request_sync(&req.to_string(), meta.clone());
+        let expected = json!({
+            "jsonrpc": "2.0",
+            "result": [
+                {
+                    "owner": [0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0],
+                    "lamports": 20,
+                    "data": [],
+                    "executable": false,
+                    "rentEpoch": 0
+                },
+                null
+            ],
+            "id": 1
+        });
+        let expected: Response =
+            serde_json::from_value(expected).expect("expected response deserialization");
+        let result: Response = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert_eq!(expected, result);
+
+        // Bad pubkeys and oversized requests are rejected
+        let req = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "getMultipleAccounts",
+            "params": [[bob_pubkey.to_string(), "a1b2c3d4"]],
+        });
+        let res = io.handle_request_sync(&req.to_string(), meta.clone());
+        let expected =
+            r#"{"jsonrpc":"2.0","error":{"code":-32600,"message":"Invalid request"},"id":1}"#;
+        let expected: Response =
+            serde_json::from_str(expected).expect("expected response deserialization");
+        let result: Response = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert_eq!(expected, result);
+
+        let pubkeys: Vec<_> = (0..=MAX_MULTIPLE_ACCOUNTS)
+            .map(|_| Pubkey::new_rand().to_string())
+            .collect();
+        let req = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "getMultipleAccounts",
+            "params": [pubkeys],
+        });
+        let res = io.handle_request_sync(&req.to_string(), meta);
+        let result: Response = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn test_rpc_get_program_accounts() {
+        let bob_pubkey = Pubkey::new_rand();
+        let (_ledger, io, meta, _blockhash, _alice, _leader_pubkey) =
+            start_rpc_handler_with_tx(&bob_pubkey);
+
+        let program_id = Pubkey::new_rand();
+        let bank = meta.request_processor.read().unwrap().bank(None);
+        let pubkey0 = Pubkey::new_rand();
+        let mut account0 = Account::new(1, 5, &program_id);
+        account0.data = vec![1, 2, 3, 4, 5];
+        bank.store_account(&pubkey0, &account0);
+        let pubkey1 = Pubkey::new_rand();
+        let mut account1 = Account::new(1, 2, &program_id);
+        account1.data = vec![2, 3];
+        bank.store_account(&pubkey1, &account1);
+
+        let get_program_accounts = |params: Value| {
+            let req = json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "getProgramAccounts",
+                "params": params,
+            });
+            let res = io.handle_request_sync(&req.to_string(), meta.clone());
+            let result: Value = serde_json::from_str(&res.expect("actual response"))
+                .expect("actual response deserialization");
+            result
+        };
+        let get_accounts = |params: Value| {
+            let mut accounts: Vec<(String, Account)> =
+                serde_json::from_value(get_program_accounts(params)["result"].clone())
+                    .expect("program accounts deserialization");
+            accounts.sort_by(|a, b| a.0.cmp(&b.0));
+            accounts
+        };
+
+        let mut expected = vec![
+            (pubkey0.to_string(), account0.clone()),
+            (pubkey1.to_string(), account1.clone()),
+        ];
+        expected.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(get_accounts(json!([program_id.to_string()])), expected);
+
+        let memcmp = RpcFilterType::Memcmp(Memcmp::new(1, &[2, 3]));
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![memcmp]),
+            data_slice: None,
+        };
+        assert_eq!(
+            get_accounts(json!([program_id.to_string(), config])),
+            vec![(pubkey0.to_string(), account0.clone())]
+        );
+
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![RpcFilterType::DataSize(2)]),
+            data_slice: Some(RpcDataSlice {
+                offset: 1,
+                length: 4,
+            }),
+        };
+        let mut sliced_account1 = account1.clone();
+        sliced_account1.data = vec![3];
+        assert_eq!(
+            get_accounts(json!([program_id.to_string(), config])),
+            vec![(pubkey1.to_string(), sliced_account1)]
+        );
+
+        let bad_memcmp = RpcFilterType::Memcmp(Memcmp {
+            offset: 0,
+            bytes: "0OIl".to_string(),
+        });
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![bad_memcmp]),
+            data_slice: None,
+        };
+        let result = get_program_accounts(json!([program_id.to_string(), config]));
+        assert_eq!(result["error"]["code"], json!(-32600));
+    }
+
+    #[test]
+    fn test_rpc_get_confirmed_block() {
+        let bob_pubkey = Pubkey::new_rand();
+        let (_ledger, io, meta, blockhash, alice, _leader_pubkey) =
+            start_rpc_handler_with_tx(&bob_pubkey);
+
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"getConfirmedBlock","params":[0]}"#;
+        let res = io.handle_request_sync(&req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        let confirmed_block: RpcConfirmedBlock =
+            serde_json::from_value(result["result"].clone()).unwrap();
+        assert_eq!(confirmed_block.parent_slot, 0);
+        assert_eq!(
+            confirmed_block.previous_blockhash,
+            Hash::default().to_string()
+        );
+
+        let transactions = confirmed_block.transactions;
+        let entry = next_entry(
+            &blockhash,
+            1,
+            transactions.iter().map(|(tx, _)| tx.clone()).collect(),
+        );
+        assert_eq!(confirmed_block.blockhash, entry.hash.to_string());
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(
+            transactions[0].0,
+            system_transaction::transfer(&alice, &bob_pubkey, 20, blockhash)
+        );
+        assert_eq!(transactions[0].1, Some(Ok(())));
+        assert_eq!(
+            transactions[1].1,
+            meta.request_processor
+                .read()
+                .unwrap()
+                .get_transaction_status_meta(transactions[1].0.signatures[0])
+                .map(|meta| meta.status)
+        );
+
+        // Slots that are not rooted are not returned
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"getConfirmedBlock","params":[1]}"#;
+        let res = io.handle_request_sync(&req, meta);
+        let expected = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+        let expected: Response =
+            serde_json::from_str(&expected).expect("expected response deserialization");
+        let result: Response = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn test_rpc_get_confirmed_transaction() {
+        let bob_pubkey = Pubkey::new_rand();
+        let (_ledger, io, meta, blockhash, alice, _leader_pubkey) =
+            start_rpc_handler_with_tx(&bob_pubkey);
+        let tx = system_transaction::transfer(&alice, &bob_pubkey, 20, blockhash);
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getConfirmedTransaction","params":["{}"]}}"#,
+            tx.signatures[0]
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        let confirmed_transaction: Option<RpcConfirmedTransaction> =
+            serde_json::from_value(result["result"].clone()).unwrap();
+        let transaction_meta = meta
+            .request_processor
+            .read()
+            .unwrap()
+            .get_transaction_status_meta(tx.signatures[0]);
+        assert_eq!(
+            confirmed_transaction,
+            Some(RpcConfirmedTransaction {
+                slot: 0,
+                transaction: tx,
+                status: Some(Ok(())),
+                meta: transaction_meta.clone(),
+            })
+        );
+        let transaction_meta = transaction_meta.unwrap();
+        assert_eq!(transaction_meta.pre_balances[1], 0);
+        assert_eq!(transaction_meta.post_balances[1], 20);
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getConfirmedTransaction","params":["{}"]}}"#,
+            Signature::new(&[1; 64])
+        );
+        let res = io.handle_request_sync(&req, meta);
+        let expected = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+        let expected: Response =
+            serde_json::from_str(&expected).expect("expected response deserialization");
+        let result: Response = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn test_rpc_get_signatures_for_address() {
+        let bob_pubkey = Pubkey::new_rand();
+        let (_ledger, io, meta, blockhash, alice, _leader_pubkey) =
+            start_rpc_handler_with_tx(&bob_pubkey);
+        let tx0 = system_transaction::transfer(&alice, &bob_pubkey, 20, blockhash);
+        let tx1 = system_transaction::transfer(&alice, &alice.pubkey(), 20, blockhash);
+
+        let get_signatures = |params: Value| {
+            let req = json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "getSignaturesForAddress",
+                "params": params,
+            });
+            let res = io.handle_request_sync(&req.to_string(), meta.clone());
+            let result: Value = serde_json::from_str(&res.expect("actual response"))
+                .expect("actual response deserialization");
+            result
+        };
+
+        let result = get_signatures(json!([bob_pubkey.to_string()]));
+        let signatures: Vec<(String, u64)> =
+            serde_json::from_value(result["result"].clone()).unwrap();
+        assert_eq!(signatures, vec![(tx0.signatures[0].to_string(), 0)]);
+
+        let result = get_signatures(json!([alice.pubkey().to_string(), null, 1]));
+        let first_page: Vec<(String, u64)> =
+            serde_json::from_value(result["result"].clone()).unwrap();
+        assert_eq!(first_page.len(), 1);
+        let result = get_signatures(json!([alice.pubkey().to_string(), first_page[0].0]));
+        let second_page: Vec<(String, u64)> =
+            serde_json::from_value(result["result"].clone()).unwrap();
+        assert_eq!(second_page.len(), 1);
+        let mut all_signatures = vec![first_page[0].0.clone(), second_page[0].0.clone()];
+        all_signatures.sort();
+        let mut expected = vec![tx0.signatures[0].to_string(), tx1.signatures[0].to_string()];
+        expected.sort();
+        assert_eq!(all_signatures, expected);
+
+        let result = get_signatures(json!([
+            bob_pubkey.to_string(),
+            null,
+            MAX_GET_SIGNATURES_FOR_ADDRESS_LIMIT + 1
+        ]));
+        assert_eq!(result["error"]["code"], -32600);
+    }
+
     #[test]
     fn test_rpc_confirm_tx() {
         let bob_pubkey = Pubkey::new_rand();
-        let (io, meta, blockhash, alice, _leader_pubkey) = start_rpc_handler_with_tx(&bob_pubkey);
+        let (_ledger, io, meta, blockhash, alice, _leader_pubkey) =
+            start_rpc_handler_with_tx(&bob_pubkey);
         let tx = system_transaction::transfer(&alice, &bob_pubkey, 20, blockhash);
 
         let req = format!(
@@ -735,7 +1721,8 @@ mod tests {
     #[test]
     fn test_rpc_get_signature_status() {
         let bob_pubkey = Pubkey::new_rand();
-        let (io, meta, blockhash, alice, _leader_pubkey) = start_rpc_handler_with_tx(&bob_pubkey);
+        let (_ledger, io, meta, blockhash, alice, _leader_pubkey) =
+            start_rpc_handler_with_tx(&bob_pubkey);
         let tx = system_transaction::transfer(&alice, &bob_pubkey, 20, blockhash);
 
         let req = format!(
@@ -755,6 +1742,30 @@ mod tests {
             .expect("actual response deserialization");
         assert_eq!(expected, result);
 
+        // Test getSignatureStatus request with the transaction status meta
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getSignatureStatus","params":["{}", true]}}"#,
+            tx.signatures[0]
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        let signature_status: Option<RpcSignatureStatus> =
+            serde_json::from_value(result["result"].clone()).unwrap();
+        let transaction_meta = meta
+            .request_processor
+            .read()
+            .unwrap()
+            .get_transaction_status_meta(tx.signatures[0]);
+        assert!(transaction_meta.is_some());
+        assert_eq!(
+            signature_status,
+            Some(RpcSignatureStatus::StatusWithMeta {
+                status: Ok(()),
+                meta: transaction_meta,
+            })
+        );
+
         // Test getSignatureStatus request on unprocessed tx
         let tx = system_transaction::transfer(&alice, &bob_pubkey, 10, blockhash);
         let req = format!(
@@ -799,7 +1810,8 @@ mod tests {
     #[test]
     fn test_rpc_get_recent_blockhash() {
         let bob_pubkey = Pubkey::new_rand();
-        let (io, meta, blockhash, _alice, _leader_pubkey) = start_rpc_handler_with_tx(&bob_pubkey);
+        let (_ledger, io, meta, blockhash, _alice, _leader_pubkey) =
+            start_rpc_handler_with_tx(&bob_pubkey);
 
         let req = format!(r#"{{"jsonrpc":"2.0","id":1,"method":"getRecentBlockhash"}}"#);
         let res = io.handle_request_sync(&req, meta);
@@ -824,7 +1836,8 @@ mod tests {
     #[test]
     fn test_rpc_fail_request_airdrop() {
         let bob_pubkey = Pubkey::new_rand();
-        let (io, meta, _blockhash, _alice, _leader_pubkey) = start_rpc_handler_with_tx(&bob_pubkey);
+        let (_ledger, io, meta, _blockhash, _alice, _leader_pubkey) =
+            start_rpc_handler_with_tx(&bob_pubkey);
 
         // Expect internal error because no drone is available
         let req = format!(
@@ -841,9 +1854,60 @@ mod tests {
         assert_eq!(expected, result);
     }
 
+    #[test]
+    fn test_rpc_simulate_transaction() {
+        let bob_pubkey = Pubkey::new_rand();
+        let (_ledger, io, meta, blockhash, alice, _leader_pubkey) =
+            start_rpc_handler_with_tx(&bob_pubkey);
+        let tx = system_transaction::transfer(&alice, &bob_pubkey, 10, blockhash);
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"simulateTransaction","params":[{}]}}"#,
+            json!(serialize(&tx).unwrap())
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        let simulated: RpcSimulatedTransaction =
+            serde_json::from_value(result["result"].clone()).unwrap();
+        assert_eq!(simulated.result, Ok(()));
+        assert!(simulated.logs.is_empty());
+        let bob_account = simulated
+            .accounts
+            .iter()
+            .find(|(pubkey, _)| *pubkey == bob_pubkey.to_string())
+            .map(|(_, account)| account.clone())
+            .unwrap();
+        assert_eq!(bob_account.lamports, 30);
+
+        // The simulated transfer was not committed
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getBalance","params":["{}"]}}"#,
+            bob_pubkey
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let expected = r#"{"jsonrpc":"2.0","result":20,"id":1}"#;
+        let expected: Response =
+            serde_json::from_str(&expected).expect("expected response deserialization");
+        let result: Response = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert_eq!(expected, result);
+
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"simulateTransaction","params":[[0,0,0,0,0,0,0,0]]}"#;
+        let res = io.handle_request_sync(req, meta);
+        let expected =
+            r#"{"jsonrpc":"2.0","error":{"code":-32600,"message":"Invalid request"},"id":1}"#;
+        let expected: Response =
+            serde_json::from_str(expected).expect("expected response deserialization");
+        let result: Response = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert_eq!(expected, result);
+    }
+
     #[test]
     fn test_rpc_send_bad_tx() {
         let exit = Arc::new(AtomicBool::new(false));
+        let (blocktree, _ledger) = new_blocktree();
 
         let mut io = MetaIoHandler::default();
         let rpc = RpcSolImpl;
@@ -854,6 +1918,7 @@ mod tests {
                     StorageState::default(),
                     JsonRpcConfig::default(),
                     new_bank_forks().0,
+                    blocktree,
                     &exit,
                 );
                 Arc::new(RwLock::new(request_processor))
@@ -861,6 +1926,7 @@ mod tests {
             cluster_info: Arc::new(RwLock::new(ClusterInfo::new_with_invalid_keypair(
                 ContactInfo::default(),
             ))),
+            client_ip: None,
         };
 
         let req =
@@ -924,13 +1990,32 @@ mod tests {
         )
     }
 
+    /// Destroys the ledger of a test once dropped, after the blocktree holding it open is gone
+    struct TmpLedger {
+        path: String,
+    }
+
+    impl Drop for TmpLedger {
+        fn drop(&mut self) {
+            let _ignored = Blocktree::destroy(&self.path);
+        }
+    }
+
+    fn new_blocktree() -> (Arc<Blocktree>, TmpLedger) {
+        let path = get_tmp_ledger_path!();
+        let blocktree = Arc::new(Blocktree::open(&path).unwrap());
+        (blocktree, TmpLedger { path })
+    }
+
     #[test]
     fn test_rpc_request_processor_config_default_trait_fullnode_exit_fails() {
         let exit = Arc::new(AtomicBool::new(false));
+        let (blocktree, _ledger) = new_blocktree();
         let request_processor = JsonRpcRequestProcessor::new(
             StorageState::default(),
             JsonRpcConfig::default(),
             new_bank_forks().0,
+            blocktree,
             &exit,
         );
         assert_eq!(request_processor.fullnode_exit(), Ok(false));
@@ -940,12 +2025,14 @@ mod tests {
     #[test]
     fn test_rpc_request_processor_allow_fullnode_exit_config() {
         let exit = Arc::new(AtomicBool::new(false));
+        let (blocktree, _ledger) = new_blocktree();
         let mut config = JsonRpcConfig::default();
         config.enable_fullnode_exit = true;
         let request_processor = JsonRpcRequestProcessor::new(
             StorageState::default(),
             config,
             new_bank_forks().0,
+            blocktree,
             &exit,
         );
         assert_eq!(request_processor.fullnode_exit(), Ok(true));
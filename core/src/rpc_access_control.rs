@@ -0,0 +1,257 @@
+//! The `rpc_access_control` module implements the JSON RPC middleware that enforces the method
+//! allow/deny lists and per-client request quotas of a `JsonRpcConfig`.
+
+use crate::rpc::{JsonRpcConfig, Meta};
+use jsonrpc_core::futures::future::{self, Either, FutureResult};
+use jsonrpc_core::futures::Future;
+use jsonrpc_core::{Call, Error, ErrorCode, Failure, Middleware, Output, Version};
+use jsonrpc_http_server::hyper;
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Methods that are only served on the admin listener once one is configured
+pub const ADMIN_METHODS: &[&str] = &["fullnodeExit"];
+
+/// Name snapshot archive downloads are checked under against the method lists and quotas
+pub const SNAPSHOT_DOWNLOAD_METHOD: &str = "getSnapshotArchive";
+
+/// JSON RPC error code returned once a client exceeds its request quota
+pub const RATE_LIMITED_ERROR_CODE: i64 = -32005;
+
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(1);
+
+/// Quota windows are pruned once this many clients are tracked
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// Quota key shared by the requests that name no client
+const UNKNOWN_CLIENT: IpAddr = IpAddr::V4(Ipv4Addr::UNSPECIFIED);
+
+/// Identify the client of an HTTP request.  The JSON RPC server does not expose the address of
+/// the connection a request arrived on, so the client is the one named by the reverse proxy the
+/// public listener is expected to sit behind, skipping the hops added by `trusted_proxies`
+pub(crate) fn client_ip(
+    request: &hyper::Request<hyper::Body>,
+    trusted_proxies: &HashSet<IpAddr>,
+) -> IpAddr {
+    let headers = request.headers();
+    let hops: Vec<&str> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .collect();
+    if hops.is_empty() {
+        return headers
+            .get("x-real-ip")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(UNKNOWN_CLIENT);
+    }
+
+    // Each proxy appends the address it received the request from, so the client is the
+    // nearest hop that is not another trusted proxy
+    for hop in hops.iter().rev() {
+        match hop.trim().parse() {
+            Ok(ip) if trusted_proxies.contains(&ip) => continue,
+            Ok(ip) => return ip,
+            Err(_) => break,
+        }
+    }
+    UNKNOWN_CLIENT
+}
+
+/// Counts each client's calls within fixed one-second windows
+struct RateLimiter {
+    max_requests_per_second: u64,
+    windows: Mutex<HashMap<IpAddr, (Instant, u64)>>,
+}
+
+impl RateLimiter {
+    fn new(max_requests_per_second: u64) -> Self {
+        Self {
+            max_requests_per_second,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record a call from `client`, returning false if it exceeds the client's quota
+    fn check(&self, client: IpAddr, now: Instant) -> bool {
+        let mut windows = self.windows.lock().unwrap();
+        if windows.len() >= MAX_TRACKED_CLIENTS && !windows.contains_key(&client) {
+            windows.retain(|_, (start, _)| now.duration_since(*start) < RATE_LIMIT_WINDOW);
+        }
+        let (start, count) = windows.entry(client).or_insert((now, 0));
+        if now.duration_since(*start) >= RATE_LIMIT_WINDOW {
+            *start = now;
+            *count = 0;
+        }
+        *count += 1;
+        *count <= self.max_requests_per_second
+    }
+}
+
+#[derive(Clone)]
+pub struct RpcAccessControl {
+    allowed_methods: Option<HashSet<String>>,
+    denied_methods: HashSet<String>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+impl RpcAccessControl {
+    /// Access control for the public listener
+    pub fn new(config: &JsonRpcConfig) -> Self {
+        let mut denied_methods = config.denied_methods.clone();
+        if config.admin_rpc_path.is_some() {
+            denied_methods.extend(ADMIN_METHODS.iter().map(|method| method.to_string()));
+        }
+        Self {
+            allowed_methods: config.allowed_methods.clone(),
+            denied_methods,
+            rate_limiter: config
+                .max_requests_per_second_per_ip
+                .map(|max_requests_per_second| Arc::new(RateLimiter::new(max_requests_per_second))),
+        }
+    }
+
+    /// Access control for the admin listener, which serves every method without limits
+    pub fn new_admin() -> Self {
+        Self {
+            allowed_methods: None,
+            denied_methods: HashSet::new(),
+            rate_limiter: None,
+        }
+    }
+
+    fn is_method_allowed(&self, method: &str) -> bool {
+        !self.denied_methods.contains(method)
+            && self
+                .allowed_methods
+                .as_ref()
+                .map_or(true, |allowed_methods| allowed_methods.contains(method))
+    }
+
+    pub(crate) fn check_call(&self, method: &str, client: Option<IpAddr>) -> Result<(), Error> {
+        if !self.is_method_allowed(method) {
+            info!("rpc method {} denied", method);
+            return Err(Error::method_not_found());
+        }
+        // Calls that did not arrive over HTTP have no client to charge
+        if let (Some(rate_limiter), Some(client)) = (&self.rate_limiter, client) {
+            if !rate_limiter.check(client, Instant::now()) {
+                info!("rpc client {} exceeded its request quota", client);
+                return Err(Error {
+                    code: ErrorCode::ServerError(RATE_LIMITED_ERROR_CODE),
+                    message: "Too many requests".to_string(),
+                    data: None,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Middleware<Meta> for RpcAccessControl {
+    type Future = Box<dyn Future<Item = Option<jsonrpc_core::Response>, Error = ()> + Send>;
+    type CallFuture = FutureResult<Option<Output>, ()>;
+
+    fn on_call<F, X>(&self, call: Call, meta: Meta, next: F) -> Either<Self::CallFuture, X>
+    where
+        F: Fn(Call, Meta) -> X + Send + Sync,
+        X: Future<Item = Option<Output>, Error = ()> + Send + 'static,
+    {
+        let result = match &call {
+            Call::MethodCall(method_call) => self
+                .check_call(&method_call.method, meta.client_ip)
+                .map_err(|error| Some((error, method_call.id.clone()))),
+            Call::Notification(notification) => self
+                .check_call(&notification.method, meta.client_ip)
+                .map_err(|_| None),
+            Call::Invalid { .. } => Ok(()),
+        };
+        match result {
+            Ok(()) => Either::B(next(call, meta)),
+            Err(failure) => Either::A(future::ok(failure.map(|(error, id)| {
+                Output::Failure(Failure {
+                    jsonrpc: Some(Version::V2),
+                    error,
+                    id,
+                })
+            }))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_rate_limiter() {
+        let rate_limiter = RateLimiter::new(2);
+        let alice = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let bob = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+        let now = Instant::now();
+
+        assert!(rate_limiter.check(alice, now));
+        assert!(rate_limiter.check(alice, now));
+        assert!(!rate_limiter.check(alice, now));
+        assert!(rate_limiter.check(bob, now));
+
+        // The quota resets once the window passes
+        assert!(rate_limiter.check(alice, now + RATE_LIMIT_WINDOW));
+    }
+
+    #[test]
+    fn test_method_lists() {
+        let mut config = JsonRpcConfig::default();
+        assert!(RpcAccessControl::new(&config).is_method_allowed("fullnodeExit"));
+
+        config.admin_rpc_path = Some(PathBuf::from("admin.rpc"));
+        config.denied_methods.insert("requestAirdrop".to_string());
+        let access_control = RpcAccessControl::new(&config);
+        assert!(!access_control.is_method_allowed("fullnodeExit"));
+        assert!(!access_control.is_method_allowed("requestAirdrop"));
+        assert!(access_control.is_method_allowed("getBalance"));
+        assert!(RpcAccessControl::new_admin().is_method_allowed("fullnodeExit"));
+
+        config.allowed_methods = Some(vec!["getBalance".to_string()].into_iter().collect());
+        let access_control = RpcAccessControl::new(&config);
+        assert!(access_control.is_method_allowed("getBalance"));
+        assert!(!access_control.is_method_allowed("getSlot"));
+    }
+
+    #[test]
+    fn test_client_ip() {
+        let ip = |ip: &str| ip.parse::<IpAddr>().unwrap();
+        let request = |name: &str, value: &str| {
+            hyper::Request::builder()
+                .header(name, value)
+                .body(hyper::Body::empty())
+                .unwrap()
+        };
+        let trusted: HashSet<IpAddr> = vec![ip("10.0.0.100"), ip("10.0.0.101")]
+            .into_iter()
+            .collect();
+
+        // The client is the last hop appended by the proxy, whatever it prepended itself
+        let forwarded = request("x-forwarded-for", "10.0.0.1, 10.0.0.2");
+        assert_eq!(client_ip(&forwarded, &HashSet::new()), ip("10.0.0.2"));
+        let forwarded = request("x-forwarded-for", "10.0.0.1, 10.0.0.2, 10.0.0.101");
+        assert_eq!(client_ip(&forwarded, &trusted), ip("10.0.0.2"));
+        let forwarded = request("x-forwarded-for", "10.0.0.100, 10.0.0.101");
+        assert_eq!(client_ip(&forwarded, &trusted), UNKNOWN_CLIENT);
+        let forwarded = request("x-forwarded-for", "garbage");
+        assert_eq!(client_ip(&forwarded, &trusted), UNKNOWN_CLIENT);
+
+        let forwarded = request("x-real-ip", "10.0.0.3");
+        assert_eq!(client_ip(&forwarded, &trusted), ip("10.0.0.3"));
+
+        let direct = hyper::Request::builder()
+            .body(hyper::Body::empty())
+            .unwrap();
+        assert_eq!(client_ip(&direct, &trusted), UNKNOWN_CLIENT);
+    }
+}
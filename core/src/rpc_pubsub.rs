@@ -1,6 +1,6 @@
 //! The `pubsub` module implements a threaded subscription service on client RPC request
 
-use crate::rpc_subscriptions::{Confirmations, RpcSubscriptions};
+use crate::rpc_subscriptions::{Confirmations, RpcSubscriptions, SlotInfo};
 use jsonrpc_core::{Error, ErrorCode, Result};
 use jsonrpc_derive::rpc;
 use jsonrpc_pubsub::typed::Subscriber;
@@ -83,6 +83,30 @@ pub trait RpcSolPubSub {
         name = "signatureUnsubscribe"
     )]
     fn signature_unsubscribe(&self, _: Option<Self::Metadata>, _: SubscriptionId) -> Result<bool>;
+
+    // Get notification when a new bank is created for a slot
+    #[pubsub(subscription = "slotNotification", subscribe, name = "slotSubscribe")]
+    fn slot_subscribe(&self, _: Self::Metadata, _: Subscriber<SlotInfo>);
+
+    // Unsubscribe from slot notification subscription.
+    #[pubsub(
+        subscription = "slotNotification",
+        unsubscribe,
+        name = "slotUnsubscribe"
+    )]
+    fn slot_unsubscribe(&self, _: Option<Self::Metadata>, _: SubscriptionId) -> Result<bool>;
+
+    // Get notification when the root slot advances
+    #[pubsub(subscription = "rootNotification", subscribe, name = "rootSubscribe")]
+    fn root_subscribe(&self, _: Self::Metadata, _: Subscriber<u64>);
+
+    // Unsubscribe from root notification subscription.
+    #[pubsub(
+        subscription = "rootNotification",
+        unsubscribe,
+        name = "rootUnsubscribe"
+    )]
+    fn root_unsubscribe(&self, _: Option<Self::Metadata>, _: SubscriptionId) -> Result<bool>;
 }
 
 #[derive(Default)]
@@ -232,6 +256,48 @@ impl RpcSolPubSub for RpcSolPubSubImpl {
             })
         }
     }
+
+    fn slot_subscribe(&self, _meta: Self::Metadata, subscriber: Subscriber<SlotInfo>) {
+        let id = self.uid.fetch_add(1, atomic::Ordering::Relaxed);
+        let sub_id = SubscriptionId::Number(id as u64);
+        info!("slot_subscribe: id={:?}", sub_id);
+        let sink = subscriber.assign_id(sub_id.clone()).unwrap();
+        self.subscriptions.add_slot_subscription(&sub_id, &sink);
+    }
+
+    fn slot_unsubscribe(&self, _meta: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool> {
+        info!("slot_unsubscribe: id={:?}", id);
+        if self.subscriptions.remove_slot_subscription(&id) {
+            Ok(true)
+        } else {
+            Err(Error {
+                code: ErrorCode::InvalidParams,
+                message: "Invalid Request: Subscription id does not exist".into(),
+                data: None,
+            })
+        }
+    }
+
+    fn root_subscribe(&self, _meta: Self::Metadata, subscriber: Subscriber<u64>) {
+        let id = self.uid.fetch_add(1, atomic::Ordering::Relaxed);
+        let sub_id = SubscriptionId::Number(id as u64);
+        info!("root_subscribe: id={:?}", sub_id);
+        let sink = subscriber.assign_id(sub_id.clone()).unwrap();
+        self.subscriptions.add_root_subscription(&sub_id, &sink);
+    }
+
+    fn root_unsubscribe(&self, _meta: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool> {
+        info!("root_unsubscribe: id={:?}", id);
+        if self.subscriptions.remove_root_subscription(&id) {
+            Ok(true)
+        } else {
+            Err(Error {
+                code: ErrorCode::InvalidParams,
+                message: "Invalid Request: Subscription id does not exist".into(),
+                data: None,
+            })
+        }
+    }
 }
 
 #[cfg(test)]
@@ -429,6 +495,7 @@ mod tests {
                    "lamports": 51,
                    "data": expected_data,
                     "executable": executable,
+                   "rentEpoch": 0,
                },
                "subscription": 0,
            }
@@ -573,6 +640,7 @@ mod tests {
                    "lamports": 100,
                    "data": [],
                    "executable": false,
+                   "rentEpoch": 0,
                },
                "subscription": 0,
            }
@@ -581,4 +649,93 @@ mod tests {
             assert_eq!(serde_json::to_string(&expected).unwrap(), response);
         }
     }
+
+    #[test]
+    fn test_slot_subscribe() {
+        let rpc = RpcSolPubSubImpl::default();
+        let session = create_session();
+        let (subscriber, _id_receiver, mut receiver) = Subscriber::new_test("slotNotification");
+        rpc.slot_subscribe(session, subscriber);
+
+        rpc.subscriptions.notify_slot(0, 0, 0);
+        // Test slot notification
+        let string = receiver.poll();
+        if let Async::Ready(Some(response)) = string.unwrap() {
+            let expected_res = SlotInfo {
+                parent: 0,
+                slot: 0,
+                root: 0,
+            };
+            let expected_res_str =
+                serde_json::to_string(&serde_json::to_value(expected_res).unwrap()).unwrap();
+            let expected = format!(r#"{{"jsonrpc":"2.0","method":"slotNotification","params":{{"result":{},"subscription":0}}}}"#, expected_res_str);
+            assert_eq!(expected, response);
+        } else {
+            panic!("expected a slot notification");
+        }
+    }
+
+    #[test]
+    fn test_slot_unsubscribe() {
+        let session = create_session();
+
+        let mut io = PubSubHandler::default();
+        let rpc = RpcSolPubSubImpl::default();
+        io.extend_with(rpc.to_delegate());
+
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"slotSubscribe"}"#;
+        let _res = io.handle_request_sync(&req, session.clone());
+
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"slotUnsubscribe","params":[0]}"#;
+        let res = io.handle_request_sync(&req, session.clone());
+        let expected = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+        let expected: Response = serde_json::from_str(&expected).unwrap();
+        let result: Response = serde_json::from_str(&res.unwrap()).unwrap();
+        assert_eq!(expected, result);
+
+        // Test bad parameter
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"slotUnsubscribe","params":[0]}"#;
+        let res = io.handle_request_sync(&req, session.clone());
+        let expected = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Invalid Request: Subscription id does not exist"},"id":1}"#;
+        let expected: Response = serde_json::from_str(&expected).unwrap();
+        let result: Response = serde_json::from_str(&res.unwrap()).unwrap();
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn test_root_subscribe() {
+        let rpc = RpcSolPubSubImpl::default();
+        let session = create_session();
+        let (subscriber, _id_receiver, mut receiver) = Subscriber::new_test("rootNotification");
+        rpc.root_subscribe(session, subscriber);
+
+        rpc.subscriptions.notify_root(3);
+        // Test root notification
+        let string = receiver.poll();
+        if let Async::Ready(Some(response)) = string.unwrap() {
+            let expected = r#"{"jsonrpc":"2.0","method":"rootNotification","params":{"result":3,"subscription":0}}"#;
+            assert_eq!(expected, response);
+        } else {
+            panic!("expected a root notification");
+        }
+    }
+
+    #[test]
+    fn test_root_unsubscribe() {
+        let session = create_session();
+
+        let mut io = PubSubHandler::default();
+        let rpc = RpcSolPubSubImpl::default();
+        io.extend_with(rpc.to_delegate());
+
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"rootSubscribe"}"#;
+        let _res = io.handle_request_sync(&req, session.clone());
+
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"rootUnsubscribe","params":[0]}"#;
+        let res = io.handle_request_sync(&req, session.clone());
+        let expected = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+        let expected: Response = serde_json::from_str(&expected).unwrap();
+        let result: Response = serde_json::from_str(&res.unwrap()).unwrap();
+        assert_eq!(expected, result);
+    }
 }
@@ -1,17 +1,34 @@
 //! The `rpc_service` module implements the Solana JSON RPC service.
 
 use crate::bank_forks::BankForks;
+use crate::blocktree::Blocktree;
 use crate::cluster_info::ClusterInfo;
 use crate::rpc::*;
+use crate::rpc_access_control::{
+    client_ip, RpcAccessControl, RATE_LIMITED_ERROR_CODE, SNAPSHOT_DOWNLOAD_METHOD,
+};
 use crate::service::Service;
+use crate::snapshot_utils;
 use crate::storage_stage::StorageState;
-use jsonrpc_core::MetaIoHandler;
-use jsonrpc_http_server::{hyper, AccessControlAllowOrigin, DomainsValidation, ServerBuilder};
-use std::net::SocketAddr;
+use jsonrpc_core::futures::{Future, Stream};
+use jsonrpc_core::{ErrorCode, MetaIoHandler};
+use jsonrpc_http_server::{
+    hyper, AccessControlAllowOrigin, DomainsValidation, RequestMiddleware, RequestMiddlewareAction,
+    ServerBuilder,
+};
+use jsonrpc_ipc_server::RequestContext;
+use std::collections::HashSet;
+use std::fs::{self, Permissions};
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, RwLock};
 use std::thread::{self, sleep, Builder, JoinHandle};
 use std::time::Duration;
+use tokio::codec::{BytesCodec, FramedRead};
+use tokio::fs::File;
 
 pub struct JsonRpcService {
     thread_hdl: JoinHandle<()>,
@@ -27,14 +44,21 @@ impl JsonRpcService {
         storage_state: StorageState,
         config: JsonRpcConfig,
         bank_forks: Arc<RwLock<BankForks>>,
+        blocktree: Arc<Blocktree>,
+        snapshot_path: Option<PathBuf>,
         exit: &Arc<AtomicBool>,
     ) -> Self {
         info!("rpc bound to {:?}", rpc_addr);
         info!("rpc configuration: {:?}", config);
+        let access_control = RpcAccessControl::new(&config);
+        let admin_rpc_path = config.admin_rpc_path.clone();
+        let trusted_proxies = config.trusted_proxies.clone();
+        let snapshot_path = snapshot_path.filter(|_| config.enable_snapshot_download);
         let request_processor = Arc::new(RwLock::new(JsonRpcRequestProcessor::new(
             storage_state,
             config,
             bank_forks,
+            blocktree,
             exit,
         )));
         let request_processor_ = request_processor.clone();
@@ -45,27 +69,31 @@ impl JsonRpcService {
         let thread_hdl = Builder::new()
             .name("solana-jsonrpc".to_string())
             .spawn(move || {
-                let mut io = MetaIoHandler::default();
-                let rpc = RpcSolImpl;
-                io.extend_with(rpc.to_delegate());
-
-                let server =
-                    ServerBuilder::with_meta_extractor(io, move |_req: &hyper::Request<hyper::Body>| Meta {
-                        request_processor: request_processor_.clone(),
-                        cluster_info: cluster_info.clone(),
-                    }).threads(4)
-                        .cors(DomainsValidation::AllowOnly(vec![
-                            AccessControlAllowOrigin::Any,
-                        ]))
-                        .start_http(&rpc_addr);
+                let server = start_http_server(
+                    access_control,
+                    &request_processor_,
+                    &cluster_info,
+                    &rpc_addr,
+                    snapshot_path,
+                    trusted_proxies,
+                );
                 if let Err(e) = server {
                     warn!("JSON RPC service unavailable error: {:?}. \nAlso, check that port {} is not already in use by another application", e, rpc_addr.port());
                     return;
                 }
+                let admin_server = admin_rpc_path.and_then(|admin_rpc_path| {
+                    info!("admin rpc bound to {:?}", admin_rpc_path);
+                    start_admin_server(&request_processor_, &cluster_info, &admin_rpc_path)
+                        .map_err(|e| warn!("Admin JSON RPC service unavailable error: {:?}", e))
+                        .ok()
+                });
                 while !exit_.load(Ordering::Relaxed) {
                     sleep(Duration::from_millis(100));
                 }
                 server.unwrap().close();
+                if let Some(admin_server) = admin_server {
+                    admin_server.close();
+                }
             })
             .unwrap();
         Self {
@@ -76,6 +104,136 @@ impl JsonRpcService {
     }
 }
 
+/// Streams the latest snapshot archive to plain GET requests, so a validator can bootstrap
+/// from the same address it sends JSON RPC requests to.  Downloads count against the same
+/// method lists and quotas as JSON RPC calls
+struct SnapshotRequestMiddleware {
+    snapshot_path: Option<PathBuf>,
+    access_control: RpcAccessControl,
+    trusted_proxies: HashSet<IpAddr>,
+}
+
+impl SnapshotRequestMiddleware {
+    fn status_response(status: hyper::StatusCode) -> RequestMiddlewareAction {
+        hyper::Response::builder()
+            .status(status)
+            .body(hyper::Body::empty())
+            .unwrap()
+            .into()
+    }
+}
+
+impl RequestMiddleware for SnapshotRequestMiddleware {
+    fn on_request(&self, request: hyper::Request<hyper::Body>) -> RequestMiddlewareAction {
+        let snapshot_path = match &self.snapshot_path {
+            Some(snapshot_path) => snapshot_path,
+            None => return request.into(),
+        };
+        if request.method() != hyper::Method::GET
+            || request.uri().path().trim_start_matches('/')
+                != snapshot_utils::SNAPSHOT_ARCHIVE_FILENAME
+        {
+            return request.into();
+        }
+
+        let client = client_ip(&request, &self.trusted_proxies);
+        if let Err(error) = self
+            .access_control
+            .check_call(SNAPSHOT_DOWNLOAD_METHOD, Some(client))
+        {
+            return Self::status_response(
+                if error.code == ErrorCode::ServerError(RATE_LIMITED_ERROR_CODE) {
+                    hyper::StatusCode::TOO_MANY_REQUESTS
+                } else {
+                    hyper::StatusCode::FORBIDDEN
+                },
+            );
+        }
+
+        // The archive is read in chunks on the runtime's blocking pool rather than loaded into
+        // memory up front
+        let archive_path = snapshot_utils::get_snapshot_archive_path(snapshot_path);
+        let response = File::open(archive_path).then(|file| {
+            let response = match file {
+                Ok(file) => {
+                    let chunks =
+                        FramedRead::new(file, BytesCodec::new()).map(|chunk| chunk.freeze());
+                    hyper::Response::builder()
+                        .header(hyper::header::CONTENT_TYPE, "application/x-bzip2")
+                        .body(hyper::Body::wrap_stream(chunks))
+                }
+                Err(_) => hyper::Response::builder()
+                    .status(hyper::StatusCode::NOT_FOUND)
+                    .body(hyper::Body::empty()),
+            };
+            Ok(response.unwrap())
+        });
+        RequestMiddlewareAction::Respond {
+            should_validate_hosts: true,
+            response: Box::new(response),
+        }
+    }
+}
+
+fn start_http_server(
+    access_control: RpcAccessControl,
+    request_processor: &Arc<RwLock<JsonRpcRequestProcessor>>,
+    cluster_info: &Arc<RwLock<ClusterInfo>>,
+    addr: &SocketAddr,
+    snapshot_path: Option<PathBuf>,
+    trusted_proxies: HashSet<IpAddr>,
+) -> io::Result<jsonrpc_http_server::Server> {
+    let request_middleware = SnapshotRequestMiddleware {
+        snapshot_path,
+        access_control: access_control.clone(),
+        trusted_proxies: trusted_proxies.clone(),
+    };
+    let mut io = MetaIoHandler::with_middleware(access_control);
+    io.extend_with(RpcSolImpl.to_delegate());
+
+    let request_processor = request_processor.clone();
+    let cluster_info = cluster_info.clone();
+    ServerBuilder::with_meta_extractor(io, move |req: &hyper::Request<hyper::Body>| Meta {
+        request_processor: request_processor.clone(),
+        cluster_info: cluster_info.clone(),
+        client_ip: Some(client_ip(req, &trusted_proxies)),
+    })
+    .threads(4)
+    .cors(DomainsValidation::AllowOnly(vec![
+        AccessControlAllowOrigin::Any,
+    ]))
+    .request_middleware(request_middleware)
+    .start_http(addr)
+}
+
+/// Serve every method without limits on a Unix socket at `path` that only the validator's own
+/// user may connect to
+fn start_admin_server(
+    request_processor: &Arc<RwLock<JsonRpcRequestProcessor>>,
+    cluster_info: &Arc<RwLock<ClusterInfo>>,
+    path: &Path,
+) -> io::Result<jsonrpc_ipc_server::Server> {
+    let mut io = MetaIoHandler::with_middleware(RpcAccessControl::new_admin());
+    io.extend_with(RpcSolImpl.to_delegate());
+
+    let request_processor = request_processor.clone();
+    let cluster_info = cluster_info.clone();
+    let server = jsonrpc_ipc_server::ServerBuilder::with_meta_extractor(
+        io,
+        move |_context: &RequestContext| Meta {
+            request_processor: request_processor.clone(),
+            cluster_info: cluster_info.clone(),
+            client_ip: None,
+        },
+    )
+    .start(&path.to_string_lossy())?;
+    if let Err(err) = fs::set_permissions(path, Permissions::from_mode(0o600)) {
+        server.close();
+        return Err(err);
+    }
+    Ok(server)
+}
+
 impl Service for JsonRpcService {
     type JoinReturnType = ();
 
@@ -87,11 +245,18 @@ impl Service for JsonRpcService {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::blocktree::get_tmp_ledger_path;
     use crate::contact_info::ContactInfo;
     use crate::genesis_utils::{create_genesis_block, GenesisBlockInfo};
+    use crate::rpc_access_control::RATE_LIMITED_ERROR_CODE;
+    use reqwest::header::CONTENT_TYPE;
+    use serde_json::{json, Value};
     use solana_runtime::bank::Bank;
     use solana_sdk::signature::KeypairUtil;
+    use std::fs::{self, remove_dir_all};
+    use std::io::{BufRead, BufReader, Write};
     use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+    use std::os::unix::net::UnixStream;
 
     #[test]
     fn test_rpc_new() {
@@ -110,12 +275,16 @@ mod tests {
             solana_netutil::find_available_port_in_range((10000, 65535)).unwrap(),
         );
         let bank_forks = Arc::new(RwLock::new(BankForks::new(bank.slot(), bank)));
+        let ledger_path = get_tmp_ledger_path!();
+        let blocktree = Arc::new(Blocktree::open(&ledger_path).unwrap());
         let rpc_service = JsonRpcService::new(
             &cluster_info,
             rpc_addr,
             StorageState::default(),
             JsonRpcConfig::default(),
             bank_forks,
+            blocktree,
+            None,
             &exit,
         );
         let thread = rpc_service.thread_hdl.thread();
@@ -127,9 +296,172 @@ mod tests {
                 .request_processor
                 .read()
                 .unwrap()
-                .get_balance(&mint_keypair.pubkey())
+                .get_balance(&mint_keypair.pubkey(), None)
+        );
+        exit.store(true, Ordering::Relaxed);
+        rpc_service.join().unwrap();
+    }
+
+    #[test]
+    fn test_rpc_access_control() {
+        let GenesisBlockInfo { genesis_block, .. } = create_genesis_block(10_000);
+        let exit = Arc::new(AtomicBool::new(false));
+        let bank = Bank::new(&genesis_block);
+        let cluster_info = Arc::new(RwLock::new(ClusterInfo::new_with_invalid_keypair(
+            ContactInfo::default(),
+        )));
+        let rpc_addr = SocketAddr::new(
+            IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
+            solana_netutil::find_available_port_in_range((10000, 65535)).unwrap(),
+        );
+        let bank_forks = Arc::new(RwLock::new(BankForks::new(bank.slot(), bank)));
+        let ledger_path = get_tmp_ledger_path!();
+        let blocktree = Arc::new(Blocktree::open(&ledger_path).unwrap());
+        let admin_rpc_path = PathBuf::from(&ledger_path).join("admin.rpc");
+        let mut config = JsonRpcConfig::default();
+        config.max_requests_per_second_per_ip = Some(2);
+        config.denied_methods.insert("getSlot".to_string());
+        config.admin_rpc_path = Some(admin_rpc_path.clone());
+        config.enable_fullnode_exit = true;
+
+        let rpc_service = JsonRpcService::new(
+            &cluster_info,
+            rpc_addr,
+            StorageState::default(),
+            config,
+            bank_forks,
+            blocktree,
+            None,
+            &exit,
+        );
+        sleep(Duration::from_millis(500));
+
+        let client = reqwest::Client::new();
+        let request = |method: &str, client_ip: &str| {
+            let request = json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": []});
+            let mut response = client
+                .post(&format!("http://{}", rpc_addr))
+                .header(CONTENT_TYPE, "application/json")
+                .header("x-forwarded-for", client_ip)
+                .body(request.to_string())
+                .send()
+                .unwrap();
+            serde_json::from_str::<Value>(&response.text().unwrap()).unwrap()
+        };
+        let admin_request = |method: &str| {
+            let request = json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": []});
+            let mut stream = UnixStream::connect(&admin_rpc_path).unwrap();
+            stream.write_all(request.to_string().as_bytes()).unwrap();
+            let mut response = String::new();
+            BufReader::new(stream).read_line(&mut response).unwrap();
+            serde_json::from_str::<Value>(&response).unwrap()
+        };
+
+        // Denied and admin-only methods are not served publicly
+        let response = request("getSlot", "10.0.0.1");
+        assert_eq!(response["error"]["code"], json!(-32601));
+        let response = request("fullnodeExit", "10.0.0.2");
+        assert_eq!(response["error"]["code"], json!(-32601));
+
+        // Each client IP forwarded by the proxy gets its own quota
+        let response = request("getTransactionCount", "10.0.0.3");
+        assert_eq!(response["result"], json!(0));
+        let response = request("getTransactionCount", "10.0.0.3");
+        assert_eq!(response["result"], json!(0));
+        let response = request("getTransactionCount", "10.0.0.3");
+        assert_eq!(response["error"]["code"], json!(RATE_LIMITED_ERROR_CODE));
+        let response = request("getTransactionCount", "10.0.0.4");
+        assert_eq!(response["result"], json!(0));
+
+        // The admin socket is private to the validator's user and serves every method without
+        // limits
+        let mode = fs::metadata(&admin_rpc_path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        let response = admin_request("getSlot");
+        assert_eq!(response["result"], json!(0));
+        let response = admin_request("fullnodeExit");
+        assert_eq!(response["result"], json!(true));
+        assert!(exit.load(Ordering::Relaxed));
+
+        rpc_service.join().unwrap();
+        remove_dir_all(&ledger_path).unwrap();
+    }
+
+    #[test]
+    fn test_rpc_snapshot_download() {
+        let GenesisBlockInfo { genesis_block, .. } = create_genesis_block(10_000);
+        let exit = Arc::new(AtomicBool::new(false));
+        let bank = Arc::new(Bank::new(&genesis_block));
+        bank.freeze();
+        let snapshot_path = get_tmp_ledger_path!();
+        let package = snapshot_utils::package_snapshot(&bank).unwrap();
+        let archive_path = snapshot_utils::get_snapshot_archive_path(&snapshot_path);
+        snapshot_utils::archive_snapshot_package(&package, &archive_path).unwrap();
+
+        let cluster_info = Arc::new(RwLock::new(ClusterInfo::new_with_invalid_keypair(
+            ContactInfo::default(),
+        )));
+        let start_rpc_service = |config: JsonRpcConfig| {
+            let rpc_addr = SocketAddr::new(
+                IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
+                solana_netutil::find_available_port_in_range((10000, 65535)).unwrap(),
+            );
+            let bank_forks = Arc::new(RwLock::new(BankForks::new(
+                bank.slot(),
+                Bank::new(&genesis_block),
+            )));
+            let ledger_path = get_tmp_ledger_path!();
+            let blocktree = Arc::new(Blocktree::open(&ledger_path).unwrap());
+            let rpc_service = JsonRpcService::new(
+                &cluster_info,
+                rpc_addr,
+                StorageState::default(),
+                config,
+                bank_forks,
+                blocktree,
+                Some(PathBuf::from(&snapshot_path)),
+                &exit,
+            );
+            (rpc_addr, rpc_service, ledger_path)
+        };
+        let mut config = JsonRpcConfig::default();
+        config.enable_snapshot_download = true;
+        config.max_requests_per_second_per_ip = Some(1);
+        let (rpc_addr, rpc_service, ledger_path) = start_rpc_service(config);
+        let (disabled_rpc_addr, disabled_rpc_service, disabled_ledger_path) =
+            start_rpc_service(JsonRpcConfig::default());
+        sleep(Duration::from_millis(500));
+
+        let download_path = PathBuf::from(&ledger_path).join("download.tar.bz2");
+        snapshot_utils::download_snapshot(&rpc_addr, &download_path).unwrap();
+        assert_eq!(
+            fs::read(&download_path).unwrap(),
+            fs::read(&archive_path).unwrap()
+        );
+
+        // Nothing is served unless downloads were enabled
+        assert!(snapshot_utils::download_snapshot(&disabled_rpc_addr, &download_path).is_err());
+
+        // Downloads are charged against the client's quota
+        let url = format!(
+            "http://{}/{}",
+            rpc_addr,
+            snapshot_utils::SNAPSHOT_ARCHIVE_FILENAME
         );
+        let response = reqwest::get(&url).unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+
+        // Nothing is served once the archive is gone
+        fs::remove_file(&archive_path).unwrap();
+        sleep(Duration::from_secs(1));
+        let response = reqwest::get(&url).unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
         exit.store(true, Ordering::Relaxed);
         rpc_service.join().unwrap();
+        disabled_rpc_service.join().unwrap();
+        remove_dir_all(&snapshot_path).unwrap();
+        remove_dir_all(&ledger_path).unwrap();
+        remove_dir_all(&disabled_ledger_path).unwrap();
     }
 }
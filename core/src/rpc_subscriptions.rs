@@ -15,6 +15,8 @@ use solana_vote_api::vote_state::MAX_LOCKOUT_HISTORY;
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 
+pub use solana_client::pubsub_client::SlotInfo;
+
 pub type Confirmations = usize;
 
 type RpcAccountSubscriptions =
@@ -24,6 +26,8 @@ type RpcProgramSubscriptions =
 type RpcSignatureSubscriptions = RwLock<
     HashMap<Signature, HashMap<SubscriptionId, (Sink<transaction::Result<()>>, Confirmations)>>,
 >;
+type RpcSlotSubscriptions = RwLock<HashMap<SubscriptionId, Sink<SlotInfo>>>;
+type RpcRootSubscriptions = RwLock<HashMap<SubscriptionId, Sink<u64>>>;
 
 fn add_subscription<K, S>(
     subscriptions: &mut HashMap<K, HashMap<SubscriptionId, (Sink<S>, Confirmations)>>,
@@ -153,6 +157,8 @@ pub struct RpcSubscriptions {
     account_subscriptions: RpcAccountSubscriptions,
     program_subscriptions: RpcProgramSubscriptions,
     signature_subscriptions: RpcSignatureSubscriptions,
+    slot_subscriptions: RpcSlotSubscriptions,
+    root_subscriptions: RpcRootSubscriptions,
 }
 
 impl Default for RpcSubscriptions {
@@ -161,6 +167,8 @@ impl Default for RpcSubscriptions {
             account_subscriptions: RpcAccountSubscriptions::default(),
             program_subscriptions: RpcProgramSubscriptions::default(),
             signature_subscriptions: RpcSignatureSubscriptions::default(),
+            slot_subscriptions: RpcSlotSubscriptions::default(),
+            root_subscriptions: RpcRootSubscriptions::default(),
         }
     }
 }
@@ -266,6 +274,46 @@ impl RpcSubscriptions {
         remove_subscription(&mut subscriptions, id)
     }
 
+    pub fn add_slot_subscription(&self, sub_id: &SubscriptionId, sink: &Sink<SlotInfo>) {
+        let mut subscriptions = self.slot_subscriptions.write().unwrap();
+        subscriptions.insert(sub_id.clone(), sink.clone());
+    }
+
+    pub fn remove_slot_subscription(&self, id: &SubscriptionId) -> bool {
+        let mut subscriptions = self.slot_subscriptions.write().unwrap();
+        subscriptions.remove(id).is_some()
+    }
+
+    pub fn add_root_subscription(&self, sub_id: &SubscriptionId, sink: &Sink<u64>) {
+        let mut subscriptions = self.root_subscriptions.write().unwrap();
+        subscriptions.insert(sub_id.clone(), sink.clone());
+    }
+
+    pub fn remove_root_subscription(&self, id: &SubscriptionId) -> bool {
+        let mut subscriptions = self.root_subscriptions.write().unwrap();
+        subscriptions.remove(id).is_some()
+    }
+
+    /// Notify slot subscribers that a new bank was created for `slot`.
+    pub fn notify_slot(&self, slot: u64, parent: u64, root: u64) {
+        let subscriptions = self.slot_subscriptions.read().unwrap();
+        for (_, sink) in subscriptions.iter() {
+            if let Err(err) = sink.notify(Ok(SlotInfo { slot, parent, root })).wait() {
+                debug!("slot notification failed: {:?}", err);
+            }
+        }
+    }
+
+    /// Notify root subscribers that the root advanced to `root`.
+    pub fn notify_root(&self, root: u64) {
+        let subscriptions = self.root_subscriptions.read().unwrap();
+        for (_, sink) in subscriptions.iter() {
+            if let Err(err) = sink.notify(Ok(root)).wait() {
+                debug!("root notification failed: {:?}", err);
+            }
+        }
+    }
+
     /// Notify subscribers of changes to any accounts or new signatures since
     /// the bank's last checkpoint.
     pub fn notify_subscribers(&self, current_slot: u64, bank_forks: &Arc<RwLock<BankForks>>) {
@@ -348,7 +396,7 @@ mod tests {
         subscriptions.check_account(&alice.pubkey(), 0, &bank_forks);
         let string = transport_receiver.poll();
         if let Async::Ready(Some(response)) = string.unwrap() {
-            let expected = format!(r#"{{"jsonrpc":"2.0","method":"accountNotification","params":{{"result":{{"data":[0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0],"executable":false,"lamports":1,"owner":[2,203,81,223,225,24,34,35,203,214,138,130,144,208,35,77,63,16,87,51,47,198,115,123,98,188,19,160,0,0,0,0]}},"subscription":0}}}}"#);
+            let expected = format!(r#"{{"jsonrpc":"2.0","method":"accountNotification","params":{{"result":{{"data":[0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0],"executable":false,"lamports":1,"owner":[2,203,81,223,225,24,34,35,203,214,138,130,144,208,35,77,63,16,87,51,47,198,115,123,98,188,19,160,0,0,0,0],"rentEpoch":0}},"subscription":0}}}}"#);
             assert_eq!(expected, response);
         }
 
@@ -403,7 +451,7 @@ mod tests {
         subscriptions.check_program(&solana_budget_api::id(), 0, &bank_forks);
         let string = transport_receiver.poll();
         if let Async::Ready(Some(response)) = string.unwrap() {
-            let expected = format!(r#"{{"jsonrpc":"2.0","method":"programNotification","params":{{"result":["{:?}",{{"data":[0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0],"executable":false,"lamports":1,"owner":[2,203,81,223,225,24,34,35,203,214,138,130,144,208,35,77,63,16,87,51,47,198,115,123,98,188,19,160,0,0,0,0]}}],"subscription":0}}}}"#, alice.pubkey());
+            let expected = format!(r#"{{"jsonrpc":"2.0","method":"programNotification","params":{{"result":["{:?}",{{"data":[0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0],"executable":false,"lamports":1,"owner":[2,203,81,223,225,24,34,35,203,214,138,130,144,208,35,77,63,16,87,51,47,198,115,123,98,188,19,160,0,0,0,0],"rentEpoch":0}}],"subscription":0}}}}"#, alice.pubkey());
             assert_eq!(expected, response);
         }
 
@@ -465,4 +513,62 @@ mod tests {
             .unwrap()
             .contains_key(&signature));
     }
+
+    #[test]
+    fn test_check_slot_subscribe() {
+        let (subscriber, _id_receiver, mut transport_receiver) =
+            Subscriber::new_test("slotNotification");
+        let sub_id = SubscriptionId::Number(0 as u64);
+        let sink = subscriber.assign_id(sub_id.clone()).unwrap();
+        let subscriptions = RpcSubscriptions::default();
+        subscriptions.add_slot_subscription(&sub_id, &sink);
+
+        assert!(subscriptions
+            .slot_subscriptions
+            .read()
+            .unwrap()
+            .contains_key(&sub_id));
+
+        subscriptions.notify_slot(2, 1, 0);
+        let string = transport_receiver.poll();
+        if let Async::Ready(Some(response)) = string.unwrap() {
+            let expected = r#"{"jsonrpc":"2.0","method":"slotNotification","params":{"result":{"parent":1,"root":0,"slot":2},"subscription":0}}"#;
+            assert_eq!(expected, response);
+        } else {
+            panic!("expected a slot notification");
+        }
+
+        // A client that has gone away is not an error for the notifier
+        drop(transport_receiver);
+        subscriptions.notify_slot(3, 2, 1);
+
+        assert!(subscriptions.remove_slot_subscription(&sub_id));
+        assert!(!subscriptions
+            .slot_subscriptions
+            .read()
+            .unwrap()
+            .contains_key(&sub_id));
+    }
+
+    #[test]
+    fn test_check_root_subscribe() {
+        let (subscriber, _id_receiver, mut transport_receiver) =
+            Subscriber::new_test("rootNotification");
+        let sub_id = SubscriptionId::Number(0 as u64);
+        let sink = subscriber.assign_id(sub_id.clone()).unwrap();
+        let subscriptions = RpcSubscriptions::default();
+        subscriptions.add_root_subscription(&sub_id, &sink);
+
+        subscriptions.notify_root(2);
+        let string = transport_receiver.poll();
+        if let Async::Ready(Some(response)) = string.unwrap() {
+            let expected = r#"{"jsonrpc":"2.0","method":"rootNotification","params":{"result":2,"subscription":0}}"#;
+            assert_eq!(expected, response);
+        } else {
+            panic!("expected a root notification");
+        }
+
+        assert!(subscriptions.remove_root_subscription(&sub_id));
+        assert!(!subscriptions.remove_root_subscription(&sub_id));
+    }
 }
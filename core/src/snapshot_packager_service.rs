@@ -0,0 +1,129 @@
+//! The `snapshot_packager_service` writes the snapshot packages `BankForks` captures as the root
+//! advances into the archive served to validators bootstrapping from this node.
+
+use crate::service::Service;
+use crate::snapshot_utils::{self, SnapshotPackage};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread::{self, Builder, JoinHandle};
+use std::time::Duration;
+
+pub type SnapshotPackageSender = Sender<SnapshotPackage>;
+pub type SnapshotPackageReceiver = Receiver<SnapshotPackage>;
+
+pub struct SnapshotPackagerService {
+    t_snapshot_packager: JoinHandle<()>,
+}
+
+impl SnapshotPackagerService {
+    pub fn new(
+        snapshot_package_receiver: SnapshotPackageReceiver,
+        snapshot_path: &str,
+        exit: &Arc<AtomicBool>,
+    ) -> Self {
+        let archive_path = snapshot_utils::get_snapshot_archive_path(snapshot_path);
+        let exit = exit.clone();
+        let t_snapshot_packager = Builder::new()
+            .name("solana-snapshot-packager".to_string())
+            .spawn(move || loop {
+                if exit.load(Ordering::Relaxed) {
+                    break;
+                }
+                if let Err(RecvTimeoutError::Disconnected) =
+                    Self::run(&snapshot_package_receiver, &archive_path)
+                {
+                    break;
+                }
+            })
+            .unwrap();
+        Self {
+            t_snapshot_packager,
+        }
+    }
+
+    /// Archive the newest package waiting, skipping any it supersedes
+    fn run(
+        snapshot_package_receiver: &SnapshotPackageReceiver,
+        archive_path: &Path,
+    ) -> Result<(), RecvTimeoutError> {
+        let mut package = snapshot_package_receiver.recv_timeout(Duration::from_secs(1))?;
+        while let Ok(newer_package) = snapshot_package_receiver.try_recv() {
+            package = newer_package;
+        }
+        if let Err(e) = snapshot_utils::archive_snapshot_package(&package, archive_path) {
+            warn!(
+                "Failed to archive snapshot for slot {}: {:?}",
+                package.slot, e
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Service for SnapshotPackagerService {
+    type JoinReturnType = ();
+
+    fn join(self) -> thread::Result<()> {
+        self.t_snapshot_packager.join()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bank_forks::BankForks;
+    use crate::blocktree::get_tmp_ledger_path;
+    use crate::genesis_utils::{create_genesis_block, GenesisBlockInfo};
+    use solana_runtime::bank::Bank;
+    use solana_sdk::pubkey::Pubkey;
+    use std::fs::remove_dir_all;
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn test_snapshot_packager_service() {
+        let GenesisBlockInfo {
+            genesis_block,
+            mint_keypair,
+            ..
+        } = create_genesis_block(10_000);
+        let snapshot_path = get_tmp_ledger_path!();
+        let archive_path = snapshot_utils::get_snapshot_archive_path(&snapshot_path);
+        let (sender, receiver) = channel();
+
+        // Squashing drops the parents of each root, so the accounts need a path they don't own
+        let accounts_path = get_tmp_ledger_path!();
+        let bank0 = Bank::new_with_paths(&genesis_block, Some(accounts_path.clone()));
+        bank0.freeze();
+        let mut bank_forks = BankForks::new(0, bank0);
+        bank_forks.set_snapshot_package_sender(sender, 2);
+        let key = Pubkey::new_rand();
+        let mut hashes = vec![];
+        for slot in 1..5 {
+            let bank = Bank::new_from_parent(&bank_forks.working_bank(), &Pubkey::default(), slot);
+            bank.transfer(slot, &mint_keypair, &key).unwrap();
+            bank.freeze();
+            hashes.push(bank.hash());
+            bank_forks.insert(bank);
+            bank_forks.set_root(slot);
+        }
+
+        // Roots 1 and 3 were packaged; only the newest is archived, as it was when rooted
+        let root_hash = hashes[2];
+        assert!(SnapshotPackagerService::run(&receiver, &archive_path).is_ok());
+        assert!(receiver.try_recv().is_err());
+        drop(bank_forks);
+        let bank =
+            snapshot_utils::bank_from_archive(&None, &archive_path, Some(&root_hash)).unwrap();
+        assert_eq!(bank.slot(), 3);
+        assert_eq!(bank.get_balance(&key), 6);
+
+        assert_eq!(
+            SnapshotPackagerService::run(&receiver, &archive_path),
+            Err(RecvTimeoutError::Disconnected)
+        );
+        remove_dir_all(&snapshot_path).unwrap();
+        remove_dir_all(&accounts_path).unwrap();
+    }
+}
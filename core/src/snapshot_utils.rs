@@ -0,0 +1,505 @@
+//! The `snapshot_utils` module packages a rooted bank and the account storage it refers to
+//! into a self-contained archive, and boots a bank back out of one. Unlike the per-slot
+//! snapshot files kept by `BankForks`, an archive can be copied to another machine.
+
+use bincode::{deserialize_from, serialize_into};
+use bzip2::bufread::BzDecoder;
+use bzip2::write::BzEncoder;
+use bzip2::Compression;
+use rand::{thread_rng, Rng};
+use solana_runtime::accounts_db::AccountsSnapshot;
+use solana_runtime::bank::{Bank, BankRc, StatusCacheRc};
+use solana_sdk::hash::Hash;
+use std::fs::{self, File};
+use std::io::{BufReader, Error, ErrorKind, Read, Result};
+use std::net::SocketAddr;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use tar::{Archive, Builder, Entries, Entry, EntryType, Header};
+
+pub const SNAPSHOT_ARCHIVE_FILENAME: &str = "snapshot.tar.bz2";
+pub const SNAPSHOT_VERSION: u32 = 1;
+pub const DEFAULT_SNAPSHOT_INTERVAL_SLOTS: u64 = 100;
+
+const MANIFEST_FILENAME: &str = "manifest.json";
+const BANK_FILENAME: &str = "bank";
+const ACCOUNTS_DIR: &str = "accounts";
+
+/// Largest entries accepted from an archive
+const MAX_MANIFEST_SIZE: u64 = 64 * 1024;
+const MAX_BANK_SIZE: u64 = 4 * 1024 * 1024 * 1024;
+const MAX_ACCOUNT_STORAGE_SIZE: u64 = 256 * 1024 * 1024;
+/// Limits on a whole archive, which bound the disk an untrusted one may take up
+const MAX_ARCHIVE_ENTRIES: usize = 1_000_000;
+const MAX_UNPACKED_SIZE: u64 = 64 * 1024 * 1024 * 1024;
+const MAX_DOWNLOAD_SIZE: u64 = 16 * 1024 * 1024 * 1024;
+
+/// Describes the bank held in an archive, checked before and after it is loaded
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotManifest {
+    pub version: u32,
+    pub slot: u64,
+    pub bank_hash: String,
+}
+
+/// Everything needed to write an archive for a rooted bank, captured so the archive can be
+/// serialized and written off the replay thread
+pub struct SnapshotPackage {
+    pub slot: u64,
+    pub bank_hash: Hash,
+    bank: Arc<Bank>,
+    /// The accounts as of the bank, which keeps the storage they refer to on disk until it
+    /// has been archived
+    accounts: AccountsSnapshot,
+}
+
+/// A directory that is removed along with everything in it when dropped
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new_in<P: AsRef<Path>>(parent: P) -> Result<Self> {
+        let name = format!(".snapshot-unpack.{:016x}", thread_rng().gen::<u64>());
+        let path = parent.as_ref().join(name);
+        fs::create_dir_all(&path)?;
+        Ok(TempDir(path))
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ignored = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn get_io_error(error: &str) -> Error {
+    warn!("snapshot error: {:?}", error);
+    Error::new(ErrorKind::Other, error)
+}
+
+pub fn get_snapshot_archive_path<P: AsRef<Path>>(snapshot_path: P) -> PathBuf {
+    snapshot_path.as_ref().join(SNAPSHOT_ARCHIVE_FILENAME)
+}
+
+/// Capture the state of a frozen, rooted bank.  Only references are taken, so this is cheap
+/// enough to do while rooting; the state is serialized once the package is archived
+pub fn package_snapshot(bank: &Arc<Bank>) -> Result<SnapshotPackage> {
+    if !bank.is_frozen() {
+        return Err(get_io_error("bank is not frozen"));
+    }
+    Ok(SnapshotPackage {
+        slot: bank.slot(),
+        bank_hash: bank.hash(),
+        bank: bank.clone(),
+        accounts: bank.accounts().accounts_db.snapshot(bank.slot()),
+    })
+}
+
+/// The bank, its status cache and its accounts index as of the packaged slot, serialized back
+/// to back.  Newer forks may have been rooted since, so neither the status cache nor the
+/// accounts are serialized as they are now
+fn serialize_snapshot_package(package: &SnapshotPackage) -> Result<Vec<u8>> {
+    let bank = &package.bank;
+    let mut state = vec![];
+    serialize_into(&mut state, &**bank).map_err(|_| get_io_error("serialize bank error"))?;
+    serialize_into(&mut state, &bank.src.clone_as_of(package.slot))
+        .map_err(|_| get_io_error("serialize bank status cache error"))?;
+    state.extend(bank.rc.serialize_snapshot(&package.accounts)?);
+    Ok(state)
+}
+
+fn append_data<W: std::io::Write>(builder: &mut Builder<W>, name: &str, data: &[u8]) -> Result<()> {
+    let mut header = Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, name, data)
+}
+
+/// Write `package` to `archive_path`, replacing any archive already there only once the new
+/// one is complete
+pub fn archive_snapshot_package<P: AsRef<Path>>(
+    package: &SnapshotPackage,
+    archive_path: P,
+) -> Result<()> {
+    let archive_path = archive_path.as_ref();
+    if let Some(parent) = archive_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp_path = archive_path.with_extension("tmp");
+    let state = serialize_snapshot_package(package)?;
+
+    let manifest = SnapshotManifest {
+        version: SNAPSHOT_VERSION,
+        slot: package.slot,
+        bank_hash: package.bank_hash.to_string(),
+    };
+    let manifest =
+        serde_json::to_vec(&manifest).map_err(|_| get_io_error("serialize manifest error"))?;
+
+    let mut builder = Builder::new(BzEncoder::new(File::create(&tmp_path)?, Compression::Best));
+    append_data(&mut builder, MANIFEST_FILENAME, &manifest)?;
+    append_data(&mut builder, BANK_FILENAME, &state)?;
+    for entry in &package.accounts.storage_entries() {
+        let path = entry.get_path();
+        let name = match (path.parent().and_then(Path::file_name), path.file_name()) {
+            (Some(dir), Some(file)) => Path::new(ACCOUNTS_DIR).join(dir).join(file),
+            _ => return Err(get_io_error("invalid account storage path")),
+        };
+        builder.append_path_with_name(&path, name)?;
+    }
+    builder.into_inner()?.finish()?;
+
+    fs::rename(&tmp_path, archive_path)?;
+    info!(
+        "snapshot archive for slot {} written to {:?}",
+        package.slot, archive_path
+    );
+    Ok(())
+}
+
+/// Check that `entry` is a regular file no larger than `max_size`.  Links and other special
+/// entries are rejected, as they could point outside of the directory they unpack into
+fn check_entry<R: Read>(entry: &Entry<R>, max_size: u64) -> Result<u64> {
+    if entry.header().entry_type() != EntryType::Regular {
+        return Err(get_io_error("snapshot archive entry is not a regular file"));
+    }
+    let size = entry.header().size()?;
+    if size > max_size {
+        return Err(get_io_error("snapshot archive entry too large"));
+    }
+    Ok(size)
+}
+
+/// Read the whole of `entry`, which may be no larger than `max_size`.  The buffer grows as
+/// the entry is read rather than trusting the size in its header
+fn read_entry<R: Read>(entry: &mut Entry<R>, max_size: u64) -> Result<Vec<u8>> {
+    check_entry(entry, max_size)?;
+    let mut data = vec![];
+    entry.take(max_size + 1).read_to_end(&mut data)?;
+    if data.len() as u64 > max_size {
+        return Err(get_io_error("snapshot archive entry too large"));
+    }
+    Ok(data)
+}
+
+/// Read the manifest, which has to be the first of `entries`
+fn read_manifest<R: Read>(entries: &mut Entries<R>) -> Result<SnapshotManifest> {
+    let mut entry = entries
+        .next()
+        .ok_or_else(|| get_io_error("snapshot manifest missing"))??;
+    if entry.path()?.as_ref() != Path::new(MANIFEST_FILENAME) {
+        return Err(get_io_error("snapshot manifest missing"));
+    }
+    let manifest = read_entry(&mut entry, MAX_MANIFEST_SIZE)?;
+    serde_json::from_slice(&manifest).map_err(|_| get_io_error("deserialize manifest error"))
+}
+
+/// Read the manifest of the archive at `archive_path`, which is the first entry so the rest of
+/// the archive is left unread
+pub fn read_snapshot_manifest<P: AsRef<Path>>(archive_path: P) -> Result<SnapshotManifest> {
+    let file = File::open(archive_path)?;
+    let mut archive = Archive::new(BzDecoder::new(BufReader::new(file)));
+    read_manifest(&mut archive.entries()?)
+}
+
+/// Returns the `<fork>.<id>/data` part of an archived account storage file, rejecting
+/// anything that would unpack outside of the account directory
+fn account_storage_path(entry_path: &Path) -> Option<PathBuf> {
+    let components: Vec<_> = entry_path.components().collect();
+    match components.as_slice() {
+        [Component::Normal(dir), Component::Normal(storage), Component::Normal(file)]
+            if dir.to_str() == Some(ACCOUNTS_DIR) =>
+        {
+            Some(Path::new(storage).join(file))
+        }
+        _ => None,
+    }
+}
+
+/// Load the bank held in the archive at `archive_path`, placing its account storage under
+/// `account_paths` once the manifest has been checked and every entry read. The returned bank
+/// is frozen and rooted, and both its hash and the hash of the accounts it holds have been
+/// verified.  Those checks only show the archive is consistent with itself, so an archive
+/// from an untrusted source must also be given the `expected_bank_hash` its bank has to match
+pub fn bank_from_archive<P: AsRef<Path>>(
+    account_paths: &Option<String>,
+    archive_path: P,
+    expected_bank_hash: Option<&Hash>,
+) -> Result<Bank> {
+    let bank_rc = BankRc::new(account_paths.clone());
+    let account_path = bank_rc
+        .account_paths()
+        .split(',')
+        .next()
+        .map(PathBuf::from)
+        .ok_or_else(|| get_io_error("no account path"))?;
+
+    let file = File::open(archive_path)?;
+    let mut archive = Archive::new(BzDecoder::new(BufReader::new(file)));
+    let mut entries = archive.entries()?;
+
+    // Nothing is extracted until the manifest shows the archive is one this node can load
+    let manifest = read_manifest(&mut entries)?;
+    if manifest.version != SNAPSHOT_VERSION {
+        return Err(get_io_error("unsupported snapshot version"));
+    }
+    if let Some(expected_bank_hash) = expected_bank_hash {
+        if manifest.bank_hash != expected_bank_hash.to_string() {
+            return Err(get_io_error("bank hash does not match the expected hash"));
+        }
+    }
+
+    // The account storage is unpacked aside, and only moved into place once the whole
+    // archive has been read
+    let unpack_dir = TempDir::new_in(&account_path)?;
+    let mut state = None;
+    let mut unpacked_size = 0;
+    for (i, entry) in entries.enumerate() {
+        if i >= MAX_ARCHIVE_ENTRIES {
+            return Err(get_io_error("snapshot archive has too many entries"));
+        }
+        let mut entry = entry?;
+        let entry_path = entry.path()?.into_owned();
+        unpacked_size += check_entry(&entry, MAX_BANK_SIZE)?;
+        if unpacked_size > MAX_UNPACKED_SIZE {
+            return Err(get_io_error("snapshot archive too large"));
+        }
+        if entry_path == Path::new(BANK_FILENAME) {
+            state = Some(read_entry(&mut entry, MAX_BANK_SIZE)?);
+        } else if let Some(storage_path) = account_storage_path(&entry_path) {
+            check_entry(&entry, MAX_ACCOUNT_STORAGE_SIZE)?;
+            let dst = unpack_dir.0.join(storage_path);
+            fs::create_dir_all(dst.parent().unwrap())?;
+            entry.unpack(&dst)?;
+        } else {
+            warn!(
+                "ignoring unexpected snapshot archive entry {:?}",
+                entry_path
+            );
+        }
+    }
+    let state = state.ok_or_else(|| get_io_error("snapshot bank missing"))?;
+    for storage_dir in fs::read_dir(&unpack_dir.0)? {
+        let storage_dir = storage_dir?;
+        let dst = account_path.join(storage_dir.file_name());
+        let _ignored = fs::remove_dir_all(&dst);
+        fs::rename(storage_dir.path(), dst)?;
+    }
+    drop(unpack_dir);
+
+    let mut stream = BufReader::new(&state[..]);
+    let mut bank: Bank =
+        deserialize_from(&mut stream).map_err(|_| get_io_error("deserialize bank error"))?;
+    let status_cache_rc: StatusCacheRc = deserialize_from(&mut stream)
+        .map_err(|_| get_io_error("deserialize bank status cache error"))?;
+    bank_rc.update_from_stream(&bank_rc.account_paths(), &mut stream)?;
+    bank.set_bank_rc(&bank_rc, &status_cache_rc);
+
+    if bank.slot() != manifest.slot || bank.hash().to_string() != manifest.bank_hash {
+        return Err(get_io_error("snapshot manifest does not match bank"));
+    }
+    if !bank.verify_hash() {
+        return Err(get_io_error("bank hash mismatch"));
+    }
+    if let Some(expected_bank_hash) = expected_bank_hash {
+        if bank.hash() != *expected_bank_hash {
+            return Err(get_io_error("bank hash does not match the expected hash"));
+        }
+    }
+
+    // Storage for forks past the bank was left out of the archive
+    let accounts = bank.accounts();
+    let mut stale_forks: Vec<_> = accounts
+        .accounts_db
+        .get_storage_entries()
+        .iter()
+        .map(|entry| entry.fork_id())
+        .filter(|fork| *fork > bank.slot())
+        .collect();
+    stale_forks.sort();
+    stale_forks.dedup();
+    for fork in stale_forks {
+        accounts.purge_fork(fork);
+    }
+
+    info!(
+        "loaded bank for slot {} from snapshot archive, hash: {}",
+        bank.slot(),
+        bank.hash()
+    );
+    Ok(bank)
+}
+
+/// Fetch the snapshot archive served by the RPC service at `rpc_addr`
+pub fn download_snapshot<P: AsRef<Path>>(rpc_addr: &SocketAddr, archive_path: P) -> Result<()> {
+    let archive_path = archive_path.as_ref();
+    if let Some(parent) = archive_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let url = format!("http://{}/{}", rpc_addr, SNAPSHOT_ARCHIVE_FILENAME);
+    info!("downloading snapshot archive from {}", url);
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(&url)
+        .send()
+        .and_then(|response| response.error_for_status())
+        .map_err(|err| get_io_error(&format!("snapshot download failed: {}", err)))?;
+
+    let tmp_path = archive_path.with_extension("tmp");
+    let mut file = File::create(&tmp_path)?;
+    let mut body = response.take(MAX_DOWNLOAD_SIZE + 1);
+    let size = std::io::copy(&mut body, &mut file)
+        .map_err(|err| get_io_error(&format!("snapshot download failed: {}", err)))?;
+    if size > MAX_DOWNLOAD_SIZE {
+        let _ignored = fs::remove_file(&tmp_path);
+        return Err(get_io_error("snapshot download too large"));
+    }
+    fs::rename(&tmp_path, archive_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocktree::get_tmp_ledger_path;
+    use crate::genesis_utils::{create_genesis_block, GenesisBlockInfo};
+    use solana_sdk::pubkey::Pubkey;
+    use solana_sdk::signature::KeypairUtil;
+    use std::fs::remove_dir_all;
+
+    #[test]
+    fn test_account_storage_path() {
+        assert_eq!(
+            account_storage_path(Path::new("accounts/3.7/data")),
+            Some(PathBuf::from("3.7/data"))
+        );
+        assert_eq!(account_storage_path(Path::new("accounts/../data")), None);
+        assert_eq!(account_storage_path(Path::new("/accounts/3.7/data")), None);
+        assert_eq!(account_storage_path(Path::new("bank/3.7/data")), None);
+    }
+
+    #[test]
+    fn test_bank_from_archive_rejects_links() {
+        let snapshot_path = get_tmp_ledger_path!();
+        fs::create_dir_all(&snapshot_path).unwrap();
+        let archive_path = get_snapshot_archive_path(&snapshot_path);
+        let manifest = serde_json::to_vec(&SnapshotManifest {
+            version: SNAPSHOT_VERSION,
+            slot: 0,
+            bank_hash: Hash::default().to_string(),
+        })
+        .unwrap();
+
+        let mut builder = Builder::new(BzEncoder::new(
+            File::create(&archive_path).unwrap(),
+            Compression::Best,
+        ));
+        append_data(&mut builder, MANIFEST_FILENAME, &manifest).unwrap();
+        let mut header = Header::new_gnu();
+        header.set_entry_type(EntryType::Symlink);
+        header.set_size(0);
+        header.set_link_name("/etc/passwd").unwrap();
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "accounts/0.0/data", std::io::empty())
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let account_path = get_tmp_ledger_path!();
+        assert!(bank_from_archive(&Some(account_path.clone()), &archive_path, None).is_err());
+        let unpacked = fs::read_dir(&account_path).map(|dir| dir.count());
+        assert_eq!(unpacked.unwrap_or(0), 0);
+
+        remove_dir_all(&snapshot_path).unwrap();
+        let _ignored = remove_dir_all(&account_path);
+    }
+
+    #[test]
+    fn test_snapshot_archive_round_trip() {
+        let GenesisBlockInfo {
+            genesis_block,
+            mint_keypair,
+            ..
+        } = create_genesis_block(10_000);
+        let snapshot_path = get_tmp_ledger_path!();
+        let archive_path = get_snapshot_archive_path(&snapshot_path);
+
+        let key = Pubkey::new_rand();
+        let (slot, hash, accounts_hash) = {
+            let bank0 = Arc::new(Bank::new(&genesis_block));
+            bank0.transfer(1_000, &mint_keypair, &key).unwrap();
+            bank0.freeze();
+            let bank1 = Bank::new_from_parent(&bank0, &Pubkey::default(), 1);
+            bank1.transfer(500, &mint_keypair, &key).unwrap();
+            bank1.freeze();
+            bank1.squash();
+
+            // A fork past the root is left out of the archive
+            let bank2 = Bank::new_from_parent(&Arc::new(bank1), &Pubkey::default(), 2);
+            bank2.transfer(250, &mint_keypair, &key).unwrap();
+            let bank1 = bank2.parent().unwrap();
+
+            let package = package_snapshot(&bank1).unwrap();
+            archive_snapshot_package(&package, &archive_path).unwrap();
+            (bank1.slot(), bank1.hash(), bank1.get_accounts_hash())
+        };
+
+        assert_eq!(
+            read_snapshot_manifest(&archive_path).unwrap(),
+            SnapshotManifest {
+                version: SNAPSHOT_VERSION,
+                slot,
+                bank_hash: hash.to_string(),
+            }
+        );
+
+        // An archive whose bank differs from the one expected is rejected before anything is
+        // unpacked
+        let account_path = get_tmp_ledger_path!();
+        assert!(bank_from_archive(
+            &Some(account_path.clone()),
+            &archive_path,
+            Some(&Hash::default())
+        )
+        .is_err());
+        let unpacked = fs::read_dir(&account_path).map(|dir| dir.count());
+        assert_eq!(unpacked.unwrap_or(0), 0);
+
+        // The original account storage is gone by now, so everything comes from the archive
+        let bank = bank_from_archive(&None, &archive_path, Some(&hash)).unwrap();
+        assert_eq!(bank.slot(), slot);
+        assert_eq!(bank.hash(), hash);
+        assert_eq!(bank.get_accounts_hash(), accounts_hash);
+        assert_eq!(bank.get_balance(&key), 1_500);
+        assert_eq!(bank.get_balance(&mint_keypair.pubkey()), 8_500);
+        assert!(!bank.accounts().accounts_db.has_accounts(2));
+
+        let child = Bank::new_from_parent(&Arc::new(bank), &Pubkey::default(), 3);
+        child.transfer(250, &mint_keypair, &key).unwrap();
+        assert_eq!(child.get_balance(&key), 1_750);
+
+        remove_dir_all(&snapshot_path).unwrap();
+        let _ignored = remove_dir_all(&account_path);
+    }
+
+    #[test]
+    fn test_snapshot_archive_corrupt() {
+        let GenesisBlockInfo { genesis_block, .. } = create_genesis_block(10_000);
+        let snapshot_path = get_tmp_ledger_path!();
+        let archive_path = get_snapshot_archive_path(&snapshot_path);
+
+        let bank = Arc::new(Bank::new(&genesis_block));
+        assert!(package_snapshot(&bank).is_err());
+        bank.freeze();
+        let mut package = package_snapshot(&bank).unwrap();
+        package.bank_hash = Hash::default();
+        archive_snapshot_package(&package, &archive_path).unwrap();
+        assert!(bank_from_archive(&None, &archive_path, None).is_err());
+
+        fs::write(&archive_path, b"not an archive").unwrap();
+        assert!(bank_from_archive(&None, &archive_path, None).is_err());
+
+        remove_dir_all(&snapshot_path).unwrap();
+    }
+}
@@ -1,5 +1,6 @@
 //! The `fullnode` module hosts all the fullnode microservices.
 
+use crate::accounts_cleanup_service::AccountsCleanupService;
 use crate::bank_forks::BankForks;
 use crate::blocktree::{Blocktree, CompletedSlotsReceiver};
 use crate::blocktree_processor::{self, BankForksInfo};
@@ -8,6 +9,7 @@ use crate::cluster_info::{ClusterInfo, Node};
 use crate::contact_info::ContactInfo;
 use crate::gossip_service::{discover_cluster, GossipService};
 use crate::leader_schedule_cache::LeaderScheduleCache;
+use crate::ledger_cleanup_service::LedgerCleanupService;
 use crate::poh_recorder::PohRecorder;
 use crate::poh_service::PohService;
 use crate::rpc::JsonRpcConfig;
@@ -15,19 +17,24 @@ use crate::rpc_pubsub_service::PubSubService;
 use crate::rpc_service::JsonRpcService;
 use crate::rpc_subscriptions::RpcSubscriptions;
 use crate::service::Service;
+use crate::snapshot_packager_service::SnapshotPackagerService;
+use crate::snapshot_utils;
 use crate::storage_stage::StorageState;
 use crate::tpu::Tpu;
 use crate::tvu::{Sockets, Tvu};
 use solana_metrics::datapoint_info;
 use solana_sdk::genesis_block::GenesisBlock;
+use solana_sdk::hash::Hash;
 use solana_sdk::poh_config::PohConfig;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::{Keypair, KeypairUtil};
 use solana_sdk::timing::timestamp;
 use solana_storage_api::SLOTS_PER_SEGMENT;
+use std::fs;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::mpsc::Receiver;
+use std::sync::mpsc::{channel, Receiver};
 use std::sync::{Arc, Mutex, RwLock};
 use std::thread::Result;
 
@@ -40,6 +47,12 @@ pub struct ValidatorConfig {
     pub account_paths: Option<String>,
     pub rpc_config: JsonRpcConfig,
     pub snapshot_path: Option<String>,
+    pub snapshot_interval_slots: u64,
+    /// Hash the bank in the snapshot archive must have, when the archive came from another node
+    pub expected_bank_hash: Option<Hash>,
+    /// Number of slots behind the root kept in the ledger, or all of them if `None`.  Requires
+    /// `snapshot_path`, as a restart replays the ledger from the latest snapshot archive
+    pub max_ledger_slots: Option<u64>,
     pub broadcast_stage_type: BroadcastStageType,
 }
 
@@ -57,6 +70,9 @@ impl Default for ValidatorConfig {
             account_paths: None,
             rpc_config: JsonRpcConfig::default(),
             snapshot_path: None,
+            snapshot_interval_slots: snapshot_utils::DEFAULT_SNAPSHOT_INTERVAL_SLOTS,
+            expected_bank_hash: None,
+            max_ledger_slots: None,
             broadcast_stage_type: BroadcastStageType::Standard,
         }
     }
@@ -68,6 +84,9 @@ pub struct Validator {
     rpc_service: Option<JsonRpcService>,
     rpc_pubsub_service: Option<PubSubService>,
     gossip_service: GossipService,
+    accounts_cleanup_service: AccountsCleanupService,
+    snapshot_packager_service: Option<SnapshotPackagerService>,
+    ledger_cleanup_service: Option<LedgerCleanupService>,
     poh_recorder: Arc<Mutex<PohRecorder>>,
     poh_service: PohService,
     tpu: Tpu,
@@ -93,7 +112,7 @@ impl Validator {
 
         info!("creating bank...");
         let (
-            bank_forks,
+            mut bank_forks,
             bank_forks_info,
             blocktree,
             ledger_signal_receiver,
@@ -104,6 +123,7 @@ impl Validator {
             ledger_path,
             config.account_paths.clone(),
             config.snapshot_path.clone(),
+            config.expected_bank_hash,
         );
 
         let leader_schedule_cache = Arc::new(leader_schedule_cache);
@@ -150,6 +170,14 @@ impl Validator {
             node.sockets.gossip.local_addr().unwrap()
         );
 
+        let snapshot_packager_service = config.snapshot_path.as_ref().map(|snapshot_path| {
+            let (snapshot_package_sender, snapshot_package_receiver) = channel();
+            bank_forks.set_snapshot_package_sender(
+                snapshot_package_sender,
+                config.snapshot_interval_slots,
+            );
+            SnapshotPackagerService::new(snapshot_package_receiver, snapshot_path, &exit)
+        });
         let bank_forks = Arc::new(RwLock::new(bank_forks));
 
         node.info.wallclock = timestamp();
@@ -169,6 +197,8 @@ impl Validator {
                 storage_state.clone(),
                 config.rpc_config.clone(),
                 bank_forks.clone(),
+                blocktree.clone(),
+                config.snapshot_path.as_ref().map(PathBuf::from),
                 &exit,
             ))
         };
@@ -190,6 +220,21 @@ impl Validator {
             ))
         };
 
+        let accounts_cleanup_service = AccountsCleanupService::new(&bank_forks, &exit);
+        let ledger_cleanup_service = config.max_ledger_slots.map(|max_ledger_slots| {
+            let snapshot_path = config
+                .snapshot_path
+                .as_ref()
+                .expect("limiting the ledger size requires a snapshot path");
+            LedgerCleanupService::new(
+                &blocktree,
+                &bank_forks,
+                max_ledger_slots,
+                snapshot_path,
+                &exit,
+            )
+        });
+
         let gossip_service = GossipService::new(
             &cluster_info,
             Some(blocktree.clone()),
@@ -274,6 +319,9 @@ impl Validator {
         Self {
             id,
             gossip_service,
+            accounts_cleanup_service,
+            snapshot_packager_service,
+            ledger_cleanup_service,
             rpc_service,
             rpc_pubsub_service,
             tpu,
@@ -301,7 +349,45 @@ fn get_bank_forks(
     blocktree: &Blocktree,
     account_paths: Option<String>,
     snapshot_path: Option<String>,
+    expected_bank_hash: Option<Hash>,
 ) -> (BankForks, Vec<BankForksInfo>, LeaderScheduleCache) {
+    if let Some(path) = &snapshot_path {
+        let archive_path = snapshot_utils::get_snapshot_archive_path(path);
+        if archive_path.exists() {
+            match snapshot_utils::bank_from_archive(
+                &account_paths,
+                &archive_path,
+                expected_bank_hash.as_ref(),
+            ) {
+                Ok(bank) => {
+                    match blocktree_processor::process_blocktree_from_root(
+                        &blocktree,
+                        Arc::new(bank),
+                    ) {
+                        Ok((mut bank_forks, bank_forks_info, leader_schedule_cache)) => {
+                            bank_forks.set_snapshot_config(snapshot_path);
+                            return (bank_forks, bank_forks_info, leader_schedule_cache);
+                        }
+                        Err(_) => warn!("Failed to replay ledger from snapshot archive"),
+                    }
+                }
+                Err(e) => {
+                    if expected_bank_hash.is_some() {
+                        // Remove the untrusted archive so a restart does not load it unchecked
+                        let _ = fs::remove_file(&archive_path);
+                        panic!(
+                            "Failed to load snapshot archive {:?}: {:?}",
+                            archive_path, e
+                        );
+                    }
+                    warn!(
+                        "Failed to load snapshot archive {:?}: {:?}",
+                        archive_path, e
+                    )
+                }
+            }
+        }
+    }
     if snapshot_path.is_some() {
         let bank_forks =
             BankForks::load_from_snapshot(&genesis_block, account_paths.clone(), &snapshot_path);
@@ -331,6 +417,7 @@ pub fn new_banks_from_blocktree(
     blocktree_path: &str,
     account_paths: Option<String>,
     snapshot_path: Option<String>,
+    expected_bank_hash: Option<Hash>,
 ) -> (
     BankForks,
     Vec<BankForksInfo>,
@@ -347,8 +434,13 @@ pub fn new_banks_from_blocktree(
         Blocktree::open_with_signal(blocktree_path)
             .expect("Expected to successfully open database ledger");
 
-    let (bank_forks, bank_forks_info, leader_schedule_cache) =
-        get_bank_forks(&genesis_block, &blocktree, account_paths, snapshot_path);
+    let (bank_forks, bank_forks_info, leader_schedule_cache) = get_bank_forks(
+        &genesis_block,
+        &blocktree,
+        account_paths,
+        snapshot_path,
+        expected_bank_hash,
+    );
 
     (
         bank_forks,
@@ -375,6 +467,13 @@ impl Service for Validator {
         }
 
         self.gossip_service.join()?;
+        self.accounts_cleanup_service.join()?;
+        if let Some(snapshot_packager_service) = self.snapshot_packager_service {
+            snapshot_packager_service.join()?;
+        }
+        if let Some(ledger_cleanup_service) = self.ledger_cleanup_service {
+            ledger_cleanup_service.join()?;
+        }
         self.tpu.join()?;
         self.tvu.join()?;
         self.ip_echo_server.shutdown_now();
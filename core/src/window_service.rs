@@ -280,7 +280,7 @@ mod test {
     use crate::packet::index_blobs;
     use crate::service::Service;
     use crate::streamer::{blob_receiver, responder};
-    use solana_runtime::epoch_schedule::MINIMUM_SLOTS_PER_EPOCH;
+    use solana_sdk::epoch_schedule::MINIMUM_SLOTS_PER_EPOCH;
     use solana_sdk::hash::Hash;
     use solana_sdk::signature::{Keypair, KeypairUtil};
     use std::fs::remove_dir_all;
@@ -0,0 +1,138 @@
+use jsonrpc_core::futures::Stream;
+use solana::bank_forks::BankForks;
+use solana::genesis_utils::{create_genesis_block, GenesisBlockInfo};
+use solana::rpc_pubsub_service::PubSubService;
+use solana::rpc_subscriptions::RpcSubscriptions;
+use solana_client::pubsub_client::{PubsubClient, PubsubSubscription, SlotInfo};
+use solana_runtime::bank::Bank;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_transaction;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread::sleep;
+use std::time::Duration;
+
+fn start_pubsub_service(
+    addr: SocketAddr,
+) -> (Arc<RpcSubscriptions>, Arc<AtomicBool>, PubSubService) {
+    let subscriptions = Arc::new(RpcSubscriptions::default());
+    let exit = Arc::new(AtomicBool::new(false));
+    let pubsub_service = PubSubService::new(&subscriptions, addr, &exit);
+    (subscriptions, exit, pubsub_service)
+}
+
+fn wait_for_subscription<T>(subscription: &PubsubSubscription<T>) {
+    for _ in 0..100 {
+        if subscription.is_subscribed() {
+            return;
+        }
+        sleep(Duration::from_millis(100));
+    }
+    panic!("subscription was never acknowledged");
+}
+
+#[test]
+fn test_pubsub_client_root_subscribe() {
+    solana_logger::setup();
+    let port = solana_netutil::find_available_port_in_range((10000, 65535)).unwrap();
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), port);
+    let (subscriptions, exit, pubsub_service) = start_pubsub_service(addr);
+
+    let client = PubsubClient::new(format!("ws://{}", addr));
+    let subscription = client.root_subscribe();
+    wait_for_subscription(&subscription);
+    subscriptions.notify_root(5);
+    let mut notifications = subscription.wait();
+    assert_eq!(notifications.next(), Some(Ok(5)));
+
+    // Restart the service; the client should reconnect and resubscribe on its own
+    exit.store(true, Ordering::Relaxed);
+    pubsub_service.close().unwrap();
+    let (subscriptions, exit, pubsub_service) = start_pubsub_service(addr);
+    let subscription = notifications.into_inner();
+    wait_for_subscription(&subscription);
+    subscriptions.notify_root(6);
+    let mut notifications = subscription.wait();
+    assert_eq!(notifications.next(), Some(Ok(6)));
+
+    // Dropping the subscription unsubscribes it on the server
+    drop(notifications);
+    sleep(Duration::from_millis(500));
+    assert!(client.is_connected());
+    assert!(!subscriptions.remove_root_subscription(&jsonrpc_pubsub::SubscriptionId::Number(0)));
+
+    drop(client);
+    exit.store(true, Ordering::Relaxed);
+    pubsub_service.close().unwrap();
+}
+
+#[test]
+fn test_pubsub_client_slot_subscribe() {
+    solana_logger::setup();
+    let port = solana_netutil::find_available_port_in_range((10000, 65535)).unwrap();
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), port);
+    let (subscriptions, exit, pubsub_service) = start_pubsub_service(addr);
+
+    let client = PubsubClient::new(format!("ws://{}", addr));
+    let subscription = client.slot_subscribe();
+    wait_for_subscription(&subscription);
+    subscriptions.notify_slot(2, 1, 0);
+    subscriptions.notify_slot(3, 2, 1);
+    let mut notifications = subscription.wait();
+    assert_eq!(
+        notifications.next(),
+        Some(Ok(SlotInfo {
+            slot: 2,
+            parent: 1,
+            root: 0,
+        }))
+    );
+    assert_eq!(
+        notifications.next(),
+        Some(Ok(SlotInfo {
+            slot: 3,
+            parent: 2,
+            root: 1,
+        }))
+    );
+
+    drop(client);
+    exit.store(true, Ordering::Relaxed);
+    pubsub_service.close().unwrap();
+}
+
+#[test]
+fn test_pubsub_client_account_subscribe() {
+    solana_logger::setup();
+    let GenesisBlockInfo {
+        genesis_block,
+        mint_keypair,
+        ..
+    } = create_genesis_block(100);
+    let bank = Bank::new(&genesis_block);
+    let blockhash = bank.last_blockhash();
+    let bank_forks = Arc::new(RwLock::new(BankForks::new(0, bank)));
+
+    let port = solana_netutil::find_available_port_in_range((10000, 65535)).unwrap();
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), port);
+    let (subscriptions, exit, pubsub_service) = start_pubsub_service(addr);
+
+    let client = PubsubClient::new(format!("ws://{}", addr));
+    let alice = Pubkey::new_rand();
+    let subscription = client.account_subscribe(&alice, None);
+    wait_for_subscription(&subscription);
+
+    let tx = system_transaction::create_user_account(&mint_keypair, &alice, 10, blockhash);
+    bank_forks.read().unwrap()[0]
+        .process_transaction(&tx)
+        .unwrap();
+    subscriptions.notify_subscribers(0, &bank_forks);
+
+    let account = subscription.wait().next().unwrap().unwrap();
+    assert_eq!(account.lamports, 10);
+
+    drop(client);
+    exit.store(true, Ordering::Relaxed);
+    pubsub_service.close().unwrap();
+}
@@ -17,7 +17,7 @@ extern crate solana_exchange_program;
 extern crate solana_storage_program;
 
 use clap::{crate_description, crate_name, crate_version, value_t_or_exit, App, Arg};
-use solana::blocktree::create_new_ledger;
+use solana::blocktree::{create_new_ledger_with_backend, BlocktreeBackend};
 use solana_sdk::account::Account;
 use solana_sdk::fee_calculator::FeeCalculator;
 use solana_sdk::genesis_block::Builder;
@@ -25,6 +25,7 @@ use solana_sdk::hash::{hash, Hash};
 use solana_sdk::poh_config::PohConfig;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::{read_keypair, Keypair, KeypairUtil};
+use solana_sdk::syscall::rent::Rent;
 use solana_sdk::system_program;
 use solana_sdk::timing;
 use solana_stake_api::stake_state;
@@ -79,6 +80,8 @@ fn main() -> Result<(), Box<dyn error::Error>> {
         .to_string();
     let default_target_tick_duration =
         &timing::duration_as_ms(&PohConfig::default().target_tick_duration).to_string();
+    let default_lamports_per_byte_epoch = &Rent::default().lamports_per_byte_epoch.to_string();
+    let default_rent_exemption_threshold = &Rent::default().exemption_threshold.to_string();
     let default_ticks_per_slot = &timing::DEFAULT_TICKS_PER_SLOT.to_string();
     let default_slots_per_epoch = &timing::DEFAULT_SLOTS_PER_EPOCH.to_string();
 
@@ -103,6 +106,14 @@ fn main() -> Result<(), Box<dyn error::Error>> {
                 .required(true)
                 .help("Use directory as persistent ledger location"),
         )
+        .arg(
+            Arg::with_name("blocktree_backend")
+                .long("blocktree-backend")
+                .value_name("BACKEND")
+                .takes_value(true)
+                .possible_values(&["rocksdb", "kvstore"])
+                .help("Database to store the ledger in, overriding SOLANA_BLOCKTREE_BACKEND [default: rocksdb]"),
+        )
         .arg(
             Arg::with_name("lamports")
                 .short("t")
@@ -176,6 +187,27 @@ fn main() -> Result<(), Box<dyn error::Error>> {
                      verification when the cluster is operating at target-signatures-per-slot",
                 ),
         )
+        .arg(
+            Arg::with_name("lamports_per_byte_epoch")
+                .long("lamports-per-byte-epoch")
+                .value_name("LAMPORTS")
+                .takes_value(true)
+                .default_value(default_lamports_per_byte_epoch)
+                .help(
+                    "The rent in lamports the cluster charges each account per byte of storage \
+                     per epoch. A value of 0 disables rent",
+                ),
+        )
+        .arg(
+            Arg::with_name("rent_exemption_threshold")
+                .long("rent-exemption-threshold")
+                .value_name("EPOCHS")
+                .takes_value(true)
+                .default_value(default_rent_exemption_threshold)
+                .help(
+                    "Accounts holding at least this many epochs of rent are exempt from paying it",
+                ),
+        )
         .arg(
             Arg::with_name("target_signatures_per_slot")
                 .long("target-signatures-per-slot")
@@ -317,6 +349,10 @@ fn main() -> Result<(), Box<dyn error::Error>> {
     fee_calculator.target_signatures_per_slot =
         value_t_or_exit!(matches, "target_signatures_per_slot", usize);
     builder = builder.fee_calculator(&FeeCalculator::new_derived(&fee_calculator, 0));
+    builder = builder.rent(&Rent::new(
+        value_t_or_exit!(matches, "lamports_per_byte_epoch", u64),
+        value_t_or_exit!(matches, "rent_exemption_threshold", u64),
+    ));
 
     let mut poh_config = PohConfig::default();
     poh_config.target_tick_duration =
@@ -355,7 +391,12 @@ fn main() -> Result<(), Box<dyn error::Error>> {
         builder = append_primordial_accounts(file, AccountFileFormat::Keypair, builder)?;
     }
 
-    create_new_ledger(ledger_path, &builder.build())?;
+    let backend = if matches.is_present("blocktree_backend") {
+        value_t_or_exit!(matches, "blocktree_backend", BlocktreeBackend)
+    } else {
+        BlocktreeBackend::default()
+    };
+    create_new_ledger_with_backend(ledger_path, &builder.build(), backend)?;
     Ok(())
 }
 
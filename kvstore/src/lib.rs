@@ -253,6 +253,8 @@ impl KvStore {
         let mut tables = self.tables.write().unwrap();
 
         storage::flush_table(&mem.values, &*self.mapper, &mut *tables)?;
+        // Record the new table before the log that also holds its rows is reset
+        dump_tables(&self.root, &*self.mapper)?;
         mem.values.clear();
         mem.mem_size = 0;
         log.reset().expect("Write-log rotation failed");
@@ -282,13 +284,14 @@ impl Default for Config {
 fn open(root: &Path, mapper: Arc<dyn Mapper>, config: Config) -> Result<KvStore> {
     let root = root.to_path_buf();
     let log_path = root.join(LOG_FILE);
-    let restore_log = log_path.exists();
+    // An empty log, e.g. one reset by a flush, has nothing to restore and can't be mapped
+    let restore_log = log_path.exists() && fs::metadata(&log_path)?.len() > 0;
 
     if !root.exists() {
         fs::create_dir(&root)?;
     }
 
-    let commit = chrono::Utc::now().timestamp();
+    let commit = chrono::Utc::now().timestamp_nanos();
     let mut log = WriteLog::open(&log_path, config.log_config)?;
     let values = if restore_log && !config.in_memory {
         log.materialize()?
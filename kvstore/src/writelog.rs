@@ -28,7 +28,8 @@ pub struct Config {
 
 impl WriteLog {
     pub fn open(path: &Path, config: Config) -> Result<Self> {
-        let file = file_opts().open(path)?;
+        // Append, so a re-opened log keeps the writes it was restored from
+        let file = file_opts().append(true).open(path)?;
 
         Ok(WriteLog {
             config,
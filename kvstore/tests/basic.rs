@@ -156,6 +156,77 @@ fn test_close_reopen() {
     teardown(&path);
 }
 
+#[test]
+fn test_reopen_empty() {
+    let path = setup("test_reopen_empty");
+    let cfg = Config::default();
+
+    // Re-opening a store that was never written to finds an empty write log
+    drop(KvStore::open(&path, cfg).unwrap());
+    let lsm = KvStore::open(&path, cfg).unwrap();
+    assert_eq!(
+        lsm.range(Key::ALL_INCLUSIVE).expect("range fail").count(),
+        0
+    );
+
+    teardown(&path);
+}
+
+#[test]
+fn test_reopen_twice() {
+    let path = setup("test_reopen_twice");
+    let cfg = Config::default();
+    let mut pairs: Vec<_> = gen::pairs(KB).take(64).collect();
+    pairs.sort_unstable_by_key(|(k, _)| *k);
+
+    let lsm = KvStore::open(&path, cfg).unwrap();
+    for (k, v) in pairs.iter().take(32) {
+        lsm.put(k, v).expect("put fail");
+    }
+
+    // Writes after a re-open are added to the log rather than written over it
+    drop(lsm);
+    let lsm = KvStore::open(&path, cfg).unwrap();
+    for (k, v) in pairs.iter().skip(32) {
+        lsm.put(k, v).expect("put fail");
+    }
+
+    drop(lsm);
+    let lsm = KvStore::open(&path, cfg).unwrap();
+    let retrieved: Vec<(Key, Vec<u8>)> =
+        lsm.range(Key::ALL_INCLUSIVE).expect("range fail").collect();
+    assert_eq!(pairs, retrieved);
+
+    teardown(&path);
+}
+
+#[test]
+fn test_reopen_after_flush() {
+    let path = setup("test_reopen_after_flush");
+    let cfg = Config {
+        max_mem: 64 * KB,
+        max_tables: 5,
+        page_size: 64 * KB,
+        ..Config::default()
+    };
+    let mut pairs: Vec<_> = gen::pairs(KB).take(256).collect();
+    pairs.sort_unstable_by_key(|(k, _)| *k);
+
+    // Enough rows to flush the memtable, after which they are only in the tables
+    let lsm = KvStore::open(&path, cfg).unwrap();
+    for (k, v) in pairs.iter() {
+        lsm.put(k, v).expect("put fail");
+    }
+
+    drop(lsm);
+    let lsm = KvStore::open(&path, cfg).unwrap();
+    let retrieved: Vec<(Key, Vec<u8>)> =
+        lsm.range(Key::ALL_INCLUSIVE).expect("range fail").collect();
+    assert_eq!(pairs, retrieved);
+
+    teardown(&path);
+}
+
 #[test]
 fn test_partitioned() {
     let path = setup("test_partitioned");
@@ -1,10 +1,206 @@
-use clap::{crate_description, crate_name, crate_version, App, Arg, SubCommand};
+use clap::{crate_description, crate_name, crate_version, value_t_or_exit, App, Arg, SubCommand};
+use solana::bank_forks::BankForks;
 use solana::blocktree::Blocktree;
-use solana::blocktree_processor::process_blocktree;
+use solana::blocktree_processor::{process_blocktree, process_blocktree_with_callback};
+use solana::ledger_export::{export_ledger, import_ledger};
 use solana_sdk::genesis_block::GenesisBlock;
-use std::io::{stdout, Write};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_instruction::SystemInstruction;
+use solana_sdk::system_program;
+use solana_stake_api::stake_instruction::StakeInstruction;
+use solana_vote_api::vote_instruction::VoteInstruction;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{stdout, BufReader, BufWriter, Write};
 use std::process::exit;
 
+/// Render `slots`, which must be sorted, as a list of inclusive ranges, e.g. "0-3, 5, 7-8"
+fn format_slot_ranges(slots: &[u64]) -> String {
+    let mut ranges: Vec<(u64, u64)> = vec![];
+    for &slot in slots {
+        if let Some(range) = ranges.last_mut() {
+            if range.1 + 1 == slot {
+                range.1 = slot;
+                continue;
+            }
+        }
+        ranges.push((slot, slot));
+    }
+    ranges
+        .iter()
+        .map(|(first, last)| {
+            if first == last {
+                first.to_string()
+            } else {
+                format!("{}-{}", first, last)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn output_bounds(blocktree: &Blocktree) {
+    let slot_metas = blocktree
+        .slot_meta_iterator(0)
+        .unwrap_or_else(|err| {
+            eprintln!("Failed to read ledger slots: {:?}", err);
+            exit(1);
+        })
+        .collect::<Vec<_>>();
+    if slot_metas.is_empty() {
+        println!("Ledger is empty");
+        return;
+    }
+    let slots: Vec<u64> = slot_metas.iter().map(|(slot, _)| *slot).collect();
+    let roots: Vec<u64> = slots
+        .iter()
+        .cloned()
+        .filter(|slot| blocktree.is_root(*slot))
+        .collect();
+    println!(
+        "Ledger has {} slots: {} through {}",
+        slots.len(),
+        slots[0],
+        slots[slots.len() - 1]
+    );
+    if roots.is_empty() {
+        println!("Roots: none");
+    } else {
+        println!("Roots: {}", format_slot_ranges(&roots));
+    }
+}
+
+fn output_graph(blocktree: &Blocktree) {
+    let slot_metas = blocktree.slot_meta_iterator(0).unwrap_or_else(|err| {
+        eprintln!("Failed to read ledger slots: {:?}", err);
+        exit(1);
+    });
+    println!("digraph Ledger {{");
+    println!("  node [shape=box];");
+    for (slot, meta) in slot_metas {
+        let (label, style) = if blocktree.is_dead(slot) {
+            ("dead", "style=filled, fillcolor=lightcoral")
+        } else if blocktree.is_root(slot) {
+            ("root", "style=filled, fillcolor=lightblue")
+        } else if !meta.is_full() {
+            ("incomplete", "style=dashed")
+        } else {
+            ("", "")
+        };
+        if label.is_empty() {
+            println!("  \"{}\";", slot);
+        } else {
+            println!(
+                "  \"{}\" [label=\"{}\\n{}\", {}];",
+                slot, slot, label, style
+            );
+        }
+        for next_slot in meta.next_slots {
+            println!("  \"{}\" -> \"{}\";", slot, next_slot);
+        }
+    }
+    println!("}}");
+}
+
+/// Decode an instruction of one of the native programs, falling back to its raw data
+fn format_instruction_data(program_id: &Pubkey, data: &[u8]) -> String {
+    let decoded = if *program_id == system_program::id() {
+        bincode::deserialize::<SystemInstruction>(data).map(|ix| format!("{:?}", ix))
+    } else if *program_id == solana_vote_api::id() {
+        bincode::deserialize::<VoteInstruction>(data).map(|ix| format!("{:?}", ix))
+    } else if *program_id == solana_stake_api::id() {
+        bincode::deserialize::<StakeInstruction>(data).map(|ix| format!("{:?}", ix))
+    } else {
+        return format!("{:?}", data);
+    };
+    decoded.unwrap_or_else(|_| format!("{:?}", data))
+}
+
+fn output_slot(blocktree: &Blocktree, slot: u64) {
+    let meta = match blocktree.meta(slot) {
+        Ok(Some(meta)) => meta,
+        Ok(None) => {
+            eprintln!("Slot {} is not in the ledger", slot);
+            exit(1);
+        }
+        Err(err) => {
+            eprintln!("Failed to read slot {}: {:?}", slot, err);
+            exit(1);
+        }
+    };
+    let entries = blocktree
+        .get_slot_entries(slot, 0, None)
+        .unwrap_or_else(|err| {
+            eprintln!("Failed to read entries for slot {}: {:?}", slot, err);
+            exit(1);
+        });
+    println!(
+        "Slot {}, parent {}, {} entries{}",
+        slot,
+        meta.parent_slot,
+        entries.len(),
+        if meta.is_full() { "" } else { " (incomplete)" }
+    );
+    for (i, entry) in entries.iter().enumerate() {
+        println!(
+            "  Entry {} - num_hashes: {}, hash: {}, transactions: {}",
+            i,
+            entry.num_hashes,
+            entry.hash,
+            entry.transactions.len()
+        );
+        for (j, transaction) in entry.transactions.iter().enumerate() {
+            // Entries come off disk unverified, so a transaction may be missing signatures or
+            // reference accounts its message doesn't hold
+            let signature = transaction
+                .signatures
+                .first()
+                .map(ToString::to_string)
+                .unwrap_or_else(|| "<missing>".to_string());
+            println!("    Transaction {} - signature: {}", j, signature);
+            let message = &transaction.message;
+            let account_key = |index: u8| {
+                message
+                    .account_keys
+                    .get(index as usize)
+                    .map(ToString::to_string)
+                    .unwrap_or_else(|| format!("<invalid index {}>", index))
+            };
+            for (k, instruction) in message.instructions.iter().enumerate() {
+                let accounts: Vec<String> = instruction
+                    .accounts
+                    .iter()
+                    .map(|index| account_key(*index))
+                    .collect();
+                println!(
+                    "      Instruction {} - program: {}, accounts: [{}]",
+                    k,
+                    account_key(instruction.program_ids_index),
+                    accounts.join(", ")
+                );
+                let data = match message
+                    .account_keys
+                    .get(instruction.program_ids_index as usize)
+                {
+                    Some(program_id) => format_instruction_data(program_id, &instruction.data),
+                    None => format!("{:?}", instruction.data),
+                };
+                println!("        {}", data);
+            }
+        }
+    }
+}
+
+fn load_bank_forks(genesis_block: &GenesisBlock, blocktree: &Blocktree) -> BankForks {
+    match process_blocktree(genesis_block, blocktree, None) {
+        Ok((bank_forks, _, _)) => bank_forks,
+        Err(err) => {
+            eprintln!("Failed to replay ledger: {:?}", err);
+            exit(1);
+        }
+    }
+}
+
 fn main() {
     solana_logger::setup();
     let matches = App::new(crate_name!()).about(crate_description!())
@@ -42,11 +238,84 @@ fn main() {
         )
         .subcommand(SubCommand::with_name("print").about("Print the ledger"))
         .subcommand(SubCommand::with_name("json").about("Print the ledger in JSON format"))
-        .subcommand(SubCommand::with_name("verify").about("Verify the ledger's PoH"))
+        .subcommand(
+            SubCommand::with_name("verify").about("Verify the ledger's PoH and accounts hash"),
+        )
+        .subcommand(SubCommand::with_name("bounds").about("Print the ledger's slot range and roots"))
+        .subcommand(
+            SubCommand::with_name("graph").about("Print the ledger's fork graph in Graphviz DOT format"),
+        )
+        .subcommand(
+            SubCommand::with_name("slot")
+                .about("Print the entries of a slot with their decoded instructions")
+                .arg(
+                    Arg::with_name("slot")
+                        .index(1)
+                        .value_name("SLOT")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Slot to print"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("accounts")
+                .about("Replay the ledger and print the accounts of the highest bank")
+                .arg(
+                    Arg::with_name("program_id")
+                        .long("program-id")
+                        .value_name("PUBKEY")
+                        .takes_value(true)
+                        .help("Only print the accounts owned by this program"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("bank-hash").about("Replay the ledger and print the hash of every bank"),
+        )
+        .subcommand(
+            SubCommand::with_name("export")
+                .about("Write the genesis block and rooted slots of the ledger to a file")
+                .arg(
+                    Arg::with_name("file")
+                        .index(1)
+                        .value_name("FILE")
+                        .takes_value(true)
+                        .required(true)
+                        .help("File to write the export to"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("import")
+                .about("Create the ledger from a file written by export")
+                .arg(
+                    Arg::with_name("file")
+                        .index(1)
+                        .value_name("FILE")
+                        .takes_value(true)
+                        .required(true)
+                        .help("File to read the export from"),
+                ),
+        )
         .get_matches();
 
     let ledger_path = matches.value_of("ledger").unwrap();
 
+    // The ledger an import creates can't be opened beforehand
+    if let ("import", Some(args_matches)) = matches.subcommand() {
+        let file = args_matches.value_of("file").unwrap();
+        let reader = File::open(file).unwrap_or_else(|err| {
+            eprintln!("Failed to open {}: {}", file, err);
+            exit(1);
+        });
+        match import_ledger(BufReader::new(reader), ledger_path) {
+            Ok(num_slots) => println!("Imported {} slots into {}", num_slots, ledger_path),
+            Err(err) => {
+                eprintln!("Failed to import ledger from {}: {}", file, err);
+                exit(1);
+            }
+        }
+        return;
+    }
+
     let genesis_block = GenesisBlock::load(ledger_path).unwrap_or_else(|err| {
         eprintln!(
             "Failed to open ledger genesis_block at {}: {}",
@@ -112,14 +381,76 @@ fn main() {
             stdout().write_all(b"\n]}\n").expect("close array");
         }
         ("verify", _) => match process_blocktree(&genesis_block, &blocktree, None) {
-            Ok((_bank_forks, bank_forks_info, _)) => {
+            Ok((bank_forks, bank_forks_info, _)) => {
                 println!("{:?}", bank_forks_info);
+                let root_bank = bank_forks.root_bank();
+                if let Some((slot, hash)) = root_bank.get_accounts_hash() {
+                    println!("accounts hash at slot {}: {}", slot, hash);
+                }
+                if !root_bank.verify_accounts_hash() {
+                    eprintln!("Ledger verification failed: accounts hash mismatch");
+                    exit(1);
+                }
             }
             Err(err) => {
                 eprintln!("Ledger verification failed: {:?}", err);
                 exit(1);
             }
         },
+        ("bounds", _) => output_bounds(&blocktree),
+        ("graph", _) => output_graph(&blocktree),
+        ("slot", Some(args_matches)) => {
+            let slot = value_t_or_exit!(args_matches, "slot", u64);
+            output_slot(&blocktree, slot);
+        }
+        ("accounts", Some(args_matches)) => {
+            let bank = load_bank_forks(&genesis_block, &blocktree).working_bank();
+            let accounts = if args_matches.is_present("program_id") {
+                let program_id = value_t_or_exit!(args_matches, "program_id", Pubkey);
+                bank.get_program_accounts(&program_id)
+            } else {
+                bank.get_all_accounts()
+            };
+            println!("Accounts at slot {}:", bank.slot());
+            for (pubkey, account) in accounts {
+                println!(
+                    "{}: lamports: {}, owner: {}, data_len: {}, executable: {}",
+                    pubkey,
+                    account.lamports,
+                    account.owner,
+                    account.data.len(),
+                    account.executable
+                );
+            }
+        }
+        ("bank-hash", _) => {
+            let mut bank_hashes = BTreeMap::new();
+            let result =
+                process_blocktree_with_callback(&genesis_block, &blocktree, None, |bank| {
+                    bank_hashes.insert(bank.slot(), bank.hash());
+                });
+            if let Err(err) = result {
+                eprintln!("Failed to replay ledger: {:?}", err);
+                exit(1);
+            }
+            for (slot, hash) in bank_hashes {
+                println!("{}: {}", slot, hash);
+            }
+        }
+        ("export", Some(args_matches)) => {
+            let file = args_matches.value_of("file").unwrap();
+            let writer = File::create(file).unwrap_or_else(|err| {
+                eprintln!("Failed to create {}: {}", file, err);
+                exit(1);
+            });
+            match export_ledger(&genesis_block, &blocktree, BufWriter::new(writer)) {
+                Ok(num_slots) => println!("Exported {} slots to {}", num_slots, file),
+                Err(err) => {
+                    eprintln!("Failed to export ledger to {}: {}", file, err);
+                    exit(1);
+                }
+            }
+        }
         ("", _) => {
             eprintln!("{}", matches.usage());
             exit(1);
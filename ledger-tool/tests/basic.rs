@@ -4,6 +4,7 @@ extern crate solana;
 use assert_cmd::prelude::*;
 use solana::blocktree::create_new_tmp_ledger;
 use solana::genesis_utils::create_genesis_block;
+use std::fs::{remove_dir_all, remove_file};
 use std::process::Command;
 use std::process::Output;
 
@@ -62,3 +63,76 @@ fn nominal() {
     assert!(output.status.success());
     assert_eq!(count_newlines(&output.stdout), 0);
 }
+
+#[test]
+fn slot_subcommands() {
+    let genesis_block = create_genesis_block(100).genesis_block;
+    let ticks_per_slot = genesis_block.ticks_per_slot;
+    let (ledger_path, _blockhash) = create_new_tmp_ledger!(&genesis_block);
+
+    let output = run_ledger_tool(&["-l", &ledger_path, "bounds"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("Ledger has 1 slots: 0 through 0"));
+
+    let output = run_ledger_tool(&["-l", &ledger_path, "graph"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("digraph Ledger {"));
+
+    // A header line, then one line per tick
+    let output = run_ledger_tool(&["-l", &ledger_path, "slot", "0"]);
+    assert!(output.status.success());
+    assert_eq!(count_newlines(&output.stdout), 1 + ticks_per_slot as usize);
+    assert!(!run_ledger_tool(&["-l", &ledger_path, "slot", "1"])
+        .status
+        .success());
+
+    // The bootstrap leader's vote account is the only one the vote program owns
+    let output = run_ledger_tool(&[
+        "-l",
+        &ledger_path,
+        "accounts",
+        "--program-id",
+        "Vote111111111111111111111111111111111111111",
+    ]);
+    assert!(output.status.success());
+    assert_eq!(count_newlines(&output.stdout), 2);
+
+    let output = run_ledger_tool(&["-l", &ledger_path, "bank-hash"]);
+    assert!(output.status.success());
+    assert_eq!(count_newlines(&output.stdout), 1);
+}
+
+#[test]
+fn export_import() {
+    let genesis_block = create_genesis_block(100).genesis_block;
+    let (ledger_path, _blockhash) = create_new_tmp_ledger!(&genesis_block);
+
+    // Replaying the ledger roots slot 0
+    assert!(run_ledger_tool(&["-l", &ledger_path, "verify"])
+        .status
+        .success());
+
+    let export_file = format!("{}.export", ledger_path);
+    let output = run_ledger_tool(&["-l", &ledger_path, "export", &export_file]);
+    assert!(output.status.success());
+
+    let import_path = format!("{}-imported", ledger_path);
+    let output = run_ledger_tool(&["-l", &import_path, "import", &export_file]);
+    assert!(output.status.success());
+
+    // Both ledgers replay to the same bank
+    let original = run_ledger_tool(&["-l", &ledger_path, "bank-hash"]);
+    let imported = run_ledger_tool(&["-l", &import_path, "bank-hash"]);
+    assert!(imported.status.success());
+    assert_eq!(original.stdout, imported.stdout);
+
+    // An existing ledger isn't overwritten
+    assert!(
+        !run_ledger_tool(&["-l", &import_path, "import", &export_file])
+            .status
+            .success()
+    );
+
+    remove_file(&export_file).unwrap();
+    remove_dir_all(&import_path).unwrap();
+}
@@ -1,7 +1,10 @@
+use crate::invoke::{self, InvokeParameters};
 use crate::Alloc;
 use libc::c_char;
 use log::*;
 use solana_rbpf::{EbpfVmRaw, MemoryRegion};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::invoke_context;
 use std::alloc::Layout;
 use std::any::Any;
 use std::ffi::CStr;
@@ -48,8 +51,24 @@ pub fn register_helpers(vm: &mut EbpfVmRaw) -> Result<(MemoryRegion), Error> {
         helper_sol_log_,
         None,
     )?;
-    vm.register_helper_ex("sol_log_64", None, helper_sol_log_u64, None)?;
-    vm.register_helper_ex("sol_log_64_", None, helper_sol_log_u64, None)?;
+    vm.register_helper_ex(
+        "sol_log_64",
+        Some(helper_sol_log_u64_verify),
+        helper_sol_log_u64,
+        None,
+    )?;
+    vm.register_helper_ex(
+        "sol_log_64_",
+        Some(helper_sol_log_u64_verify),
+        helper_sol_log_u64,
+        None,
+    )?;
+    vm.register_helper_ex(
+        "sol_invoke_",
+        Some(helper_sol_invoke_verify_),
+        helper_sol_invoke_,
+        None,
+    )?;
 
     let heap = vec![0_u8; DEFAULT_HEAP_SIZE];
     let heap_region = MemoryRegion::new_from_slice(&heap);
@@ -64,6 +83,13 @@ pub fn register_helpers(vm: &mut EbpfVmRaw) -> Result<(MemoryRegion), Error> {
     Ok(heap_region)
 }
 
+/// Charges the transaction's compute budget for a helper call, halting the program once the
+/// budget is spent
+fn consume_compute_units(units: u64) -> Result<(()), Error> {
+    invoke_context::consume_compute_units(units)
+        .map_err(|_| Error::new(ErrorKind::Other, "Error: Exceeded compute budget"))
+}
+
 /// Verifies a string passed out of the program
 fn verify_string(addr: u64, ro_regions: &[MemoryRegion]) -> Result<(()), Error> {
     for region in ro_regions.iter() {
@@ -157,7 +183,7 @@ pub fn helper_sol_panic(
 }
 
 /// Logging helper functions, called when the BPF program calls `sol_log_()` or
-/// `sol_log_64_()`.
+/// `sol_log_64_()`.  Each message is charged against the compute budget
 pub fn helper_sol_log_verify(
     addr: u64,
     _arg2: u64,
@@ -168,6 +194,7 @@ pub fn helper_sol_log_verify(
     ro_regions: &[MemoryRegion],
     _rw_regions: &[MemoryRegion],
 ) -> Result<(()), Error> {
+    consume_compute_units(invoke_context::compute_budget().log_units)?;
     verify_string(addr, ro_regions)
 }
 pub fn helper_sol_log(
@@ -181,7 +208,10 @@ pub fn helper_sol_log(
     let c_buf: *const c_char = addr as *const c_char;
     let c_str: &CStr = unsafe { CStr::from_ptr(c_buf) };
     match c_str.to_str() {
-        Ok(slice) => info!("info!: {:?}", slice),
+        Ok(slice) => {
+            info!("info!: {:?}", slice);
+            invoke_context::log(slice);
+        }
         Err(e) => warn!("Error: Cannot print invalid string: {}", e),
     };
     0
@@ -196,6 +226,7 @@ pub fn helper_sol_log_verify_(
     ro_regions: &[MemoryRegion],
     _rw_regions: &[MemoryRegion],
 ) -> Result<(()), Error> {
+    consume_compute_units(invoke_context::compute_budget().log_units)?;
     for region in ro_regions.iter() {
         if region.addr <= addr && (addr as u64) + len <= region.addr + region.len {
             return Ok(());
@@ -217,8 +248,23 @@ pub fn helper_sol_log_(
     let ptr: *const u8 = addr as *const u8;
     let message = unsafe { from_utf8(from_raw_parts(ptr, len as usize)) };
     info!("sol_log: {:?}", message);
+    if let Ok(message) = message {
+        invoke_context::log(message);
+    }
     0
 }
+pub fn helper_sol_log_u64_verify(
+    _arg1: u64,
+    _arg2: u64,
+    _arg3: u64,
+    _arg4: u64,
+    _arg5: u64,
+    _context: &mut Option<Box<Any + 'static>>,
+    _ro_regions: &[MemoryRegion],
+    _rw_regions: &[MemoryRegion],
+) -> Result<(()), Error> {
+    consume_compute_units(invoke_context::compute_budget().log_units)
+}
 pub fn helper_sol_log_u64(
     arg1: u64,
     arg2: u64,
@@ -227,13 +273,97 @@ pub fn helper_sol_log_u64(
     arg5: u64,
     _context: &mut Option<Box<Any + 'static>>,
 ) -> u64 {
-    info!(
-        "info!: {:#x}, {:#x}, {:#x}, {:#x}, {:#x}",
+    let message = format!(
+        "{:#x}, {:#x}, {:#x}, {:#x}, {:#x}",
         arg1, arg2, arg3, arg4, arg5
     );
+    info!("info!: {}", message);
+    invoke_context::log(&message);
     0
 }
 
+/// Cross-program invocation helper functions, called when the BPF program calls
+/// `sol_invoke_()` with a bincode serialized `Instruction`.  Returns 0 if the invoked
+/// instruction succeeded, in which case the program's input reflects its changes.
+pub fn helper_sol_invoke_verify_(
+    addr: u64,
+    len: u64,
+    _arg3: u64,
+    _arg4: u64,
+    _arg5: u64,
+    context: &mut Option<Box<Any + 'static>>,
+    ro_regions: &[MemoryRegion],
+    rw_regions: &[MemoryRegion],
+) -> Result<(()), Error> {
+    // The input the program's parameters point at must be the memory the VM is running over
+    if let Some(parameters) = invoke_parameters(context) {
+        let input = parameters.input();
+        if !rw_regions
+            .iter()
+            .any(|region| region.addr == input.addr && region.len == input.len)
+        {
+            return Err(Error::new(
+                ErrorKind::Other,
+                "Error: Program input is not mapped",
+            ));
+        }
+    }
+    for region in ro_regions.iter() {
+        if region.addr <= addr && (addr as u64) + len <= region.addr + region.len {
+            return Ok(());
+        }
+    }
+    Err(Error::new(
+        ErrorKind::Other,
+        "Error: Load segfault, bad instruction pointer",
+    ))
+}
+pub fn helper_sol_invoke_(
+    addr: u64,
+    len: u64,
+    _arg3: u64,
+    _arg4: u64,
+    _arg5: u64,
+    context: &mut Option<Box<Any + 'static>>,
+) -> u64 {
+    // Copy the instruction out before the program's input, where it may live, is written to
+    let ptr: *const u8 = addr as *const u8;
+    let instruction: Instruction =
+        match bincode::deserialize(unsafe { from_raw_parts(ptr, len as usize) }) {
+            Ok(instruction) => instruction,
+            Err(err) => {
+                warn!("sol_invoke: invalid instruction: {:?}", err);
+                return invoke::ERROR;
+            }
+        };
+    match invoke_parameters(context) {
+        Some(parameters) => invoke::invoke(parameters, &instruction),
+        None => {
+            warn!("sol_invoke: no program is running");
+            invoke::ERROR
+        }
+    }
+}
+
+fn invoke_parameters(context: &mut Option<Box<Any + 'static>>) -> Option<&mut InvokeParameters> {
+    context
+        .as_mut()
+        .and_then(|context| context.downcast_mut::<InvokeParameters>())
+}
+
+/// Give `sol_invoke_` the accounts and input of the program `vm` is about to run
+pub fn register_invoke_helper(
+    vm: &mut EbpfVmRaw,
+    parameters: InvokeParameters,
+) -> Result<(), Error> {
+    vm.register_helper_ex(
+        "sol_invoke_",
+        Some(helper_sol_invoke_verify_),
+        helper_sol_invoke_,
+        Some(Box::new(parameters)),
+    )
+}
+
 /// Dynamic memory allocation helper called when the BPF program calls
 /// `sol_alloc_free_()`.  The allocator is expected to allocate/free
 /// from/to a given chunk of memory and enforce size restrictions.  The
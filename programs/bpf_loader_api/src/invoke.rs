@@ -0,0 +1,146 @@
+//! Lets a running BPF program invoke other programs via `sol_invoke_`. The program's accounts
+//! and the region of its serialized input are kept as the helper's context in the program's
+//! VM, so the accounts can be brought up to date with the program's writes before the call and
+//! the input with the callee's changes after it.
+
+use crate::{deserialize_parameters, update_parameters};
+use log::*;
+use solana_rbpf::MemoryRegion;
+use solana_sdk::account::KeyedAccount;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::invoke_context;
+use std::slice::from_raw_parts_mut;
+
+/// Returned to the program when the invoked instruction succeeded
+pub const SUCCESS: u64 = 0;
+/// Returned to the program when the instruction couldn't be invoked or failed
+pub const ERROR: u64 = 1;
+
+/// The running program's accounts and input, as seen by `sol_invoke_`
+pub struct InvokeParameters {
+    keyed_accounts: *mut [KeyedAccount<'static>],
+    /// Only the location of the input is kept, the VM holds the borrow of it while it runs
+    input: MemoryRegion,
+}
+
+impl InvokeParameters {
+    /// The parameters must be dropped, along with the VM they're registered with, before the
+    /// borrow of `keyed_accounts` ends
+    #[allow(clippy::useless_transmute)]
+    pub fn new(keyed_accounts: &mut [KeyedAccount], input: &mut [u8]) -> Self {
+        //UNSAFE: The lifetime is erased so the accounts fit the VM's `'static` helper context.
+        //They are only dereferenced by `invoke()`, which the program calls while it runs
+        let keyed_accounts: *mut [KeyedAccount<'static>] =
+            unsafe { std::mem::transmute(keyed_accounts) };
+        Self {
+            keyed_accounts,
+            input: MemoryRegion::new_from_slice(input),
+        }
+    }
+
+    /// The region the VM must be executing the program over for its input to be updated
+    pub fn input(&self) -> &MemoryRegion {
+        &self.input
+    }
+}
+
+/// Invoke `instruction` on behalf of the running program. The instruction must already be
+/// copied out of the program's memory, since its input is written to
+pub fn invoke(parameters: &mut InvokeParameters, instruction: &Instruction) -> u64 {
+    //UNSAFE: The accounts outlive the running program, see `InvokeParameters::new()`. The input
+    //is the VM's own memory, which it doesn't touch while the helper runs, and no other
+    //reference to it is made during the call
+    let (keyed_accounts, buffer) = unsafe {
+        (
+            &mut *parameters.keyed_accounts,
+            from_raw_parts_mut(
+                parameters.input.addr as *mut u8,
+                parameters.input.len as usize,
+            ),
+        )
+    };
+
+    let result = deserialize_parameters(keyed_accounts, buffer)
+        .and_then(|_| invoke_context::invoke(instruction, keyed_accounts))
+        .and_then(|_| update_parameters(keyed_accounts, buffer));
+    match result {
+        Ok(()) => SUCCESS,
+        Err(err) => {
+            warn!(
+                "sol_invoke: program {} failed: {:?}",
+                instruction.program_ids_index, err
+            );
+            ERROR
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serialize_parameters;
+    use solana_sdk::account::Account;
+    use solana_sdk::compute_budget::ComputeBudget;
+    use solana_sdk::instruction::{AccountMeta, InstructionError};
+    use solana_sdk::invoke_context::{with_invoke_context, InvokeContext};
+    use solana_sdk::pubkey::Pubkey;
+
+    /// Moves a lamport from the first account to the second
+    struct TransferContext(ComputeBudget);
+
+    impl InvokeContext for TransferContext {
+        fn invoke(
+            &self,
+            _instruction: &Instruction,
+            keyed_accounts: &mut [KeyedAccount],
+        ) -> Result<(), InstructionError> {
+            keyed_accounts[0].account.lamports -= 1;
+            keyed_accounts[1].account.lamports += 1;
+            Ok(())
+        }
+
+        fn compute_budget(&self) -> &ComputeBudget {
+            &self.0
+        }
+
+        fn consume_compute_units(&self, _units: u64) -> Result<(), InstructionError> {
+            Ok(())
+        }
+
+        fn remaining_compute_units(&self) -> u64 {
+            self.0.max_units
+        }
+
+        fn log(&self, _message: &str) {}
+    }
+
+    #[test]
+    fn test_invoke() {
+        let (from, to) = (Pubkey::new_rand(), Pubkey::new_rand());
+        let mut from_account = Account::new(10, 1, &Pubkey::default());
+        let mut to_account = Account::new(0, 1, &Pubkey::default());
+        let mut keyed_accounts = vec![
+            KeyedAccount::new(&from, true, &mut from_account),
+            KeyedAccount::new(&to, false, &mut to_account),
+        ];
+        let instruction = Instruction::new(
+            Pubkey::new_rand(),
+            &(),
+            vec![AccountMeta::new(from, true), AccountMeta::new(to, false)],
+        );
+        let mut buffer = serialize_parameters(&Pubkey::new_rand(), &mut keyed_accounts, &[]);
+
+        {
+            let mut parameters = InvokeParameters::new(&mut keyed_accounts, &mut buffer);
+            assert_eq!(invoke(&mut parameters, &instruction), ERROR);
+            with_invoke_context(&TransferContext(ComputeBudget::default()), || {
+                assert_eq!(invoke(&mut parameters, &instruction), SUCCESS);
+            });
+        }
+
+        // The program sees the callee's changes in its input buffer
+        deserialize_parameters(&mut keyed_accounts, &buffer).unwrap();
+        assert_eq!(keyed_accounts[0].account.lamports, 9);
+        assert_eq!(keyed_accounts[1].account.lamports, 1);
+    }
+}
@@ -3,6 +3,7 @@ pub mod allocator_bump;
 pub mod allocator_system;
 pub mod bpf_verifier;
 pub mod helpers;
+pub mod invoke;
 
 #[macro_export]
 macro_rules! solana_bpf_loader {
@@ -19,7 +20,9 @@ use byteorder::{ByteOrder, LittleEndian, WriteBytesExt};
 use log::*;
 use solana_rbpf::{EbpfVmRaw, MemoryRegion};
 use solana_sdk::account::KeyedAccount;
+use solana_sdk::compute_budget::ComputeBudget;
 use solana_sdk::instruction::InstructionError;
+use solana_sdk::invoke_context;
 use solana_sdk::loader_instruction::LoaderInstruction;
 use solana_sdk::pubkey::Pubkey;
 use std::io::prelude::*;
@@ -29,7 +32,7 @@ use std::mem;
 pub fn create_vm(prog: &[u8]) -> Result<(EbpfVmRaw, MemoryRegion), Error> {
     let mut vm = EbpfVmRaw::new(None)?;
     vm.set_verifier(bpf_verifier::check)?;
-    vm.set_max_instruction_count(36000)?;
+    vm.set_max_instruction_count(ComputeBudget::default().max_units)?;
     vm.set_elf(&prog)?;
 
     let heap_region = helpers::register_helpers(&mut vm)?;
@@ -63,7 +66,11 @@ fn serialize_parameters(
     v
 }
 
-fn deserialize_parameters(keyed_accounts: &mut [KeyedAccount], buffer: &[u8]) {
+/// Copy the lamports and data the program wrote into `buffer` back into `keyed_accounts`
+fn deserialize_parameters(
+    keyed_accounts: &mut [KeyedAccount],
+    buffer: &[u8],
+) -> Result<(), InstructionError> {
     assert_eq!(32, mem::size_of::<Pubkey>());
 
     let mut start = mem::size_of::<u64>();
@@ -72,14 +79,47 @@ fn deserialize_parameters(keyed_accounts: &mut [KeyedAccount], buffer: &[u8]) {
         start += mem::size_of::<Pubkey>(); // skip pubkey
         info.account.lamports = LittleEndian::read_u64(&buffer[start..]);
 
-        start += mem::size_of::<u64>() // skip lamports
-                  + mem::size_of::<u64>(); // skip length tag
+        start += mem::size_of::<u64>(); // skip lamports
+        if LittleEndian::read_u64(&buffer[start..]) != info.account.data.len() as u64 {
+            // The account was resized by an invoked program and no longer fits the buffer
+            return Err(InstructionError::InvalidAccountData);
+        }
+        start += mem::size_of::<u64>(); // skip length tag
         let end = start + info.account.data.len();
         info.account.data.clone_from_slice(&buffer[start..end]);
 
         start += info.account.data.len() // skip data
                   + mem::size_of::<Pubkey>(); // skip owner
     }
+    Ok(())
+}
+
+/// Write the lamports, data and owner of `keyed_accounts` into `buffer`, the inverse of
+/// `deserialize_parameters()`
+fn update_parameters(
+    keyed_accounts: &[KeyedAccount],
+    buffer: &mut [u8],
+) -> Result<(), InstructionError> {
+    let mut start = mem::size_of::<u64>();
+    for info in keyed_accounts.iter() {
+        start += mem::size_of::<u64>(); // skip signer_key boolean
+        start += mem::size_of::<Pubkey>(); // skip pubkey
+        LittleEndian::write_u64(&mut buffer[start..], info.account.lamports);
+
+        start += mem::size_of::<u64>(); // skip lamports
+        if LittleEndian::read_u64(&buffer[start..]) != info.account.data.len() as u64 {
+            return Err(InstructionError::InvalidAccountData);
+        }
+        start += mem::size_of::<u64>(); // skip length tag
+        let end = start + info.account.data.len();
+        buffer[start..end].copy_from_slice(&info.account.data);
+
+        start += info.account.data.len(); // skip data
+        let end = start + mem::size_of::<Pubkey>();
+        buffer[start..end].copy_from_slice(info.account.owner.as_ref());
+        start = end; // skip owner
+    }
+    Ok(())
 }
 
 pub fn process_instruction(
@@ -100,10 +140,42 @@ pub fn process_instruction(
                 return Err(InstructionError::GenericError);
             }
         };
+        // The program may execute as many instructions as the transaction has units left;
+        // outside of a transaction it gets a budget of its own
+        let max_units = invoke_context::remaining_compute_units()
+            .unwrap_or_else(|| invoke_context::compute_budget().max_units);
+        if max_units == 0 {
+            return Err(InstructionError::ComputationalBudgetExceeded);
+        }
+        if let Err(e) = vm.set_max_instruction_count(max_units) {
+            warn!("Failed to meter BPF VM: {}", e);
+            return Err(InstructionError::GenericError);
+        }
         let mut v = serialize_parameters(program_id, params, &tx_data);
+        if let Err(e) =
+            helpers::register_invoke_helper(&mut vm, invoke::InvokeParameters::new(params, &mut v))
+        {
+            warn!("Failed to register BPF invoke helper: {}", e);
+            return Err(InstructionError::GenericError);
+        }
 
-        match vm.execute_program(v.as_mut_slice(), &[], &[heap_region]) {
+        let result = vm.execute_program(&mut v, &[], &[heap_region]);
+        let instruction_count = vm.get_last_instruction_count();
+        let metered = invoke_context::consume_compute_units(instruction_count);
+        let remaining_units = invoke_context::remaining_compute_units();
+        let message = format!(
+            "Program {} consumed {} of {} compute units",
+            program_id,
+            remaining_units.map_or(instruction_count, |remaining_units| {
+                max_units.saturating_sub(remaining_units)
+            }),
+            max_units
+        );
+        info!("{}", message);
+        invoke_context::log(&message);
+        match result {
             Ok(status) => {
+                metered?;
                 if 0 == status {
                     warn!("BPF program failed: {}", status);
                     return Err(InstructionError::GenericError);
@@ -111,14 +183,16 @@ pub fn process_instruction(
             }
             Err(e) => {
                 warn!("BPF VM failed to run program: {}", e);
+                // Running out of instructions, or of units to charge a helper, exhausts the
+                // budget
+                if metered.is_err() || instruction_count >= max_units || remaining_units == Some(0)
+                {
+                    return Err(InstructionError::ComputationalBudgetExceeded);
+                }
                 return Err(InstructionError::GenericError);
             }
         }
-        deserialize_parameters(params, &v);
-        info!(
-            "BPF program executed {} instructions",
-            vm.get_last_instruction_count()
-        );
+        deserialize_parameters(params, &v)?;
     } else if let Ok(instruction) = bincode::deserialize(tx_data) {
         if keyed_accounts[0].signer_key().is_none() {
             warn!("key[0] did not sign the transaction");
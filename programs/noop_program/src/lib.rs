@@ -1,6 +1,7 @@
 use log::*;
 use solana_sdk::account::KeyedAccount;
 use solana_sdk::instruction::InstructionError;
+use solana_sdk::invoke_context;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::solana_entrypoint;
 
@@ -14,5 +15,6 @@ fn entrypoint(
     trace!("noop: program_id: {:?}", program_id);
     trace!("noop: keyed_accounts: {:#?}", keyed_accounts);
     trace!("noop: data: {:?}", data);
+    invoke_context::log("noop");
     Ok(())
 }
@@ -519,6 +519,7 @@ mod tests {
                 data: vec![],
                 owner: id(),
                 executable: false,
+                rent_epoch: 0,
             },
         };
         let segment_index = 0_usize;
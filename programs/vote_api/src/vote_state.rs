@@ -388,10 +388,17 @@ mod tests {
     use crate::vote_state;
     use solana_sdk::account::Account;
     use solana_sdk::account_utils::State;
+    use solana_sdk::epoch_schedule::MINIMUM_SLOTS_PER_EPOCH;
     use solana_sdk::hash::hash;
 
     const MAX_RECENT_VOTES: usize = 16;
 
+    #[test]
+    fn test_minimum_slots_per_epoch() {
+        // the shortest epoch must outlast any vote lockout
+        assert_eq!(MINIMUM_SLOTS_PER_EPOCH, MAX_LOCKOUT_HISTORY as u64 + 1);
+    }
+
     #[test]
     fn test_initialize_vote_account() {
         let vote_account_pubkey = Pubkey::new_rand();
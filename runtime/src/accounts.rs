@@ -1,11 +1,13 @@
 use crate::accounts_db::{
     get_paths_vec, AccountInfo, AccountStorage, AccountsDB, AppendVecId, ErrorCounters,
-    InstructionAccounts, InstructionCredits, InstructionLoaders,
+    InstructionAccounts, InstructionCredits, InstructionLoaders, TransactionRent,
 };
 use crate::accounts_index::{AccountsIndex, Fork};
 use crate::append_vec::StoredAccount;
 use crate::blockhash_queue::BlockhashQueue;
 use crate::message_processor::has_duplicates;
+use crate::nonce_utils;
+use crate::rent_collector::RentCollector;
 use bincode::serialize;
 use log::*;
 use solana_metrics::inc_new_counter_error;
@@ -112,11 +114,15 @@ impl Accounts {
         }
     }
 
+    /// Restore the accounts serialized in `stream`, whose storage files are found in the
+    /// comma separated `account_paths`
     pub fn update_from_stream<R: Read>(
         &self,
+        account_paths: &str,
         stream: &mut BufReader<R>,
     ) -> std::result::Result<(), std::io::Error> {
-        self.accounts_db.update_from_stream(stream)
+        self.accounts_db
+            .update_from_stream(&get_paths_vec(account_paths), stream)
     }
 
     fn load_tx_accounts(
@@ -125,8 +131,9 @@ impl Accounts {
         accounts_index: &AccountsIndex<AccountInfo>,
         tx: &Transaction,
         fee: u64,
+        rent_collector: &RentCollector,
         error_counters: &mut ErrorCounters,
-    ) -> Result<(Vec<Account>, InstructionCredits)> {
+    ) -> Result<(Vec<Account>, InstructionCredits, TransactionRent)> {
         // Copy all the accounts
         let message = tx.message();
         if tx.signatures.is_empty() && fee != 0 {
@@ -142,13 +149,24 @@ impl Accounts {
             // If a fee can pay for execution then the program will be scheduled
             let mut called_accounts: Vec<Account> = vec![];
             let mut credits: InstructionCredits = vec![];
-            for key in &message.account_keys {
+            let mut rent: TransactionRent = 0;
+            for (i, key) in message.account_keys.iter().enumerate() {
                 if !message.program_ids().contains(&key) {
-                    called_accounts.push(
-                        AccountsDB::load(storage, ancestors, accounts_index, key)
-                            .map(|(account, _)| account)
-                            .unwrap_or_default(),
-                    );
+                    let mut account = AccountsDB::load(storage, ancestors, accounts_index, key)
+                        .map(|(account, _)| account)
+                        .unwrap_or_else(|| {
+                            // a new account owes no rent for the epochs before it existed
+                            Account {
+                                rent_epoch: rent_collector.epoch,
+                                ..Account::default()
+                            }
+                        });
+                    // Credit-only accounts may be loaded by several transactions in a batch, so
+                    // only accounts this transaction has exclusive access to pay rent
+                    if message.is_debitable(i) {
+                        rent += rent_collector.update(&mut account);
+                    }
+                    called_accounts.push(account);
                     credits.push(0);
                 }
             }
@@ -163,7 +181,7 @@ impl Accounts {
                 Err(TransactionError::InsufficientFundsForFee)
             } else {
                 called_accounts[0].lamports -= fee;
-                Ok((called_accounts, credits))
+                Ok((called_accounts, credits, rent))
             }
         }
     }
@@ -246,8 +264,16 @@ impl Accounts {
         txs: &[Transaction],
         lock_results: Vec<Result<()>>,
         hash_queue: &BlockhashQueue,
+        rent_collector: &RentCollector,
         error_counters: &mut ErrorCounters,
-    ) -> Vec<Result<(InstructionAccounts, InstructionLoaders, InstructionCredits)>> {
+    ) -> Vec<
+        Result<(
+            InstructionAccounts,
+            InstructionLoaders,
+            InstructionCredits,
+            TransactionRent,
+        )>,
+    > {
         //PERF: hold the lock to scan for the references, but not to clone the accounts
         //TODO: two locks usually leads to deadlocks, should this be one structure?
         let accounts_index = self.accounts_db.accounts_index.read().unwrap();
@@ -256,17 +282,18 @@ impl Accounts {
             .zip(lock_results.into_iter())
             .map(|etx| match etx {
                 (tx, Ok(())) => {
-                    let fee_calculator = hash_queue
-                        .get_fee_calculator(&tx.message().recent_blockhash)
-                        .ok_or(TransactionError::BlockhashNotFound)?;
+                    let fee_calculator =
+                        nonce_utils::fee_calculator_for_transaction(hash_queue, tx)
+                            .ok_or(TransactionError::BlockhashNotFound)?;
 
                     let fee = fee_calculator.calculate_fee(tx.message());
-                    let (accounts, credits) = Self::load_tx_accounts(
+                    let (accounts, credits, rent) = Self::load_tx_accounts(
                         &storage,
                         ancestors,
                         &accounts_index,
                         tx,
                         fee,
+                        rent_collector,
                         error_counters,
                     )?;
                     let loaders = Self::load_loaders(
@@ -276,7 +303,7 @@ impl Accounts {
                         tx,
                         error_counters,
                     )?;
-                    Ok((accounts, loaders, credits))
+                    Ok((accounts, loaders, credits, rent))
                 }
                 (_, Err(e)) => Err(e),
             })
@@ -294,7 +321,23 @@ impl Accounts {
             .filter(|(acc, _)| acc.lamports != 0)
     }
 
+    /// Load the accounts owned by `program_id` that were stored in `fork`
     pub fn load_by_program(&self, fork: Fork, program_id: &Pubkey) -> Vec<(Pubkey, Account)> {
+        let accounts_index = self.accounts_db.accounts_index.read().unwrap();
+        if let Some(mut pubkeys) = accounts_index.program_accounts(program_id) {
+            pubkeys.sort();
+            let storage = self.accounts_db.storage.read().unwrap();
+            return pubkeys
+                .into_iter()
+                .filter_map(|pubkey| {
+                    AccountsDB::load_fork(&storage, fork, &accounts_index, &pubkey)
+                        .map(|account| (pubkey, account))
+                })
+                .filter(|(_, account)| account.owner == *program_id)
+                .collect();
+        }
+        drop(accounts_index);
+
         let accumulator: Vec<Vec<(Pubkey, u64, Account)>> = self.accounts_db.scan_account_storage(
             fork,
             |stored_account: &StoredAccount,
@@ -317,6 +360,57 @@ impl Accounts {
         versions.into_iter().map(|s| (s.0, s.2)).collect()
     }
 
+    /// Load the latest version of every account owned by `program_id` visible from `ancestors`
+    pub fn load_by_program_slow(
+        &self,
+        ancestors: &HashMap<Fork, usize>,
+        program_id: &Pubkey,
+    ) -> Vec<(Pubkey, Account)> {
+        let accounts_index = self.accounts_db.accounts_index.read().unwrap();
+        if let Some(mut pubkeys) = accounts_index.program_accounts(program_id) {
+            pubkeys.sort();
+            let storage = self.accounts_db.storage.read().unwrap();
+            return pubkeys
+                .into_iter()
+                .filter_map(|pubkey| {
+                    AccountsDB::load(&storage, ancestors, &accounts_index, &pubkey)
+                        .map(|(account, _)| (pubkey, account))
+                })
+                .filter(|(_, account)| account.owner == *program_id && account.lamports != 0)
+                .collect();
+        }
+        drop(accounts_index);
+
+        self.accounts_db.scan_accounts(
+            ancestors,
+            |collector: &mut Vec<(Pubkey, Account)>, option| {
+                if let Some(data) = option
+                    .filter(|(_, account, _)| account.owner == *program_id && account.lamports != 0)
+                    .map(|(pubkey, account, _fork)| (*pubkey, account))
+                {
+                    collector.push(data)
+                }
+            },
+        )
+    }
+
+    /// Every live account visible from `ancestors`, sorted by pubkey
+    pub fn load_all(&self, ancestors: &HashMap<Fork, usize>) -> Vec<(Pubkey, Account)> {
+        let mut accounts = self.accounts_db.scan_accounts(
+            ancestors,
+            |collector: &mut Vec<(Pubkey, Account)>, option| {
+                if let Some(data) = option
+                    .filter(|(_, account, _)| account.lamports != 0)
+                    .map(|(pubkey, account, _fork)| (*pubkey, account))
+                {
+                    collector.push(data)
+                }
+            },
+        );
+        accounts.sort_by_key(|(pubkey, _)| *pubkey);
+        accounts
+    }
+
     /// Slow because lock is held for 1 operation instead of many
     pub fn store_slow(&self, fork: Fork, pubkey: &Pubkey, account: &Account) {
         let mut accounts = HashMap::new();
@@ -436,7 +530,12 @@ impl Accounts {
         fork: Fork,
         txs: &[Transaction],
         res: &[Result<()>],
-        loaded: &[Result<(InstructionAccounts, InstructionLoaders, InstructionCredits)>],
+        loaded: &[Result<(
+            InstructionAccounts,
+            InstructionLoaders,
+            InstructionCredits,
+            TransactionRent,
+        )>],
     ) {
         let accounts = collect_accounts(txs, res, loaded);
         self.accounts_db.store(fork, &accounts);
@@ -451,12 +550,37 @@ impl Accounts {
     pub fn add_root(&self, fork: Fork) {
         self.accounts_db.add_root(fork)
     }
+
+    /// Reclaim storage held by old roots, returning the number of bytes reclaimed
+    pub fn clean_accounts(&self) -> usize {
+        self.accounts_db.clean_accounts()
+    }
+
+    /// Number of roots left for `clean_accounts` to look at
+    pub fn num_uncleaned_roots(&self) -> usize {
+        self.accounts_db.num_uncleaned_roots()
+    }
+
+    /// The Merkle root over every rooted account and the root fork it covers
+    pub fn accounts_hash(&self) -> Option<(Fork, Hash)> {
+        self.accounts_db.accounts_hash()
+    }
+
+    /// Check the accounts hash against one recomputed from storage
+    pub fn verify_accounts_hash(&self) -> bool {
+        self.accounts_db.verify_accounts_hash()
+    }
 }
 
 fn collect_accounts<'a>(
     txs: &'a [Transaction],
     res: &'a [Result<()>],
-    loaded: &'a [Result<(InstructionAccounts, InstructionLoaders, InstructionCredits)>],
+    loaded: &'a [Result<(
+        InstructionAccounts,
+        InstructionLoaders,
+        InstructionCredits,
+        TransactionRent,
+    )>],
 ) -> HashMap<&'a Pubkey, (&'a Account, LamportCredit)> {
     let mut accounts: HashMap<&Pubkey, (&Account, LamportCredit)> = HashMap::new();
     for (i, raccs) in loaded.iter().enumerate() {
@@ -507,7 +631,14 @@ mod tests {
         ka: &Vec<(Pubkey, Account)>,
         fee_calculator: &FeeCalculator,
         error_counters: &mut ErrorCounters,
-    ) -> Vec<Result<(InstructionAccounts, InstructionLoaders, InstructionCredits)>> {
+    ) -> Vec<
+        Result<(
+            InstructionAccounts,
+            InstructionLoaders,
+            InstructionCredits,
+            TransactionRent,
+        )>,
+    > {
         let mut hash_queue = BlockhashQueue::new(100);
         hash_queue.register_hash(&tx.message().recent_blockhash, &fee_calculator);
         let accounts = Accounts::new(None);
@@ -516,8 +647,14 @@ mod tests {
         }
 
         let ancestors = vec![(0, 0)].into_iter().collect();
-        let res =
-            accounts.load_accounts(&ancestors, &[tx], vec![Ok(())], &hash_queue, error_counters);
+        let res = accounts.load_accounts(
+            &ancestors,
+            &[tx],
+            vec![Ok(())],
+            &hash_queue,
+            &RentCollector::default(),
+            error_counters,
+        );
         res
     }
 
@@ -525,7 +662,14 @@ mod tests {
         tx: Transaction,
         ka: &Vec<(Pubkey, Account)>,
         error_counters: &mut ErrorCounters,
-    ) -> Vec<Result<(InstructionAccounts, InstructionLoaders, InstructionCredits)>> {
+    ) -> Vec<
+        Result<(
+            InstructionAccounts,
+            InstructionLoaders,
+            InstructionCredits,
+            TransactionRent,
+        )>,
+    > {
         let fee_calculator = FeeCalculator::default();
         load_accounts_with_fee(tx, ka, &fee_calculator, error_counters)
     }
@@ -701,7 +845,7 @@ mod tests {
         assert_eq!(error_counters.account_not_found, 0);
         assert_eq!(loaded_accounts.len(), 1);
         match &loaded_accounts[0] {
-            Ok((instruction_accounts, instruction_loaders, instruction_credits)) => {
+            Ok((instruction_accounts, instruction_loaders, instruction_credits, _rent)) => {
                 assert_eq!(instruction_accounts.len(), 2);
                 assert_eq!(instruction_accounts[0], accounts[0].1);
                 assert_eq!(instruction_loaders.len(), 1);
@@ -887,7 +1031,7 @@ mod tests {
         assert_eq!(error_counters.account_not_found, 0);
         assert_eq!(loaded_accounts.len(), 1);
         match &loaded_accounts[0] {
-            Ok((instruction_accounts, instruction_loaders, instruction_credits)) => {
+            Ok((instruction_accounts, instruction_loaders, instruction_credits, _rent)) => {
                 assert_eq!(instruction_accounts.len(), 1);
                 assert_eq!(instruction_accounts[0], accounts[0].1);
                 assert_eq!(instruction_loaders.len(), 2);
@@ -960,6 +1104,77 @@ mod tests {
         assert_eq!(loaded, vec![]);
     }
 
+    #[test]
+    fn test_load_by_program_without_index() {
+        let accounts = Accounts::new(None);
+        *accounts.accounts_db.accounts_index.write().unwrap() = AccountsIndex::new(false);
+
+        let program_id = Pubkey::new(&[2; 32]);
+        let pubkey0 = Pubkey::new_rand();
+        let account0 = Account::new(1, 0, &program_id);
+        accounts.store_slow(0, &pubkey0, &account0);
+        let pubkey1 = Pubkey::new_rand();
+        let account1 = Account::new(1, 0, &Pubkey::new(&[3; 32]));
+        accounts.store_slow(0, &pubkey1, &account1);
+
+        let loaded = accounts.load_by_program(0, &program_id);
+        assert_eq!(loaded, vec![(pubkey0, account0.clone())]);
+        let ancestors = vec![(0, 0)].into_iter().collect();
+        let loaded = accounts.load_by_program_slow(&ancestors, &program_id);
+        assert_eq!(loaded, vec![(pubkey0, account0)]);
+    }
+
+    #[test]
+    fn test_load_by_program_slow() {
+        let accounts = Accounts::new(None);
+
+        // Reassign one of the program's accounts in a child fork
+        let program_id = Pubkey::new(&[2; 32]);
+        let pubkey0 = Pubkey::new_rand();
+        let account0 = Account::new(1, 0, &program_id);
+        accounts.store_slow(0, &pubkey0, &account0);
+        let pubkey1 = Pubkey::new_rand();
+        let account1 = Account::new(1, 0, &program_id);
+        accounts.store_slow(0, &pubkey1, &account1);
+        let account1_reassigned = Account::new(2, 0, &Pubkey::new(&[3; 32]));
+        accounts.store_slow(1, &pubkey1, &account1_reassigned);
+        let pubkey2 = Pubkey::new_rand();
+        let account2 = Account::new(1, 0, &program_id);
+        accounts.store_slow(1, &pubkey2, &account2);
+
+        let ancestors = vec![(0, 0)].into_iter().collect();
+        let mut loaded = accounts.load_by_program_slow(&ancestors, &program_id);
+        loaded.sort_by_key(|(pubkey, _)| *pubkey);
+        let mut expected = vec![(pubkey0, account0.clone()), (pubkey1, account1)];
+        expected.sort_by_key(|(pubkey, _)| *pubkey);
+        assert_eq!(loaded, expected);
+
+        let ancestors = vec![(0, 1), (1, 0)].into_iter().collect();
+        let mut loaded = accounts.load_by_program_slow(&ancestors, &program_id);
+        loaded.sort_by_key(|(pubkey, _)| *pubkey);
+        let mut expected = vec![(pubkey0, account0), (pubkey2, account2)];
+        expected.sort_by_key(|(pubkey, _)| *pubkey);
+        assert_eq!(loaded, expected);
+    }
+
+    #[test]
+    fn test_load_all() {
+        let accounts = Accounts::new(None);
+        let pubkey0 = Pubkey::new_rand();
+        let account0 = Account::new(1, 0, &Pubkey::default());
+        accounts.store_slow(0, &pubkey0, &account0);
+        let pubkey1 = Pubkey::new_rand();
+        accounts.store_slow(0, &pubkey1, &Account::new(1, 0, &Pubkey::default()));
+        accounts.store_slow(1, &pubkey1, &Account::new(0, 0, &Pubkey::default()));
+
+        let ancestors = vec![(0, 0)].into_iter().collect();
+        assert_eq!(accounts.load_all(&ancestors).len(), 2);
+
+        // Emptied accounts are left out
+        let ancestors = vec![(0, 1), (1, 0)].into_iter().collect();
+        assert_eq!(accounts.load_all(&ancestors), vec![(pubkey0, account0)]);
+    }
+
     #[test]
     fn test_accounts_account_not_found() {
         let accounts = Accounts::new(None);
@@ -1025,7 +1240,9 @@ mod tests {
 
         let mut reader = BufReader::new(&buf[..]);
         let daccounts = Accounts::new(Some("serialize_accounts".to_string()));
-        assert!(daccounts.update_from_stream(&mut reader).is_ok());
+        assert!(daccounts
+            .update_from_stream(&daccounts.paths, &mut reader)
+            .is_ok());
         check_accounts(&daccounts, &pubkeys, 100);
         assert_eq!(
             accounts.hash_internal_state(0),
@@ -1070,6 +1287,7 @@ mod tests {
             instruction_accounts0,
             instruction_loaders0,
             instruction_credits0,
+            0,
         ));
 
         let instruction_accounts1 = vec![account1, account2.clone()];
@@ -1079,6 +1297,7 @@ mod tests {
             instruction_accounts1,
             instruction_loaders1,
             instruction_credits1,
+            0,
         ));
 
         let loaded = vec![loaded0, loaded1];
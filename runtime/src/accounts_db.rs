@@ -18,31 +18,40 @@
 //! tracks the number of commits to the entire data store. So the latest
 //! commit for each fork entry would be indexed.
 
-use crate::accounts_index::{AccountsIndex, Fork};
-use crate::append_vec::{AppendVec, StorageMeta, StoredAccount};
-use bincode::{deserialize_from, serialize_into, serialized_size};
+use crate::accounts_hash::AccountsHashTree;
+use crate::accounts_index::{AccountsIndex, Fork, RefCount, NUM_SHARDS};
+use crate::append_vec::{AppendVec, SerializedAppendVec, StorageMeta, StoredAccount};
+use bincode::{deserialize_from, serialize, serialize_into};
 use log::*;
 use rand::{thread_rng, Rng};
 use rayon::prelude::*;
 use rayon::ThreadPool;
-use serde::de::{MapAccess, Visitor};
 use serde::ser::{SerializeMap, Serializer};
 use serde::{Deserialize, Serialize};
 use solana_sdk::account::{Account, LamportCredit};
+use solana_sdk::hash::{hashv, Hash};
 use solana_sdk::pubkey::Pubkey;
-use std::collections::{HashMap, HashSet};
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fmt;
 use std::fs::{create_dir_all, remove_dir_all};
-use std::io::{BufReader, Cursor, Error, ErrorKind, Read};
-use std::path::Path;
+use std::io::{BufReader, Error, ErrorKind, Read};
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
 use sys_info;
 
 const ACCOUNT_DATA_FILE_SIZE: u64 = 16 * 1024 * 1024;
 const ACCOUNT_DATA_FILE: &str = "data";
 pub const NUM_THREADS: u32 = 10;
 
+/// A rooted fork is rewritten once its live accounts take up less than this percentage of
+/// the bytes written to its storage
+const SHRINK_THRESHOLD_PERCENT: usize = 80;
+
+/// Most roots `clean_accounts` looks at in one pass
+const CLEAN_BATCH_ROOTS: usize = 8;
+
 #[derive(Debug, Default)]
 pub struct ErrorCounters {
     pub account_not_found: usize,
@@ -76,35 +85,31 @@ pub type AppendVecId = usize;
 pub type InstructionAccounts = Vec<Account>;
 pub type InstructionCredits = Vec<LamportCredit>;
 pub type InstructionLoaders = Vec<Vec<(Pubkey, Account)>>;
+pub type TransactionRent = u64;
 
 #[derive(Default, Debug)]
 pub struct AccountStorage(HashMap<Fork, HashMap<usize, Arc<AccountStorageEntry>>>);
 
-struct AccountStorageVisitor;
-
-impl<'de> Visitor<'de> for AccountStorageVisitor {
-    type Value = AccountStorage;
-
-    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        formatter.write_str("Expecting AccountStorage")
-    }
-
-    #[allow(clippy::mutex_atomic)]
-    fn visit_map<M>(self, mut access: M) -> Result<Self::Value, M::Error>
-    where
-        M: MapAccess<'de>,
-    {
+impl AccountStorage {
+    /// Open the deserialized storage `entries`, finding their files in `account_paths`
+    fn open(
+        entries: HashMap<AppendVecId, SerializedAccountStorageEntry>,
+        account_paths: &[String],
+    ) -> Self {
         let mut map = HashMap::new();
-
-        while let Some((storage_id, storage_entry)) = access.next_entry()? {
-            let storage_entry: AccountStorageEntry = storage_entry;
+        for (storage_id, entry) in entries {
+            let storage_entry = AccountStorageEntry {
+                id: entry.id,
+                fork_id: entry.fork_id,
+                accounts: AppendVec::open(entry.accounts, account_paths),
+                count_and_status: entry.count_and_status,
+            };
             let storage_fork_map = map
                 .entry(storage_entry.fork_id)
                 .or_insert_with(HashMap::new);
             storage_fork_map.insert(storage_id, Arc::new(storage_entry));
         }
-
-        Ok(AccountStorage(map))
+        AccountStorage(map)
     }
 }
 
@@ -127,15 +132,6 @@ impl Serialize for AccountStorage {
     }
 }
 
-impl<'de> Deserialize<'de> for AccountStorage {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: serde::Deserializer<'de>,
-    {
-        deserializer.deserialize_map(AccountStorageVisitor)
-    }
-}
-
 #[derive(Debug, PartialEq, Copy, Clone, Deserialize, Serialize)]
 pub enum AccountStorageStatus {
     Available = 0,
@@ -144,7 +140,7 @@ pub enum AccountStorageStatus {
 }
 
 /// Persistent storage structure holding the accounts
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Serialize)]
 pub struct AccountStorageEntry {
     id: AppendVecId,
 
@@ -161,6 +157,15 @@ pub struct AccountStorageEntry {
     count_and_status: RwLock<(usize, AccountStorageStatus)>,
 }
 
+/// An `AccountStorageEntry` as it is serialized, before its AppendVec is opened
+#[derive(Deserialize)]
+struct SerializedAccountStorageEntry {
+    id: AppendVecId,
+    fork_id: Fork,
+    accounts: SerializedAppendVec,
+    count_and_status: RwLock<(usize, AccountStorageStatus)>,
+}
+
 impl AccountStorageEntry {
     pub fn new(path: &str, fork_id: Fork, id: usize, file_size: u64) -> Self {
         let p = format!("{}/{}.{}", path, fork_id, id);
@@ -177,10 +182,12 @@ impl AccountStorageEntry {
         }
     }
 
-    pub fn set_status(&self, mut status: AccountStorageStatus) {
+    /// Returns the (owner, pubkey) pairs of the accounts dropped if the storage is reset
+    pub fn set_status(&self, mut status: AccountStorageStatus) -> Vec<(Pubkey, Pubkey)> {
         let mut count_and_status = self.count_and_status.write().unwrap();
 
         let count = count_and_status.0;
+        let mut dropped = vec![];
 
         if status == AccountStorageStatus::Full && count == 0 {
             // this case arises when the append_vec is full (store_ptrs fails),
@@ -191,11 +198,13 @@ impl AccountStorageEntry {
             //          **and**
             //  the append_vec has previously been completely full
             //
+            dropped = self.stored_keys();
             self.accounts.reset();
             status = AccountStorageStatus::Available;
         }
 
         *count_and_status = (count, status);
+        dropped
     }
 
     pub fn status(&self) -> AccountStorageStatus {
@@ -206,6 +215,23 @@ impl AccountStorageEntry {
         self.count_and_status.read().unwrap().0
     }
 
+    pub fn fork_id(&self) -> Fork {
+        self.fork_id
+    }
+
+    pub fn get_path(&self) -> PathBuf {
+        self.accounts.get_path()
+    }
+
+    /// The (owner, pubkey) pair of every account held by the storage, live or not
+    fn stored_keys(&self) -> Vec<(Pubkey, Pubkey)> {
+        self.accounts
+            .accounts(0)
+            .into_iter()
+            .map(|stored_account| (stored_account.balance.owner, stored_account.meta.pubkey))
+            .collect()
+    }
+
     fn add_account(&self) {
         let mut count_and_status = self.count_and_status.write().unwrap();
         *count_and_status = (count_and_status.0 + 1, count_and_status.1);
@@ -223,9 +249,12 @@ impl AccountStorageEntry {
         }
     }
 
-    fn remove_account(&self) -> usize {
+    /// Returns the remaining count, and the (owner, pubkey) pairs of the accounts dropped if
+    /// the storage is reset
+    fn remove_account(&self) -> (usize, Vec<(Pubkey, Pubkey)>) {
         let mut count_and_status = self.count_and_status.write().unwrap();
         let (count, mut status) = *count_and_status;
+        let mut dropped = vec![];
 
         if count == 1 && status == AccountStorageStatus::Full {
             // this case arises when we remove the last account from the
@@ -239,6 +268,7 @@ impl AccountStorageEntry {
             //
             // otherwise, the storage may be in flight with a store()
             //   call
+            dropped = self.stored_keys();
             self.accounts.reset();
             status = AccountStorageStatus::Available;
         }
@@ -246,7 +276,41 @@ impl AccountStorageEntry {
         if count > 0 {
             *count_and_status = (count - 1, status);
         }
-        count_and_status.0
+        (count_and_status.0, dropped)
+    }
+}
+
+/// The accounts as of a root, captured by `AccountsDB::snapshot` so they can be serialized
+/// once newer forks have been rooted.  Taking one only copies the roots and clones `Arc`s
+pub struct AccountsSnapshot {
+    fork: Fork,
+    roots: HashSet<Fork>,
+    /// Storage of the forks up to `fork`, and the length of each when it was captured
+    storage: Vec<(Arc<AccountStorageEntry>, usize)>,
+    accounts_hash: Option<(Fork, Hash)>,
+    _pending: PendingSnapshot,
+}
+
+/// Counts a snapshot as pending in `AccountsDB::pending_snapshots` until it is dropped
+struct PendingSnapshot(Arc<AtomicUsize>);
+
+impl Drop for PendingSnapshot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl AccountsSnapshot {
+    pub fn fork(&self) -> Fork {
+        self.fork
+    }
+
+    /// Storage the snapshot refers to.  Holding the entries keeps their files on disk
+    pub fn storage_entries(&self) -> Vec<Arc<AccountStorageEntry>> {
+        self.storage
+            .iter()
+            .map(|(store, _)| store.clone())
+            .collect()
     }
 }
 
@@ -273,6 +337,26 @@ pub struct AccountsDB {
 
     /// Thread pool used for par_iter
     thread_pool: ThreadPool,
+
+    /// Hash of the latest rooted version of every account holding lamports
+    rooted_account_hashes: RwLock<AccountsHashTree>,
+
+    /// Root of `rooted_account_hashes` and the fork it was last updated at
+    accounts_hash: RwLock<Option<(Fork, Hash)>>,
+
+    /// Accounts stored without lamports, which may be purged from the index once their
+    /// zero-lamport version is the only copy left in storage
+    zero_lamport_accounts: Mutex<HashSet<Pubkey>>,
+
+    /// Roots whose accounts may shadow older rooted versions not yet purged
+    uncleaned_roots: Mutex<BTreeSet<Fork>>,
+
+    /// Number of snapshots not yet dropped.  The storage they refer to must stay as it is
+    /// until they are serialized, so accounts are not cleaned meanwhile
+    pending_snapshots: Arc<AtomicUsize>,
+
+    /// Held while accounts are cleaned, so no snapshot is taken in the middle of it
+    clean_lock: Mutex<()>,
 }
 
 pub fn get_paths_vec(paths: &str) -> Vec<String> {
@@ -292,6 +376,12 @@ impl Default for AccountsDB {
                 .num_threads(2)
                 .build()
                 .unwrap(),
+            rooted_account_hashes: RwLock::new(AccountsHashTree::default()),
+            accounts_hash: RwLock::new(None),
+            zero_lamport_accounts: Mutex::new(HashSet::new()),
+            uncleaned_roots: Mutex::new(BTreeSet::new()),
+            pending_snapshots: Arc::new(AtomicUsize::new(0)),
+            clean_lock: Mutex::new(()),
         }
     }
 }
@@ -310,6 +400,12 @@ impl AccountsDB {
                 .num_threads(sys_info::cpu_num().unwrap_or(NUM_THREADS) as usize)
                 .build()
                 .unwrap(),
+            rooted_account_hashes: RwLock::new(AccountsHashTree::default()),
+            accounts_hash: RwLock::new(None),
+            zero_lamport_accounts: Mutex::new(HashSet::new()),
+            uncleaned_roots: Mutex::new(BTreeSet::new()),
+            pending_snapshots: Arc::new(AtomicUsize::new(0)),
+            clean_lock: Mutex::new(()),
         }
     }
 
@@ -317,20 +413,25 @@ impl AccountsDB {
         Self::new_with_file_size(paths, ACCOUNT_DATA_FILE_SIZE)
     }
 
+    /// Restore the accounts serialized in `stream`, whose storage files are found in
+    /// `account_paths`
     pub fn update_from_stream<R: Read>(
         &self,
+        account_paths: &[String],
         mut stream: &mut BufReader<R>,
     ) -> Result<(), std::io::Error> {
-        AppendVec::set_account_paths(&self.paths);
-
         let _len: usize = deserialize_from(&mut stream)
             .map_err(|_| AccountsDB::get_io_error("len deserialize error"))?;
         let accounts_index: AccountsIndex<AccountInfo> = deserialize_from(&mut stream)
             .map_err(|_| AccountsDB::get_io_error("accounts index deserialize error"))?;
-        let storage: AccountStorage = deserialize_from(&mut stream)
-            .map_err(|_| AccountsDB::get_io_error("storage deserialize error"))?;
+        let storage: HashMap<AppendVecId, SerializedAccountStorageEntry> =
+            deserialize_from(&mut stream)
+                .map_err(|_| AccountsDB::get_io_error("storage deserialize error"))?;
+        let storage = AccountStorage::open(storage, account_paths);
         let version: u64 = deserialize_from(&mut stream)
             .map_err(|_| AccountsDB::get_io_error("write version deserialize error"))?;
+        let accounts_hash: Option<(Fork, Hash)> = deserialize_from(&mut stream)
+            .map_err(|_| AccountsDB::get_io_error("accounts hash deserialize error"))?;
 
         let mut ids: Vec<usize> = storage
             .0
@@ -353,6 +454,22 @@ impl AccountsDB {
         self.write_version
             .fetch_add(version as usize, Ordering::Relaxed);
         self.generate_index();
+        let roots = self.accounts_index.read().unwrap().roots.clone();
+        self.uncleaned_roots.lock().unwrap().extend(roots);
+
+        let mut rooted_account_hashes = self.compute_rooted_account_hashes(Fork::max_value());
+        if let Some((fork, hash)) = accounts_hash {
+            let computed_hash = rooted_account_hashes.root();
+            if computed_hash != hash {
+                warn!(
+                    "accounts hash mismatch at fork {}: expected {}, computed {}",
+                    fork, hash, computed_hash
+                );
+                return Err(AccountsDB::get_io_error("accounts hash mismatch"));
+            }
+        }
+        *self.rooted_account_hashes.write().unwrap() = rooted_account_hashes;
+        *self.accounts_hash.write().unwrap() = accounts_hash;
         Ok(())
     }
 
@@ -365,6 +482,17 @@ impl AccountsDB {
         )
     }
 
+    /// Every storage entry currently held, across all forks
+    pub fn get_storage_entries(&self) -> Vec<Arc<AccountStorageEntry>> {
+        self.storage
+            .read()
+            .unwrap()
+            .0
+            .values()
+            .flat_map(|fork_storage| fork_storage.values().cloned())
+            .collect()
+    }
+
     pub fn has_accounts(&self, fork: Fork) -> bool {
         if let Some(storage_forks) = self.storage.read().unwrap().0.get(&fork) {
             for x in storage_forks.values() {
@@ -376,6 +504,37 @@ impl AccountsDB {
         false
     }
 
+    /// Scan the latest version of every account visible from `ancestors`
+    pub fn scan_accounts<F, A>(&self, ancestors: &HashMap<Fork, usize>, scan_func: F) -> A
+    where
+        F: Fn(&mut A, Option<(&Pubkey, Account, Fork)>) -> (),
+        A: Default,
+    {
+        let mut collector = A::default();
+        let accounts_index = self.accounts_index.read().unwrap();
+        let storage = self.storage.read().unwrap();
+        accounts_index.scan_accounts(ancestors, |pubkey, (account_info, fork)| {
+            scan_func(
+                &mut collector,
+                storage
+                    .0
+                    .get(&fork)
+                    .and_then(|storage_map| storage_map.get(&account_info.id))
+                    .and_then(|store| {
+                        Some(
+                            store
+                                .accounts
+                                .get_account(account_info.offset)?
+                                .0
+                                .clone_account(),
+                        )
+                    })
+                    .map(|account| (pubkey, account, fork)),
+            )
+        });
+        collector
+    }
+
     /// Scan a specific fork through all the account storage in parallel with sequential read
     // PERF: Sequentially read each storage entry in parallel
     pub fn scan_account_storage<F, B>(&self, fork_id: Fork, scan_func: F) -> Vec<B>
@@ -427,6 +586,21 @@ impl AccountsDB {
         }
     }
 
+    /// The version of an account stored in exactly `fork`
+    pub fn load_fork(
+        storage: &AccountStorage,
+        fork: Fork,
+        accounts_index: &AccountsIndex<AccountInfo>,
+        pubkey: &Pubkey,
+    ) -> Option<Account> {
+        let info = accounts_index.get_fork(pubkey, fork)?;
+        storage
+            .0
+            .get(&fork)?
+            .get(&info.id)
+            .and_then(|store| Some(store.accounts.get_account(info.offset)?.0.clone_account()))
+    }
+
     pub fn load_slow(
         &self,
         ancestors: &HashMap<Fork, usize>,
@@ -471,10 +645,52 @@ impl AccountsDB {
         //add_root should be called first
         let is_root = self.accounts_index.read().unwrap().is_root(fork);
         if !is_root {
-            self.storage.write().unwrap().0.remove(&fork);
+            let fork_storage = self.storage.write().unwrap().0.remove(&fork);
+            if let Some(fork_storage) = fork_storage {
+                self.remove_dropped_accounts(
+                    fork_storage
+                        .values()
+                        .flat_map(|store| store.stored_keys())
+                        .collect(),
+                );
+            }
         }
     }
 
+    /// Release the index references held by copies of accounts dropped from storage, given
+    /// as (owner, pubkey) pairs, and drop their secondary index entries unless another
+    /// version of the account still has the same owner
+    fn remove_dropped_accounts(&self, mut dropped: Vec<(Pubkey, Pubkey)>) {
+        if dropped.is_empty() {
+            return;
+        }
+        let accounts_index = self.accounts_index.read().unwrap();
+        let pubkeys: Vec<Pubkey> = dropped.iter().map(|(_, pubkey)| *pubkey).collect();
+        accounts_index.remove_refs(&pubkeys);
+        if !accounts_index.has_program_accounts() {
+            return;
+        }
+        dropped.sort();
+        dropped.dedup();
+
+        let storage = self.storage.read().unwrap();
+        accounts_index.remove_program_accounts(&dropped, |owner, pubkey| {
+            !accounts_index
+                .get_entries(pubkey)
+                .iter()
+                .any(|(fork, info)| {
+                    storage
+                        .0
+                        .get(fork)
+                        .and_then(|fork_storage| fork_storage.get(&info.id))
+                        .and_then(|store| store.accounts.get_account(info.offset))
+                        .map_or(false, |(stored_account, _)| {
+                            stored_account.balance.owner == *owner
+                        })
+                })
+        });
+    }
+
     fn store_accounts(
         &self,
         fork_id: Fork,
@@ -506,11 +722,12 @@ impl AccountsDB {
             })
             .collect();
         let mut infos: Vec<AccountInfo> = vec![];
+        let mut dropped = vec![];
         while infos.len() < with_meta.len() {
             let storage = self.find_storage_candidate(fork_id);
             let rvs = storage.accounts.append_accounts(&with_meta[infos.len()..]);
             if rvs.is_empty() {
-                storage.set_status(AccountStorageStatus::Full);
+                dropped.extend(storage.set_status(AccountStorageStatus::Full));
                 continue;
             }
             for (offset, (_, _, lamports)) in rvs.iter().zip(&with_meta[infos.len()..]) {
@@ -524,6 +741,7 @@ impl AccountsDB {
             // restore the state to available
             storage.set_status(AccountStorageStatus::Available);
         }
+        self.remove_dropped_accounts(dropped);
         infos
     }
 
@@ -534,17 +752,35 @@ impl AccountsDB {
         accounts: &HashMap<&Pubkey, (&Account, LamportCredit)>,
     ) -> (Vec<(Fork, AccountInfo)>, u64) {
         let mut reclaims: Vec<(Fork, AccountInfo)> = Vec::with_capacity(infos.len() * 2);
-        let mut index = self.accounts_index.write().unwrap();
+        let mut zero_lamport_pubkeys = vec![];
+        let index = self.accounts_index.read().unwrap();
         for (info, account) in infos.into_iter().zip(accounts.iter()) {
             let key = &account.0;
+            if info.lamports == 0 {
+                zero_lamport_pubkeys.push(**key);
+            }
             index.insert(fork_id, key, info, &mut reclaims);
         }
-        (reclaims, index.last_root)
+        index.add_program_accounts(
+            accounts
+                .iter()
+                .map(|(pubkey, (account, _))| (&account.owner, *pubkey)),
+        );
+        let last_root = index.last_root;
+        drop(index);
+        if !zero_lamport_pubkeys.is_empty() {
+            self.zero_lamport_accounts
+                .lock()
+                .unwrap()
+                .extend(zero_lamport_pubkeys);
+        }
+        (reclaims, last_root)
     }
 
     fn remove_dead_accounts(&self, reclaims: Vec<(Fork, AccountInfo)>) -> HashSet<Fork> {
         let storage = self.storage.read().unwrap();
         let mut dead_forks = HashSet::new();
+        let mut dropped = vec![];
         for (fork_id, account_info) in reclaims {
             if let Some(fork_storage) = storage.0.get(&fork_id) {
                 if let Some(store) = fork_storage.get(&account_info.id) {
@@ -552,7 +788,8 @@ impl AccountsDB {
                         fork_id, store.fork_id,
                         "AccountDB::accounts_index corrupted. Storage should only point to one fork"
                     );
-                    let count = store.remove_account();
+                    let (count, reset) = store.remove_account();
+                    dropped.extend(reset);
                     if count == 0 {
                         dead_forks.insert(fork_id);
                     }
@@ -570,6 +807,8 @@ impl AccountsDB {
             }
             true
         });
+        drop(storage);
+        self.remove_dropped_accounts(dropped);
 
         dead_forks
     }
@@ -604,20 +843,453 @@ impl AccountsDB {
     }
 
     pub fn add_root(&self, fork: Fork) {
-        self.accounts_index.write().unwrap().add_root(fork)
+        // The accounts hash only covers a fork once it is rooted in the index, which
+        // `verify_accounts_hash` relies on to scan the roots the hash covers
+        let mut rooted_account_hashes = self.rooted_account_hashes.write().unwrap();
+        self.accounts_index.write().unwrap().add_root(fork);
+        self.update_accounts_hash(&mut rooted_account_hashes, fork);
+        self.uncleaned_roots.lock().unwrap().insert(fork);
+    }
+
+    fn hash_account(pubkey: &Pubkey, stored_account: &StoredAccount) -> Hash {
+        hashv(&[
+            pubkey.as_ref(),
+            &serialize(stored_account.balance).unwrap(),
+            stored_account.data,
+        ])
+    }
+
+    /// Fold the accounts stored in the newly rooted `fork` into the accounts hash
+    fn update_accounts_hash(&self, rooted_account_hashes: &mut AccountsHashTree, fork: Fork) {
+        let mut accounts_hash = self.accounts_hash.write().unwrap();
+        if accounts_hash.map_or(false, |(last_fork, _)| fork <= last_fork) {
+            return;
+        }
+
+        let accumulator: Vec<Vec<(Pubkey, u64, Option<Hash>)>> = self.scan_account_storage(
+            fork,
+            |stored_account: &StoredAccount,
+             _id: AppendVecId,
+             accum: &mut Vec<(Pubkey, u64, Option<Hash>)>| {
+                let pubkey = stored_account.meta.pubkey;
+                let hash = if stored_account.balance.lamports == 0 {
+                    None
+                } else {
+                    Some(Self::hash_account(&pubkey, stored_account))
+                };
+                accum.push((pubkey, stored_account.meta.write_version, hash));
+            },
+        );
+        let mut updates: Vec<_> = accumulator.into_iter().flat_map(|x| x).collect();
+        updates.sort_by_key(|(pubkey, write_version, _)| (*pubkey, Reverse(*write_version)));
+        updates.dedup_by_key(|(pubkey, _, _)| *pubkey);
+        for (pubkey, _, hash) in updates {
+            match hash {
+                Some(hash) => rooted_account_hashes.insert(pubkey, hash),
+                None => rooted_account_hashes.remove(&pubkey),
+            }
+        }
+        *accounts_hash = Some((fork, rooted_account_hashes.root()));
+    }
+
+    /// Hash the latest version of every account rooted no later than `max_root` straight
+    /// from storage.  The index is let go of between its shards, so roots may be added while
+    /// the scan runs, but they are left out of it
+    fn compute_rooted_account_hashes(&self, max_root: Fork) -> AccountsHashTree {
+        let mut rooted_account_hashes = AccountsHashTree::default();
+        for shard in 0..NUM_SHARDS {
+            let accounts_index = self.accounts_index.read().unwrap();
+            let storage = self.storage.read().unwrap();
+            accounts_index.scan_shard_rooted(shard, max_root, |pubkey, (account_info, fork)| {
+                if account_info.lamports == 0 {
+                    return;
+                }
+                if let Some((stored_account, _)) = storage
+                    .0
+                    .get(&fork)
+                    .and_then(|fork_storage| fork_storage.get(&account_info.id))
+                    .and_then(|store| store.accounts.get_account(account_info.offset))
+                {
+                    rooted_account_hashes
+                        .insert(*pubkey, Self::hash_account(pubkey, &stored_account));
+                }
+            });
+        }
+        rooted_account_hashes
+    }
+
+    /// The Merkle root over the latest rooted version of every account, and the root fork
+    /// it covers, or `None` until a fork is rooted
+    pub fn accounts_hash(&self) -> Option<(Fork, Hash)> {
+        *self.accounts_hash.read().unwrap()
+    }
+
+    /// Capture the accounts as of the root `fork`, to be serialized later with
+    /// `serialize_snapshot`
+    pub fn snapshot(&self, fork: Fork) -> AccountsSnapshot {
+        let _clean_lock = self.clean_lock.lock().unwrap();
+        self.pending_snapshots.fetch_add(1, Ordering::Relaxed);
+        let pending = PendingSnapshot(self.pending_snapshots.clone());
+        let roots = self
+            .accounts_index
+            .read()
+            .unwrap()
+            .roots
+            .iter()
+            .filter(|root| **root <= fork)
+            .cloned()
+            .collect();
+        let storage = self
+            .storage
+            .read()
+            .unwrap()
+            .0
+            .iter()
+            .filter(|(store_fork, _)| **store_fork <= fork)
+            .flat_map(|(_, fork_storage)| fork_storage.values())
+            .map(|store| (store.clone(), store.accounts.len()))
+            .collect();
+        AccountsSnapshot {
+            fork,
+            roots,
+            storage,
+            accounts_hash: self.accounts_hash(),
+            _pending: pending,
+        }
+    }
+
+    /// Serialize the accounts as of `snapshot`, in the encoding of the `AccountsDB` itself.
+    /// Fails if storage the snapshot refers to was reset since, as its accounts are gone
+    pub fn serialize_snapshot(&self, snapshot: &AccountsSnapshot) -> Result<Vec<u8>, Error> {
+        if snapshot
+            .storage
+            .iter()
+            .any(|(store, len)| store.accounts.len() != *len)
+        {
+            return Err(AccountsDB::get_io_error("snapshot storage was reset"));
+        }
+        let mut accounts_index = AccountsIndex::<AccountInfo>::new(false);
+        accounts_index.roots = snapshot.roots.clone();
+        let mut storage = AccountStorage(HashMap::new());
+        for (store, _) in &snapshot.storage {
+            storage
+                .0
+                .entry(store.fork_id)
+                .or_insert_with(HashMap::new)
+                .insert(store.id, store.clone());
+        }
+        let version = self.write_version.load(Ordering::Relaxed) as u64;
+        let state =
+            Self::serialize_state(&accounts_index, &storage, version, snapshot.accounts_hash)
+                .and_then(|state| serialize(&state))
+                .map_err(|_| AccountsDB::get_io_error("serialize accounts snapshot error"))?;
+        Ok(state)
+    }
+
+    fn serialize_state(
+        accounts_index: &AccountsIndex<AccountInfo>,
+        storage: &AccountStorage,
+        version: u64,
+        accounts_hash: Option<(Fork, Hash)>,
+    ) -> bincode::Result<Vec<u8>> {
+        let mut state = vec![];
+        serialize_into(&mut state, accounts_index)?;
+        serialize_into(&mut state, storage)?;
+        serialize_into(&mut state, &version)?;
+        serialize_into(&mut state, &accounts_hash)?;
+        Ok(state)
+    }
+
+    /// Recompute the accounts hash from storage and check it against the one maintained as
+    /// forks were rooted.  The scan only looks at the roots the accounts hash covered when it
+    /// began, so it doesn't hold up `add_root`, while cleaning, which could reclaim the
+    /// versions of accounts it reads, waits for it
+    pub fn verify_accounts_hash(&self) -> bool {
+        let _clean_lock = self.clean_lock.lock().unwrap();
+        match self.accounts_hash() {
+            Some((fork, hash)) => self.compute_rooted_account_hashes(fork).root() == hash,
+            None => true,
+        }
+    }
+
+    /// Remove from the index the accounts whose only version is rooted and holds no
+    /// lamports, once storage holds no older copy that could bring back a funded version.
+    /// Returns the removed versions so their storage can be reclaimed
+    fn purge_zero_lamport_accounts(&self) -> Vec<(Fork, AccountInfo)> {
+        let candidates: Vec<Pubkey> = self.zero_lamport_accounts.lock().unwrap().drain().collect();
+        let mut reclaims = vec![];
+        let mut pending = vec![];
+        {
+            let index = self.accounts_index.read().unwrap();
+            for pubkey in candidates {
+                let reclaim = index.remove_last_version(&pubkey, |(fork, info)| {
+                    info.lamports == 0 && index.is_root(*fork)
+                });
+                if let Some(reclaim) = reclaim {
+                    reclaims.push(reclaim);
+                } else if index
+                    .get_entries(&pubkey)
+                    .iter()
+                    .any(|(_, info)| info.lamports == 0)
+                {
+                    // Wait for the version to be rooted and the older copies to be dropped
+                    pending.push(pubkey);
+                }
+            }
+        }
+        self.zero_lamport_accounts.lock().unwrap().extend(pending);
+        reclaims
+    }
+
+    /// Release the storage of `reclaims`, purging the forks left without live accounts,
+    /// deleting empty storage entries and compacting the older roots left sparse.  Returns
+    /// the number of bytes reclaimed
+    fn reclaim_dead_accounts(&self, reclaims: Vec<(Fork, AccountInfo)>, last_root: Fork) -> usize {
+        // The latest root may still be written to while its bank is frozen, so only forks
+        // behind it are compacted
+        let mut shrink_forks: Vec<Fork> = reclaims
+            .iter()
+            .map(|(fork, _)| *fork)
+            .filter(|fork| *fork < last_root)
+            .collect();
+        shrink_forks.sort();
+        shrink_forks.dedup();
+
+        let mut dead_forks = self.remove_dead_accounts(reclaims);
+        self.cleanup_dead_forks(&mut dead_forks, last_root);
+        let mut reclaimed_bytes = 0;
+        for fork in dead_forks {
+            reclaimed_bytes += self.storage_size(fork);
+            self.purge_fork(fork);
+        }
+        for fork in shrink_forks {
+            reclaimed_bytes += self.remove_empty_storage_entries(fork);
+            reclaimed_bytes += self.shrink_fork(fork);
+        }
+        reclaimed_bytes
+    }
+
+    /// Reclaim the space held by old roots, looking at a bounded batch of the roots added
+    /// since the last pass: drop the index entries their accounts shadow, rewrite the forks
+    /// left sparsely populated into compact storage, and purge accounts left without
+    /// lamports.  Nothing is cleaned while a snapshot is pending.  Returns the number of
+    /// bytes reclaimed
+    pub fn clean_accounts(&self) -> usize {
+        let _clean_lock = self.clean_lock.lock().unwrap();
+        if self.pending_snapshots.load(Ordering::Relaxed) > 0 {
+            return 0;
+        }
+        let roots: Vec<Fork> = {
+            let mut uncleaned_roots = self.uncleaned_roots.lock().unwrap();
+            let roots: Vec<Fork> = uncleaned_roots
+                .iter()
+                .take(CLEAN_BATCH_ROOTS)
+                .cloned()
+                .collect();
+            for root in &roots {
+                uncleaned_roots.remove(root);
+            }
+            roots
+        };
+
+        let pubkeys: HashSet<Pubkey> = {
+            let storage = self.storage.read().unwrap();
+            roots
+                .iter()
+                .filter_map(|root| storage.0.get(root))
+                .flat_map(|fork_storage| fork_storage.values())
+                .flat_map(|store| store.stored_keys())
+                .map(|(_, pubkey)| pubkey)
+                .collect()
+        };
+        let pubkeys: Vec<Pubkey> = pubkeys.into_iter().collect();
+        let (reclaims, last_root) = {
+            let index = self.accounts_index.read().unwrap();
+            (index.purge_shadowed_roots(&pubkeys), index.last_root)
+        };
+        let mut reclaimed_bytes = self.reclaim_dead_accounts(reclaims, last_root);
+
+        // Shrinking drops shadowed copies, which may leave zero-lamport versions purgeable
+        let reclaims = self.purge_zero_lamport_accounts();
+        reclaimed_bytes += self.reclaim_dead_accounts(reclaims, last_root);
+
+        inc_new_counter_info!("accounts_db-clean_reclaimed_bytes", reclaimed_bytes);
+        reclaimed_bytes
+    }
+
+    /// Number of roots added since they were last looked at by `clean_accounts`
+    pub fn num_uncleaned_roots(&self) -> usize {
+        self.uncleaned_roots.lock().unwrap().len()
+    }
+
+    fn storage_size(&self, fork: Fork) -> usize {
+        self.storage
+            .read()
+            .unwrap()
+            .0
+            .get(&fork)
+            .map_or(0, |fork_storage| {
+                fork_storage
+                    .values()
+                    .map(|store| store.accounts.capacity() as usize)
+                    .sum()
+            })
+    }
+
+    /// Drop the storage entries of `fork` that no longer hold any live accounts, which
+    /// deletes their files
+    fn remove_empty_storage_entries(&self, fork: Fork) -> usize {
+        let mut storage = self.storage.write().unwrap();
+        let mut reclaimed_bytes = 0;
+        let mut dropped = vec![];
+        if let Some(fork_storage) = storage.0.get_mut(&fork) {
+            fork_storage.retain(|_, store| {
+                if store.count() == 0 {
+                    reclaimed_bytes += store.accounts.capacity() as usize;
+                    dropped.extend(store.stored_keys());
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+        drop(storage);
+        self.remove_dropped_accounts(dropped);
+        reclaimed_bytes
+    }
+
+    /// Copy the live accounts of a rooted `fork` into a single storage entry sized to fit
+    /// them, once dead accounts take up enough of the fork's existing storage
+    fn shrink_fork(&self, fork: Fork) -> usize {
+        let stores: Vec<Arc<AccountStorageEntry>> = self
+            .storage
+            .read()
+            .unwrap()
+            .0
+            .get(&fork)
+            .map_or(vec![], |fork_storage| {
+                fork_storage.values().cloned().collect()
+            });
+        if stores.is_empty() {
+            return 0;
+        }
+
+        let mut live_accounts = vec![];
+        let mut written_bytes = 0;
+        let mut alive_bytes = 0;
+        {
+            let index = self.accounts_index.read().unwrap();
+            for store in &stores {
+                written_bytes += store.accounts.len();
+                let mut start = 0;
+                while let Some((stored_account, next)) = store.accounts.get_account(start) {
+                    let is_live = index
+                        .get_fork(&stored_account.meta.pubkey, fork)
+                        .map_or(false, |info| {
+                            info.id == store.id && info.offset == stored_account.offset
+                        });
+                    if is_live {
+                        alive_bytes += next - start;
+                        live_accounts.push((
+                            stored_account.meta.clone(),
+                            stored_account.clone_account(),
+                            store.id,
+                            stored_account.offset,
+                        ));
+                    }
+                    start = next;
+                }
+            }
+        }
+        if live_accounts.is_empty() || alive_bytes * 100 >= written_bytes * SHRINK_THRESHOLD_PERCENT
+        {
+            return 0;
+        }
+
+        let path_index = thread_rng().gen_range(0, self.paths.len());
+        let new_store = Arc::new(AccountStorageEntry::new(
+            &self.paths[path_index],
+            fork,
+            self.next_id.fetch_add(1, Ordering::Relaxed),
+            alive_bytes as u64,
+        ));
+        let with_meta: Vec<(StorageMeta, &Account, u64)> = live_accounts
+            .iter()
+            .map(|(meta, account, _, _)| (meta.clone(), account, account.lamports))
+            .collect();
+        let offsets = new_store.accounts.append_accounts(&with_meta);
+        if offsets.len() != live_accounts.len() {
+            warn!("failed to shrink fork {}", fork);
+            return 0;
+        }
+
+        let index = self.accounts_index.read().unwrap();
+        // Count the copies before the old ones are dropped
+        let copied: Vec<Pubkey> = live_accounts
+            .iter()
+            .map(|(meta, _, _, _)| meta.pubkey)
+            .collect();
+        index.add_refs(&copied);
+        let mut storage = self.storage.write().unwrap();
+        for ((meta, _, old_id, old_offset), offset) in live_accounts.iter().zip(offsets) {
+            let moved = index.update(&meta.pubkey, |list| {
+                let entry = list.iter_mut().find(|(entry_fork, info)| {
+                    *entry_fork == fork && info.id == *old_id && info.offset == *old_offset
+                });
+                // The account may have been reclaimed since it was copied
+                if let Some((_, info)) = entry {
+                    info.id = new_store.id;
+                    info.offset = offset;
+                    true
+                } else {
+                    false
+                }
+            });
+            if moved == Some(true) {
+                new_store.add_account();
+            }
+        }
+        let mut dropped = new_store.set_status(AccountStorageStatus::Full);
+        let fork_storage = storage.0.entry(fork).or_insert_with(HashMap::new);
+        for store in &stores {
+            fork_storage.remove(&store.id);
+            dropped.extend(store.stored_keys());
+        }
+        fork_storage.insert(new_store.id, new_store.clone());
+        drop(storage);
+        drop(index);
+        self.remove_dropped_accounts(dropped);
+
+        let old_size: usize = stores
+            .iter()
+            .map(|store| store.accounts.capacity() as usize)
+            .sum();
+        old_size.saturating_sub(new_store.accounts.capacity() as usize)
     }
 
     fn merge(
-        dest: &mut HashMap<Pubkey, (u64, AccountInfo)>,
-        source: &HashMap<Pubkey, (u64, AccountInfo)>,
+        dest: &mut HashMap<Pubkey, (u64, AccountInfo, Pubkey, RefCount)>,
+        source: &HashMap<Pubkey, (u64, AccountInfo, Pubkey, RefCount)>,
     ) {
-        for (key, (source_version, source_info)) in source.iter() {
-            if let Some((dest_version, _)) = dest.get(key) {
-                if dest_version > source_version {
-                    continue;
+        for (key, (source_version, source_info, source_owner, source_ref_count)) in source.iter() {
+            if let Some((dest_version, dest_info, dest_owner, dest_ref_count)) = dest.get_mut(key) {
+                *dest_ref_count += source_ref_count;
+                if *dest_version < *source_version {
+                    *dest_version = *source_version;
+                    *dest_info = source_info.clone();
+                    *dest_owner = *source_owner;
                 }
+                continue;
             }
-            dest.insert(*key, (*source_version, source_info.clone()));
+            dest.insert(
+                *key,
+                (
+                    *source_version,
+                    source_info.clone(),
+                    *source_owner,
+                    *source_ref_count,
+                ),
+            );
         }
     }
 
@@ -633,21 +1305,26 @@ impl AccountsDB {
         let mut accounts_index = self.accounts_index.write().unwrap();
         accounts_index.roots.insert(0);
         for fork_id in forks.iter() {
-            let mut accumulator: Vec<HashMap<Pubkey, (u64, AccountInfo)>> = self
-                .scan_account_storage(
+            let mut accumulator: Vec<HashMap<Pubkey, (u64, AccountInfo, Pubkey, RefCount)>> =
+                self.scan_account_storage(
                     *fork_id,
                     |stored_account: &StoredAccount,
                      id: AppendVecId,
-                     accum: &mut HashMap<Pubkey, (u64, AccountInfo)>| {
+                     accum: &mut HashMap<Pubkey, (u64, AccountInfo, Pubkey, RefCount)>| {
                         let account_info = AccountInfo {
                             id,
                             offset: stored_account.offset,
                             lamports: stored_account.balance.lamports,
                         };
-                        accum.insert(
-                            stored_account.meta.pubkey,
-                            (stored_account.meta.write_version, account_info),
-                        );
+                        let write_version = stored_account.meta.write_version;
+                        let owner = stored_account.balance.owner;
+                        let entry = accum
+                            .entry(stored_account.meta.pubkey)
+                            .or_insert((write_version, account_info.clone(), owner, 0));
+                        if entry.0 <= write_version {
+                            *entry = (write_version, account_info, owner, entry.3);
+                        }
+                        entry.3 += 1;
                     },
                 );
 
@@ -655,9 +1332,18 @@ impl AccountsDB {
             while let Some(maps) = accumulator.pop() {
                 AccountsDB::merge(&mut account_maps, &maps);
             }
-            for (pubkey, (_, account_info)) in account_maps.iter() {
-                accounts_index.add_index(*fork_id, pubkey, account_info.clone());
+            let mut zero_lamport_accounts = self.zero_lamport_accounts.lock().unwrap();
+            for (pubkey, (_, account_info, _, ref_count)) in account_maps.iter() {
+                if account_info.lamports == 0 {
+                    zero_lamport_accounts.insert(*pubkey);
+                }
+                accounts_index.add_index(*fork_id, pubkey, account_info.clone(), *ref_count);
             }
+            accounts_index.add_program_accounts(
+                account_maps
+                    .iter()
+                    .map(|(pubkey, (_, _, owner, _))| (owner, pubkey)),
+            );
         }
     }
 }
@@ -670,17 +1356,10 @@ impl Serialize for AccountsDB {
         use serde::ser::Error;
         let accounts_index = self.accounts_index.read().unwrap();
         let storage = self.storage.read().unwrap();
-        let len = serialized_size(&*accounts_index).unwrap()
-            + serialized_size(&*storage).unwrap()
-            + std::mem::size_of::<u64>() as u64;
-        let mut buf = vec![0u8; len as usize];
-        let mut wr = Cursor::new(&mut buf[..]);
         let version: u64 = self.write_version.load(Ordering::Relaxed) as u64;
-        serialize_into(&mut wr, &*accounts_index).map_err(Error::custom)?;
-        serialize_into(&mut wr, &*storage).map_err(Error::custom)?;
-        serialize_into(&mut wr, &version).map_err(Error::custom)?;
-        let len = wr.position() as usize;
-        serializer.serialize_bytes(&wr.into_inner()[..len])
+        let state = Self::serialize_state(&accounts_index, &storage, version, self.accounts_hash())
+            .map_err(Error::custom)?;
+        serializer.serialize_bytes(&state)
     }
 }
 
@@ -692,6 +1371,7 @@ mod tests {
     use maplit::hashmap;
     use rand::{thread_rng, Rng};
     use solana_sdk::account::Account;
+    use std::io::Cursor;
 
     fn cleanup_paths(paths: &str) {
         let paths = get_paths_vec(&paths);
@@ -768,6 +1448,14 @@ mod tests {
 
         let ancestors = vec![(1, 1), (0, 0)].into_iter().collect();
         assert_eq!(&db.load_slow(&ancestors, &key).unwrap().0, &account1);
+
+        let accounts: Vec<Account> =
+            db.scan_accounts(&ancestors, |accounts: &mut Vec<Account>, option| {
+                if let Some(data) = option {
+                    accounts.push(data.1);
+                }
+            });
+        assert_eq!(accounts, vec![account1]);
     }
 
     #[test]
@@ -1185,6 +1873,219 @@ mod tests {
         assert!(accounts.load_slow(&ancestors, &pubkeys[0]).is_some());
     }
 
+    #[test]
+    fn test_purge_fork_program_accounts() {
+        let paths = get_tmp_accounts_path!();
+        let accounts = AccountsDB::new(&paths.paths);
+        let owner = Pubkey::new_rand();
+        let rooted_key = Pubkey::new_rand();
+        let forked_key = Pubkey::new_rand();
+        let account = Account::new(1, 0, &owner);
+        accounts.store(0, &hashmap!(&rooted_key => (&account, 0)));
+        accounts.add_root(0);
+        accounts.store(
+            1,
+            &hashmap!(&rooted_key => (&account, 0), &forked_key => (&account, 0)),
+        );
+
+        let program_accounts = |owner| {
+            let mut pubkeys = accounts
+                .accounts_index
+                .read()
+                .unwrap()
+                .program_accounts(owner)
+                .unwrap();
+            pubkeys.sort();
+            pubkeys
+        };
+        let mut expected = vec![rooted_key, forked_key];
+        expected.sort();
+        assert_eq!(program_accounts(&owner), expected);
+
+        // The rooted version of `rooted_key` keeps it in the owner index
+        accounts.purge_fork(1);
+        assert_eq!(program_accounts(&owner), vec![rooted_key]);
+    }
+
+    #[test]
+    fn test_purge_zero_lamport_accounts() {
+        let paths = get_tmp_accounts_path!();
+        let accounts = AccountsDB::new(&paths.paths);
+        let pubkey = Pubkey::new_rand();
+        let other_pubkey = Pubkey::new_rand();
+        let account = Account::new(1, 0, &Account::default().owner);
+        let zero_lamport_account = Account::new(0, 0, &Account::default().owner);
+        accounts.store(
+            0,
+            &hashmap!(&pubkey => (&account, 0), &other_pubkey => (&account, 0)),
+        );
+        accounts.add_root(0);
+        accounts.store(1, &hashmap!(&pubkey => (&zero_lamport_account, 0)));
+        let ancestors = vec![(0, 0), (1, 1)].into_iter().collect();
+
+        // The zero-lamport version is not rooted yet, so the account is kept
+        accounts.clean_accounts();
+        assert!(accounts.load_slow(&ancestors, &pubkey).is_some());
+
+        // Shrinking fork 0 drops the funded copy, so the account can go
+        accounts.add_root(1);
+        accounts.clean_accounts();
+        assert!(accounts.load_slow(&ancestors, &pubkey).is_none());
+        assert_eq!(
+            accounts.load_slow(&ancestors, &other_pubkey),
+            Some((account, 0))
+        );
+        assert!(accounts.zero_lamport_accounts.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_purge_zero_lamport_accounts_not_resurrected() {
+        let paths = get_tmp_accounts_path!();
+        let accounts = AccountsDB::new(&paths.paths);
+        let mut pubkeys: Vec<Pubkey> = vec![];
+        create_account(&accounts, &mut pubkeys, 0, 10, 0, 0);
+        accounts.add_root(0);
+        let zero_lamport_account = Account::new(0, 0, &Account::default().owner);
+        accounts.store(1, &hashmap!(&pubkeys[0] => (&zero_lamport_account, 0)));
+        accounts.add_root(1);
+
+        // Fork 0 is too full to shrink, so its funded copy keeps the account in the index
+        accounts.clean_accounts();
+        assert_eq!(accounts.storage.read().unwrap().0[&0][&0].count(), 9);
+        let ancestors = HashMap::new();
+        assert_eq!(
+            accounts.load_slow(&ancestors, &pubkeys[0]),
+            Some((zero_lamport_account, 1))
+        );
+        assert!(accounts
+            .zero_lamport_accounts
+            .lock()
+            .unwrap()
+            .contains(&pubkeys[0]));
+
+        // Rebuilding the index from storage must not bring back the funded version
+        let mut buf = vec![0u8; serialized_size(&accounts).unwrap() as usize];
+        let mut writer = Cursor::new(&mut buf[..]);
+        serialize_into(&mut writer, &accounts).unwrap();
+        let mut reader = BufReader::new(&buf[..]);
+        let daccounts = AccountsDB::new(&paths.paths);
+        assert!(daccounts
+            .update_from_stream(&get_paths_vec(&paths.paths), &mut reader)
+            .is_ok());
+        let (account, fork) = daccounts.load_slow(&ancestors, &pubkeys[0]).unwrap();
+        assert_eq!((account.lamports, fork), (0, 1));
+        for (idx, pubkey) in pubkeys.iter().enumerate().skip(1) {
+            let (account, fork) = daccounts.load_slow(&ancestors, pubkey).unwrap();
+            assert_eq!((account.lamports, fork), ((idx + 1) as u64, 0));
+        }
+    }
+
+    #[test]
+    fn test_clean_accounts_shrinks_old_roots() {
+        let paths = get_tmp_accounts_path!();
+        let accounts = AccountsDB::new(&paths.paths);
+        let mut pubkeys: Vec<Pubkey> = vec![];
+        create_account(&accounts, &mut pubkeys, 0, 100, 0, 0);
+        accounts.add_root(0);
+        modify_accounts(&accounts, &pubkeys, 1, 90, 2);
+        accounts.add_root(1);
+        let old_size = accounts.storage_size(0);
+
+        // The shadowed versions stay indexed until the clean pass
+        assert_eq!(accounts.storage.read().unwrap().0[&0][&0].count(), 100);
+        assert!(accounts.clean_accounts() > 0);
+
+        let stores = accounts.storage.read().unwrap();
+        assert_eq!(stores.0[&0].len(), 1);
+        let store = stores.0[&0].values().next().unwrap();
+        assert_eq!(store.count(), 10);
+        assert_eq!(store.status(), AccountStorageStatus::Full);
+        drop(stores);
+        assert!(accounts.storage_size(0) < old_size);
+
+        let ancestors = HashMap::new();
+        for (idx, pubkey) in pubkeys.iter().enumerate() {
+            let (account, fork) = accounts.load_slow(&ancestors, pubkey).unwrap();
+            if idx < 90 {
+                assert_eq!((account.lamports, fork), ((idx + 2) as u64, 1));
+            } else {
+                assert_eq!((account.lamports, fork), ((idx + 1) as u64, 0));
+            }
+        }
+
+        // Nothing is left to reclaim
+        assert_eq!(accounts.clean_accounts(), 0);
+    }
+
+    #[test]
+    fn test_clean_accounts_removes_dead_forks() {
+        let paths = get_tmp_accounts_path!();
+        let accounts = AccountsDB::new(&paths.paths);
+        let mut pubkeys: Vec<Pubkey> = vec![];
+        create_account(&accounts, &mut pubkeys, 0, 10, 0, 0);
+        accounts.add_root(0);
+        modify_accounts(&accounts, &pubkeys, 1, 10, 2);
+        accounts.add_root(1);
+
+        // Every account in fork 0 is shadowed, so its storage is deleted outright
+        assert!(accounts.clean_accounts() > 0);
+        assert!(accounts.storage.read().unwrap().0.get(&0).is_none());
+        assert!(!accounts.accounts_index.read().unwrap().is_root(0));
+        check_accounts(&accounts, &pubkeys, 1, 10, 2);
+    }
+
+    #[test]
+    fn test_clean_accounts_waits_for_snapshots() {
+        let paths = get_tmp_accounts_path!();
+        let accounts = AccountsDB::new(&paths.paths);
+        let mut pubkeys: Vec<Pubkey> = vec![];
+        create_account(&accounts, &mut pubkeys, 0, 10, 0, 0);
+        accounts.add_root(0);
+        modify_accounts(&accounts, &pubkeys, 1, 10, 2);
+        accounts.add_root(1);
+
+        // The storage a snapshot refers to is left alone until it is dropped
+        let snapshot = accounts.snapshot(1);
+        assert_eq!(accounts.clean_accounts(), 0);
+        assert!(accounts.serialize_snapshot(&snapshot).is_ok());
+        drop(snapshot);
+        assert!(accounts.clean_accounts() > 0);
+        assert!(accounts.storage.read().unwrap().0.get(&0).is_none());
+    }
+
+    #[test]
+    fn test_clean_accounts_batches_roots() {
+        let paths = get_tmp_accounts_path!();
+        let accounts = AccountsDB::new(&paths.paths);
+        let mut pubkeys: Vec<Pubkey> = vec![];
+        create_account(&accounts, &mut pubkeys, 0, 1, 0, 0);
+        accounts.add_root(0);
+        let num_roots = 2 * CLEAN_BATCH_ROOTS as u64;
+        for fork in 1..=num_roots {
+            modify_accounts(&accounts, &pubkeys, fork, 1, fork as usize + 1);
+            accounts.add_root(fork);
+        }
+
+        // Each pass only looks at a batch of the roots added since the last one
+        accounts.clean_accounts();
+        assert_eq!(
+            accounts.uncleaned_roots.lock().unwrap().len(),
+            num_roots as usize + 1 - CLEAN_BATCH_ROOTS
+        );
+        accounts.clean_accounts();
+        accounts.clean_accounts();
+        assert!(accounts.uncleaned_roots.lock().unwrap().is_empty());
+        let forks: Vec<Fork> = accounts
+            .accounts_index
+            .read()
+            .unwrap()
+            .get_entries(&pubkeys[0])
+            .iter()
+            .map(|(fork, _)| *fork)
+            .collect();
+        assert_eq!(forks, vec![num_roots]);
+    }
+
     #[test]
     fn test_lazy_gc_fork() {
         //This test is pedantic
@@ -1247,13 +2148,96 @@ mod tests {
 
         let mut reader = BufReader::new(&buf[..]);
         let daccounts = AccountsDB::new(&paths.paths);
-        assert!(daccounts.update_from_stream(&mut reader).is_ok());
+        assert!(daccounts
+            .update_from_stream(&get_paths_vec(&paths.paths), &mut reader)
+            .is_ok());
         assert_eq!(
             daccounts.write_version.load(Ordering::Relaxed),
             accounts.write_version.load(Ordering::Relaxed)
         );
         check_accounts(&daccounts, &pubkeys, 0, 100, 2);
         check_accounts(&daccounts, &pubkeys1, 1, 10, 1);
+        assert!(accounts.accounts_hash().is_some());
+        assert_eq!(daccounts.accounts_hash(), accounts.accounts_hash());
+        assert!(daccounts.verify_accounts_hash());
+    }
+
+    #[test]
+    fn test_accounts_db_serialize_accounts_hash_mismatch() {
+        let paths = get_tmp_accounts_path!();
+        let accounts = AccountsDB::new(&paths.paths);
+        let mut pubkeys: Vec<Pubkey> = vec![];
+        create_account(&accounts, &mut pubkeys, 0, 10, 0, 0);
+        accounts.add_root(0);
+        *accounts.accounts_hash.write().unwrap() = Some((0, Hash::default()));
+
+        let mut buf = vec![0u8; serialized_size(&accounts).unwrap() as usize];
+        let mut writer = Cursor::new(&mut buf[..]);
+        serialize_into(&mut writer, &accounts).unwrap();
+
+        let mut reader = BufReader::new(&buf[..]);
+        let daccounts = AccountsDB::new(&paths.paths);
+        assert!(daccounts
+            .update_from_stream(&get_paths_vec(&paths.paths), &mut reader)
+            .is_err());
+    }
+
+    #[test]
+    fn test_accounts_hash() {
+        let paths = get_tmp_accounts_path!();
+        let accounts = AccountsDB::new(&paths.paths);
+        assert_eq!(accounts.accounts_hash(), None);
+        assert!(accounts.verify_accounts_hash());
+
+        let pubkey0 = Pubkey::new_rand();
+        let pubkey1 = Pubkey::new_rand();
+        let account = Account::new(1, 0, &Account::default().owner);
+        accounts.store(
+            0,
+            &hashmap!(&pubkey0 => (&account, 0), &pubkey1 => (&account, 0)),
+        );
+        accounts.add_root(0);
+        let (fork, hash0) = accounts.accounts_hash().unwrap();
+        assert_eq!(fork, 0);
+        assert!(accounts.verify_accounts_hash());
+
+        // Unrooted forks don't change the hash
+        let account1 = Account::new(2, 0, &Account::default().owner);
+        accounts.store(1, &hashmap!(&pubkey1 => (&account1, 0)));
+        assert_eq!(accounts.accounts_hash(), Some((0, hash0)));
+        accounts.add_root(1);
+        let (fork, hash1) = accounts.accounts_hash().unwrap();
+        assert_eq!(fork, 1);
+        assert_ne!(hash1, hash0);
+        assert!(accounts.verify_accounts_hash());
+
+        // Accounts without lamports drop out of the hash
+        let zero_account = Account::new(0, 0, &Account::default().owner);
+        accounts.store(2, &hashmap!(&pubkey0 => (&zero_account, 0)));
+        accounts.add_root(2);
+        assert!(accounts.verify_accounts_hash());
+
+        // The same account set hashes the same however it was reached
+        let paths = get_tmp_accounts_path!();
+        let other = AccountsDB::new(&paths.paths);
+        other.store(5, &hashmap!(&pubkey1 => (&account1, 0)));
+        other.add_root(5);
+        assert_eq!(
+            other.accounts_hash().unwrap().1,
+            accounts.accounts_hash().unwrap().1
+        );
+
+        // A fork rooted in the index, but not yet folded into the accounts hash, as when it
+        // is rooted during verification, is left out of it
+        let account3 = Account::new(3, 0, &Account::default().owner);
+        accounts.store(3, &hashmap!(&pubkey1 => (&account3, 0)));
+        accounts.accounts_index.write().unwrap().add_root(3);
+        assert_eq!(accounts.accounts_hash().unwrap().0, 2);
+        assert!(accounts.verify_accounts_hash());
+
+        // A stale accounts hash is caught
+        *accounts.accounts_hash.write().unwrap() = Some((2, hash0));
+        assert!(!accounts.verify_accounts_hash());
     }
 
     #[test]
@@ -0,0 +1,119 @@
+//! The `accounts_hash` module maintains a Merkle root over the latest rooted version of every
+//! account.  Accounts are spread by pubkey across a fixed number of buckets, each with its own
+//! Merkle tree, and the root is taken over the roots of the buckets, so rooting a fork only
+//! rebuilds the trees of the buckets its accounts fall in rather than one over every account.
+
+use solana_merkle_tree::MerkleTree;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Number of buckets the accounts are spread across.  Each bucket holds the accounts whose
+/// pubkeys share their leading 12 bits
+const NUM_BUCKETS: usize = 4096;
+
+#[derive(Debug)]
+pub struct AccountsHashTree {
+    buckets: Vec<BTreeMap<Pubkey, Hash>>,
+    /// The Merkle root of each bucket, as of the last time the tree was hashed
+    bucket_roots: Vec<Hash>,
+    /// Buckets changed since the tree was last hashed
+    dirty: BTreeSet<usize>,
+}
+
+impl Default for AccountsHashTree {
+    fn default() -> Self {
+        Self {
+            buckets: vec![BTreeMap::new(); NUM_BUCKETS],
+            bucket_roots: vec![Hash::default(); NUM_BUCKETS],
+            dirty: BTreeSet::new(),
+        }
+    }
+}
+
+impl AccountsHashTree {
+    fn bucket(pubkey: &Pubkey) -> usize {
+        let bytes = pubkey.as_ref();
+        ((bytes[0] as usize) << 8 | bytes[1] as usize) >> 4
+    }
+
+    /// Set the hash of the latest rooted version of the account at `pubkey`
+    pub fn insert(&mut self, pubkey: Pubkey, hash: Hash) {
+        let bucket = Self::bucket(&pubkey);
+        if self.buckets[bucket].insert(pubkey, hash) != Some(hash) {
+            self.dirty.insert(bucket);
+        }
+    }
+
+    /// Forget the account at `pubkey`, once its latest rooted version holds no lamports
+    pub fn remove(&mut self, pubkey: &Pubkey) {
+        let bucket = Self::bucket(pubkey);
+        if self.buckets[bucket].remove(pubkey).is_some() {
+            self.dirty.insert(bucket);
+        }
+    }
+
+    /// The Merkle root over `hashes`, or the default hash if there are none
+    fn merkle_root<'a, I: Iterator<Item = &'a Hash>>(hashes: I) -> Hash {
+        let leaves: Vec<&[u8]> = hashes.map(AsRef::as_ref).collect();
+        MerkleTree::new(&leaves)
+            .get_root()
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// The Merkle root over the roots of the buckets holding accounts, rebuilding the trees
+    /// of the buckets changed since it was last asked for
+    pub fn root(&mut self) -> Hash {
+        for bucket in std::mem::replace(&mut self.dirty, BTreeSet::new()) {
+            self.bucket_roots[bucket] = Self::merkle_root(self.buckets[bucket].values());
+        }
+        Self::merkle_root(
+            self.bucket_roots
+                .iter()
+                .zip(&self.buckets)
+                .filter(|(_, accounts)| !accounts.is_empty())
+                .map(|(bucket_root, _)| bucket_root),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::hash::hash;
+
+    #[test]
+    fn test_accounts_hash_tree() {
+        let mut tree = AccountsHashTree::default();
+        assert_eq!(tree.root(), Hash::default());
+
+        let accounts: Vec<_> = (0..100u8)
+            .map(|i| (Pubkey::new_rand(), hash(&[i])))
+            .collect();
+        for (pubkey, hash) in &accounts {
+            tree.insert(*pubkey, *hash);
+        }
+        let root = tree.root();
+        assert_ne!(root, Hash::default());
+
+        // The root only depends on the accounts, not the order they were inserted in or
+        // how often it was asked for along the way
+        let mut other = AccountsHashTree::default();
+        for (pubkey, hash) in accounts.iter().rev() {
+            other.insert(*pubkey, *hash);
+            other.root();
+        }
+        assert_eq!(other.root(), root);
+
+        tree.insert(accounts[0].0, hash(&[0xff]));
+        assert_ne!(tree.root(), root);
+        tree.insert(accounts[0].0, accounts[0].1);
+        assert_eq!(tree.root(), root);
+
+        for (pubkey, _) in &accounts {
+            tree.remove(pubkey);
+        }
+        assert_eq!(tree.root(), Hash::default());
+    }
+}
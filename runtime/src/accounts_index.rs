@@ -4,13 +4,39 @@ use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
 use std::collections;
 use std::collections::HashSet;
+use std::sync::RwLock;
 
 pub type Fork = u64;
 
-#[derive(Debug, Default, Deserialize, Serialize)]
+/// Number of locks the accounts of the index are spread across
+pub const NUM_SHARDS: usize = 16;
+
+/// Number of copies of an account held in storage, whether or not the index still points
+/// at them
+pub type RefCount = u64;
+
+pub type AccountMap<T> = HashMap<Pubkey, (Vec<(Fork, T)>, RefCount)>;
+
+fn new_shards<T>() -> Vec<RwLock<AccountMap<T>>> {
+    (0..NUM_SHARDS)
+        .map(|_| RwLock::new(HashMap::new()))
+        .collect()
+}
+
+/// Tracks the versions of each account by fork.  Accounts are sharded by pubkey across
+/// several locks so that threads storing different accounts rarely contend; the roots are
+/// only changed through `&mut self`, so callers holding the index behind a `RwLock` take the
+/// write lock just for root updates.
+#[derive(Debug, Deserialize, Serialize)]
 pub struct AccountsIndex<T> {
+    #[serde(skip, default = "new_shards")]
+    shards: Vec<RwLock<AccountMap<T>>>,
+
+    /// Secondary index from an owner to the accounts stored with that owner in any fork, if
+    /// enabled.  It may name accounts that have since changed owner, so readers must check
+    /// the owner of the version they load
     #[serde(skip)]
-    pub account_maps: HashMap<Pubkey, Vec<(Fork, T)>>,
+    program_accounts: Option<RwLock<HashMap<Pubkey, HashSet<Pubkey>>>>,
 
     pub roots: HashSet<Fork>,
 
@@ -19,6 +45,31 @@ pub struct AccountsIndex<T> {
     pub last_root: Fork,
 }
 
+impl<T> Default for AccountsIndex<T> {
+    fn default() -> Self {
+        Self::new(true)
+    }
+}
+
+impl<T> AccountsIndex<T> {
+    pub fn new(with_program_accounts: bool) -> Self {
+        Self {
+            shards: new_shards(),
+            program_accounts: if with_program_accounts {
+                Some(RwLock::new(HashMap::new()))
+            } else {
+                None
+            },
+            roots: HashSet::new(),
+            last_root: 0,
+        }
+    }
+
+    fn shard(&self, pubkey: &Pubkey) -> &RwLock<AccountMap<T>> {
+        &self.shards[pubkey.as_ref()[0] as usize % NUM_SHARDS]
+    }
+}
+
 impl<T: Clone> AccountsIndex<T> {
     /// Get an account
     /// The latest account that appears in `ancestors` or `roots` is returned.
@@ -26,8 +77,93 @@ impl<T: Clone> AccountsIndex<T> {
         &self,
         pubkey: &Pubkey,
         ancestors: &collections::HashMap<Fork, usize>,
-    ) -> Option<(&T, Fork)> {
-        let list = self.account_maps.get(pubkey)?;
+    ) -> Option<(T, Fork)> {
+        let shard = self.shard(pubkey).read().unwrap();
+        let (list, _) = shard.get(pubkey)?;
+        self.latest_fork(ancestors, list)
+            .map(|(info, fork)| (info.clone(), fork))
+    }
+
+    /// The version of an account stored in exactly `fork`
+    pub fn get_fork(&self, pubkey: &Pubkey, fork: Fork) -> Option<T> {
+        let shard = self.shard(pubkey).read().unwrap();
+        shard
+            .get(pubkey)?
+            .0
+            .iter()
+            .find(|(entry_fork, _)| *entry_fork == fork)
+            .map(|(_, info)| info.clone())
+    }
+
+    /// Every version of an account, oldest fork first
+    pub fn get_entries(&self, pubkey: &Pubkey) -> Vec<(Fork, T)> {
+        self.shard(pubkey)
+            .read()
+            .unwrap()
+            .get(pubkey)
+            .map(|(list, _)| list.clone())
+            .unwrap_or_default()
+    }
+
+    /// The number of copies of an account held in storage
+    pub fn ref_count(&self, pubkey: &Pubkey) -> RefCount {
+        self.shard(pubkey)
+            .read()
+            .unwrap()
+            .get(pubkey)
+            .map_or(0, |(_, ref_count)| *ref_count)
+    }
+
+    /// Call `func` with the latest version of every account that appears in
+    /// `ancestors` or `roots`.
+    pub fn scan_accounts<F>(&self, ancestors: &collections::HashMap<Fork, usize>, mut func: F)
+    where
+        F: FnMut(&Pubkey, (&T, Fork)) -> (),
+    {
+        for shard in 0..NUM_SHARDS {
+            self.scan_shard(shard, ancestors, &mut func);
+        }
+    }
+
+    /// Call `func` with the latest version of every account of the `shard`th of the
+    /// `NUM_SHARDS` shards that appears in `ancestors` or `roots`
+    pub fn scan_shard<F>(
+        &self,
+        shard: usize,
+        ancestors: &collections::HashMap<Fork, usize>,
+        mut func: F,
+    ) where
+        F: FnMut(&Pubkey, (&T, Fork)) -> (),
+    {
+        for (pubkey, (list, _)) in self.shards[shard].read().unwrap().iter() {
+            if let Some(fork_info) = self.latest_fork(ancestors, list) {
+                func(pubkey, fork_info);
+            }
+        }
+    }
+
+    /// Call `func` with the latest version of every account of the `shard`th of the
+    /// `NUM_SHARDS` shards that is stored in a root no newer than `max_root`
+    pub fn scan_shard_rooted<F>(&self, shard: usize, max_root: Fork, mut func: F)
+    where
+        F: FnMut(&Pubkey, (&T, Fork)) -> (),
+    {
+        for (pubkey, (list, _)) in self.shards[shard].read().unwrap().iter() {
+            if let Some((fork, info)) = list
+                .iter()
+                .filter(|(fork, _)| *fork <= max_root && self.is_root(*fork))
+                .max_by_key(|(fork, _)| *fork)
+            {
+                func(pubkey, (info, *fork));
+            }
+        }
+    }
+
+    pub fn latest_fork<'a>(
+        &self,
+        ancestors: &collections::HashMap<Fork, usize>,
+        list: &'a [(Fork, T)],
+    ) -> Option<(&'a T, Fork)> {
         let mut max = 0;
         let mut rv = None;
         for e in list.iter().rev() {
@@ -51,17 +187,17 @@ impl<T: Clone> AccountsIndex<T> {
     }
 
     pub fn insert(
-        &mut self,
+        &self,
         fork: Fork,
         pubkey: &Pubkey,
         account_info: T,
         reclaims: &mut Vec<(Fork, T)>,
     ) {
-        let roots = &self.roots;
-        let fork_vec = self
-            .account_maps
+        let mut shard = self.shard(pubkey).write().unwrap();
+        let (fork_vec, ref_count) = shard
             .entry(*pubkey)
-            .or_insert_with(|| (Vec::with_capacity(32)));
+            .or_insert_with(|| (Vec::with_capacity(32), 0));
+        *ref_count += 1;
 
         // filter out old entries
         reclaims.extend(fork_vec.iter().filter(|(f, _)| *f == fork).cloned());
@@ -70,7 +206,7 @@ impl<T: Clone> AccountsIndex<T> {
         // add the new entry
         fork_vec.push((fork, account_info));
 
-        let max_root = Self::get_max_root(roots, fork_vec);
+        let max_root = Self::get_max_root(&self.roots, fork_vec);
 
         reclaims.extend(
             fork_vec
@@ -81,9 +217,148 @@ impl<T: Clone> AccountsIndex<T> {
         fork_vec.retain(|(fork, _)| !Self::can_purge(max_root, *fork));
     }
 
-    pub fn add_index(&mut self, fork: Fork, pubkey: &Pubkey, account_info: T) {
-        let entry = self.account_maps.entry(*pubkey).or_insert_with(|| vec![]);
-        entry.push((fork, account_info));
+    /// Call `func` with every version of an account, so it may update them in place
+    pub fn update<F, R>(&self, pubkey: &Pubkey, func: F) -> Option<R>
+    where
+        F: FnOnce(&mut Vec<(Fork, T)>) -> R,
+    {
+        let mut shard = self.shard(pubkey).write().unwrap();
+        shard.get_mut(pubkey).map(|(list, _)| func(list))
+    }
+
+    /// Remove the only version of an account if `predicate` holds for it and storage holds
+    /// no other copy of the account, returning the removed version so its storage can be
+    /// reclaimed.  Any older copy would otherwise be found again when the index is rebuilt
+    /// from storage
+    pub fn remove_last_version<F>(&self, pubkey: &Pubkey, predicate: F) -> Option<(Fork, T)>
+    where
+        F: FnOnce(&(Fork, T)) -> bool,
+    {
+        let mut shard = self.shard(pubkey).write().unwrap();
+        let (list, ref_count) = shard.get_mut(pubkey)?;
+        if list.len() != 1 || *ref_count != 1 || !predicate(&list[0]) {
+            return None;
+        }
+        // The entry stays until the removed version is dropped from storage
+        list.pop()
+    }
+
+    /// Record new copies of each of `pubkeys` in storage
+    pub fn add_refs(&self, pubkeys: &[Pubkey]) {
+        for pubkey in pubkeys {
+            let mut shard = self.shard(pubkey).write().unwrap();
+            shard.entry(*pubkey).or_insert_with(|| (vec![], 0)).1 += 1;
+        }
+    }
+
+    /// Record that a copy of each of `pubkeys` was dropped from storage, forgetting the
+    /// accounts left with neither versions nor copies
+    pub fn remove_refs(&self, pubkeys: &[Pubkey]) {
+        for pubkey in pubkeys {
+            let mut shard = self.shard(pubkey).write().unwrap();
+            let is_dropped = shard.get_mut(pubkey).map_or(false, |(list, ref_count)| {
+                *ref_count = ref_count.saturating_sub(1);
+                list.is_empty() && *ref_count == 0
+            });
+            if is_dropped {
+                shard.remove(pubkey);
+            }
+        }
+    }
+
+    /// Remove the entries of `pubkeys` that are shadowed by a newer rooted entry of the same
+    /// account, returning the removed entries so their storage can be reclaimed
+    pub fn purge_shadowed_roots(&self, pubkeys: &[Pubkey]) -> Vec<(Fork, T)> {
+        let roots = &self.roots;
+        let mut reclaims = vec![];
+        for pubkey in pubkeys {
+            if let Some((fork_vec, _)) = self.shard(pubkey).write().unwrap().get_mut(pubkey) {
+                let max_root = Self::get_max_root(roots, fork_vec);
+                reclaims.extend(
+                    fork_vec
+                        .iter()
+                        .filter(|(fork, _)| Self::can_purge(max_root, *fork))
+                        .cloned(),
+                );
+                fork_vec.retain(|(fork, _)| !Self::can_purge(max_root, *fork));
+            }
+        }
+        reclaims
+    }
+
+    /// Add the version of an account found in `fork` of storage, which holds `ref_count`
+    /// copies of the account in that fork
+    pub fn add_index(&self, fork: Fork, pubkey: &Pubkey, account_info: T, ref_count: RefCount) {
+        let mut shard = self.shard(pubkey).write().unwrap();
+        let (list, entry_ref_count) = shard.entry(*pubkey).or_insert_with(|| (vec![], 0));
+        list.push((fork, account_info));
+        *entry_ref_count += ref_count;
+    }
+
+    pub fn has_program_accounts(&self) -> bool {
+        self.program_accounts.is_some()
+    }
+
+    /// Record that `pubkey` was stored with `owner`
+    pub fn add_program_accounts<'a, I>(&self, accounts: I)
+    where
+        I: Iterator<Item = (&'a Pubkey, &'a Pubkey)> + Clone,
+    {
+        if let Some(program_accounts) = &self.program_accounts {
+            // Most stores update accounts the index already knows about
+            let is_known = {
+                let program_accounts = program_accounts.read().unwrap();
+                accounts.clone().all(|(owner, pubkey)| {
+                    program_accounts
+                        .get(owner)
+                        .map_or(false, |pubkeys| pubkeys.contains(pubkey))
+                })
+            };
+            if !is_known {
+                let mut program_accounts = program_accounts.write().unwrap();
+                for (owner, pubkey) in accounts {
+                    program_accounts
+                        .entry(*owner)
+                        .or_insert_with(HashSet::new)
+                        .insert(*pubkey);
+                }
+            }
+        }
+    }
+
+    /// Forget each of `accounts`, (owner, pubkey) pairs, for which `is_stale` holds.  The
+    /// secondary index stays locked while `is_stale` runs, so it may safely consult the
+    /// versions of the account
+    pub fn remove_program_accounts<F>(&self, accounts: &[(Pubkey, Pubkey)], is_stale: F)
+    where
+        F: Fn(&Pubkey, &Pubkey) -> bool,
+    {
+        if let Some(program_accounts) = &self.program_accounts {
+            let mut program_accounts = program_accounts.write().unwrap();
+            for (owner, pubkey) in accounts {
+                if !is_stale(owner, pubkey) {
+                    continue;
+                }
+                if let Some(pubkeys) = program_accounts.get_mut(owner) {
+                    pubkeys.remove(pubkey);
+                    if pubkeys.is_empty() {
+                        program_accounts.remove(owner);
+                    }
+                }
+            }
+        }
+    }
+
+    /// The accounts that have been stored with `owner`, or `None` if the secondary index
+    /// is disabled
+    pub fn program_accounts(&self, owner: &Pubkey) -> Option<Vec<Pubkey>> {
+        self.program_accounts.as_ref().map(|program_accounts| {
+            program_accounts
+                .read()
+                .unwrap()
+                .get(owner)
+                .map_or(vec![], |pubkeys| pubkeys.iter().cloned().collect())
+        })
     }
 
     pub fn is_purged(&self, fork: Fork) -> bool {
@@ -129,7 +404,7 @@ mod tests {
     #[test]
     fn test_insert_no_ancestors() {
         let key = Keypair::new();
-        let mut index = AccountsIndex::<bool>::default();
+        let index = AccountsIndex::<bool>::default();
         let mut gc = Vec::new();
         index.insert(0, &key.pubkey(), true, &mut gc);
         assert!(gc.is_empty());
@@ -141,7 +416,7 @@ mod tests {
     #[test]
     fn test_insert_wrong_ancestors() {
         let key = Keypair::new();
-        let mut index = AccountsIndex::<bool>::default();
+        let index = AccountsIndex::<bool>::default();
         let mut gc = Vec::new();
         index.insert(0, &key.pubkey(), true, &mut gc);
         assert!(gc.is_empty());
@@ -153,13 +428,37 @@ mod tests {
     #[test]
     fn test_insert_with_ancestors() {
         let key = Keypair::new();
-        let mut index = AccountsIndex::<bool>::default();
+        let index = AccountsIndex::<bool>::default();
         let mut gc = Vec::new();
         index.insert(0, &key.pubkey(), true, &mut gc);
         assert!(gc.is_empty());
 
         let ancestors = vec![(0, 0)].into_iter().collect();
-        assert_eq!(index.get(&key.pubkey(), &ancestors), Some((&true, 0)));
+        assert_eq!(index.get(&key.pubkey(), &ancestors), Some((true, 0)));
+    }
+
+    #[test]
+    fn test_scan_accounts_with_ancestors() {
+        let key = Keypair::new();
+        let index = AccountsIndex::<bool>::default();
+        let mut gc = Vec::new();
+        index.insert(0, &key.pubkey(), true, &mut gc);
+        index.insert(1, &key.pubkey(), false, &mut gc);
+        assert!(gc.is_empty());
+
+        let ancestors = vec![(2, 0)].into_iter().collect();
+        let mut found = vec![];
+        index.scan_accounts(&ancestors, |pubkey, (info, fork)| {
+            found.push((*pubkey, *info, fork))
+        });
+        assert!(found.is_empty());
+
+        let ancestors = vec![(0, 1), (1, 0)].into_iter().collect();
+        let mut found = vec![];
+        index.scan_accounts(&ancestors, |pubkey, (info, fork)| {
+            found.push((*pubkey, *info, fork))
+        });
+        assert_eq!(found, vec![(key.pubkey(), false, 1)]);
     }
 
     #[test]
@@ -180,7 +479,7 @@ mod tests {
 
         let ancestors = vec![].into_iter().collect();
         index.add_root(0);
-        assert_eq!(index.get(&key.pubkey(), &ancestors), Some((&true, 0)));
+        assert_eq!(index.get(&key.pubkey(), &ancestors), Some((true, 0)));
     }
 
     #[test]
@@ -230,32 +529,89 @@ mod tests {
     #[test]
     fn test_update_last_wins() {
         let key = Keypair::new();
-        let mut index = AccountsIndex::<bool>::default();
+        let index = AccountsIndex::<bool>::default();
         let ancestors = vec![(0, 0)].into_iter().collect();
         let mut gc = Vec::new();
         index.insert(0, &key.pubkey(), true, &mut gc);
         assert!(gc.is_empty());
-        assert_eq!(index.get(&key.pubkey(), &ancestors), Some((&true, 0)));
+        assert_eq!(index.get(&key.pubkey(), &ancestors), Some((true, 0)));
 
         let mut gc = Vec::new();
         index.insert(0, &key.pubkey(), false, &mut gc);
         assert_eq!(gc, vec![(0, true)]);
-        assert_eq!(index.get(&key.pubkey(), &ancestors), Some((&false, 0)));
+        assert_eq!(index.get(&key.pubkey(), &ancestors), Some((false, 0)));
     }
 
     #[test]
     fn test_update_new_fork() {
         let key = Keypair::new();
-        let mut index = AccountsIndex::<bool>::default();
+        let index = AccountsIndex::<bool>::default();
         let ancestors = vec![(0, 0)].into_iter().collect();
         let mut gc = Vec::new();
         index.insert(0, &key.pubkey(), true, &mut gc);
         assert!(gc.is_empty());
         index.insert(1, &key.pubkey(), false, &mut gc);
         assert!(gc.is_empty());
-        assert_eq!(index.get(&key.pubkey(), &ancestors), Some((&true, 0)));
+        assert_eq!(index.get(&key.pubkey(), &ancestors), Some((true, 0)));
         let ancestors = vec![(1, 0)].into_iter().collect();
-        assert_eq!(index.get(&key.pubkey(), &ancestors), Some((&false, 1)));
+        assert_eq!(index.get(&key.pubkey(), &ancestors), Some((false, 1)));
+    }
+
+    #[test]
+    fn test_purge_shadowed_roots() {
+        let key = Keypair::new();
+        let other_key = Keypair::new();
+        let mut index = AccountsIndex::<bool>::default();
+        let mut gc = Vec::new();
+        index.insert(0, &key.pubkey(), true, &mut gc);
+        index.insert(1, &key.pubkey(), false, &mut gc);
+        index.insert(2, &key.pubkey(), true, &mut gc);
+        index.insert(0, &other_key.pubkey(), true, &mut gc);
+        assert!(gc.is_empty());
+
+        let pubkeys = vec![key.pubkey(), other_key.pubkey()];
+
+        // Nothing is shadowed until a newer version is rooted
+        index.add_root(0);
+        assert!(index.purge_shadowed_roots(&pubkeys).is_empty());
+
+        // Only the accounts asked for are purged
+        index.add_root(1);
+        assert!(index.purge_shadowed_roots(&pubkeys[1..]).is_empty());
+        assert_eq!(index.purge_shadowed_roots(&pubkeys), vec![(0, true)]);
+        assert_eq!(
+            index.get_entries(&key.pubkey()),
+            vec![(1, false), (2, true)]
+        );
+        assert_eq!(index.get_entries(&other_key.pubkey()), vec![(0, true)]);
+    }
+
+    #[test]
+    fn test_remove_last_version() {
+        let key = Keypair::new();
+        let mut index = AccountsIndex::<bool>::default();
+        let mut gc = Vec::new();
+        index.insert(0, &key.pubkey(), true, &mut gc);
+        index.insert(1, &key.pubkey(), false, &mut gc);
+        assert_eq!(index.ref_count(&key.pubkey()), 2);
+        index.add_root(0);
+        index.add_root(1);
+        assert_eq!(index.purge_shadowed_roots(&[key.pubkey()]), vec![(0, true)]);
+
+        // The copy stored in fork 0 is still around
+        assert_eq!(index.remove_last_version(&key.pubkey(), |_| true), None);
+        index.remove_refs(&[key.pubkey()]);
+        assert_eq!(index.remove_last_version(&key.pubkey(), |_| false), None);
+        assert_eq!(
+            index.remove_last_version(&key.pubkey(), |(_, info)| !info),
+            Some((1, false))
+        );
+        assert!(index.get_entries(&key.pubkey()).is_empty());
+        assert_eq!(index.ref_count(&key.pubkey()), 1);
+
+        index.remove_refs(&[key.pubkey()]);
+        assert_eq!(index.ref_count(&key.pubkey()), 0);
+        assert_eq!(index.remove_last_version(&key.pubkey(), |_| true), None);
     }
 
     #[test]
@@ -274,6 +630,57 @@ mod tests {
         index.insert(4, &key.pubkey(), true, &mut gc);
         assert_eq!(gc, vec![(0, true), (1, false), (2, true)]);
         let ancestors = vec![].into_iter().collect();
-        assert_eq!(index.get(&key.pubkey(), &ancestors), Some((&true, 3)));
+        assert_eq!(index.get(&key.pubkey(), &ancestors), Some((true, 3)));
+    }
+
+    #[test]
+    fn test_program_accounts() {
+        let owner = Pubkey::new_rand();
+        let key0 = Pubkey::new_rand();
+        let key1 = Pubkey::new_rand();
+        let index = AccountsIndex::<bool>::default();
+        assert!(index.has_program_accounts());
+        assert_eq!(index.program_accounts(&owner), Some(vec![]));
+
+        index.add_program_accounts(vec![(&owner, &key0), (&owner, &key1)].into_iter());
+        let mut pubkeys = index.program_accounts(&owner).unwrap();
+        pubkeys.sort();
+        let mut expected = vec![key0, key1];
+        expected.sort();
+        assert_eq!(pubkeys, expected);
+
+        // Only the stale pairs are forgotten
+        index.remove_program_accounts(&[(owner, key0), (owner, key1)], |_, pubkey| *pubkey == key0);
+        assert_eq!(index.program_accounts(&owner), Some(vec![key1]));
+        index.remove_program_accounts(&[(owner, key1)], |_, _| true);
+        assert_eq!(index.program_accounts(&owner), Some(vec![]));
+    }
+
+    #[test]
+    fn test_program_accounts_disabled() {
+        let owner = Pubkey::new_rand();
+        let key = Pubkey::new_rand();
+        let index = AccountsIndex::<bool>::new(false);
+        assert!(!index.has_program_accounts());
+        index.add_program_accounts(vec![(&owner, &key)].into_iter());
+        assert_eq!(index.program_accounts(&owner), None);
+    }
+
+    #[test]
+    fn test_insert_across_shards() {
+        let index = AccountsIndex::<bool>::default();
+        let keys: Vec<_> = (0..=255u8).map(|i| Pubkey::new(&[i; 32])).collect();
+        let mut gc = Vec::new();
+        for key in &keys {
+            index.insert(0, key, true, &mut gc);
+        }
+        assert!(gc.is_empty());
+        let ancestors = vec![(0, 0)].into_iter().collect();
+        for key in &keys {
+            assert_eq!(index.get(key, &ancestors), Some((true, 0)));
+        }
+        let mut num = 0;
+        index.scan_accounts(&ancestors, |_pubkey, _index| num += 1);
+        assert_eq!(num, keys.len());
     }
 }
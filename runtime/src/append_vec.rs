@@ -1,5 +1,4 @@
 use bincode::{deserialize_from, serialize_into, serialized_size};
-use lazy_static::lazy_static;
 use log::warn;
 use memmap::MmapMut;
 use serde::{Deserialize, Serialize};
@@ -39,6 +38,8 @@ pub struct AccountBalance {
     pub owner: Pubkey,
     /// this account's data contains a loaded program (and is now read-only)
     pub executable: bool,
+    /// the epoch through which this account has paid rent
+    pub rent_epoch: u64,
 }
 
 /// References to Memory Mapped memory
@@ -58,15 +59,12 @@ impl<'a> StoredAccount<'a> {
             lamports: self.balance.lamports,
             owner: self.balance.owner,
             executable: self.balance.executable,
+            rent_epoch: self.balance.rent_epoch,
             data: self.data.to_vec(),
         }
     }
 }
 
-lazy_static! {
-    static ref ACCOUNT_PATHS: Mutex<Vec<String>> = Mutex::new(vec![]);
-}
-
 #[derive(Debug)]
 #[allow(clippy::mutex_atomic)]
 pub struct AppendVec {
@@ -133,6 +131,10 @@ impl AppendVec {
         self.file_size
     }
 
+    pub fn get_path(&self) -> PathBuf {
+        self.path.clone()
+    }
+
     fn get_slice(&self, offset: usize, size: usize) -> Option<(&[u8], usize)> {
         let len = self.len();
         if len < offset + size {
@@ -235,6 +237,7 @@ impl AppendVec {
                 lamports: *lamports,
                 owner: account.owner,
                 executable: account.executable,
+                rent_epoch: account.rent_epoch,
             };
             let balance_ptr = &balance as *const AccountBalance;
             let data_len = storage_meta.data_len as usize;
@@ -263,11 +266,64 @@ impl AppendVec {
         self.append_account(data.0.clone(), &data.1)
     }
 
-    pub fn set_account_paths(paths: &[String]) {
-        ACCOUNT_PATHS.lock().unwrap().extend_from_slice(paths);
+    /// Open the data file of a deserialized AppendVec.  The file is looked up in
+    /// `account_paths` by the storage directory it was serialized with, falling back to the
+    /// serialized path itself when no account paths are given
+    #[allow(clippy::mutex_atomic)]
+    pub fn open(serialized: SerializedAppendVec, account_paths: &[String]) -> Self {
+        let SerializedAppendVec {
+            path,
+            current_len,
+            file_size,
+            offset,
+        } = serialized;
+
+        let split_path: Vec<&str> = path.to_str().unwrap().rsplit('/').collect();
+        let mut account_path = path.clone();
+        if split_path.len() >= 2 {
+            for dir_path in account_paths.iter() {
+                let fullpath = format!("{}/{}/{}", dir_path, split_path[1], split_path[0]);
+                let file_path = Path::new(&fullpath);
+                account_path = file_path.to_path_buf();
+                if file_path.exists() {
+                    break;
+                }
+            }
+        }
+
+        let data = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(false)
+            .open(account_path.as_path());
+
+        if data.is_err() {
+            warn!("account open {:?} failed", account_path);
+            std::fs::create_dir_all(&account_path.parent().unwrap())
+                .expect("Create directory failed");
+            return AppendVec::new(&account_path, true, file_size as usize);
+        }
+
+        let map = unsafe { MmapMut::map_mut(&data.unwrap()).expect("failed to map the data file") };
+        AppendVec {
+            path: account_path,
+            map,
+            append_offset: Mutex::new(offset),
+            current_len: AtomicUsize::new(current_len as usize),
+            file_size,
+        }
     }
 }
 
+/// An AppendVec as it is serialized, before its data file is opened with `AppendVec::open`
+#[derive(Debug)]
+pub struct SerializedAppendVec {
+    path: PathBuf,
+    current_len: u64,
+    file_size: u64,
+    offset: usize,
+}
+
 pub mod test_utils {
     use super::StorageMeta;
     use rand::distributions::Alphanumeric;
@@ -339,13 +395,12 @@ impl Serialize for AppendVec {
 struct AppendVecVisitor;
 
 impl<'a> serde::de::Visitor<'a> for AppendVecVisitor {
-    type Value = AppendVec;
+    type Value = SerializedAppendVec;
 
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         formatter.write_str("Expecting AppendVec")
     }
 
-    #[allow(clippy::mutex_atomic)]
     fn visit_bytes<E>(self, data: &[u8]) -> std::result::Result<Self::Value, E>
     where
         E: serde::de::Error,
@@ -356,46 +411,16 @@ impl<'a> serde::de::Visitor<'a> for AppendVecVisitor {
         let current_len: u64 = deserialize_from(&mut rd).map_err(Error::custom)?;
         let file_size: u64 = deserialize_from(&mut rd).map_err(Error::custom)?;
         let offset: usize = deserialize_from(&mut rd).map_err(Error::custom)?;
-
-        let split_path: Vec<&str> = path.to_str().unwrap().rsplit('/').collect();
-        let account_paths = ACCOUNT_PATHS.lock().unwrap().clone();
-        let mut account_path = path.clone();
-        if split_path.len() >= 2 {
-            for dir_path in account_paths.iter() {
-                let fullpath = format!("{}/{}/{}", dir_path, split_path[1], split_path[0]);
-                let file_path = Path::new(&fullpath);
-                account_path = file_path.to_path_buf();
-                if file_path.exists() {
-                    break;
-                }
-            }
-        }
-
-        let data = OpenOptions::new()
-            .read(true)
-            .write(true)
-            .create(false)
-            .open(account_path.as_path());
-
-        if data.is_err() {
-            warn!("account open {:?} failed", account_path);
-            std::fs::create_dir_all(&account_path.parent().unwrap())
-                .expect("Create directory failed");
-            return Ok(AppendVec::new(&account_path, true, file_size as usize));
-        }
-
-        let map = unsafe { MmapMut::map_mut(&data.unwrap()).expect("failed to map the data file") };
-        Ok(AppendVec {
-            path: account_path,
-            map,
-            append_offset: Mutex::new(offset),
-            current_len: AtomicUsize::new(current_len as usize),
+        Ok(SerializedAppendVec {
+            path,
+            current_len,
             file_size,
+            offset,
         })
     }
 }
 
-impl<'de> Deserialize<'de> for AppendVec {
+impl<'de> Deserialize<'de> for SerializedAppendVec {
     fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
     where
         D: ::serde::Deserializer<'de>,
@@ -404,6 +429,16 @@ impl<'de> Deserialize<'de> for AppendVec {
     }
 }
 
+impl<'de> Deserialize<'de> for AppendVec {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: ::serde::Deserializer<'de>,
+    {
+        SerializedAppendVec::deserialize(deserializer)
+            .map(|serialized| AppendVec::open(serialized, &[]))
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::test_utils::*;
@@ -476,8 +511,8 @@ pub mod tests {
 
     #[test]
     fn test_append_vec_serialize() {
-        let path = Path::new("append_vec_serialize");
-        let av: AppendVec = AppendVec::new(path, true, 1024 * 1024);
+        let path = get_append_vec_path("test_append_vec_serialize");
+        let av: AppendVec = AppendVec::new(&path.path, true, 1024 * 1024);
         let account1 = create_test_account(1);
         let index1 = av.append_account_test(&account1).unwrap();
         assert_eq!(index1, 0);
@@ -497,7 +532,7 @@ pub mod tests {
 
         assert_eq!(dav.get_account_test(index2).unwrap(), account2);
         assert_eq!(dav.get_account_test(index1).unwrap(), account1);
-        std::fs::remove_file(path).unwrap();
+        std::fs::remove_file(&path.path).unwrap();
 
         let mut reader = Cursor::new(&mut buf[..]);
         let dav: AppendVec = deserialize_from(&mut reader).unwrap();
@@ -4,13 +4,15 @@
 //! already been signed and verified.
 use crate::accounts::Accounts;
 use crate::accounts_db::{
-    ErrorCounters, InstructionAccounts, InstructionCredits, InstructionLoaders,
+    AccountsSnapshot, ErrorCounters, InstructionAccounts, InstructionCredits, InstructionLoaders,
+    TransactionRent,
 };
 use crate::accounts_index::Fork;
 use crate::blockhash_queue::BlockhashQueue;
-use crate::epoch_schedule::EpochSchedule;
 use crate::locked_accounts_results::LockedAccountsResults;
 use crate::message_processor::{MessageProcessor, ProcessInstruction};
+use crate::nonce_utils;
+use crate::rent_collector::RentCollector;
 use crate::serde_utils::{
     deserialize_atomicbool, deserialize_atomicusize, serialize_atomicbool, serialize_atomicusize,
 };
@@ -25,21 +27,26 @@ use solana_metrics::{
     datapoint_info, inc_new_counter_debug, inc_new_counter_error, inc_new_counter_info,
 };
 use solana_sdk::account::Account;
+use solana_sdk::account_utils::State;
+use solana_sdk::epoch_schedule::EpochSchedule;
 use solana_sdk::fee_calculator::FeeCalculator;
 use solana_sdk::genesis_block::GenesisBlock;
 use solana_sdk::hash::{extend_and_hash, Hash};
 use solana_sdk::inflation::Inflation;
 use solana_sdk::native_loader;
+use solana_sdk::nonce_state::NonceState;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::{Keypair, Signature};
 use solana_sdk::syscall::{
-    current, fees, rewards,
+    current, epoch_schedule, fees, recent_blockhashes, rent, rewards,
     slot_hashes::{self, SlotHashes},
+    stake_history::{self, StakeHistory},
     tick_height,
 };
 use solana_sdk::system_transaction;
 use solana_sdk::timing::{duration_as_ms, duration_as_ns, duration_as_us, MAX_RECENT_BLOCKHASHES};
 use solana_sdk::transaction::{Result, Transaction, TransactionError};
+use solana_sdk::transaction_status::TransactionStatusMeta;
 use std::cmp;
 use std::collections::HashMap;
 use std::fmt;
@@ -70,13 +77,30 @@ impl BankRc {
         }
     }
 
+    /// Restore the accounts serialized in `stream`, whose storage files are found in the
+    /// comma separated `account_paths`
     pub fn update_from_stream<R: Read>(
         &self,
+        account_paths: &str,
         mut stream: &mut BufReader<R>,
     ) -> std::result::Result<(), std::io::Error> {
         let _len: usize = deserialize_from(&mut stream)
             .map_err(|_| BankRc::get_io_error("len deserialize error"))?;
-        self.accounts.update_from_stream(stream)
+        self.accounts.update_from_stream(account_paths, stream)
+    }
+
+    /// Comma separated list of the directories the accounts are stored in
+    pub fn account_paths(&self) -> String {
+        self.accounts.paths.clone()
+    }
+
+    /// Serialize the accounts as of `snapshot`, in the encoding of the `BankRc` itself
+    pub fn serialize_snapshot(
+        &self,
+        snapshot: &AccountsSnapshot,
+    ) -> std::result::Result<Vec<u8>, std::io::Error> {
+        let accounts_db = self.accounts.accounts_db.serialize_snapshot(snapshot)?;
+        serialize(&accounts_db).map_err(|_| BankRc::get_io_error("serialize accounts error"))
     }
 
     fn get_io_error(error: &str) -> std::io::Error {
@@ -163,6 +187,16 @@ impl StatusCacheRc {
         let sc = status_cache_rc.status_cache.write().unwrap();
         self.status_cache.write().unwrap().append(&sc);
     }
+
+    /// A copy of the status cache as of the root `fork`, without the statuses and roots of
+    /// later forks
+    pub fn clone_as_of(&self, fork: Fork) -> Self {
+        Self {
+            status_cache: Arc::new(RwLock::new(
+                self.status_cache.read().unwrap().clone_as_of(fork),
+            )),
+        }
+    }
 }
 
 /// Manager for the state of all accounts and programs after processing its entries.
@@ -233,6 +267,14 @@ pub struct Bank {
     /// Latest transaction fees for transactions processed by this bank
     fee_calculator: FeeCalculator,
 
+    /// Rent that has been collected
+    #[serde(serialize_with = "serialize_atomicusize")]
+    #[serde(deserialize_with = "deserialize_atomicusize")]
+    collected_rent: AtomicUsize, // TODO: Use AtomicU64 if/when available
+
+    /// Charges accounts loaded by this bank's transactions for their storage
+    rent_collector: RentCollector,
+
     /// initialized from genesis
     epoch_schedule: EpochSchedule,
 
@@ -257,6 +299,15 @@ pub struct Bank {
 
     /// The Message processor
     message_processor: MessageProcessor,
+
+    /// A boolean reflecting whether this bank only simulates transactions, in which
+    /// case its slot is shared with real banks and must not be purged on drop
+    #[serde(skip)]
+    is_simulation: bool,
+
+    /// What was recorded about each transaction committed to this bank, by signature
+    #[serde(skip)]
+    transaction_statuses: RwLock<HashMap<Signature, TransactionStatusMeta>>,
 }
 
 impl Default for BlockhashQueue {
@@ -267,6 +318,12 @@ impl Default for BlockhashQueue {
 
 pub const DUMMY_REPLICATOR_POINTS: u64 = 100;
 
+/// The messages logged while executing a transaction
+pub type TransactionLogMessages = Vec<String>;
+
+/// The lamports held by each of a transaction's account keys
+pub type TransactionBalances = Vec<u64>;
+
 impl Bank {
     pub fn new(genesis_block: &GenesisBlock) -> Self {
         Self::new_with_paths(&genesis_block, None)
@@ -286,6 +343,7 @@ impl Bank {
             }
         }
         bank.update_current();
+        bank.update_stake_history(None);
         bank
     }
 
@@ -341,6 +399,7 @@ impl Bank {
         self.rc.parent = RwLock::new(Some(parent.clone()));
         self.parent_hash = parent.hash();
         self.collector_id = *collector_id;
+        self.rent_collector = parent.rent_collector.clone_with_epoch(self.epoch());
 
         self.rc.accounts = Arc::new(Accounts::new_from_parent(&parent.rc.accounts));
 
@@ -361,11 +420,34 @@ impl Bank {
         });
 
         self.update_rewards(parent.epoch());
+        self.update_stake_history(Some(parent.epoch()));
         self.update_current();
         self.update_fees();
+        self.update_recent_blockhashes();
         self
     }
 
+    /// Create a child of `parent` for executing transactions that are never committed.
+    /// Unlike `new_from_parent`, `parent` is not frozen and nothing is stored on the
+    /// child's behalf.
+    fn new_for_simulation(parent: &Arc<Bank>) -> Self {
+        let mut bank = Self::default();
+        bank.is_simulation = true;
+        bank.ticks_per_slot = parent.ticks_per_slot;
+        bank.slots_per_year = parent.slots_per_year;
+        bank.epoch_schedule = parent.epoch_schedule;
+        bank.slot = parent.slot() + 1;
+        bank.max_tick_height = parent.max_tick_height;
+        bank.blockhash_queue = RwLock::new(parent.blockhash_queue.read().unwrap().clone());
+        bank.src.status_cache = parent.src.status_cache.clone();
+        bank.fee_calculator = parent.fee_calculator.clone();
+        bank.rent_collector = parent.rent_collector.clone_with_epoch(bank.epoch());
+        bank.ancestors = parent.ancestors.clone();
+        bank.rc.parent = RwLock::new(Some(parent.clone()));
+        bank.rc.accounts = Arc::new(Accounts::new_from_parent(&parent.rc.accounts));
+        bank
+    }
+
     pub fn collector_id(&self) -> &Pubkey {
         &self.collector_id
     }
@@ -430,6 +512,53 @@ impl Bank {
         self.store_account(&fees::id(), &fees::create_account(1, &self.fee_calculator));
     }
 
+    fn update_recent_blockhashes(&self) {
+        let recent_blockhashes = self
+            .blockhash_queue
+            .read()
+            .unwrap()
+            .get_recent_blockhashes();
+        self.store_account(
+            &recent_blockhashes::id(),
+            &recent_blockhashes::create_account(1, &recent_blockhashes),
+        );
+    }
+
+    fn update_rent(&self) {
+        self.store_account(
+            &rent::id(),
+            &rent::create_account(1, &self.rent_collector.rent),
+        );
+    }
+
+    fn update_epoch_schedule(&self) {
+        self.store_account(
+            &epoch_schedule::id(),
+            &epoch_schedule::create_account(1, &self.epoch_schedule),
+        );
+    }
+
+    // record the stake activation of the epoch that just ended, if any
+    fn update_stake_history(&self, epoch: Option<u64>) {
+        if epoch == Some(self.epoch()) {
+            return;
+        }
+        let mut stake_history = self
+            .get_account(&stake_history::id())
+            .and_then(|account| StakeHistory::from(&account))
+            .unwrap_or_default();
+        if let Some(epoch) = epoch {
+            stake_history.add(
+                epoch,
+                self.stakes.read().unwrap().stake_history_entry(epoch),
+            );
+        }
+        self.store_account(
+            &stake_history::id(),
+            &stake_history::create_account(1, &stake_history),
+        );
+    }
+
     fn update_tick_height(&self) {
         self.store_account(
             &tick_height::id(),
@@ -481,7 +610,8 @@ impl Bank {
     fn set_hash(&self) -> bool {
         let mut hash = self.hash.write().unwrap();
         if *hash == Hash::default() {
-            let collector_fees = self.collector_fees.load(Ordering::Relaxed) as u64;
+            let collector_fees = self.collector_fees.load(Ordering::Relaxed) as u64
+                + self.collected_rent.load(Ordering::Relaxed) as u64;
             if collector_fees != 0 {
                 self.deposit(&self.collector_id, collector_fees);
             }
@@ -503,6 +633,11 @@ impl Bank {
         &self.epoch_schedule
     }
 
+    /// The balance an account with `data_len` bytes of data needs to be exempt from rent
+    pub fn get_minimum_balance_for_rent_exemption(&self, data_len: usize) -> u64 {
+        self.rent_collector.rent.minimum_balance(data_len)
+    }
+
     /// squash the parent's state up into this Bank,
     ///   this Bank becomes a root
     pub fn squash(&self) {
@@ -531,6 +666,30 @@ impl Bank {
         );
     }
 
+    /// Reclaim the account storage held by roots older than this bank, returning the number
+    /// of bytes reclaimed
+    pub fn clean_accounts(&self) -> usize {
+        self.rc.accounts.clean_accounts()
+    }
+
+    /// Number of roots whose account storage is yet to be cleaned
+    pub fn num_uncleaned_roots(&self) -> usize {
+        self.rc.accounts.num_uncleaned_roots()
+    }
+
+    /// The Merkle root over the latest rooted version of every account, and the slot of the
+    /// root it covers.  Squashing a bank roots its parents, so the hash trails the latest
+    /// squashed bank
+    pub fn get_accounts_hash(&self) -> Option<(u64, Hash)> {
+        self.rc.accounts.accounts_hash()
+    }
+
+    /// Recompute the accounts hash over the full account set and check that it matches the
+    /// one maintained incrementally as slots were rooted
+    pub fn verify_accounts_hash(&self) -> bool {
+        self.rc.accounts.verify_accounts_hash()
+    }
+
     /// Return the more recent checkpoint of this bank instance.
     pub fn parent(&self) -> Option<Arc<Bank>> {
         self.rc.parent.read().unwrap().clone()
@@ -562,6 +721,7 @@ impl Bank {
             .write()
             .unwrap()
             .genesis_hash(&genesis_block.hash(), &self.fee_calculator);
+        self.update_recent_blockhashes();
 
         self.ticks_per_slot = genesis_block.ticks_per_slot;
         self.max_tick_height = (self.slot + 1) * self.ticks_per_slot - 1;
@@ -580,9 +740,13 @@ impl Bank {
             genesis_block.stakers_slot_offset,
             genesis_block.epoch_warmup,
         );
+        self.update_epoch_schedule();
 
         self.inflation = genesis_block.inflation.clone();
 
+        self.rent_collector = RentCollector::new(self.epoch(), &genesis_block.rent);
+        self.update_rent();
+
         // Add native programs mandatory for the MessageProcessor to function
         self.register_native_instruction_processor(
             "solana_system_program",
@@ -713,6 +877,7 @@ impl Bank {
                 .write()
                 .unwrap()
                 .register_hash(hash, &self.fee_calculator);
+            self.update_recent_blockhashes();
         }
     }
 
@@ -725,6 +890,45 @@ impl Bank {
             .map_or(Ok(()), |sig| self.get_signature_status(sig).unwrap())
     }
 
+    /// Execute `tx` on a child of `parent` without committing it.  Returns the result of
+    /// the transaction, the messages logged by its programs, and, if it succeeded, the
+    /// post-execution state of the accounts it may write.  A transaction whose signatures do
+    /// not verify is not executed at all.
+    pub fn simulate_transaction(
+        parent: &Arc<Bank>,
+        tx: &Transaction,
+    ) -> (Result<()>, Vec<String>, Vec<(Pubkey, Account)>) {
+        if let Err(err) = tx.verify() {
+            return (Err(err), vec![], vec![]);
+        }
+        let bank = Self::new_for_simulation(parent);
+        let txs = vec![tx.clone()];
+        let lock_results = bank.lock_accounts(&txs);
+
+        let (loaded_accounts, executed, mut log_messages, _, _) =
+            bank.load_and_execute_transactions(&txs, &lock_results, MAX_RECENT_BLOCKHASHES);
+
+        let result = executed[0].clone();
+        let logs = log_messages.remove(0);
+        let accounts = match (&result, &loaded_accounts[0]) {
+            (Ok(()), Ok((accounts, _, _, _))) => {
+                let message = tx.message();
+                let program_ids = message.program_ids();
+                message
+                    .account_keys
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, key)| !program_ids.contains(key))
+                    .zip(accounts.iter())
+                    .filter(|((i, _), _)| message.is_debitable(*i))
+                    .map(|((_, key), account)| (*key, account.clone()))
+                    .collect()
+            }
+            _ => vec![],
+        };
+        (result, logs, accounts)
+    }
+
     pub fn lock_accounts<'a, 'b>(
         &'a self,
         txs: &'b [Transaction],
@@ -753,12 +957,20 @@ impl Bank {
         txs: &[Transaction],
         results: Vec<Result<()>>,
         error_counters: &mut ErrorCounters,
-    ) -> Vec<Result<(InstructionAccounts, InstructionLoaders, InstructionCredits)>> {
+    ) -> Vec<
+        Result<(
+            InstructionAccounts,
+            InstructionLoaders,
+            InstructionCredits,
+            TransactionRent,
+        )>,
+    > {
         self.rc.accounts.load_accounts(
             &self.ancestors,
             txs,
             results,
             &self.blockhash_queue.read().unwrap(),
+            &self.rent_collector,
             error_counters,
         )
     }
@@ -793,6 +1005,7 @@ impl Bank {
             .map(|(tx, lock_res)| {
                 if lock_res.is_ok()
                     && !hash_queue.check_hash_age(&tx.message().recent_blockhash, max_age)
+                    && !self.check_tx_durable_nonce(tx)
                 {
                     error_counters.reserve_blockhash += 1;
                     Err(TransactionError::BlockhashNotFound)
@@ -802,6 +1015,41 @@ impl Bank {
             })
             .collect()
     }
+    /// Check that `tx` advances a nonce account holding its recent blockhash, signed by the
+    /// nonce authority
+    fn check_tx_durable_nonce(&self, tx: &Transaction) -> bool {
+        nonce_utils::transaction_uses_durable_nonce(tx)
+            .and_then(|instruction| nonce_utils::get_nonce_pubkey_from_instruction(instruction, tx))
+            .and_then(|nonce_pubkey| self.get_account(nonce_pubkey))
+            .map_or(false, |nonce_account| {
+                nonce_utils::verify_nonce(&nonce_account, &tx.message().recent_blockhash)
+                    && nonce_utils::verify_nonce_authority_signed(&nonce_account, tx)
+            })
+    }
+
+    /// Store the nonce account of `tx` advanced to the last blockhash, as its `NonceAdvance`
+    /// would have done had the transaction not failed.  Only a transaction its nonce
+    /// authority signed may advance the nonce
+    fn advance_tx_durable_nonce(&self, tx: &Transaction, last_blockhash: &Hash) {
+        let nonce = nonce_utils::transaction_uses_durable_nonce(tx)
+            .and_then(|instruction| nonce_utils::get_nonce_pubkey_from_instruction(instruction, tx))
+            .and_then(|nonce_pubkey| {
+                self.get_account(nonce_pubkey)
+                    .map(|nonce_account| (nonce_pubkey, nonce_account))
+            });
+        if let Some((nonce_pubkey, mut nonce_account)) = nonce {
+            if let Ok(NonceState::Initialized(meta, _)) = nonce_account.state() {
+                if nonce_utils::verify_nonce_authority_signed(&nonce_account, tx)
+                    && nonce_account
+                        .set_state(&NonceState::Initialized(meta, *last_blockhash))
+                        .is_ok()
+                {
+                    self.store_account(nonce_pubkey, &nonce_account);
+                }
+            }
+        }
+    }
+
     fn check_signatures(
         &self,
         txs: &[Transaction],
@@ -911,8 +1159,18 @@ impl Bank {
         lock_results: &LockedAccountsResults,
         max_age: usize,
     ) -> (
-        Vec<Result<(InstructionAccounts, InstructionLoaders, InstructionCredits)>>,
+        Vec<
+            Result<(
+                InstructionAccounts,
+                InstructionLoaders,
+                InstructionCredits,
+                TransactionRent,
+            )>,
+        >,
         Vec<Result<()>>,
+        Vec<TransactionLogMessages>,
+        Vec<TransactionBalances>,
+        Vec<u64>,
     ) {
         debug!("processing transactions: {}", txs.len());
         let mut error_counters = ErrorCounters::default();
@@ -924,22 +1182,38 @@ impl Bank {
             &mut error_counters,
         );
         let mut loaded_accounts = self.load_accounts(txs, sig_results, &mut error_counters);
+        let pre_balances = Self::loaded_balances(txs, &loaded_accounts);
 
         let load_elapsed = now.elapsed();
         let now = Instant::now();
         let mut signature_count = 0;
-        let executed: Vec<Result<()>> = loaded_accounts
-            .iter_mut()
-            .zip(txs.iter())
-            .map(|(accs, tx)| match accs {
-                Err(e) => Err(e.clone()),
-                Ok((ref mut accounts, ref mut loaders, ref mut credits)) => {
-                    signature_count += tx.message().header.num_required_signatures as usize;
-                    self.message_processor
-                        .process_message(tx.message(), loaders, accounts, credits)
-                }
-            })
-            .collect();
+        let mut compute_units_consumed = Vec::with_capacity(txs.len());
+        let (executed, log_messages): (Vec<Result<()>>, Vec<TransactionLogMessages>) =
+            loaded_accounts
+                .iter_mut()
+                .zip(txs.iter())
+                .map(|(accs, tx)| match accs {
+                    Err(e) => {
+                        compute_units_consumed.push(0);
+                        (Err(e.clone()), vec![])
+                    }
+                    Ok((ref mut accounts, ref mut loaders, ref mut credits, _)) => {
+                        signature_count += tx.message().header.num_required_signatures as usize;
+                        let mut units = 0;
+                        let mut log_messages = vec![];
+                        let result = self.message_processor.process_message(
+                            tx.message(),
+                            loaders,
+                            accounts,
+                            credits,
+                            &mut units,
+                            &mut log_messages,
+                        );
+                        compute_units_consumed.push(units);
+                        (result, log_messages)
+                    }
+                })
+                .unzip();
 
         let execution_elapsed = now.elapsed();
 
@@ -978,22 +1252,72 @@ impl Bank {
         inc_new_counter_info!("bank-process_transactions-txs", tx_count, 0, 1000);
         inc_new_counter_info!("bank-process_transactions-sigs", signature_count, 0, 1000);
         Self::update_error_counters(&error_counters);
-        (loaded_accounts, executed)
+        (
+            loaded_accounts,
+            executed,
+            log_messages,
+            pre_balances,
+            compute_units_consumed,
+        )
     }
 
+    /// The balance of each account key of the loaded transactions, read from their loaded
+    /// accounts rather than the bank
+    fn loaded_balances(
+        txs: &[Transaction],
+        loaded_accounts: &[Result<(
+            InstructionAccounts,
+            InstructionLoaders,
+            InstructionCredits,
+            TransactionRent,
+        )>],
+    ) -> Vec<TransactionBalances> {
+        txs.iter()
+            .zip(loaded_accounts.iter())
+            .map(|(tx, loaded)| match loaded {
+                Ok((accounts, loaders, _, _)) => {
+                    let message = tx.message();
+                    let program_ids = message.program_ids();
+                    let mut accounts = accounts.iter();
+                    message
+                        .account_keys
+                        .iter()
+                        .enumerate()
+                        .map(|(i, key)| {
+                            if program_ids.contains(&key) {
+                                // program accounts are loaded last in their instruction's loaders
+                                message
+                                    .instructions
+                                    .iter()
+                                    .zip(loaders.iter())
+                                    .find(|(ix, _)| ix.program_ids_index as usize == i)
+                                    .and_then(|(_, loaders)| loaders.last())
+                                    .map(|(_, account)| account.lamports)
+                                    .unwrap_or(0)
+                            } else {
+                                accounts.next().map(|account| account.lamports).unwrap_or(0)
+                            }
+                        })
+                        .collect()
+                }
+                Err(_) => vec![],
+            })
+            .collect()
+    }
+
+    /// Returns the result of each transaction once its fee is collected, along with the fee
     fn filter_program_errors_and_collect_fee(
         &self,
         txs: &[Transaction],
         executed: &[Result<()>],
-    ) -> Vec<Result<()>> {
+    ) -> Vec<Result<u64>> {
         let hash_queue = self.blockhash_queue.read().unwrap();
         let mut fees = 0;
         let results = txs
             .iter()
             .zip(executed.iter())
             .map(|(tx, res)| {
-                let fee_calculator = hash_queue
-                    .get_fee_calculator(&tx.message().recent_blockhash)
+                let fee_calculator = nonce_utils::fee_calculator_for_transaction(&hash_queue, tx)
                     .ok_or(TransactionError::BlockhashNotFound)?;
                 let fee = fee_calculator.calculate_fee(tx.message());
 
@@ -1005,13 +1329,16 @@ impl Bank {
                         // work of doing so (in accounts.load()) is ignored by store_account()
                         self.withdraw(&message.account_keys[0], fee)?;
                         fees += fee;
-                        Ok(())
+                        // the nonce must be advanced even if its own NonceAdvance failed,
+                        // lest the transaction be replayed once its signature is forgotten
+                        self.advance_tx_durable_nonce(tx, &hash_queue.last_hash());
+                        Ok(fee)
                     }
                     Ok(()) => {
                         fees += fee;
-                        Ok(())
+                        Ok(fee)
                     }
-                    _ => res.clone(),
+                    Err(ref err) => Err(err.clone()),
                 }
             })
             .collect();
@@ -1024,8 +1351,16 @@ impl Bank {
     pub fn commit_transactions(
         &self,
         txs: &[Transaction],
-        loaded_accounts: &[Result<(InstructionAccounts, InstructionLoaders, InstructionCredits)>],
+        loaded_accounts: &[Result<(
+            InstructionAccounts,
+            InstructionLoaders,
+            InstructionCredits,
+            TransactionRent,
+        )>],
         executed: &[Result<()>],
+        log_messages: &[TransactionLogMessages],
+        pre_balances: &[TransactionBalances],
+        compute_units_consumed: &[u64],
     ) -> Vec<Result<()>> {
         if self.is_frozen() {
             warn!("=========== FIXME: commit_transactions() working on a frozen bank! ================");
@@ -1043,6 +1378,7 @@ impl Bank {
             .store_accounts(self.slot(), txs, executed, loaded_accounts);
 
         self.update_cached_accounts(txs, executed, loaded_accounts);
+        self.collect_rent(executed, loaded_accounts);
 
         // once committed there is no way to unroll
         let write_elapsed = now.elapsed();
@@ -1052,7 +1388,87 @@ impl Bank {
             txs.len(),
         );
         self.update_transaction_statuses(txs, &executed);
-        self.filter_program_errors_and_collect_fee(txs, executed)
+        let results = self.filter_program_errors_and_collect_fee(txs, executed);
+        let post_balances = Self::loaded_balances(txs, loaded_accounts);
+        self.record_transaction_status_metas(
+            txs,
+            executed,
+            &results,
+            pre_balances,
+            post_balances,
+            log_messages,
+            compute_units_consumed,
+        );
+        results
+            .into_iter()
+            .map(|result| result.map(|_| ()))
+            .collect()
+    }
+
+    /// Balances are read from the loaded accounts, which had the fee withdrawn at load and
+    /// which hold the effects of execution afterwards
+    fn record_transaction_status_metas(
+        &self,
+        txs: &[Transaction],
+        executed: &[Result<()>],
+        results: &[Result<u64>],
+        pre_balances: &[TransactionBalances],
+        post_balances: Vec<TransactionBalances>,
+        log_messages: &[TransactionLogMessages],
+        compute_units_consumed: &[u64],
+    ) {
+        let mut transaction_statuses = self.transaction_statuses.write().unwrap();
+        for (i, post_balances) in post_balances.into_iter().enumerate() {
+            if !Self::can_commit(&executed[i]) || txs[i].signatures.is_empty() {
+                continue;
+            }
+            let fee = results[i].as_ref().map(|fee| *fee).unwrap_or(0);
+            let loaded_balances = pre_balances.get(i).cloned().unwrap_or_default();
+            // a failed transaction only pays its fee, which was already withdrawn at load
+            let post_balances = if executed[i].is_ok() {
+                post_balances
+            } else {
+                loaded_balances.clone()
+            };
+            let mut pre_balances = loaded_balances;
+            if let Some(payer_balance) = pre_balances.first_mut() {
+                *payer_balance += fee;
+            }
+            transaction_statuses.insert(
+                txs[i].signatures[0],
+                TransactionStatusMeta {
+                    status: executed[i].clone(),
+                    fee,
+                    pre_balances,
+                    post_balances,
+                    log_messages: log_messages.get(i).cloned().unwrap_or_default(),
+                    compute_units_consumed: compute_units_consumed.get(i).cloned().unwrap_or(0),
+                },
+            );
+        }
+    }
+
+    /// Rent is only collected from the accounts of transactions whose accounts are stored
+    fn collect_rent(
+        &self,
+        executed: &[Result<()>],
+        loaded_accounts: &[Result<(
+            InstructionAccounts,
+            InstructionLoaders,
+            InstructionCredits,
+            TransactionRent,
+        )>],
+    ) {
+        let collected_rent: u64 = executed
+            .iter()
+            .zip(loaded_accounts.iter())
+            .filter_map(|(res, loaded)| match (res, loaded) {
+                (Ok(()), Ok((_, _, _, rent))) => Some(*rent),
+                _ => None,
+            })
+            .sum();
+        self.collected_rent
+            .fetch_add(collected_rent as usize, Ordering::Relaxed);
     }
 
     /// Process a batch of transactions.
@@ -1063,10 +1479,17 @@ impl Bank {
         lock_results: &LockedAccountsResults,
         max_age: usize,
     ) -> Vec<Result<()>> {
-        let (loaded_accounts, executed) =
+        let (loaded_accounts, executed, log_messages, pre_balances, compute_units_consumed) =
             self.load_and_execute_transactions(txs, lock_results, max_age);
 
-        self.commit_transactions(txs, &loaded_accounts, &executed)
+        self.commit_transactions(
+            txs,
+            &loaded_accounts,
+            &executed,
+            &log_messages,
+            &pre_balances,
+            &compute_units_consumed,
+        )
     }
 
     #[must_use]
@@ -1136,7 +1559,12 @@ impl Bank {
     }
 
     pub fn deposit(&self, pubkey: &Pubkey, lamports: u64) {
-        let mut account = self.get_account(pubkey).unwrap_or_default();
+        let mut account = self.get_account(pubkey).unwrap_or_else(|| {
+            // a new account owes no rent for the epochs before it existed
+            let mut account = Account::default();
+            account.rent_epoch = self.epoch();
+            account
+        });
         account.lamports += lamports;
         self.store_account(pubkey, &account);
     }
@@ -1161,6 +1589,16 @@ impl Bank {
             .map(|(account, _)| account)
     }
 
+    pub fn get_program_accounts(&self, program_id: &Pubkey) -> Vec<(Pubkey, Account)> {
+        self.rc
+            .accounts
+            .load_by_program_slow(&self.ancestors, program_id)
+    }
+
+    pub fn get_all_accounts(&self) -> Vec<(Pubkey, Account)> {
+        self.rc.accounts.load_all(&self.ancestors)
+    }
+
     pub fn get_program_accounts_modified_since_parent(
         &self,
         program_id: &Pubkey,
@@ -1204,10 +1642,39 @@ impl Bank {
             .map(|v| v.1)
     }
 
+    /// What was recorded about the transaction with `signature` when this bank or one of its
+    /// unsquashed parents committed it
+    pub fn get_transaction_status_meta(
+        &self,
+        signature: &Signature,
+    ) -> Option<TransactionStatusMeta> {
+        if let Some(meta) = self.transaction_statuses.read().unwrap().get(signature) {
+            return Some(meta.clone());
+        }
+        self.parent()
+            .and_then(|parent| parent.get_transaction_status_meta(signature))
+    }
+
+    /// What was recorded about every transaction committed to this bank
+    pub fn transaction_status_metas(&self) -> Vec<(Signature, TransactionStatusMeta)> {
+        self.transaction_statuses
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(signature, meta)| (*signature, meta.clone()))
+            .collect()
+    }
+
     pub fn has_signature(&self, signature: &Signature) -> bool {
         self.get_signature_confirmation_status(signature).is_some()
     }
 
+    /// Recalculate the hash of this frozen bank's account delta and check that it
+    ///  matches the hash the bank was frozen with
+    pub fn verify_hash(&self) -> bool {
+        self.is_frozen() && self.hash() == self.hash_internal_state()
+    }
+
     /// Hash the `accounts` HashMap. This represents a validator's interpretation
     ///  of the delta of the ledger since the last vote and up to now
     fn hash_internal_state(&self) -> Hash {
@@ -1262,7 +1729,12 @@ impl Bank {
         &self,
         txs: &[Transaction],
         res: &[Result<()>],
-        loaded: &[Result<(InstructionAccounts, InstructionLoaders, InstructionCredits)>],
+        loaded: &[Result<(
+            InstructionAccounts,
+            InstructionLoaders,
+            InstructionCredits,
+            TransactionRent,
+        )>],
     ) {
         for (i, raccs) in loaded.iter().enumerate() {
             if res[i].is_err() || raccs.is_err() {
@@ -1375,6 +1847,9 @@ impl Bank {
 
 impl Drop for Bank {
     fn drop(&mut self) {
+        if self.is_simulation {
+            return;
+        }
         // For root forks this is a noop
         self.rc.accounts.purge_fork(self.slot());
     }
@@ -1383,18 +1858,23 @@ impl Drop for Bank {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::epoch_schedule::MINIMUM_SLOTS_PER_EPOCH;
     use crate::genesis_utils::{
         create_genesis_block_with_leader, GenesisBlockInfo, BOOTSTRAP_LEADER_LAMPORTS,
     };
     use bincode::{deserialize_from, serialize_into, serialized_size};
+    use solana_sdk::epoch_schedule::MINIMUM_SLOTS_PER_EPOCH;
     use solana_sdk::genesis_block::create_genesis_block;
     use solana_sdk::hash;
     use solana_sdk::instruction::InstructionError;
+    use solana_sdk::message::Message;
     use solana_sdk::poh_config::PohConfig;
     use solana_sdk::signature::{Keypair, KeypairUtil};
-    use solana_sdk::syscall::{fees::Fees, rewards::Rewards, tick_height::TickHeight};
-    use solana_sdk::system_instruction;
+    use solana_sdk::syscall::{
+        fees::Fees, recent_blockhashes::RecentBlockhashes, rent::Rent, rewards::Rewards,
+        tick_height::TickHeight,
+    };
+    use solana_sdk::system_instruction::{self, SystemError};
+    use solana_sdk::system_program;
     use solana_sdk::system_transaction;
     use solana_sdk::timing::DEFAULT_TICKS_PER_SLOT;
     use solana_vote_api::vote_instruction;
@@ -1759,6 +2239,110 @@ mod tests {
         assert_eq!(bank.get_balance(&leader), initial_balance + 6);
     }
 
+    #[test]
+    fn test_bank_transaction_status_meta() {
+        let (mut genesis_block, mint_keypair) = create_genesis_block(100);
+        genesis_block.fee_calculator.lamports_per_signature = 3;
+        let bank = Arc::new(Bank::new(&genesis_block));
+
+        let key = Keypair::new();
+        let tx =
+            system_transaction::transfer(&mint_keypair, &key.pubkey(), 2, bank.last_blockhash());
+        let system_balance = bank.get_balance(&system_program::id());
+        assert_eq!(bank.process_transaction(&tx), Ok(()));
+        assert_eq!(
+            bank.get_transaction_status_meta(&tx.signatures[0]),
+            Some(TransactionStatusMeta {
+                status: Ok(()),
+                fee: 3,
+                pre_balances: vec![100, 0, system_balance],
+                post_balances: vec![95, 2, system_balance],
+                log_messages: vec![],
+                compute_units_consumed: 1_000,
+            })
+        );
+
+        // An InstructionError is recorded along with the fee it was charged
+        let mut tx =
+            system_transaction::transfer(&mint_keypair, &key.pubkey(), 1, bank.last_blockhash());
+        tx.message.instructions[0].data[0] = 40;
+        bank.process_transaction(&tx)
+            .expect_err("instruction error");
+        let meta = bank.get_transaction_status_meta(&tx.signatures[0]).unwrap();
+        assert_eq!(
+            meta.status,
+            Err(TransactionError::InstructionError(
+                0,
+                InstructionError::InvalidInstructionData
+            ))
+        );
+        assert_eq!(meta.fee, 3);
+        assert_eq!(meta.pre_balances[..2], [95, 2]);
+        assert_eq!(meta.post_balances[..2], [92, 2]);
+
+        // Transactions that are never committed aren't recorded
+        let tx =
+            system_transaction::transfer(&key, &mint_keypair.pubkey(), 1, bank.last_blockhash());
+        bank.process_transaction(&tx)
+            .expect_err("insufficient funds for fee");
+        assert_eq!(bank.get_transaction_status_meta(&tx.signatures[0]), None);
+
+        // Children find the metas of their parents' transactions
+        let child = Bank::new_from_parent(&bank, &Pubkey::default(), 1);
+        assert_eq!(bank.transaction_status_metas().len(), 2);
+        assert!(child.transaction_status_metas().is_empty());
+        for (signature, meta) in bank.transaction_status_metas() {
+            assert_eq!(child.get_transaction_status_meta(&signature), Some(meta));
+        }
+    }
+
+    #[test]
+    fn test_bank_rent_collection() {
+        let leader = Pubkey::new_rand();
+        let GenesisBlockInfo {
+            mut genesis_block,
+            mint_keypair,
+            ..
+        } = create_genesis_block_with_leader(10_000, &leader, 3);
+        genesis_block.rent = Rent::new(1, 10);
+        genesis_block.slots_per_epoch = MINIMUM_SLOTS_PER_EPOCH;
+        genesis_block.epoch_warmup = false;
+        let bank = Bank::new(&genesis_block);
+        assert_eq!(bank.get_minimum_balance_for_rent_exemption(0), 1280);
+        assert_eq!(bank.get_minimum_balance_for_rent_exemption(10), 1380);
+
+        let alice = Keypair::new();
+        let bob = Pubkey::new_rand();
+        bank.transfer(1_000, &mint_keypair, &alice.pubkey())
+            .unwrap();
+        assert_eq!(bank.get_balance(&alice.pubkey()), 1_000);
+
+        // Two epochs later alice owes rent for both of them the next time she is loaded
+        let mut bank = Bank::new_from_parent(&Arc::new(bank), &leader, 2 * MINIMUM_SLOTS_PER_EPOCH);
+        assert_eq!(bank.epoch(), 2);
+        let initial_balance = bank.get_balance(&leader);
+        bank.transfer(1, &alice, &bob).unwrap();
+        assert_eq!(bank.get_balance(&alice.pubkey()), 1_000 - 256 - 1);
+        assert_eq!(bank.get_balance(&bob), 1);
+        // The mint is above the exemption threshold, so it is never charged
+        assert_eq!(bank.get_balance(&mint_keypair.pubkey()), 9_000);
+
+        // Alice has already paid for this epoch
+        bank.transfer(2, &alice, &bob).unwrap();
+        assert_eq!(bank.get_balance(&alice.pubkey()), 1_000 - 256 - 3);
+
+        // The collector receives the rent once the bank is frozen
+        goto_end_of_slot(&mut bank);
+        assert_eq!(bank.get_balance(&leader), initial_balance + 256);
+
+        // Emptied accounts read as missing
+        let bank = Bank::new_from_parent(&Arc::new(bank), &leader, 2 * MINIMUM_SLOTS_PER_EPOCH + 1);
+        bank.transfer(1_000 - 256 - 3, &alice, &bob).unwrap();
+        bank.squash();
+        assert_eq!(bank.get_account(&alice.pubkey()), None);
+        assert_eq!(bank.get_balance(&bob), 1_000 - 256);
+    }
+
     #[test]
     fn test_bank_blockhash_fee_schedule() {
         //solana_logger::setup();
@@ -1840,8 +2424,8 @@ mod tests {
         let results = bank.filter_program_errors_and_collect_fee(&vec![tx1, tx2], &results);
         bank.freeze();
         assert_eq!(bank.get_balance(&leader), initial_balance + 2 + 2);
-        assert_eq!(results[0], Ok(()));
-        assert_eq!(results[1], Ok(()));
+        assert_eq!(results[0], Ok(2));
+        assert_eq!(results[1], Ok(2));
     }
 
     #[test]
@@ -2086,6 +2670,21 @@ mod tests {
         assert_eq!(bank0.hash_internal_state(), bank2.hash_internal_state());
     }
 
+    #[test]
+    fn test_bank_verify_hash() {
+        let (genesis_block, mint_keypair) = create_genesis_block(2_000);
+        let bank = Bank::new(&genesis_block);
+        assert!(!bank.verify_hash());
+        bank.transfer(1_000, &mint_keypair, &Pubkey::new_rand())
+            .unwrap();
+        bank.freeze();
+        assert!(bank.verify_hash());
+
+        // A write landing in the fork after it was frozen is caught
+        bank.store_account(&Pubkey::new_rand(), &Account::new(1, 0, &Pubkey::default()));
+        assert!(!bank.verify_hash());
+    }
+
     #[test]
     fn test_hash_internal_state_genesis() {
         let bank0 = Bank::new(&create_genesis_block(10).0);
@@ -2192,6 +2791,29 @@ mod tests {
         assert_eq!(parent.get_balance(&key1.pubkey()), 1);
     }
 
+    #[test]
+    fn test_bank_accounts_hash() {
+        let (genesis_block, mint_keypair) = create_genesis_block(500);
+        let bank0 = Arc::new(Bank::new(&genesis_block));
+        assert_eq!(bank0.get_accounts_hash(), None);
+
+        let key1 = Keypair::new();
+        bank0.transfer(1, &mint_keypair, &key1.pubkey()).unwrap();
+        let bank1 = Arc::new(new_from_parent(&bank0));
+        bank1.squash();
+        let (slot, hash0) = bank1.get_accounts_hash().unwrap();
+        assert_eq!(slot, 0);
+        assert!(bank1.verify_accounts_hash());
+
+        bank1.transfer(2, &mint_keypair, &key1.pubkey()).unwrap();
+        let bank2 = new_from_parent(&bank1);
+        bank2.squash();
+        let (slot, hash1) = bank2.get_accounts_hash().unwrap();
+        assert_eq!(slot, 1);
+        assert_ne!(hash0, hash1);
+        assert!(bank2.verify_accounts_hash());
+    }
+
     #[test]
     fn test_bank_get_account_in_parent_after_squash2() {
         solana_logger::setup();
@@ -2508,6 +3130,45 @@ mod tests {
         assert_eq!(fees.fee_calculator.lamports_per_signature, 12345);
     }
 
+    #[test]
+    fn test_bank_epoch_schedule_account() {
+        let (genesis_block, _) = create_genesis_block(500);
+        let bank = Bank::new(&genesis_block);
+
+        let epoch_schedule_account = bank.get_account(&epoch_schedule::id()).unwrap();
+        let epoch_schedule = EpochSchedule::from(&epoch_schedule_account).unwrap();
+        assert_eq!(&epoch_schedule, bank.epoch_schedule());
+    }
+
+    #[test]
+    fn test_bank_stake_history_account() {
+        let GenesisBlockInfo { genesis_block, .. } =
+            create_genesis_block_with_leader(500, &Pubkey::new_rand(), 30);
+        let bank = Arc::new(Bank::new(&genesis_block));
+        let stake_history_account = bank.get_account(&stake_history::id()).unwrap();
+        assert_eq!(
+            StakeHistory::from(&stake_history_account),
+            Some(StakeHistory::default())
+        );
+
+        // crossing into the next epoch records the one that just ended
+        let bank1 = Arc::new(new_from_parent(&bank));
+        assert_eq!(
+            bank1.get_account(&stake_history::id()),
+            Some(stake_history_account)
+        );
+        let slot = bank.epoch_schedule().get_first_slot_in_epoch(1);
+        let bank2 = Bank::new_from_parent(&bank1, &Pubkey::default(), slot);
+        let stake_history =
+            StakeHistory::from(&bank2.get_account(&stake_history::id()).unwrap()).unwrap();
+        assert_eq!(stake_history.len(), 1);
+        assert_eq!(
+            stake_history.get(0),
+            Some(&bank.stakes.read().unwrap().stake_history_entry(0))
+        );
+        assert!(stake_history.get(0).unwrap().effective > 0);
+    }
+
     #[test]
     fn test_bank_tick_height_account() {
         let (genesis_block, _) = create_genesis_block(1);
@@ -2523,6 +3184,149 @@ mod tests {
         assert_eq!(tick_height, 10);
     }
 
+    /// Register a blockhash on `bank` for each of `num_slots` slots
+    fn register_slot_blockhashes(bank: &Bank, num_slots: usize) {
+        for _ in 0..num_slots as u64 * bank.ticks_per_slot() {
+            bank.register_tick(&hash::hash(&serialize(&bank.tick_height()).unwrap()));
+        }
+    }
+
+    #[test]
+    fn test_bank_recent_blockhashes_account() {
+        let (genesis_block, _) = create_genesis_block(1);
+        let bank = Bank::new(&genesis_block);
+
+        let recent_blockhashes_account = bank.get_account(&recent_blockhashes::id()).unwrap();
+        let recent_blockhashes = RecentBlockhashes::from(&recent_blockhashes_account).unwrap();
+        assert_eq!(*recent_blockhashes, vec![bank.last_blockhash()]);
+
+        register_slot_blockhashes(&bank, recent_blockhashes::MAX_ENTRIES + 1);
+        let recent_blockhashes_account = bank.get_account(&recent_blockhashes::id()).unwrap();
+        let recent_blockhashes = RecentBlockhashes::from(&recent_blockhashes_account).unwrap();
+        assert_eq!(recent_blockhashes.len(), recent_blockhashes::MAX_ENTRIES);
+        assert_eq!(recent_blockhashes[0], bank.last_blockhash());
+    }
+
+    fn get_nonce_blockhash(bank: &Bank, nonce_pubkey: &Pubkey) -> Option<Hash> {
+        match NonceState::from(&bank.get_account(nonce_pubkey)?) {
+            Some(NonceState::Initialized(_, hash)) => Some(hash),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_bank_durable_nonce() {
+        let (genesis_block, mint_keypair) = create_genesis_block(10_000);
+        let bank = Bank::new(&genesis_block);
+        let mint_pubkey = mint_keypair.pubkey();
+        let nonce_pubkey = Pubkey::new_rand();
+        let to_pubkey = Pubkey::new_rand();
+
+        let tx = Transaction::new_signed_instructions(
+            &[&mint_keypair],
+            system_instruction::create_nonce_account(
+                &mint_pubkey,
+                &nonce_pubkey,
+                &mint_pubkey,
+                100,
+            ),
+            bank.last_blockhash(),
+        );
+        assert_eq!(bank.process_transaction(&tx), Ok(()));
+        let nonce_blockhash = get_nonce_blockhash(&bank, &nonce_pubkey).unwrap();
+        assert_eq!(nonce_blockhash, bank.last_blockhash());
+
+        // Age the nonce's blockhash out of the queue
+        register_slot_blockhashes(&bank, MAX_RECENT_BLOCKHASHES + 1);
+        let tx = system_transaction::transfer(&mint_keypair, &to_pubkey, 1, nonce_blockhash);
+        assert_eq!(
+            bank.process_transaction(&tx),
+            Err(TransactionError::BlockhashNotFound)
+        );
+
+        // The nonce stands in for the expired blockhash, once
+        let tx = Transaction::new_signed_instructions(
+            &[&mint_keypair],
+            vec![
+                system_instruction::nonce_advance(&nonce_pubkey, &mint_pubkey),
+                system_instruction::transfer(&mint_pubkey, &to_pubkey, 2),
+            ],
+            nonce_blockhash,
+        );
+        assert_eq!(bank.process_transaction(&tx), Ok(()));
+        assert_eq!(bank.get_balance(&to_pubkey), 2);
+        assert_eq!(
+            get_nonce_blockhash(&bank, &nonce_pubkey),
+            Some(bank.last_blockhash())
+        );
+        assert_eq!(
+            bank.process_transaction(&tx),
+            Err(TransactionError::BlockhashNotFound)
+        );
+
+        // A failing instruction after the advance still consumes the nonce
+        register_slot_blockhashes(&bank, 1);
+        let nonce_blockhash = get_nonce_blockhash(&bank, &nonce_pubkey).unwrap();
+        let tx = Transaction::new_signed_instructions(
+            &[&mint_keypair],
+            vec![
+                system_instruction::nonce_advance(&nonce_pubkey, &mint_pubkey),
+                system_instruction::transfer(&mint_pubkey, &to_pubkey, 100_000),
+            ],
+            nonce_blockhash,
+        );
+        assert_eq!(
+            bank.process_transaction(&tx),
+            Err(TransactionError::InstructionError(
+                1,
+                InstructionError::CustomError(SystemError::ResultWithNegativeLamports as u32)
+            ))
+        );
+        assert_eq!(
+            get_nonce_blockhash(&bank, &nonce_pubkey),
+            Some(bank.last_blockhash())
+        );
+        assert_ne!(bank.last_blockhash(), nonce_blockhash);
+
+        // So does a failure of the NonceAdvance itself
+        register_slot_blockhashes(&bank, 1);
+        let nonce_blockhash = get_nonce_blockhash(&bank, &nonce_pubkey).unwrap();
+        let mut advance = system_instruction::nonce_advance(&nonce_pubkey, &mint_pubkey);
+        advance.accounts[1].pubkey = to_pubkey;
+        let tx =
+            Transaction::new_signed_instructions(&[&mint_keypair], vec![advance], nonce_blockhash);
+        assert_eq!(
+            bank.process_transaction(&tx),
+            Err(TransactionError::InstructionError(
+                0,
+                InstructionError::InvalidArgument
+            ))
+        );
+        assert_eq!(
+            get_nonce_blockhash(&bank, &nonce_pubkey),
+            Some(bank.last_blockhash())
+        );
+
+        // Only the nonce authority may stand in its nonce for the recent blockhash
+        register_slot_blockhashes(&bank, MAX_RECENT_BLOCKHASHES + 1);
+        let nonce_blockhash = get_nonce_blockhash(&bank, &nonce_pubkey).unwrap();
+        let payer_keypair = Keypair::new();
+        bank.transfer(10, &mint_keypair, &payer_keypair.pubkey())
+            .unwrap();
+        let mut advance = system_instruction::nonce_advance(&nonce_pubkey, &mint_pubkey);
+        advance.accounts[2].is_signer = false;
+        let message = Message::new_with_payer(vec![advance], Some(&payer_keypair.pubkey()));
+        let tx = Transaction::new(&[&payer_keypair], message, nonce_blockhash);
+        assert_eq!(
+            bank.process_transaction(&tx),
+            Err(TransactionError::BlockhashNotFound)
+        );
+        assert_eq!(
+            get_nonce_blockhash(&bank, &nonce_pubkey),
+            Some(nonce_blockhash)
+        );
+    }
+
     #[test]
     fn test_is_delta_with_no_committables() {
         let (genesis_block, mint_keypair) = create_genesis_block(8000);
@@ -2586,8 +3390,69 @@ mod tests {
             &BankRc::new(Some(bank0.accounts().paths.clone())),
             &StatusCacheRc::default(),
         );
-        assert!(dbank.rc.update_from_stream(&mut reader).is_ok());
+        assert!(dbank
+            .rc
+            .update_from_stream(&dbank.rc.account_paths(), &mut reader)
+            .is_ok());
         assert_eq!(dbank.get_balance(&key.pubkey()), 10);
         bank.compare_bank(&dbank);
     }
+
+    #[test]
+    fn test_bank_simulate_transaction() {
+        let (genesis_block, mint_keypair) = create_genesis_block(10_000);
+        let bank = Arc::new(Bank::new(&genesis_block));
+        let pubkey = Pubkey::new_rand();
+
+        let tx = system_transaction::create_user_account(
+            &mint_keypair,
+            &pubkey,
+            500,
+            bank.last_blockhash(),
+        );
+        let (result, logs, accounts) = Bank::simulate_transaction(&bank, &tx);
+        assert_eq!(result, Ok(()));
+        assert!(logs.is_empty());
+        assert_eq!(accounts.len(), 2);
+        assert_eq!(accounts[0].0, mint_keypair.pubkey());
+        assert_eq!(accounts[0].1.lamports, 10_000 - 500);
+        assert_eq!(accounts[1].0, pubkey);
+        assert_eq!(accounts[1].1.lamports, 500);
+
+        // Nothing was committed, and the parent is still open for transactions
+        assert!(!bank.is_frozen());
+        assert_eq!(bank.get_balance(&pubkey), 0);
+        assert_eq!(bank.transaction_count(), 0);
+        assert_eq!(bank.process_transaction(&tx), Ok(()));
+        assert_eq!(bank.get_balance(&pubkey), 500);
+
+        let tx = system_transaction::create_user_account(
+            &mint_keypair,
+            &Pubkey::new_rand(),
+            20_000,
+            bank.last_blockhash(),
+        );
+        let (result, _, accounts) = Bank::simulate_transaction(&bank, &tx);
+        assert_eq!(
+            result,
+            Err(TransactionError::InstructionError(
+                0,
+                InstructionError::CustomError(SystemError::ResultWithNegativeLamports as u32)
+            ))
+        );
+        assert!(accounts.is_empty());
+
+        // A transaction that would succeed fails if it is not properly signed
+        let mut tx = system_transaction::create_user_account(
+            &mint_keypair,
+            &Pubkey::new_rand(),
+            500,
+            bank.last_blockhash(),
+        );
+        tx.signatures[0] = Signature::default();
+        let (result, logs, accounts) = Bank::simulate_transaction(&bank, &tx);
+        assert_eq!(result, Err(TransactionError::SignatureFailure));
+        assert!(logs.is_empty());
+        assert!(accounts.is_empty());
+    }
 }
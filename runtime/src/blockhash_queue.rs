@@ -105,6 +105,20 @@ impl BlockhashQueue {
         self.last_hash = Some(*hash);
     }
 
+    /// Registered hashes, most recent first
+    pub fn get_recent_blockhashes(&self) -> Vec<Hash> {
+        let mut recent_blockhashes: Vec<_> = self
+            .ages
+            .iter()
+            .map(|(hash, age)| (age.hash_height, *hash))
+            .collect();
+        recent_blockhashes.sort_by(|a, b| b.0.cmp(&a.0));
+        recent_blockhashes
+            .into_iter()
+            .map(|(_, hash)| hash)
+            .collect()
+    }
+
     /// Maps a hash height to a timestamp
     pub fn hash_height_to_timestamp(&self, hash_height: u64) -> Option<u64> {
         for age in self.ages.values() {
@@ -150,4 +164,16 @@ mod tests {
         assert_eq!(last_hash, hash_queue.last_hash());
         assert!(hash_queue.check_hash_age(&last_hash, 0));
     }
+
+    #[test]
+    fn test_get_recent_blockhashes() {
+        let mut hash_queue = BlockhashQueue::new(100);
+        let hashes: Vec<_> = (0..10).map(|i| hash(&serialize(&i).unwrap())).collect();
+        for hash in &hashes {
+            hash_queue.register_hash(hash, &FeeCalculator::default());
+        }
+        let recent_blockhashes = hash_queue.get_recent_blockhashes();
+        assert_eq!(recent_blockhashes.len(), hashes.len());
+        assert!(recent_blockhashes.iter().eq(hashes.iter().rev()));
+    }
 }
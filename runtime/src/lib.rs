@@ -1,17 +1,19 @@
 mod accounts;
 pub mod accounts_db;
+pub mod accounts_hash;
 pub mod accounts_index;
 pub mod append_vec;
 pub mod bank;
 pub mod bank_client;
 mod blockhash_queue;
 pub mod bloom;
-pub mod epoch_schedule;
 pub mod genesis_utils;
 pub mod loader_utils;
 pub mod locked_accounts_results;
 pub mod message_processor;
 mod native_loader;
+mod nonce_utils;
+pub mod rent_collector;
 mod serde_utils;
 pub mod stakes;
 pub mod status_cache;
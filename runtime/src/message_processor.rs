@@ -2,12 +2,15 @@ use crate::native_loader;
 use crate::system_instruction_processor;
 use serde::{Deserialize, Serialize};
 use solana_sdk::account::{create_keyed_accounts, Account, KeyedAccount, LamportCredit};
-use solana_sdk::instruction::{CompiledInstruction, InstructionError};
+use solana_sdk::compute_budget::ComputeBudget;
+use solana_sdk::instruction::{CompiledInstruction, Instruction, InstructionError};
 use solana_sdk::instruction_processor_utils;
+use solana_sdk::invoke_context::{self, with_invoke_context, InvokeContext};
 use solana_sdk::message::Message;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::system_program;
 use solana_sdk::transaction::TransactionError;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::sync::RwLock;
 
@@ -90,17 +93,300 @@ fn verify_instruction(
     Ok(())
 }
 
+/// Maximum depth of nested cross-program invocations, counting the message instruction
+pub const MAX_INVOKE_DEPTH: usize = 4;
+
+/// The state of an account before the running program changed it
+struct PreAccount {
+    key: Pubkey,
+    is_signer: bool,
+    is_debitable: bool,
+    owner: Pubkey,
+    lamports: u64,
+    data: Vec<u8>,
+    /// The account the runtime handed the program, which invoked instructions must operate on
+    account: *const Account,
+}
+
+impl PreAccount {
+    fn new(key: &Pubkey, is_signer: bool, is_debitable: bool, account: &Account) -> Self {
+        Self {
+            key: *key,
+            is_signer,
+            is_debitable,
+            owner: account.owner,
+            lamports: account.lamports,
+            data: account.data.clone(),
+            account,
+        }
+    }
+
+    fn verify(&self, program_id: &Pubkey, account: &Account) -> Result<(), InstructionError> {
+        verify_instruction(
+            self.is_debitable,
+            program_id,
+            &self.owner,
+            self.lamports,
+            &self.data,
+            account,
+        )
+    }
+
+    fn update(&mut self, account: &Account) {
+        self.owner = account.owner;
+        self.lamports = account.lamports;
+        self.data.clone_from(&account.data);
+    }
+}
+
+/// A program executing in a chain of cross-program invocations
+struct InvokeFrame {
+    program_id: Pubkey,
+    pre_accounts: Vec<PreAccount>,
+}
+
+/// Executes the instructions a program invokes while a message instruction runs
+struct MessageInvokeContext<'a> {
+    message_processor: &'a MessageProcessor,
+    frames: RefCell<Vec<InvokeFrame>>,
+    /// The first error an invoked instruction returned
+    error: RefCell<Option<InstructionError>>,
+    /// Units left in the transaction's compute budget
+    compute_meter: &'a Cell<u64>,
+    /// Messages logged by the transaction's programs
+    log_messages: &'a RefCell<Vec<String>>,
+}
+
+impl<'a> MessageInvokeContext<'a> {
+    fn new(
+        message_processor: &'a MessageProcessor,
+        program_id: &Pubkey,
+        pre_accounts: Vec<PreAccount>,
+        compute_meter: &'a Cell<u64>,
+        log_messages: &'a RefCell<Vec<String>>,
+    ) -> Self {
+        Self {
+            message_processor,
+            frames: RefCell::new(vec![InvokeFrame {
+                program_id: *program_id,
+                pre_accounts,
+            }]),
+            error: RefCell::new(None),
+            compute_meter,
+            log_messages,
+        }
+    }
+
+    fn into_inner(self) -> (Vec<InvokeFrame>, Option<InstructionError>) {
+        (self.frames.into_inner(), self.error.into_inner())
+    }
+
+    /// Check that the caller may pass each account `instruction` references with the
+    /// privileges it asks for, and verify the changes the caller has made so far
+    fn enter(
+        &self,
+        instruction: &Instruction,
+        keyed_accounts: &[KeyedAccount],
+    ) -> Result<InvokeFrame, InstructionError> {
+        let mut frames = self.frames.borrow_mut();
+        if frames.len() >= MAX_INVOKE_DEPTH {
+            return Err(InstructionError::CallDepthExceeded);
+        }
+        let caller = frames.last_mut().unwrap();
+        let mut pre_accounts = vec![];
+        for meta in &instruction.accounts {
+            let pre_account = caller
+                .pre_accounts
+                .iter_mut()
+                .find(|pre_account| pre_account.key == meta.pubkey)
+                .ok_or(InstructionError::MissingAccount)?;
+            if (meta.is_signer && !pre_account.is_signer)
+                || (meta.is_debitable && !pre_account.is_debitable)
+            {
+                return Err(InstructionError::PrivilegeEscalation);
+            }
+            let account = keyed_accounts
+                .iter()
+                .find(|keyed_account| {
+                    *keyed_account.unsigned_key() == meta.pubkey
+                        && std::ptr::eq(&*keyed_account.account, pre_account.account)
+                })
+                .map(|keyed_account| &*keyed_account.account)
+                .ok_or(InstructionError::MissingAccount)?;
+            pre_account.verify(&caller.program_id, account)?;
+            pre_account.update(account);
+            pre_accounts.push(PreAccount::new(
+                &meta.pubkey,
+                meta.is_signer,
+                meta.is_debitable,
+                account,
+            ));
+        }
+        Ok(InvokeFrame {
+            program_id: instruction.program_ids_index,
+            pre_accounts,
+        })
+    }
+
+    fn invoke_checked(
+        &self,
+        instruction: &Instruction,
+        keyed_accounts: &mut [KeyedAccount],
+    ) -> Result<(), InstructionError> {
+        let program_id = instruction.program_ids_index;
+        self.consume_compute_units(self.compute_budget().invoke_units)?;
+
+        // Programs other than the ones linked into the runtime are loaded from their account,
+        // which the caller must have been given.  The program is taken as it was before the
+        // caller ran, so the caller can't have its own writes run under the program's id
+        let mut executable_accounts =
+            if self.message_processor.is_instruction_processor(&program_id) {
+                vec![(program_id, Account::default())]
+            } else {
+                let frames = self.frames.borrow();
+                let pre_account = frames
+                    .last()
+                    .unwrap()
+                    .pre_accounts
+                    .iter()
+                    .find(|pre_account| pre_account.key == program_id)
+                    .ok_or(InstructionError::MissingAccount)?;
+                let program = keyed_accounts
+                    .iter()
+                    .find(|keyed_account| {
+                        *keyed_account.unsigned_key() == program_id
+                            && std::ptr::eq(&*keyed_account.account, pre_account.account)
+                    })
+                    .map(|keyed_account| Account {
+                        lamports: pre_account.lamports,
+                        data: pre_account.data.clone(),
+                        owner: pre_account.owner,
+                        ..keyed_account.account.clone()
+                    })
+                    .ok_or(InstructionError::MissingAccount)?;
+                if !program.executable {
+                    return Err(InstructionError::AccountNotExecutable);
+                }
+                vec![(program.owner, program)]
+            };
+        let frame = self.enter(instruction, keyed_accounts)?;
+
+        let indexes = instruction
+            .accounts
+            .iter()
+            .map(|meta| {
+                keyed_accounts
+                    .iter()
+                    .position(|keyed_account| *keyed_account.unsigned_key() == meta.pubkey)
+                    .map(|index| index as u8)
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or(InstructionError::MissingAccount)?;
+        let callee_accounts = get_subset_unchecked_mut(keyed_accounts, &indexes)?;
+        let pre_total: u128 = frame
+            .pre_accounts
+            .iter()
+            .map(|pre_account| u128::from(pre_account.lamports))
+            .sum();
+
+        let mut callee_keyed_accounts = create_keyed_accounts(&mut executable_accounts);
+        let num_executables = callee_keyed_accounts.len();
+        callee_keyed_accounts.extend(instruction.accounts.iter().zip(callee_accounts).map(
+            |(meta, keyed_account)| {
+                KeyedAccount::new(&meta.pubkey, meta.is_signer, keyed_account.account)
+            },
+        ));
+
+        self.frames.borrow_mut().push(frame);
+        let result = self.message_processor.dispatch(
+            &program_id,
+            num_executables,
+            &mut callee_keyed_accounts,
+            &instruction.data,
+            self,
+        );
+        let frame = self.frames.borrow_mut().pop().unwrap();
+        result?;
+
+        // Verify the callee, then fold its changes into the caller's pre-state
+        let callee_accounts = &callee_keyed_accounts[num_executables..];
+        for (pre_account, keyed_account) in frame.pre_accounts.iter().zip(callee_accounts) {
+            pre_account.verify(&program_id, keyed_account.account)?;
+        }
+        let post_total: u128 = callee_accounts
+            .iter()
+            .map(|keyed_account| u128::from(keyed_account.account.lamports))
+            .sum();
+        if pre_total != post_total {
+            return Err(InstructionError::UnbalancedInstruction);
+        }
+        let mut frames = self.frames.borrow_mut();
+        let caller = frames.last_mut().unwrap();
+        for keyed_account in callee_accounts {
+            if let Some(pre_account) = caller
+                .pre_accounts
+                .iter_mut()
+                .find(|pre_account| pre_account.key == *keyed_account.unsigned_key())
+            {
+                pre_account.update(keyed_account.account);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a> InvokeContext for MessageInvokeContext<'a> {
+    fn invoke(
+        &self,
+        instruction: &Instruction,
+        keyed_accounts: &mut [KeyedAccount],
+    ) -> Result<(), InstructionError> {
+        let result = self.invoke_checked(instruction, keyed_accounts);
+        if let Err(err) = &result {
+            self.error.borrow_mut().get_or_insert_with(|| err.clone());
+        }
+        result
+    }
+
+    fn compute_budget(&self) -> &ComputeBudget {
+        &self.message_processor.compute_budget
+    }
+
+    fn consume_compute_units(&self, units: u64) -> Result<(), InstructionError> {
+        let remaining = self.compute_meter.get();
+        if units > remaining {
+            self.compute_meter.set(0);
+            return Err(InstructionError::ComputationalBudgetExceeded);
+        }
+        self.compute_meter.set(remaining - units);
+        Ok(())
+    }
+
+    fn remaining_compute_units(&self) -> u64 {
+        self.compute_meter.get()
+    }
+
+    fn log(&self, message: &str) {
+        self.log_messages.borrow_mut().push(message.to_string());
+    }
+}
+
 pub type ProcessInstruction =
     fn(&Pubkey, &mut [KeyedAccount], &[u8]) -> Result<(), InstructionError>;
 
-pub type SymbolCache = RwLock<HashMap<Vec<u8>, Symbol<instruction_processor_utils::Entrypoint>>>;
+pub type SymbolCache =
+    RwLock<HashMap<Vec<u8>, Symbol<instruction_processor_utils::ContextEntrypoint>>>;
 
 #[derive(Serialize, Deserialize)]
 pub struct MessageProcessor {
     #[serde(skip)]
     instruction_processors: Vec<(Pubkey, ProcessInstruction)>,
     #[serde(skip)]
+    loaders: Vec<(Pubkey, ProcessInstruction)>,
+    #[serde(skip)]
     symbol_cache: SymbolCache,
+    #[serde(skip)]
+    compute_budget: ComputeBudget,
 }
 
 impl Default for MessageProcessor {
@@ -112,7 +398,9 @@ impl Default for MessageProcessor {
 
         Self {
             instruction_processors,
+            loaders: vec![],
             symbol_cache: RwLock::new(HashMap::new()),
+            compute_budget: ComputeBudget::default(),
         }
     }
 }
@@ -128,6 +416,23 @@ impl MessageProcessor {
             .push((program_id, process_instruction));
     }
 
+    /// Add a static entrypoint for the programs owned by `loader_id`, which are otherwise
+    /// loaded dynamically through the loader's native program.
+    pub fn add_loader(&mut self, loader_id: Pubkey, process_instruction: ProcessInstruction) {
+        self.loaders.push((loader_id, process_instruction));
+    }
+
+    /// Meter each transaction against `compute_budget` instead of the default
+    pub fn set_compute_budget(&mut self, compute_budget: ComputeBudget) {
+        self.compute_budget = compute_budget;
+    }
+
+    fn is_instruction_processor(&self, program_id: &Pubkey) -> bool {
+        self.instruction_processors
+            .iter()
+            .any(|(id, _)| id == program_id)
+    }
+
     /// Process an instruction
     /// This method calls the instruction's program entrypoint method
     fn process_instruction(
@@ -136,9 +441,10 @@ impl MessageProcessor {
         instruction: &CompiledInstruction,
         executable_accounts: &mut [(Pubkey, Account)],
         program_accounts: &mut [&mut Account],
+        invoke_context: &dyn InvokeContext,
     ) -> Result<(), InstructionError> {
         let program_id = instruction.program_id(&message.account_keys);
-
+        let num_executables = executable_accounts.len();
         let mut keyed_accounts = create_keyed_accounts(executable_accounts);
         let mut keyed_accounts2: Vec<_> = instruction
             .accounts
@@ -153,21 +459,64 @@ impl MessageProcessor {
             .collect();
         keyed_accounts.append(&mut keyed_accounts2);
 
+        self.dispatch(
+            program_id,
+            num_executables,
+            &mut keyed_accounts,
+            &instruction.data,
+            invoke_context,
+        )
+    }
+
+    /// Call the entrypoint of `program_id`. `keyed_accounts` starts with the program's
+    /// `num_executables` executable accounts, keyed by their loaders, followed by the
+    /// instruction's accounts. Dynamically loaded programs are handed `invoke_context`, which
+    /// is already installed for the ones linked into the runtime
+    fn dispatch(
+        &self,
+        program_id: &Pubkey,
+        num_executables: usize,
+        keyed_accounts: &mut [KeyedAccount],
+        data: &[u8],
+        invoke_context: &dyn InvokeContext,
+    ) -> Result<(), InstructionError> {
         for (id, process_instruction) in &self.instruction_processors {
             if id == program_id {
+                invoke_context::consume_compute_units(
+                    self.compute_budget.native_instruction_units,
+                )?;
+                return process_instruction(&program_id, &mut keyed_accounts[1..], data);
+            }
+        }
+
+        // The last executable account, if any, is the program itself, keyed by its loader
+        let program_index = num_executables.saturating_sub(1);
+        let loader_id = if num_executables > 0 {
+            Some(*keyed_accounts[program_index].unsigned_key())
+        } else {
+            None
+        };
+        for (id, process_instruction) in &self.loaders {
+            if Some(*id) == loader_id {
                 return process_instruction(
                     &program_id,
-                    &mut keyed_accounts[1..],
-                    &instruction.data,
+                    &mut keyed_accounts[program_index..],
+                    data,
                 );
             }
         }
 
+        // Programs of other loaders are metered by their loader, as BPF programs are by their
+        // VM, and native programs by the instruction
+        if num_executables <= 1 {
+            invoke_context::consume_compute_units(self.compute_budget.native_instruction_units)?;
+        }
         native_loader::entrypoint(
             &program_id,
-            &mut keyed_accounts,
-            &instruction.data,
+            keyed_accounts,
+            data,
             &self.symbol_cache,
+            invoke_context,
         )
     }
 
@@ -182,6 +531,8 @@ impl MessageProcessor {
         executable_accounts: &mut [(Pubkey, Account)],
         program_accounts: &mut [&mut Account],
         credits: &mut [&mut LamportCredit],
+        compute_meter: &Cell<u64>,
+        log_messages: &RefCell<Vec<String>>,
     ) -> Result<(), InstructionError> {
         let program_id = instruction.program_id(&message.account_keys);
         // TODO: the runtime should be checking read/write access to memory
@@ -190,31 +541,53 @@ impl MessageProcessor {
             .iter()
             .map(|a| u128::from(a.lamports))
             .sum();
-        let pre_data: Vec<_> = program_accounts
-            .iter_mut()
-            .map(|a| (a.owner, a.lamports, a.data.clone()))
+        let pre_accounts: Vec<_> = instruction
+            .accounts
+            .iter()
+            .zip(program_accounts.iter())
+            .map(|(&index, account)| {
+                let index = index as usize;
+                PreAccount::new(
+                    &message.account_keys[index],
+                    index < message.header.num_required_signatures as usize,
+                    message.is_debitable(index),
+                    account,
+                )
+            })
             .collect();
+        let pre_lamports: Vec<_> = program_accounts.iter().map(|a| a.lamports).collect();
 
-        self.process_instruction(message, instruction, executable_accounts, program_accounts)?;
-        // Verify the instruction
-        for ((pre_program_id, pre_lamports, pre_data), (i, post_account, is_debitable)) in
-            pre_data.iter().zip(
-                program_accounts
-                    .iter()
-                    .enumerate()
-                    .map(|(i, program_account)| (i, program_account, message.is_debitable(i))),
+        let invoke_context =
+            MessageInvokeContext::new(self, program_id, pre_accounts, compute_meter, log_messages);
+        let result = with_invoke_context(&invoke_context, || {
+            self.process_instruction(
+                message,
+                instruction,
+                executable_accounts,
+                program_accounts,
+                &invoke_context,
             )
+        });
+        let (mut frames, invoke_error) = invoke_context.into_inner();
+        // An invoked instruction that failed fails the whole instruction, even if its caller
+        // carried on
+        if let Some(err) = invoke_error {
+            return Err(err);
+        }
+        result?;
+
+        // Verify the instruction. The pre-state reflects the changes of any instructions the
+        // program invoked, which were verified on their return
+        let frame = frames.pop().unwrap();
+        for (i, (pre_account, post_account)) in frame
+            .pre_accounts
+            .iter()
+            .zip(program_accounts.iter())
+            .enumerate()
         {
-            verify_instruction(
-                is_debitable,
-                &program_id,
-                pre_program_id,
-                *pre_lamports,
-                pre_data,
-                post_account,
-            )?;
-            if !is_debitable {
-                *credits[i] += post_account.lamports - *pre_lamports;
+            pre_account.verify(&program_id, post_account)?;
+            if !pre_account.is_debitable {
+                *credits[i] += post_account.lamports - pre_lamports[i];
             }
         }
         // The total sum of all the lamports in all the accounts cannot change.
@@ -231,12 +604,42 @@ impl MessageProcessor {
     /// Process a message.
     /// This method calls each instruction in the message over the set of loaded Accounts
     /// The accounts are committed back to the bank only if every instruction succeeds
+    /// The units the message consumed from its compute budget are left in
+    /// `compute_units_consumed`, and the messages its programs logged in `log_messages`,
+    /// whether or not it succeeds
     pub fn process_message(
         &self,
         message: &Message,
         loaders: &mut [Vec<(Pubkey, Account)>],
         accounts: &mut [Account],
         credits: &mut [LamportCredit],
+        compute_units_consumed: &mut u64,
+        log_messages: &mut Vec<String>,
+    ) -> Result<(), TransactionError> {
+        // Every instruction draws from the one budget
+        let compute_meter = Cell::new(self.compute_budget.max_units);
+        let log_collector = RefCell::new(vec![]);
+        let result = self.process_instructions(
+            message,
+            loaders,
+            accounts,
+            credits,
+            &compute_meter,
+            &log_collector,
+        );
+        *compute_units_consumed = self.compute_budget.max_units - compute_meter.get();
+        *log_messages = log_collector.into_inner();
+        result
+    }
+
+    fn process_instructions(
+        &self,
+        message: &Message,
+        loaders: &mut [Vec<(Pubkey, Account)>],
+        accounts: &mut [Account],
+        credits: &mut [LamportCredit],
+        compute_meter: &Cell<u64>,
+        log_messages: &RefCell<Vec<String>>,
     ) -> Result<(), TransactionError> {
         for (instruction_index, instruction) in message.instructions.iter().enumerate() {
             let executable_index = message
@@ -256,6 +659,8 @@ impl MessageProcessor {
                 executable_accounts,
                 &mut program_accounts,
                 &mut instruction_credits,
+                compute_meter,
+                log_messages,
             )
             .map_err(|err| TransactionError::InstructionError(instruction_index as u8, err))?;
         }
@@ -267,8 +672,10 @@ impl MessageProcessor {
 mod tests {
     use super::*;
     use solana_sdk::instruction::{AccountMeta, Instruction, InstructionError};
+    use solana_sdk::invoke_context::invoke;
     use solana_sdk::message::Message;
     use solana_sdk::native_loader::{create_loadable_account, id};
+    use solana_sdk::system_instruction::{self, SystemError};
 
     #[test]
     fn test_has_duplicates() {
@@ -462,8 +869,14 @@ mod tests {
         )]);
         let mut deltas = vec![0, 0];
 
-        let result =
-            message_processor.process_message(&message, &mut loaders, &mut accounts, &mut deltas);
+        let result = message_processor.process_message(
+            &message,
+            &mut loaders,
+            &mut accounts,
+            &mut deltas,
+            &mut 0,
+            &mut vec![],
+        );
         assert_eq!(result, Ok(()));
         assert_eq!(accounts[0].lamports, 50);
         assert_eq!(accounts[1].lamports, 50);
@@ -476,8 +889,14 @@ mod tests {
         )]);
         let mut deltas = vec![0, 0];
 
-        let result =
-            message_processor.process_message(&message, &mut loaders, &mut accounts, &mut deltas);
+        let result = message_processor.process_message(
+            &message,
+            &mut loaders,
+            &mut accounts,
+            &mut deltas,
+            &mut 0,
+            &mut vec![],
+        );
         assert_eq!(
             result,
             Err(TransactionError::InstructionError(
@@ -493,8 +912,14 @@ mod tests {
         )]);
         let mut deltas = vec![0, 0];
 
-        let result =
-            message_processor.process_message(&message, &mut loaders, &mut accounts, &mut deltas);
+        let result = message_processor.process_message(
+            &message,
+            &mut loaders,
+            &mut accounts,
+            &mut deltas,
+            &mut 0,
+            &mut vec![],
+        );
         assert_eq!(
             result,
             Err(TransactionError::InstructionError(
@@ -503,4 +928,398 @@ mod tests {
             ))
         );
     }
+
+    #[test]
+    fn test_process_message_invoke() {
+        #[derive(Serialize, Deserialize)]
+        enum MockInvokeInstruction {
+            Transfer { lamports: u64 },
+            TransferFromPayee { lamports: u64 },
+            TransferToUnknown { lamports: u64 },
+            IgnoreError { lamports: u64 },
+            Recurse,
+        }
+
+        fn mock_invoke_process_instruction(
+            program_id: &Pubkey,
+            keyed_accounts: &mut [KeyedAccount],
+            data: &[u8],
+        ) -> Result<(), InstructionError> {
+            let from_pubkey = *keyed_accounts[0].unsigned_key();
+            let to_pubkey = *keyed_accounts[1].unsigned_key();
+            match bincode::deserialize(data)
+                .map_err(|_| InstructionError::InvalidInstructionData)?
+            {
+                MockInvokeInstruction::Transfer { lamports } => invoke(
+                    &system_instruction::transfer(&from_pubkey, &to_pubkey, lamports),
+                    keyed_accounts,
+                ),
+                MockInvokeInstruction::TransferFromPayee { lamports } => invoke(
+                    &system_instruction::transfer(&to_pubkey, &from_pubkey, lamports),
+                    keyed_accounts,
+                ),
+                MockInvokeInstruction::TransferToUnknown { lamports } => invoke(
+                    &system_instruction::transfer(&from_pubkey, &Pubkey::new_rand(), lamports),
+                    keyed_accounts,
+                ),
+                MockInvokeInstruction::IgnoreError { lamports } => {
+                    let _ = invoke(
+                        &system_instruction::transfer(&from_pubkey, &to_pubkey, lamports),
+                        keyed_accounts,
+                    );
+                    Ok(())
+                }
+                MockInvokeInstruction::Recurse => invoke(
+                    &Instruction::new(
+                        *program_id,
+                        &MockInvokeInstruction::Recurse,
+                        vec![
+                            AccountMeta::new(from_pubkey, true),
+                            AccountMeta::new(to_pubkey, false),
+                        ],
+                    ),
+                    keyed_accounts,
+                ),
+            }
+        }
+
+        let mock_invoke_program_id = Pubkey::new(&[3u8; 32]);
+        let mut message_processor = MessageProcessor::default();
+        message_processor
+            .add_instruction_processor(mock_invoke_program_id, mock_invoke_process_instruction);
+
+        let mut loaders: Vec<Vec<(Pubkey, Account)>> = Vec::new();
+        let account = create_loadable_account("mock_invoke_program");
+        loaders.push(vec![(id(), account)]);
+
+        let from_pubkey = Pubkey::new_rand();
+        let to_pubkey = Pubkey::new_rand();
+        let account_metas = vec![
+            AccountMeta::new(from_pubkey, true),
+            AccountMeta::new(to_pubkey, false),
+        ];
+        let process = |mock_instruction: MockInvokeInstruction, accounts: &mut Vec<Account>| {
+            let message = Message::new(vec![Instruction::new(
+                mock_invoke_program_id,
+                &mock_instruction,
+                account_metas.clone(),
+            )]);
+            message_processor
+                .process_message(
+                    &message,
+                    &mut loaders.clone(),
+                    accounts,
+                    &mut [0, 0],
+                    &mut 0,
+                    &mut vec![],
+                )
+                .map_err(|err| match err {
+                    TransactionError::InstructionError(0, err) => err,
+                    err => panic!("unexpected error {:?}", err),
+                })
+        };
+        let new_accounts = || {
+            vec![
+                Account::new(100, 0, &system_program::id()),
+                Account::new(0, 0, &system_program::id()),
+            ]
+        };
+
+        let mut accounts = new_accounts();
+        assert_eq!(
+            process(
+                MockInvokeInstruction::Transfer { lamports: 50 },
+                &mut accounts
+            ),
+            Ok(())
+        );
+        assert_eq!(accounts[0].lamports, 50);
+        assert_eq!(accounts[1].lamports, 50);
+
+        // The payee wasn't given to the caller as a signer, so it can't be passed on as one
+        assert_eq!(
+            process(
+                MockInvokeInstruction::TransferFromPayee { lamports: 50 },
+                &mut accounts
+            ),
+            Err(InstructionError::PrivilegeEscalation)
+        );
+        assert_eq!(
+            process(
+                MockInvokeInstruction::TransferToUnknown { lamports: 50 },
+                &mut accounts
+            ),
+            Err(InstructionError::MissingAccount)
+        );
+        assert_eq!(
+            process(MockInvokeInstruction::Recurse, &mut accounts),
+            Err(InstructionError::CallDepthExceeded)
+        );
+
+        // A failed invocation fails the caller even when it ignores the error
+        assert_eq!(
+            process(
+                MockInvokeInstruction::IgnoreError { lamports: 1000 },
+                &mut accounts
+            ),
+            Err(InstructionError::CustomError(
+                SystemError::ResultWithNegativeLamports as u32
+            ))
+        );
+    }
+
+    #[test]
+    fn test_process_message_invoke_rewritten_program() {
+        #[derive(Serialize, Deserialize)]
+        enum MockLoaderInstruction {
+            /// Rewrite the program the loader owns, invoke it and restore it
+            InvokeRewritten,
+            /// Run as the loaded program
+            Run,
+        }
+
+        fn mock_loader_process_instruction(
+            _program_id: &Pubkey,
+            keyed_accounts: &mut [KeyedAccount],
+            data: &[u8],
+        ) -> Result<(), InstructionError> {
+            match bincode::deserialize(data)
+                .map_err(|_| InstructionError::InvalidInstructionData)?
+            {
+                MockLoaderInstruction::InvokeRewritten => {
+                    let program_pubkey = *keyed_accounts[0].unsigned_key();
+                    keyed_accounts[0].account.data = b"evil".to_vec();
+                    let result = invoke(
+                        &Instruction::new(program_pubkey, &MockLoaderInstruction::Run, vec![]),
+                        keyed_accounts,
+                    );
+                    keyed_accounts[0].account.data = b"good".to_vec();
+                    result
+                }
+                MockLoaderInstruction::Run => {
+                    if keyed_accounts[0].account.data == b"good" {
+                        Ok(())
+                    } else {
+                        Err(InstructionError::InvalidAccountData)
+                    }
+                }
+            }
+        }
+
+        let mock_loader_id = Pubkey::new(&[5u8; 32]);
+        let mut message_processor = MessageProcessor::default();
+        message_processor
+            .add_instruction_processor(mock_loader_id, mock_loader_process_instruction);
+        message_processor.add_loader(mock_loader_id, mock_loader_process_instruction);
+
+        let mut loaders: Vec<Vec<(Pubkey, Account)>> = Vec::new();
+        loaders.push(vec![(id(), create_loadable_account("mock_loader"))]);
+        let mut program = Account::new(1, 0, &mock_loader_id);
+        program.data = b"good".to_vec();
+        program.executable = true;
+        let mut accounts = vec![program];
+
+        // The invoked program runs the code it held before its loader rewrote it
+        let message = Message::new(vec![Instruction::new(
+            mock_loader_id,
+            &MockLoaderInstruction::InvokeRewritten,
+            vec![AccountMeta::new(Pubkey::new_rand(), false)],
+        )]);
+        assert_eq!(
+            message_processor.process_message(
+                &message,
+                &mut loaders,
+                &mut accounts,
+                &mut [0],
+                &mut 0,
+                &mut vec![],
+            ),
+            Ok(())
+        );
+        assert_eq!(accounts[0].data, b"good");
+    }
+
+    #[test]
+    fn test_process_message_compute_budget() {
+        #[derive(Serialize, Deserialize)]
+        enum MockMeteredInstruction {
+            Noop,
+            Transfer { lamports: u64 },
+        }
+
+        fn mock_metered_process_instruction(
+            _program_id: &Pubkey,
+            keyed_accounts: &mut [KeyedAccount],
+            data: &[u8],
+        ) -> Result<(), InstructionError> {
+            match bincode::deserialize(data)
+                .map_err(|_| InstructionError::InvalidInstructionData)?
+            {
+                MockMeteredInstruction::Noop => Ok(()),
+                MockMeteredInstruction::Transfer { lamports } => {
+                    let from_pubkey = *keyed_accounts[0].unsigned_key();
+                    let to_pubkey = *keyed_accounts[1].unsigned_key();
+                    invoke(
+                        &system_instruction::transfer(&from_pubkey, &to_pubkey, lamports),
+                        keyed_accounts,
+                    )
+                }
+            }
+        }
+
+        let mock_metered_program_id = Pubkey::new(&[4u8; 32]);
+        let mut message_processor = MessageProcessor::default();
+        message_processor
+            .add_instruction_processor(mock_metered_program_id, mock_metered_process_instruction);
+        let compute_budget = ComputeBudget {
+            max_units: 2_500,
+            native_instruction_units: 1_000,
+            invoke_units: 1_000,
+            ..ComputeBudget::default()
+        };
+        message_processor.set_compute_budget(compute_budget);
+
+        let mut loaders: Vec<Vec<(Pubkey, Account)>> = Vec::new();
+        let account = create_loadable_account("mock_metered_program");
+        loaders.push(vec![(id(), account)]);
+
+        let from_pubkey = Pubkey::new_rand();
+        let to_pubkey = Pubkey::new_rand();
+        let account_metas = vec![
+            AccountMeta::new(from_pubkey, true),
+            AccountMeta::new(to_pubkey, false),
+        ];
+        let process = |instructions: Vec<MockMeteredInstruction>| {
+            let message = Message::new(
+                instructions
+                    .iter()
+                    .map(|instruction| {
+                        Instruction::new(
+                            mock_metered_program_id,
+                            instruction,
+                            account_metas.clone(),
+                        )
+                    })
+                    .collect(),
+            );
+            let mut accounts = vec![
+                Account::new(100, 0, &system_program::id()),
+                Account::new(0, 0, &system_program::id()),
+            ];
+            let mut compute_units_consumed = 0;
+            let result = message_processor.process_message(
+                &message,
+                &mut loaders.clone(),
+                &mut accounts,
+                &mut [0, 0],
+                &mut compute_units_consumed,
+                &mut vec![],
+            );
+            (result, compute_units_consumed)
+        };
+
+        // Each transaction is metered from a full budget
+        for _ in 0..2 {
+            assert_eq!(
+                process(vec![
+                    MockMeteredInstruction::Noop,
+                    MockMeteredInstruction::Noop
+                ]),
+                (Ok(()), 2_000)
+            );
+        }
+        assert_eq!(
+            process(vec![
+                MockMeteredInstruction::Noop,
+                MockMeteredInstruction::Noop,
+                MockMeteredInstruction::Noop
+            ]),
+            (
+                Err(TransactionError::InstructionError(
+                    2,
+                    InstructionError::ComputationalBudgetExceeded
+                )),
+                2_500
+            )
+        );
+
+        // The invocation and the invoked system instruction are charged too
+        assert_eq!(
+            process(vec![MockMeteredInstruction::Transfer { lamports: 50 }]),
+            (
+                Err(TransactionError::InstructionError(
+                    0,
+                    InstructionError::ComputationalBudgetExceeded
+                )),
+                2_500
+            )
+        );
+    }
+
+    #[test]
+    fn test_process_message_log_messages() {
+        fn mock_logging_process_instruction(
+            _program_id: &Pubkey,
+            _keyed_accounts: &mut [KeyedAccount],
+            data: &[u8],
+        ) -> Result<(), InstructionError> {
+            let fail: bool = bincode::deserialize(data).unwrap();
+            invoke_context::log(&format!("fail: {}", fail));
+            if fail {
+                Err(InstructionError::GenericError)
+            } else {
+                Ok(())
+            }
+        }
+
+        let mock_logging_program_id = Pubkey::new(&[5u8; 32]);
+        let mut message_processor = MessageProcessor::default();
+        message_processor
+            .add_instruction_processor(mock_logging_program_id, mock_logging_process_instruction);
+
+        let mut loaders: Vec<Vec<(Pubkey, Account)>> = Vec::new();
+        let account = create_loadable_account("mock_logging_program");
+        loaders.push(vec![(id(), account)]);
+
+        let account_metas = vec![AccountMeta::new(Pubkey::new_rand(), true)];
+        let process = |fails: Vec<bool>| {
+            let message = Message::new(
+                fails
+                    .iter()
+                    .map(|fail| {
+                        Instruction::new(mock_logging_program_id, fail, account_metas.clone())
+                    })
+                    .collect(),
+            );
+            let mut log_messages = vec![];
+            let result = message_processor.process_message(
+                &message,
+                &mut loaders.clone(),
+                &mut [Account::new(100, 0, &system_program::id())],
+                &mut [0],
+                &mut 0,
+                &mut log_messages,
+            );
+            (result, log_messages)
+        };
+
+        // Every instruction's messages are collected, including those of the one that failed
+        assert_eq!(
+            process(vec![false, false]),
+            (
+                Ok(()),
+                vec!["fail: false".to_string(), "fail: false".to_string()]
+            )
+        );
+        assert_eq!(
+            process(vec![false, true]),
+            (
+                Err(TransactionError::InstructionError(
+                    1,
+                    InstructionError::GenericError
+                )),
+                vec!["fail: false".to_string(), "fail: true".to_string()]
+            )
+        );
+    }
 }
@@ -9,6 +9,7 @@ use log::*;
 use solana_sdk::account::KeyedAccount;
 use solana_sdk::instruction::InstructionError;
 use solana_sdk::instruction_processor_utils;
+use solana_sdk::invoke_context::InvokeContext;
 use solana_sdk::loader_instruction::LoaderInstruction;
 use solana_sdk::pubkey::Pubkey;
 use std::env;
@@ -71,6 +72,7 @@ pub fn entrypoint(
     keyed_accounts: &mut [KeyedAccount],
     ix_data: &[u8],
     symbol_cache: &SymbolCache,
+    invoke_context: &dyn InvokeContext,
 ) -> Result<(), InstructionError> {
     if keyed_accounts[0].account.executable {
         // dispatch it
@@ -78,7 +80,7 @@ pub fn entrypoint(
         let name_vec = &names[0].account.data;
         if let Some(entrypoint) = symbol_cache.read().unwrap().get(name_vec) {
             unsafe {
-                return entrypoint(program_id, params, ix_data);
+                return entrypoint(program_id, params, ix_data, invoke_context);
             }
         }
         let name = match str::from_utf8(name_vec) {
@@ -92,19 +94,19 @@ pub fn entrypoint(
         let path = create_path(&name);
         match library_open(&path) {
             Ok(library) => unsafe {
-                let entrypoint: Symbol<instruction_processor_utils::Entrypoint> =
-                    match library.get(instruction_processor_utils::ENTRYPOINT.as_bytes()) {
+                let entrypoint: Symbol<instruction_processor_utils::ContextEntrypoint> =
+                    match library.get(instruction_processor_utils::CONTEXT_ENTRYPOINT.as_bytes()) {
                         Ok(s) => s,
                         Err(e) => {
                             warn!(
                                 "{:?}: Unable to find {:?} in program",
                                 e,
-                                instruction_processor_utils::ENTRYPOINT
+                                instruction_processor_utils::CONTEXT_ENTRYPOINT
                             );
                             return Err(InstructionError::GenericError);
                         }
                     };
-                let ret = entrypoint(program_id, params, ix_data);
+                let ret = entrypoint(program_id, params, ix_data, invoke_context);
                 symbol_cache
                     .write()
                     .unwrap()
@@ -0,0 +1,154 @@
+//! Helpers for transactions that name a durable nonce, rather than a blockhash still in the
+//! Bank's queue, as their recent blockhash
+
+use crate::blockhash_queue::BlockhashQueue;
+use solana_sdk::account::Account;
+use solana_sdk::account_utils::State;
+use solana_sdk::fee_calculator::FeeCalculator;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::CompiledInstruction;
+use solana_sdk::nonce_state::NonceState;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_instruction::SystemInstruction;
+use solana_sdk::system_program;
+use solana_sdk::transaction::Transaction;
+
+/// The `NonceAdvance` instruction of `tx`, if `tx` begins with one
+pub fn transaction_uses_durable_nonce(tx: &Transaction) -> Option<&CompiledInstruction> {
+    let message = tx.message();
+    message.instructions.get(0).filter(|instruction| {
+        message
+            .account_keys
+            .get(instruction.program_ids_index as usize)
+            .map_or(false, system_program::check_id)
+            && match bincode::deserialize(&instruction.data) {
+                Ok(SystemInstruction::NonceAdvance) => true,
+                _ => false,
+            }
+    })
+}
+
+/// The nonce account that `instruction`, a `NonceAdvance` of `tx`, advances
+pub fn get_nonce_pubkey_from_instruction<'a>(
+    instruction: &CompiledInstruction,
+    tx: &'a Transaction,
+) -> Option<&'a Pubkey> {
+    instruction
+        .accounts
+        .get(0)
+        .and_then(|index| tx.message().account_keys.get(*index as usize))
+}
+
+/// Check that `account` is an initialized nonce account storing `hash`
+pub fn verify_nonce(account: &Account, hash: &Hash) -> bool {
+    if !system_program::check_id(&account.owner) {
+        return false;
+    }
+    match account.state() {
+        Ok(NonceState::Initialized(_, ref stored_hash)) => stored_hash == hash,
+        _ => false,
+    }
+}
+
+/// Check that the authority of the nonce in `account` signed `tx`
+pub fn verify_nonce_authority_signed(account: &Account, tx: &Transaction) -> bool {
+    match account.state() {
+        Ok(NonceState::Initialized(meta, _)) => {
+            let message = tx.message();
+            message
+                .account_keys
+                .iter()
+                .take(message.header.num_required_signatures as usize)
+                .any(|key| *key == meta.nonce_authority)
+        }
+        _ => false,
+    }
+}
+
+/// The fee calculator for `tx`: that of its recent blockhash or, since a durable nonce may
+/// outlive every blockhash in the queue, that of the last blockhash for a nonce transaction
+pub fn fee_calculator_for_transaction<'a>(
+    hash_queue: &'a BlockhashQueue,
+    tx: &Transaction,
+) -> Option<&'a FeeCalculator> {
+    hash_queue
+        .get_fee_calculator(&tx.message().recent_blockhash)
+        .or_else(|| {
+            transaction_uses_durable_nonce(tx)
+                .and_then(|_| hash_queue.get_fee_calculator(&hash_queue.last_hash()))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::message::Message;
+    use solana_sdk::nonce_state::Meta;
+    use solana_sdk::signature::{Keypair, KeypairUtil};
+    use solana_sdk::system_instruction;
+
+    #[test]
+    fn test_transaction_uses_durable_nonce() {
+        let from_keypair = Keypair::new();
+        let nonce_pubkey = Pubkey::new_rand();
+        let to_pubkey = Pubkey::new_rand();
+        let advance = system_instruction::nonce_advance(&nonce_pubkey, &from_keypair.pubkey());
+        let transfer = system_instruction::transfer(&from_keypair.pubkey(), &to_pubkey, 42);
+
+        let message = Message::new(vec![advance.clone(), transfer.clone()]);
+        let tx = Transaction::new(&[&from_keypair], message, Hash::default());
+        let instruction = transaction_uses_durable_nonce(&tx).unwrap();
+        assert_eq!(
+            get_nonce_pubkey_from_instruction(instruction, &tx),
+            Some(&nonce_pubkey)
+        );
+
+        // The advance must come first
+        let message = Message::new(vec![transfer, advance]);
+        let tx = Transaction::new(&[&from_keypair], message, Hash::default());
+        assert!(transaction_uses_durable_nonce(&tx).is_none());
+    }
+
+    #[test]
+    fn test_verify_nonce() {
+        let hash = Hash::new(&[1; 32]);
+        let mut account = Account::new(1, NonceState::size(), &system_program::id());
+        assert!(!verify_nonce(&account, &hash));
+
+        account
+            .set_state(&NonceState::Initialized(Meta::default(), hash))
+            .unwrap();
+        assert!(verify_nonce(&account, &hash));
+        assert!(!verify_nonce(&account, &Hash::default()));
+
+        account.owner = Pubkey::new_rand();
+        assert!(!verify_nonce(&account, &hash));
+    }
+
+    #[test]
+    fn test_verify_nonce_authority_signed() {
+        let authority_keypair = Keypair::new();
+        let nonce_pubkey = Pubkey::new_rand();
+        let mut account = Account::new(1, NonceState::size(), &system_program::id());
+        let advance = system_instruction::nonce_advance(&nonce_pubkey, &authority_keypair.pubkey());
+        let message = Message::new(vec![advance]);
+        let tx = Transaction::new(&[&authority_keypair], message, Hash::default());
+        assert!(!verify_nonce_authority_signed(&account, &tx));
+
+        account
+            .set_state(&NonceState::Initialized(
+                Meta::new(&authority_keypair.pubkey()),
+                Hash::default(),
+            ))
+            .unwrap();
+        assert!(verify_nonce_authority_signed(&account, &tx));
+
+        account
+            .set_state(&NonceState::Initialized(
+                Meta::new(&Pubkey::new_rand()),
+                Hash::default(),
+            ))
+            .unwrap();
+        assert!(!verify_nonce_authority_signed(&account, &tx));
+    }
+}
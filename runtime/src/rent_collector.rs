@@ -0,0 +1,74 @@
+//! The `rent_collector` module charges accounts the rent they owe for their storage
+use solana_sdk::account::Account;
+use solana_sdk::syscall::rent::Rent;
+use solana_sdk::timing::Epoch;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default, Debug, PartialEq)]
+pub struct RentCollector {
+    /// The epoch rent is collected through
+    pub epoch: Epoch,
+    pub rent: Rent,
+}
+
+impl RentCollector {
+    pub fn new(epoch: Epoch, rent: &Rent) -> Self {
+        Self { epoch, rent: *rent }
+    }
+
+    pub fn clone_with_epoch(&self, epoch: Epoch) -> Self {
+        Self { epoch, ..*self }
+    }
+
+    /// Charge `account` the rent it owes for the epochs since it last paid, returning the
+    /// lamports collected.  An account that cannot pay in full is emptied
+    pub fn update(&self, account: &mut Account) -> u64 {
+        if account.executable || account.rent_epoch >= self.epoch {
+            return 0;
+        }
+        let epochs = self.epoch - account.rent_epoch;
+        account.rent_epoch = self.epoch;
+
+        let due = self
+            .rent
+            .due(account.lamports, account.data.len(), epochs)
+            .min(account.lamports);
+        account.lamports -= due;
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+    use solana_sdk::syscall::rent::ACCOUNT_STORAGE_OVERHEAD;
+
+    #[test]
+    fn test_rent_collector_update() {
+        let rent_collector = RentCollector::new(3, &Rent::new(1, 10));
+        let per_epoch = ACCOUNT_STORAGE_OVERHEAD;
+
+        let mut account = Account::new(per_epoch * 5, 0, &Pubkey::default());
+        assert_eq!(rent_collector.update(&mut account), per_epoch * 3);
+        assert_eq!(account.lamports, per_epoch * 2);
+        assert_eq!(account.rent_epoch, 3);
+
+        // Rent is only collected once per epoch
+        assert_eq!(rent_collector.update(&mut account), 0);
+
+        // Accounts that cannot pay are emptied
+        let rent_collector = rent_collector.clone_with_epoch(6);
+        assert_eq!(rent_collector.update(&mut account), per_epoch * 2);
+        assert_eq!(account.lamports, 0);
+
+        // Exempt accounts pay nothing but are brought up to date
+        let mut account = Account::new(per_epoch * 10, 0, &Pubkey::default());
+        assert_eq!(rent_collector.update(&mut account), 0);
+        assert_eq!(account.rent_epoch, 6);
+
+        let mut account = Account::new(1, 0, &Pubkey::default());
+        account.executable = true;
+        assert_eq!(rent_collector.update(&mut account), 0);
+        assert_eq!(account.rent_epoch, 0);
+    }
+}
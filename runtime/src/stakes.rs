@@ -2,7 +2,8 @@
 //! node stakes
 use solana_sdk::account::Account;
 use solana_sdk::pubkey::Pubkey;
-use solana_stake_api::stake_state::StakeState;
+use solana_sdk::syscall::stake_history::{Epoch, StakeHistoryEntry};
+use solana_stake_api::stake_state::{StakeState, STAKE_WARMUP_EPOCHS};
 use solana_vote_api::vote_state::VoteState;
 use std::collections::HashMap;
 
@@ -145,6 +146,26 @@ impl Stakes {
             .map(|vote_state| vote_state.node_pubkey)
     }
 
+    /// sum up the stake delegated through the stake accounts as of the given epoch, split
+    ///  by whether it is warming up, effective, or cooling down
+    pub fn stake_history_entry(&self, epoch: Epoch) -> StakeHistoryEntry {
+        self.stake_accounts
+            .values()
+            .filter_map(StakeState::stake_from)
+            .fold(StakeHistoryEntry::default(), |mut entry, stake| {
+                let effective = stake.stake(epoch);
+                entry.effective += effective;
+                if epoch >= stake.activated && epoch < stake.deactivated {
+                    if epoch - stake.activated < STAKE_WARMUP_EPOCHS {
+                        entry.activating += stake.stake - effective;
+                    } else if stake.deactivated - epoch < STAKE_WARMUP_EPOCHS {
+                        entry.deactivating += effective;
+                    }
+                }
+                entry
+            })
+    }
+
     /// currently unclaimed points
     pub fn points(&self) -> u64 {
         self.points
@@ -161,6 +182,7 @@ impl Stakes {
 #[cfg(test)]
 pub mod tests {
     use super::*;
+    use solana_sdk::account_utils::State;
     use solana_sdk::pubkey::Pubkey;
     use solana_stake_api::stake_state::{self, STAKE_WARMUP_EPOCHS};
     use solana_vote_api::vote_state::{self, VoteState, MAX_LOCKOUT_HISTORY};
@@ -183,6 +205,50 @@ pub mod tests {
         )
     }
 
+    #[test]
+    fn test_stakes_stake_history_entry() {
+        let mut stakes = Stakes::default();
+        let ((vote_pubkey, vote_account), (stake_pubkey, stake_account)) =
+            create_staked_node_accounts(STAKE_WARMUP_EPOCHS * 10);
+        stakes.store(&vote_pubkey, &vote_account);
+        stakes.store(&stake_pubkey, &stake_account);
+
+        for epoch in 0..STAKE_WARMUP_EPOCHS {
+            let effective = 10 * (epoch + 1);
+            assert_eq!(
+                stakes.stake_history_entry(epoch),
+                StakeHistoryEntry {
+                    effective,
+                    activating: STAKE_WARMUP_EPOCHS * 10 - effective,
+                    deactivating: 0,
+                }
+            );
+        }
+        assert_eq!(
+            stakes.stake_history_entry(STAKE_WARMUP_EPOCHS),
+            StakeHistoryEntry {
+                effective: STAKE_WARMUP_EPOCHS * 10,
+                ..StakeHistoryEntry::default()
+            }
+        );
+
+        // cool down from epoch 10
+        let mut stake = StakeState::stake_from(&stake_account).unwrap();
+        stake.deactivated = 10;
+        let mut stake_account = stake_account.clone();
+        stake_account.set_state(&StakeState::Stake(stake)).unwrap();
+        stakes.store(&stake_pubkey, &stake_account);
+        assert_eq!(
+            stakes.stake_history_entry(9),
+            StakeHistoryEntry {
+                effective: 10,
+                activating: 0,
+                deactivating: 10,
+            }
+        );
+        assert_eq!(stakes.stake_history_entry(10), StakeHistoryEntry::default());
+    }
+
     #[test]
     fn test_stakes_basic() {
         for i in 0..STAKE_WARMUP_EPOCHS + 1 {
@@ -174,6 +174,44 @@ impl<T: Serialize + Clone> StatusCache<T> {
         self.roots = self.roots.union(&status_cache.roots).cloned().collect();
     }
 
+    /// A copy holding only the roots up to `fork` and the statuses recorded in forks up to
+    /// it, with the statuses of both caches in the one that is serialized
+    pub fn clone_as_of(&self, fork: ForkId) -> Self {
+        let mut merged: StatusMap<T> = HashMap::new();
+        for cache in &self.cache {
+            for (blockhash, (_, index, sig_map)) in cache {
+                for (sig_slice, fork_status) in sig_map {
+                    let fork_status: ForkStatus<T> = fork_status
+                        .iter()
+                        .filter(|(status_fork, _)| *status_fork <= fork)
+                        .cloned()
+                        .collect();
+                    if let Some(max_fork) = fork_status.iter().map(|(fork, _)| *fork).max() {
+                        let entry =
+                            merged
+                                .entry(*blockhash)
+                                .or_insert((max_fork, *index, HashMap::new()));
+                        entry.0 = std::cmp::max(entry.0, max_fork);
+                        entry
+                            .2
+                            .entry(*sig_slice)
+                            .or_insert_with(Vec::new)
+                            .extend(fork_status);
+                    }
+                }
+            }
+        }
+        Self {
+            cache: vec![HashMap::new(), merged],
+            roots: self
+                .roots
+                .iter()
+                .filter(|root| **root <= fork)
+                .cloned()
+                .collect(),
+        }
+    }
+
     pub fn merge_caches(&mut self) {
         let mut cache = HashMap::new();
         std::mem::swap(&mut cache, &mut self.cache[1]);
@@ -271,6 +309,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_clone_as_of() {
+        let sig = Signature::default();
+        let other_sig = Signature::new(&[1u8; 64]);
+        let mut status_cache = BankStatusCache::default();
+        let blockhash = hash(Hash::default().as_ref());
+        status_cache.insert(&blockhash, &sig, 0, ());
+        status_cache.add_root(0);
+        status_cache.merge_caches();
+        status_cache.insert(&blockhash, &other_sig, 1, ());
+        status_cache.add_root(1);
+        status_cache.insert(&blockhash, &sig, 2, ());
+        status_cache.add_root(2);
+
+        let ancestors = HashMap::new();
+        let clone = status_cache.clone_as_of(1);
+        assert_eq!(
+            clone.get_signature_status(&sig, &blockhash, &ancestors),
+            Some((0, ()))
+        );
+        assert_eq!(
+            clone.get_signature_status(&other_sig, &blockhash, &ancestors),
+            Some((1, ()))
+        );
+        let clone = status_cache.clone_as_of(0);
+        assert_eq!(
+            clone.get_signature_status(&other_sig, &blockhash, &ancestors),
+            None
+        );
+        assert!(!clone.roots.contains(&1));
+
+        // Both caches end up in the one that is serialized
+        let mut buf = vec![];
+        serialize_into(&mut buf, &status_cache.clone_as_of(2)).unwrap();
+        let deserialized: BankStatusCache = deserialize_from(&buf[..]).unwrap();
+        assert_eq!(
+            deserialized.get_signature_status(&other_sig, &blockhash, &ancestors),
+            Some((1, ()))
+        );
+        assert_eq!(
+            deserialized.get_signature_status_slow(&sig, &ancestors),
+            Some((0, ()))
+        );
+    }
+
     #[test]
     fn test_insert_picks_latest_blockhash_fork() {
         let sig = Signature::default();
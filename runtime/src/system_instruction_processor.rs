@@ -1,13 +1,36 @@
 use log::*;
 use solana_sdk::account::KeyedAccount;
+use solana_sdk::account_utils::State;
+use solana_sdk::hash::Hash;
 use solana_sdk::instruction::InstructionError;
+use solana_sdk::nonce_state::{Meta, NonceState};
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::syscall;
+use solana_sdk::syscall::{recent_blockhashes, rent};
 use solana_sdk::system_instruction::{SystemError, SystemInstruction};
 use solana_sdk::system_program;
+use std::collections::HashSet;
 
 const FROM_ACCOUNT_INDEX: usize = 0;
 const TO_ACCOUNT_INDEX: usize = 1;
+const CREATE_BASE_ACCOUNT_INDEX: usize = 2;
+const SEED_BASE_ACCOUNT_INDEX: usize = 1;
+const SEED_TO_ACCOUNT_INDEX: usize = 2;
+const NONCE_ACCOUNT_INDEX: usize = 0;
+const NONCE_TO_ACCOUNT_INDEX: usize = 1;
+
+/// Accounts holding data, such as nonce accounts, are only debited or reassigned by the
+/// instructions of the program that understands that data, never by their keypair alone
+fn verify_source_without_data(keyed_account: &KeyedAccount) -> Result<(), SystemError> {
+    if !keyed_account.account.data.is_empty() {
+        debug!(
+            "account[from] {} holds data and cannot be used as a source",
+            keyed_account.unsigned_key()
+        );
+        Err(SystemError::SourceHasData)?;
+    }
+    Ok(())
+}
 
 fn create_system_account(
     keyed_accounts: &mut [KeyedAccount],
@@ -22,6 +45,7 @@ fn create_system_account(
         );
         Err(SystemError::SourceNotSystemAccount)?;
     }
+    verify_source_without_data(&keyed_accounts[FROM_ACCOUNT_INDEX])?;
 
     if !keyed_accounts[TO_ACCOUNT_INDEX].account.data.is_empty()
         || !system_program::check_id(&keyed_accounts[TO_ACCOUNT_INDEX].account.owner)
@@ -64,10 +88,40 @@ fn create_system_account(
     Ok(())
 }
 
+/// Check that `keyed_account` is at the address derived from `base`, `seed` and `program_id`
+fn verify_address_with_seed(
+    keyed_account: &KeyedAccount,
+    base: &Pubkey,
+    seed: &str,
+    program_id: &Pubkey,
+) -> Result<(), SystemError> {
+    if *keyed_account.unsigned_key() != Pubkey::create_with_seed(base, seed, program_id)? {
+        debug!(
+            "address {} does not match base {}, seed {:?} and program id {}",
+            keyed_account.unsigned_key(),
+            base,
+            seed,
+            program_id
+        );
+        Err(SystemError::AddressWithSeedMismatch)?;
+    }
+    Ok(())
+}
+
+/// Check that the base key an address was derived from signed the transaction
+fn verify_base_signer(keyed_account: &KeyedAccount, base: &Pubkey) -> Result<(), InstructionError> {
+    if keyed_account.signer_key() != Some(base) {
+        debug!("base {} is unsigned", base);
+        Err(InstructionError::MissingRequiredSignature)?;
+    }
+    Ok(())
+}
+
 fn assign_account_to_program(
     keyed_accounts: &mut [KeyedAccount],
     program_id: &Pubkey,
 ) -> Result<(), SystemError> {
+    verify_source_without_data(&keyed_accounts[FROM_ACCOUNT_INDEX])?;
     keyed_accounts[FROM_ACCOUNT_INDEX].account.owner = *program_id;
     Ok(())
 }
@@ -75,6 +129,7 @@ fn transfer_lamports(
     keyed_accounts: &mut [KeyedAccount],
     lamports: u64,
 ) -> Result<(), SystemError> {
+    verify_source_without_data(&keyed_accounts[FROM_ACCOUNT_INDEX])?;
     if lamports > keyed_accounts[FROM_ACCOUNT_INDEX].account.lamports {
         debug!(
             "Transfer: insufficient lamports ({}, need {})",
@@ -87,6 +142,163 @@ fn transfer_lamports(
     Ok(())
 }
 
+fn nonce_error(err: SystemError) -> InstructionError {
+    InstructionError::CustomError(err as u32)
+}
+
+fn get_signers(keyed_accounts: &[KeyedAccount]) -> HashSet<Pubkey> {
+    keyed_accounts
+        .iter()
+        .filter_map(|keyed_account| keyed_account.signer_key())
+        .cloned()
+        .collect()
+}
+
+fn verify_nonce_authority(
+    signers: &HashSet<Pubkey>,
+    nonce_authority: &Pubkey,
+) -> Result<(), InstructionError> {
+    if !signers.contains(nonce_authority) {
+        debug!("nonce authority {} is unsigned", nonce_authority);
+        Err(InstructionError::MissingRequiredSignature)?;
+    }
+    Ok(())
+}
+
+/// Reject a malformed nonce instruction given fewer than the `num_accounts` accounts it
+/// indexes
+fn check_nonce_accounts(
+    keyed_accounts: &[KeyedAccount],
+    num_accounts: usize,
+) -> Result<(), InstructionError> {
+    if keyed_accounts.len() < num_accounts {
+        debug!("not enough accounts for nonce instruction");
+        Err(InstructionError::InvalidArgument)?;
+    }
+    Ok(())
+}
+
+/// Check that the instruction was given at least the `num_accounts` accounts it indexes
+fn check_num_accounts(
+    keyed_accounts: &[KeyedAccount],
+    num_accounts: usize,
+) -> Result<(), InstructionError> {
+    if keyed_accounts.len() < num_accounts {
+        debug!("not enough accounts for instruction");
+        Err(InstructionError::NotEnoughAccountKeys)?;
+    }
+    Ok(())
+}
+
+/// The most recent blockhash, which every nonce instruction stores in the nonce account
+fn most_recent_blockhash(keyed_account: &KeyedAccount) -> Result<Hash, InstructionError> {
+    recent_blockhashes::from_keyed_account(keyed_account)?
+        .first()
+        .cloned()
+        .ok_or_else(|| nonce_error(SystemError::NonceNoRecentBlockhashes))
+}
+
+fn nonce_advance(keyed_accounts: &mut [KeyedAccount]) -> Result<(), InstructionError> {
+    check_nonce_accounts(keyed_accounts, 2)?;
+    let recent_blockhash = most_recent_blockhash(&keyed_accounts[1])?;
+    let signers = get_signers(keyed_accounts);
+
+    let meta = match keyed_accounts[NONCE_ACCOUNT_INDEX].state()? {
+        NonceState::Initialized(meta, hash) => {
+            if hash == recent_blockhash {
+                Err(nonce_error(SystemError::NonceBlockhashNotExpired))?;
+            }
+            meta
+        }
+        NonceState::Uninitialized => return Err(InstructionError::UninitializedAccount),
+    };
+    verify_nonce_authority(&signers, &meta.nonce_authority)?;
+    keyed_accounts[NONCE_ACCOUNT_INDEX].set_state(&NonceState::Initialized(meta, recent_blockhash))
+}
+
+fn nonce_withdraw(
+    keyed_accounts: &mut [KeyedAccount],
+    lamports: u64,
+) -> Result<(), InstructionError> {
+    check_nonce_accounts(keyed_accounts, 4)?;
+    let recent_blockhash = most_recent_blockhash(&keyed_accounts[2])?;
+    let rent = rent::from_keyed_account(&keyed_accounts[3])?;
+    let signers = get_signers(keyed_accounts);
+
+    let nonce_account = &keyed_accounts[NONCE_ACCOUNT_INDEX];
+    let balance = nonce_account.account.lamports;
+    let signer = match nonce_account.state()? {
+        NonceState::Uninitialized => {
+            if lamports > balance {
+                Err(InstructionError::InsufficientFunds)?;
+            }
+            *nonce_account.unsigned_key()
+        }
+        NonceState::Initialized(meta, hash) => {
+            if lamports == balance {
+                // Emptying the account retires the nonce, which must not be in use
+                if hash == recent_blockhash {
+                    Err(nonce_error(SystemError::NonceBlockhashNotExpired))?;
+                }
+            } else if lamports
+                .saturating_add(rent.minimum_balance(nonce_account.account.data.len()))
+                > balance
+            {
+                Err(InstructionError::InsufficientFunds)?;
+            }
+            meta.nonce_authority
+        }
+    };
+    verify_nonce_authority(&signers, &signer)?;
+
+    keyed_accounts[NONCE_ACCOUNT_INDEX].account.lamports -= lamports;
+    keyed_accounts[NONCE_TO_ACCOUNT_INDEX].account.lamports += lamports;
+    Ok(())
+}
+
+fn nonce_initialize(
+    keyed_accounts: &mut [KeyedAccount],
+    nonce_authority: &Pubkey,
+) -> Result<(), InstructionError> {
+    check_nonce_accounts(keyed_accounts, 3)?;
+    let recent_blockhash = most_recent_blockhash(&keyed_accounts[1])?;
+    let rent = rent::from_keyed_account(&keyed_accounts[2])?;
+
+    let nonce_account = &mut keyed_accounts[NONCE_ACCOUNT_INDEX];
+    match nonce_account.state()? {
+        NonceState::Uninitialized => {
+            if !rent.is_exempt(
+                nonce_account.account.lamports,
+                nonce_account.account.data.len(),
+            ) {
+                Err(InstructionError::InsufficientFunds)?;
+            }
+            nonce_account.set_state(&NonceState::Initialized(
+                Meta::new(nonce_authority),
+                recent_blockhash,
+            ))
+        }
+        NonceState::Initialized(_, _) => Err(InstructionError::AccountAlreadyInitialized),
+    }
+}
+
+fn nonce_authorize(
+    keyed_accounts: &mut [KeyedAccount],
+    nonce_authority: &Pubkey,
+) -> Result<(), InstructionError> {
+    check_nonce_accounts(keyed_accounts, 1)?;
+    let signers = get_signers(keyed_accounts);
+
+    match keyed_accounts[NONCE_ACCOUNT_INDEX].state()? {
+        NonceState::Initialized(meta, hash) => {
+            verify_nonce_authority(&signers, &meta.nonce_authority)?;
+            keyed_accounts[NONCE_ACCOUNT_INDEX]
+                .set_state(&NonceState::Initialized(Meta::new(nonce_authority), hash))
+        }
+        NonceState::Uninitialized => Err(InstructionError::UninitializedAccount),
+    }
+}
+
 pub fn process_instruction(
     _program_id: &Pubkey,
     keyed_accounts: &mut [KeyedAccount],
@@ -96,8 +308,22 @@ pub fn process_instruction(
         trace!("process_instruction: {:?}", instruction);
         trace!("keyed_accounts: {:?}", keyed_accounts);
 
-        // All system instructions require that accounts_keys[0] be a signer
-        if keyed_accounts[FROM_ACCOUNT_INDEX].signer_key().is_none() {
+        // Instructions on accounts at derived addresses are signed by the base key instead,
+        // and those on nonce accounts by the nonce authority
+        let is_signed_by_other = match instruction {
+            SystemInstruction::AssignWithSeed { .. }
+            | SystemInstruction::TransferWithSeed { .. }
+            | SystemInstruction::NonceAdvance
+            | SystemInstruction::NonceWithdraw { .. }
+            | SystemInstruction::NonceInitialize { .. }
+            | SystemInstruction::NonceAuthorize { .. } => true,
+            _ => false,
+        };
+        // All other system instructions require that accounts_keys[0] be a signer
+        if !is_signed_by_other {
+            check_num_accounts(keyed_accounts, FROM_ACCOUNT_INDEX + 1)?;
+        }
+        if !is_signed_by_other && keyed_accounts[FROM_ACCOUNT_INDEX].signer_key().is_none() {
             debug!("account[from] is unsigned");
             Err(InstructionError::MissingRequiredSignature)?;
         }
@@ -115,6 +341,84 @@ pub fn process_instruction(
                 assign_account_to_program(keyed_accounts, &program_id)
             }
             SystemInstruction::Transfer { lamports } => transfer_lamports(keyed_accounts, lamports),
+            SystemInstruction::CreateAccountWithSeed {
+                base,
+                seed,
+                lamports,
+                space,
+                program_id,
+            } => {
+                check_num_accounts(keyed_accounts, TO_ACCOUNT_INDEX + 1)?;
+                let base_index = if *keyed_accounts[FROM_ACCOUNT_INDEX].unsigned_key() == base {
+                    FROM_ACCOUNT_INDEX
+                } else {
+                    check_num_accounts(keyed_accounts, CREATE_BASE_ACCOUNT_INDEX + 1)?;
+                    CREATE_BASE_ACCOUNT_INDEX
+                };
+                verify_base_signer(&keyed_accounts[base_index], &base)?;
+                verify_address_with_seed(
+                    &keyed_accounts[TO_ACCOUNT_INDEX],
+                    &base,
+                    &seed,
+                    &program_id,
+                )
+                .and_then(|_| create_system_account(keyed_accounts, lamports, space, &program_id))
+            }
+            SystemInstruction::AssignWithSeed { seed, program_id } => {
+                check_num_accounts(keyed_accounts, SEED_BASE_ACCOUNT_INDEX + 1)?;
+                let base = *keyed_accounts[SEED_BASE_ACCOUNT_INDEX].unsigned_key();
+                verify_base_signer(&keyed_accounts[SEED_BASE_ACCOUNT_INDEX], &base)?;
+                if !system_program::check_id(&keyed_accounts[FROM_ACCOUNT_INDEX].account.owner) {
+                    Err(InstructionError::IncorrectProgramId)?;
+                }
+                verify_address_with_seed(
+                    &keyed_accounts[FROM_ACCOUNT_INDEX],
+                    &base,
+                    &seed,
+                    &program_id,
+                )
+                .and_then(|_| assign_account_to_program(keyed_accounts, &program_id))
+            }
+            SystemInstruction::TransferWithSeed {
+                lamports,
+                from_seed,
+                from_owner,
+            } => {
+                check_num_accounts(keyed_accounts, SEED_TO_ACCOUNT_INDEX + 1)?;
+                let base = *keyed_accounts[SEED_BASE_ACCOUNT_INDEX].unsigned_key();
+                verify_base_signer(&keyed_accounts[SEED_BASE_ACCOUNT_INDEX], &base)?;
+                verify_address_with_seed(
+                    &keyed_accounts[FROM_ACCOUNT_INDEX],
+                    &base,
+                    &from_seed,
+                    &from_owner,
+                )
+                .and_then(|_| {
+                    // Move the lamports as a plain transfer between the source and destination
+                    let from_key = *keyed_accounts[FROM_ACCOUNT_INDEX].unsigned_key();
+                    let to_key = *keyed_accounts[SEED_TO_ACCOUNT_INDEX].unsigned_key();
+                    let (from, rest) = keyed_accounts.split_at_mut(SEED_BASE_ACCOUNT_INDEX);
+                    let mut accounts = [
+                        KeyedAccount::new(&from_key, false, &mut from[FROM_ACCOUNT_INDEX].account),
+                        KeyedAccount::new(
+                            &to_key,
+                            false,
+                            &mut rest[SEED_TO_ACCOUNT_INDEX - SEED_BASE_ACCOUNT_INDEX].account,
+                        ),
+                    ];
+                    transfer_lamports(&mut accounts, lamports)
+                })
+            }
+            SystemInstruction::NonceAdvance => return nonce_advance(keyed_accounts),
+            SystemInstruction::NonceWithdraw { lamports } => {
+                return nonce_withdraw(keyed_accounts, lamports)
+            }
+            SystemInstruction::NonceInitialize { nonce_authority } => {
+                return nonce_initialize(keyed_accounts, &nonce_authority)
+            }
+            SystemInstruction::NonceAuthorize { nonce_authority } => {
+                return nonce_authorize(keyed_accounts, &nonce_authority)
+            }
         }
         .map_err(|e| InstructionError::CustomError(e as u32))
     } else {
@@ -133,7 +437,9 @@ mod tests {
     use solana_sdk::client::SyncClient;
     use solana_sdk::genesis_block::create_genesis_block;
     use solana_sdk::instruction::{AccountMeta, Instruction, InstructionError};
+    use solana_sdk::message::Message;
     use solana_sdk::signature::{Keypair, KeypairUtil};
+    use solana_sdk::system_instruction;
     use solana_sdk::system_program;
     use solana_sdk::transaction::TransactionError;
 
@@ -251,6 +557,7 @@ mod tests {
             data: vec![0, 1, 2, 3],
             owner: Pubkey::default(),
             executable: false,
+            rent_epoch: 0,
         };
         let unchanged_account = populated_account.clone();
 
@@ -365,4 +672,389 @@ mod tests {
         assert_eq!(bank_client.get_balance(&alice_pubkey).unwrap(), 50);
         assert_eq!(bank_client.get_balance(&mallory_pubkey).unwrap(), 50);
     }
+
+    #[test]
+    fn test_system_accounts_with_seed() {
+        let (genesis_block, alice_keypair) = create_genesis_block(100);
+        let alice_pubkey = alice_keypair.pubkey();
+        let bob_pubkey = Pubkey::new_rand();
+        let mallory_keypair = Keypair::new();
+        let mallory_pubkey = mallory_keypair.pubkey();
+        let bank = Bank::new(&genesis_block);
+        let bank_client = BankClient::new(bank);
+        bank_client
+            .transfer(10, &alice_keypair, &mallory_pubkey)
+            .unwrap();
+
+        // Create an account at an address derived from alice's key, with no keypair of its own
+        let system_id = system_program::id();
+        let derived_pubkey = Pubkey::create_with_seed(&alice_pubkey, "seed", &system_id).unwrap();
+        let instruction = system_instruction::create_account_with_seed(
+            &alice_pubkey,
+            &derived_pubkey,
+            &alice_pubkey,
+            "seed",
+            50,
+            0,
+            &system_id,
+        );
+        bank_client
+            .send_instruction(&alice_keypair, instruction)
+            .unwrap();
+        assert_eq!(bank_client.get_balance(&derived_pubkey).unwrap(), 50);
+
+        // Only the base key may move its lamports
+        let instruction = system_instruction::transfer_with_seed(
+            &derived_pubkey,
+            &alice_pubkey,
+            "seed",
+            &system_id,
+            &bob_pubkey,
+            10,
+        );
+        bank_client
+            .send_instruction(&alice_keypair, instruction)
+            .unwrap();
+        assert_eq!(bank_client.get_balance(&derived_pubkey).unwrap(), 40);
+        assert_eq!(bank_client.get_balance(&bob_pubkey).unwrap(), 10);
+
+        let instruction = system_instruction::transfer_with_seed(
+            &derived_pubkey,
+            &mallory_pubkey,
+            "seed",
+            &system_id,
+            &bob_pubkey,
+            10,
+        );
+        assert_eq!(
+            bank_client
+                .send_instruction(&mallory_keypair, instruction)
+                .unwrap_err()
+                .unwrap(),
+            TransactionError::InstructionError(
+                0,
+                InstructionError::CustomError(SystemError::AddressWithSeedMismatch as u32)
+            )
+        );
+
+        let mut instruction = system_instruction::transfer_with_seed(
+            &derived_pubkey,
+            &alice_pubkey,
+            "seed",
+            &system_id,
+            &mallory_pubkey,
+            10,
+        );
+        instruction.accounts[1].is_signer = false;
+        let message = Message::new_with_payer(vec![instruction], Some(&mallory_pubkey));
+        assert_eq!(
+            bank_client
+                .send_message(&[&mallory_keypair], message)
+                .unwrap_err()
+                .unwrap(),
+            TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature)
+        );
+        assert_eq!(bank_client.get_balance(&derived_pubkey).unwrap(), 40);
+
+        // Hand a derived account to a program
+        let program_id = Pubkey::new_rand();
+        let assigned_pubkey =
+            Pubkey::create_with_seed(&alice_pubkey, "assign", &program_id).unwrap();
+        bank_client
+            .transfer(1, &alice_keypair, &assigned_pubkey)
+            .unwrap();
+        let instruction = system_instruction::assign_with_seed(
+            &assigned_pubkey,
+            &alice_pubkey,
+            "wrong",
+            &program_id,
+        );
+        assert_eq!(
+            bank_client
+                .send_instruction(&alice_keypair, instruction)
+                .unwrap_err()
+                .unwrap(),
+            TransactionError::InstructionError(
+                0,
+                InstructionError::CustomError(SystemError::AddressWithSeedMismatch as u32)
+            )
+        );
+        let instruction = system_instruction::assign_with_seed(
+            &assigned_pubkey,
+            &alice_pubkey,
+            "assign",
+            &program_id,
+        );
+        bank_client
+            .send_instruction(&alice_keypair, instruction)
+            .unwrap();
+        assert_eq!(
+            bank_client
+                .get_account(&assigned_pubkey)
+                .unwrap()
+                .unwrap()
+                .owner,
+            program_id
+        );
+    }
+
+    fn process_nonce_instruction(
+        instruction: &SystemInstruction,
+        keyed_accounts: &mut [KeyedAccount],
+    ) -> Result<(), InstructionError> {
+        let data = serialize(instruction).unwrap();
+        process_instruction(&system_program::id(), keyed_accounts, &data)
+    }
+
+    #[test]
+    fn test_nonce_instructions() {
+        let nonce = Pubkey::new_rand();
+        let authority = Pubkey::new_rand();
+        let to = Pubkey::new_rand();
+        let recent_blockhashes_id = recent_blockhashes::id();
+        let rent_id = rent::id();
+        let (hash0, hash1, hash2) = (
+            Hash::new(&[0; 32]),
+            Hash::new(&[1; 32]),
+            Hash::new(&[2; 32]),
+        );
+        let mut rent_account = rent::create_account(1, &rent::Rent::new(1, 1));
+        let rent_exempt_balance = rent::Rent::new(1, 1).minimum_balance(NonceState::size());
+        let mut nonce_account = Account::new(
+            rent_exempt_balance - 1,
+            NonceState::size(),
+            &system_program::id(),
+        );
+        let mut authority_account = Account::default();
+        let mut to_account = Account::default();
+
+        // Initialize requires a rent exempt nonce account
+        let initialize = SystemInstruction::NonceInitialize {
+            nonce_authority: authority,
+        };
+        let mut recent_blockhashes_account = recent_blockhashes::create_account(1, &[hash0]);
+        let mut keyed_accounts = [
+            KeyedAccount::new(&nonce, false, &mut nonce_account),
+            KeyedAccount::new(
+                &recent_blockhashes_id,
+                false,
+                &mut recent_blockhashes_account,
+            ),
+            KeyedAccount::new(&rent_id, false, &mut rent_account),
+        ];
+        assert_eq!(
+            process_nonce_instruction(&initialize, &mut keyed_accounts),
+            Err(InstructionError::InsufficientFunds)
+        );
+        keyed_accounts[0].account.lamports = rent_exempt_balance + 100;
+        process_nonce_instruction(&initialize, &mut keyed_accounts).unwrap();
+        assert_eq!(
+            process_nonce_instruction(&initialize, &mut keyed_accounts),
+            Err(InstructionError::AccountAlreadyInitialized)
+        );
+        assert_eq!(
+            NonceState::from(&nonce_account),
+            Some(NonceState::Initialized(Meta::new(&authority), hash0))
+        );
+
+        // Advance only once the stored blockhash has been superseded
+        let mut keyed_accounts = [
+            KeyedAccount::new(&nonce, false, &mut nonce_account),
+            KeyedAccount::new(
+                &recent_blockhashes_id,
+                false,
+                &mut recent_blockhashes_account,
+            ),
+            KeyedAccount::new(&authority, false, &mut authority_account),
+        ];
+        assert_eq!(
+            process_nonce_instruction(&SystemInstruction::NonceAdvance, &mut keyed_accounts),
+            Err(InstructionError::CustomError(
+                SystemError::NonceBlockhashNotExpired as u32
+            ))
+        );
+        *keyed_accounts[1].account = recent_blockhashes::create_account(1, &[hash1, hash0]);
+        assert_eq!(
+            process_nonce_instruction(&SystemInstruction::NonceAdvance, &mut keyed_accounts),
+            Err(InstructionError::MissingRequiredSignature)
+        );
+        let mut keyed_accounts = [
+            KeyedAccount::new(&nonce, false, &mut nonce_account),
+            KeyedAccount::new(
+                &recent_blockhashes_id,
+                false,
+                &mut recent_blockhashes_account,
+            ),
+            KeyedAccount::new(&authority, true, &mut authority_account),
+        ];
+        process_nonce_instruction(&SystemInstruction::NonceAdvance, &mut keyed_accounts).unwrap();
+        assert_eq!(
+            NonceState::from(&nonce_account),
+            Some(NonceState::Initialized(Meta::new(&authority), hash1))
+        );
+
+        // Withdraw down to the rent exempt minimum, or everything once the nonce is expired
+        let mut keyed_accounts = [
+            KeyedAccount::new(&nonce, false, &mut nonce_account),
+            KeyedAccount::new(&to, false, &mut to_account),
+            KeyedAccount::new(
+                &recent_blockhashes_id,
+                false,
+                &mut recent_blockhashes_account,
+            ),
+            KeyedAccount::new(&rent_id, false, &mut rent_account),
+            KeyedAccount::new(&authority, true, &mut authority_account),
+        ];
+        assert_eq!(
+            process_nonce_instruction(
+                &SystemInstruction::NonceWithdraw { lamports: 101 },
+                &mut keyed_accounts
+            ),
+            Err(InstructionError::InsufficientFunds)
+        );
+        process_nonce_instruction(
+            &SystemInstruction::NonceWithdraw { lamports: 100 },
+            &mut keyed_accounts,
+        )
+        .unwrap();
+        let withdraw_all = SystemInstruction::NonceWithdraw {
+            lamports: rent_exempt_balance,
+        };
+        assert_eq!(
+            process_nonce_instruction(&withdraw_all, &mut keyed_accounts),
+            Err(InstructionError::CustomError(
+                SystemError::NonceBlockhashNotExpired as u32
+            ))
+        );
+        *keyed_accounts[2].account = recent_blockhashes::create_account(1, &[hash2, hash1]);
+        process_nonce_instruction(&withdraw_all, &mut keyed_accounts).unwrap();
+        assert_eq!(nonce_account.lamports, 0);
+        assert_eq!(to_account.lamports, rent_exempt_balance + 100);
+
+        // Only the current authority may hand the nonce to another
+        let new_authority = Pubkey::new_rand();
+        let authorize = SystemInstruction::NonceAuthorize {
+            nonce_authority: new_authority,
+        };
+        let mut new_authority_account = Account::default();
+        let mut keyed_accounts = [
+            KeyedAccount::new(&nonce, false, &mut nonce_account),
+            KeyedAccount::new(&new_authority, true, &mut new_authority_account),
+        ];
+        assert_eq!(
+            process_nonce_instruction(&authorize, &mut keyed_accounts),
+            Err(InstructionError::MissingRequiredSignature)
+        );
+        let mut keyed_accounts = [
+            KeyedAccount::new(&nonce, false, &mut nonce_account),
+            KeyedAccount::new(&authority, true, &mut authority_account),
+        ];
+        process_nonce_instruction(&authorize, &mut keyed_accounts).unwrap();
+        assert_eq!(
+            NonceState::from(&nonce_account),
+            Some(NonceState::Initialized(Meta::new(&new_authority), hash1))
+        );
+    }
+
+    #[test]
+    fn test_nonce_instructions_missing_accounts() {
+        let nonce = Pubkey::new_rand();
+        let mut nonce_account = Account::new(100, NonceState::size(), &system_program::id());
+        for instruction in &[
+            SystemInstruction::NonceAdvance,
+            SystemInstruction::NonceWithdraw { lamports: 1 },
+            SystemInstruction::NonceInitialize {
+                nonce_authority: nonce,
+            },
+        ] {
+            let mut keyed_accounts = [KeyedAccount::new(&nonce, true, &mut nonce_account)];
+            assert_eq!(
+                process_nonce_instruction(instruction, &mut keyed_accounts),
+                Err(InstructionError::InvalidArgument)
+            );
+        }
+        let authorize = SystemInstruction::NonceAuthorize {
+            nonce_authority: nonce,
+        };
+        assert_eq!(
+            process_nonce_instruction(&authorize, &mut []),
+            Err(InstructionError::InvalidArgument)
+        );
+    }
+
+    #[test]
+    fn test_seed_instructions_missing_accounts() {
+        let base = Pubkey::new_rand();
+        let mut base_account = Account::new(100, 0, &system_program::id());
+        for instruction in &[
+            SystemInstruction::CreateAccountWithSeed {
+                base: Pubkey::new_rand(),
+                seed: "seed".to_string(),
+                lamports: 1,
+                space: 0,
+                program_id: Pubkey::new_rand(),
+            },
+            SystemInstruction::AssignWithSeed {
+                seed: "seed".to_string(),
+                program_id: Pubkey::new_rand(),
+            },
+            SystemInstruction::TransferWithSeed {
+                lamports: 1,
+                from_seed: "seed".to_string(),
+                from_owner: Pubkey::new_rand(),
+            },
+        ] {
+            let data = serialize(instruction).unwrap();
+            let mut keyed_accounts = [KeyedAccount::new(&base, true, &mut base_account)];
+            assert_eq!(
+                process_instruction(&Pubkey::default(), &mut keyed_accounts, &data),
+                Err(InstructionError::NotEnoughAccountKeys)
+            );
+            assert_eq!(
+                process_instruction(&Pubkey::default(), &mut [], &data),
+                Err(InstructionError::NotEnoughAccountKeys)
+            );
+        }
+    }
+
+    #[test]
+    fn test_nonce_account_is_not_a_source() {
+        let nonce = Pubkey::new_rand();
+        let to = Pubkey::new_rand();
+        let mut nonce_account = Account::new(100, NonceState::size(), &system_program::id());
+        nonce_account
+            .set_state(&NonceState::Initialized(
+                Meta::new(&Pubkey::new_rand()),
+                Hash::default(),
+            ))
+            .unwrap();
+        let unchanged_account = nonce_account.clone();
+        let mut to_account = Account::default();
+
+        // Signing with the nonce account's own keypair bypasses neither the nonce authority
+        // nor the rent exempt minimum
+        for instruction in &[
+            SystemInstruction::Transfer { lamports: 50 },
+            SystemInstruction::Assign {
+                program_id: Pubkey::new_rand(),
+            },
+            SystemInstruction::CreateAccount {
+                lamports: 50,
+                space: 0,
+                program_id: Pubkey::new_rand(),
+            },
+        ] {
+            let mut keyed_accounts = [
+                KeyedAccount::new(&nonce, true, &mut nonce_account),
+                KeyedAccount::new(&to, false, &mut to_account),
+            ];
+            assert_eq!(
+                process_nonce_instruction(instruction, &mut keyed_accounts),
+                Err(InstructionError::CustomError(
+                    SystemError::SourceHasData as u32
+                ))
+            );
+            assert_eq!(nonce_account, unchanged_account);
+        }
+    }
 }
@@ -5,6 +5,8 @@ use solana_sdk::client::SyncClient;
 use solana_sdk::genesis_block::create_genesis_block;
 use solana_sdk::native_loader;
 use solana_sdk::signature::KeypairUtil;
+use solana_sdk::transaction::Transaction;
+use std::sync::Arc;
 
 #[test]
 fn test_program_native_noop() {
@@ -23,3 +25,26 @@ fn test_program_native_noop() {
         .send_instruction(&alice_keypair, instruction)
         .unwrap();
 }
+
+#[test]
+fn test_program_native_noop_log_messages() {
+    solana_logger::setup();
+
+    let (genesis_block, alice_keypair) = create_genesis_block(50);
+    let bank = Arc::new(Bank::new(&genesis_block));
+    let bank_client = BankClient::new_shared(&bank);
+
+    let program = "solana_noop_program".as_bytes().to_vec();
+    let program_id = load_program(&bank_client, &alice_keypair, &native_loader::id(), program);
+
+    // The messages a dynamically loaded program logs reach the bank through the native loader
+    let instruction = create_invoke_instruction(alice_keypair.pubkey(), program_id, &1u8);
+    let tx = Transaction::new_signed_instructions(
+        &[&alice_keypair],
+        vec![instruction],
+        bank.last_blockhash(),
+    );
+    let (result, log_messages, _) = Bank::simulate_transaction(&bank, &tx);
+    assert_eq!(result, Ok(()));
+    assert_eq!(log_messages, vec!["noop".to_string()]);
+}
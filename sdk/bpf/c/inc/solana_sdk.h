@@ -186,6 +186,20 @@ if (!(expr)) {          \
   sol_panic(); \
 }
 
+/**
+ * Invokes another program
+ *
+ * The instruction is bincode serialized: the program id, the number of
+ * accounts as a uint64_t, each account's key followed by its signer and
+ * debitable flags as one byte each, then the data length as a uint64_t and
+ * the data.  Every account must have been passed to the calling program,
+ * with at least the privileges requested.
+ *
+ * Returns 0 on success, in which case the calling program's accounts reflect
+ * the changes made by the invoked program
+ */
+uint64_t sol_invoke_(const uint8_t *instruction, uint64_t len);
+
 /**
  * Structure that the program's entrypoint input data is deserialized into.
  */
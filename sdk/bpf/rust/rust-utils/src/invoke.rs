@@ -0,0 +1,16 @@
+//! @brief Solana Rust-based BPF program cross-program invocation
+
+/// Invokes another program
+///
+/// @param instruction - Bincode serialized instruction: the program id, the
+///                      number of accounts as a u64, each account's key
+///                      followed by its signer and debitable flags as one byte
+///                      each, then the data length as a u64 and the data
+/// @return 0 on success, in which case the program's accounts reflect the
+///         changes made by the invoked program
+pub fn sol_invoke(instruction: &[u8]) -> u64 {
+    unsafe { sol_invoke_(instruction.as_ptr(), instruction.len() as u64) }
+}
+extern "C" {
+    fn sol_invoke_(instruction: *const u8, length: u64) -> u64;
+}
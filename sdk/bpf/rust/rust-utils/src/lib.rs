@@ -13,6 +13,7 @@ extern crate compiler_builtins;
 
 pub mod allocator;
 pub mod entrypoint;
+pub mod invoke;
 pub mod log;
 pub mod panic;
 
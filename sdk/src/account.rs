@@ -4,6 +4,7 @@ use std::{cmp, fmt};
 /// An Account with data that is stored on chain
 #[repr(C)]
 #[derive(Serialize, Deserialize, Clone, Default, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
 pub struct Account {
     /// lamports in the account
     pub lamports: u64,
@@ -13,6 +14,8 @@ pub struct Account {
     pub owner: Pubkey,
     /// this account's data contains a loaded program (and is now read-only)
     pub executable: bool,
+    /// the epoch through which this account has paid rent
+    pub rent_epoch: u64,
 }
 
 impl fmt::Debug for Account {
@@ -25,11 +28,12 @@ impl fmt::Debug for Account {
         };
         write!(
             f,
-            "Account {{ lamports: {} data.len: {} owner: {} executable: {}{} }}",
+            "Account {{ lamports: {} data.len: {} owner: {} executable: {} rent_epoch: {}{} }}",
             self.lamports,
             self.data.len(),
             self.owner,
             self.executable,
+            self.rent_epoch,
             data_str,
         )
     }
@@ -43,6 +47,7 @@ impl Account {
             data: vec![0u8; space],
             owner: *owner,
             executable: false,
+            rent_epoch: 0,
         }
     }
 
@@ -57,6 +62,7 @@ impl Account {
             data,
             owner: *owner,
             executable: false,
+            rent_epoch: 0,
         })
     }
 
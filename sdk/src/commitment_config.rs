@@ -0,0 +1,59 @@
+/// The bank a read is served from, ordered from most recent to most final
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum CommitmentLevel {
+    /// The node's working bank, which may be on a fork that is later abandoned
+    Recent,
+    /// The most recent bank a supermajority of the cluster stake has voted on
+    MaxConfirmed,
+    /// The node's root bank, which will not be rolled back
+    Root,
+}
+
+impl Default for CommitmentLevel {
+    fn default() -> Self {
+        CommitmentLevel::Recent
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitmentConfig {
+    pub commitment: CommitmentLevel,
+}
+
+impl CommitmentConfig {
+    pub fn recent() -> Self {
+        Self {
+            commitment: CommitmentLevel::Recent,
+        }
+    }
+
+    pub fn max_confirmed() -> Self {
+        Self {
+            commitment: CommitmentLevel::MaxConfirmed,
+        }
+    }
+
+    pub fn root() -> Self {
+        Self {
+            commitment: CommitmentLevel::Root,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commitment_config_serde() {
+        assert_eq!(
+            serde_json::to_string(&CommitmentConfig::max_confirmed()).unwrap(),
+            r#"{"commitment":"max-confirmed"}"#
+        );
+        let config: CommitmentConfig = serde_json::from_str(r#"{"commitment":"root"}"#).unwrap();
+        assert_eq!(config, CommitmentConfig::root());
+        assert_eq!(CommitmentConfig::default(), CommitmentConfig::recent());
+    }
+}
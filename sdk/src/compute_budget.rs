@@ -0,0 +1,30 @@
+//! The `compute_budget` module defines the limits a transaction's instructions are metered
+//! against.  Every transaction draws from a budget of compute units: BPF programs spend one
+//! unit per instruction executed plus a charge for each helper they call, native programs
+//! are charged a flat amount per instruction, and each cross-program invocation costs extra.
+
+/// Units a transaction may consume, unless configured otherwise
+pub const DEFAULT_MAX_UNITS: u64 = 200_000;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct ComputeBudget {
+    /// Units a transaction may consume across all of its instructions
+    pub max_units: u64,
+    /// Units charged for each instruction handled by a native program
+    pub native_instruction_units: u64,
+    /// Units charged each time a program logs a message
+    pub log_units: u64,
+    /// Units charged for each cross-program invocation
+    pub invoke_units: u64,
+}
+
+impl Default for ComputeBudget {
+    fn default() -> Self {
+        Self {
+            max_units: DEFAULT_MAX_UNITS,
+            native_instruction_units: 1_000,
+            log_units: 100,
+            invoke_units: 1_000,
+        }
+    }
+}
@@ -1,6 +1,10 @@
-use solana_vote_api::vote_state::MAX_LOCKOUT_HISTORY;
+//! The `epoch_schedule` module maps slots to epochs.  Epochs start out short and double in
+//! length during warmup until they reach the configured number of slots
+//!
 
-pub const MINIMUM_SLOTS_PER_EPOCH: u64 = (MAX_LOCKOUT_HISTORY + 1) as u64;
+/// One more than the vote program's `MAX_LOCKOUT_HISTORY`, so the shortest epoch outlasts
+/// any vote lockout
+pub const MINIMUM_SLOTS_PER_EPOCH: u64 = 32;
 
 #[derive(Default, Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
 pub struct EpochSchedule {
@@ -7,6 +7,7 @@ use crate::inflation::Inflation;
 use crate::poh_config::PohConfig;
 use crate::pubkey::Pubkey;
 use crate::signature::{Keypair, KeypairUtil};
+use crate::syscall::rent::Rent;
 use crate::system_program;
 use crate::timing::{DEFAULT_SLOTS_PER_EPOCH, DEFAULT_TICKS_PER_SLOT};
 use bincode::{deserialize, serialize};
@@ -27,6 +28,7 @@ pub struct GenesisBlock {
     pub poh_config: PohConfig,
     pub fee_calculator: FeeCalculator,
     pub inflation: Inflation,
+    pub rent: Rent,
 }
 
 // useful for basic tests
@@ -57,6 +59,7 @@ impl Default for GenesisBlock {
             poh_config: PohConfig::default(),
             inflation: Inflation::default(),
             fee_calculator: FeeCalculator::default(),
+            rent: Rent::default(),
         }
     }
 }
@@ -142,6 +145,10 @@ impl Builder {
         self.genesis_block.inflation = inflation.clone();
         self
     }
+    pub fn rent(mut self, rent: &Rent) -> Self {
+        self.genesis_block.rent = *rent;
+        self
+    }
 }
 
 impl GenesisBlock {
@@ -215,6 +222,7 @@ mod tests {
             )
             .accounts(&[(Pubkey::new_rand(), Account::new(1, 0, &Pubkey::default()))])
             .native_instruction_processor("hi", Pubkey::new_rand())
+            .rent(&Rent::new(1, 10))
             .build();
 
         assert_eq!(block.accounts.len(), 2);
@@ -226,7 +234,7 @@ mod tests {
         block.write(&path).expect("write");
         let loaded_block = GenesisBlock::load(&path).expect("load");
         assert_eq!(block.hash(), loaded_block.hash());
+        assert_eq!(loaded_block.rent, Rent::new(1, 10));
         let _ignored = std::fs::remove_file(&path);
     }
-
 }
@@ -61,6 +61,27 @@ pub enum InstructionError {
     /// An account was referenced more than once in a single instruction
     DuplicateAccountIndex,
 
+    /// Cross-program invocation is not available to the running program
+    InvokeUnavailable,
+
+    /// Cross-program invocations were nested deeper than the runtime allows
+    CallDepthExceeded,
+
+    /// An invoked instruction asked for a signer or debitable account the caller doesn't have
+    PrivilegeEscalation,
+
+    /// An invoked instruction referenced an account that wasn't passed to the caller
+    MissingAccount,
+
+    /// An invoked program's account is not executable
+    AccountNotExecutable,
+
+    /// The transaction's instructions consumed more compute units than its budget allows
+    ComputationalBudgetExceeded,
+
+    /// The instruction was given fewer accounts than it requires
+    NotEnoughAccountKeys,
+
     /// CustomError allows on-chain programs to implement program-specific error types and see
     /// them returned by the Solana runtime. A CustomError may be any type that is represented
     /// as or serialized to a u32 integer.
@@ -73,7 +94,7 @@ impl InstructionError {
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub struct Instruction {
     /// Pubkey of the instruction processor that executes this instruction
     pub program_ids_index: Pubkey,
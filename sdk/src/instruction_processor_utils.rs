@@ -1,5 +1,6 @@
 use crate::account::KeyedAccount;
 use crate::instruction::InstructionError;
+use crate::invoke_context::InvokeContext;
 use crate::pubkey::Pubkey;
 use num_traits::FromPrimitive;
 
@@ -13,7 +14,19 @@ pub type Entrypoint = unsafe extern "C" fn(
     data: &[u8],
 ) -> Result<(), InstructionError>;
 
-// Convenience macro to define the native program entrypoint.  Supply a fn to this macro that
+// The native loader calls the process_with_context() symbol, which runs process() with the
+// runtime's `InvokeContext` installed in the program's own copy of the sdk
+pub const CONTEXT_ENTRYPOINT: &str = "process_with_context";
+
+// Native program CONTEXT_ENTRYPOINT prototype
+pub type ContextEntrypoint = unsafe extern "C" fn(
+    program_id: &Pubkey,
+    keyed_accounts: &mut [KeyedAccount],
+    data: &[u8],
+    invoke_context: &dyn InvokeContext,
+) -> Result<(), InstructionError>;
+
+// Convenience macro to define the native program entrypoints.  Supply a fn to this macro that
 // conforms to the `Entrypoint` type signature.
 #[macro_export]
 macro_rules! solana_entrypoint(
@@ -26,6 +39,18 @@ macro_rules! solana_entrypoint(
         ) -> Result<(), $crate::instruction::InstructionError> {
             $entrypoint(program_id, keyed_accounts, data)
         }
+
+        #[no_mangle]
+        pub extern "C" fn process_with_context(
+            program_id: &$crate::pubkey::Pubkey,
+            keyed_accounts: &mut [$crate::account::KeyedAccount],
+            data: &[u8],
+            invoke_context: &dyn $crate::invoke_context::InvokeContext,
+        ) -> Result<(), $crate::instruction::InstructionError> {
+            $crate::invoke_context::with_invoke_context(invoke_context, || {
+                $entrypoint(program_id, keyed_accounts, data)
+            })
+        }
     )
 );
 
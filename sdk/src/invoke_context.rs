@@ -0,0 +1,204 @@
+//! The `invoke_context` module lets an executing program call into another program. The
+//! runtime installs an `InvokeContext` on the current thread for the duration of each
+//! instruction and programs reach it through `invoke()`.
+//!
+//! Native programs loaded dynamically through the native loader link their own copy of this
+//! crate, so the loader hands them the runtime's context through their `process_with_context()`
+//! entrypoint, which installs it in their copy.
+//!
+//! The context also meters the transaction's `ComputeBudget`, which programs outside of one
+//! are not held to.
+
+use crate::account::KeyedAccount;
+use crate::compute_budget::ComputeBudget;
+use crate::instruction::{Instruction, InstructionError};
+use std::cell::Cell;
+
+/// Implemented by the runtime to execute instructions on behalf of the running program
+pub trait InvokeContext {
+    /// Execute `instruction` with the caller's `keyed_accounts`, which must include every
+    /// account the instruction references
+    fn invoke(
+        &self,
+        instruction: &Instruction,
+        keyed_accounts: &mut [KeyedAccount],
+    ) -> Result<(), InstructionError>;
+
+    /// The limits the transaction's instructions are metered against
+    fn compute_budget(&self) -> &ComputeBudget;
+
+    /// Charge `units` against the transaction's compute budget, failing once it is spent
+    fn consume_compute_units(&self, units: u64) -> Result<(), InstructionError>;
+
+    /// Units left in the transaction's compute budget
+    fn remaining_compute_units(&self) -> u64;
+
+    /// Record a message logged by the running program
+    fn log(&self, message: &str);
+}
+
+thread_local! {
+    static CONTEXT: Cell<Option<*const dyn InvokeContext>> = Cell::new(None);
+}
+
+/// Make `context` available to `invoke()` while `f` runs on the current thread
+#[allow(clippy::useless_transmute)]
+pub fn with_invoke_context<R, F: FnOnce() -> R>(context: &dyn InvokeContext, f: F) -> R {
+    //UNSAFE: The lifetime is erased so the pointer can be kept in a thread local. It is only
+    //dereferenced by `invoke()` while `f` runs, during which `context` is borrowed
+    let context: *const dyn InvokeContext = unsafe { std::mem::transmute(context) };
+    let _restore = RestoreContext(CONTEXT.with(|current| current.replace(Some(context))));
+    f()
+}
+
+/// Reinstalls the context that was current before `with_invoke_context()`, even if `f`
+/// panics, so a pointer to `context` never outlives its borrow
+struct RestoreContext(Option<*const dyn InvokeContext>);
+
+impl Drop for RestoreContext {
+    fn drop(&mut self) {
+        CONTEXT.with(|current| current.set(self.0));
+    }
+}
+
+/// Execute `instruction` as a call from the running program. Every account the instruction
+/// references must be among `keyed_accounts`, and it may only ask for the signer and debitable
+/// privileges the caller was given
+pub fn invoke(
+    instruction: &Instruction,
+    keyed_accounts: &mut [KeyedAccount],
+) -> Result<(), InstructionError> {
+    match CONTEXT.with(Cell::get) {
+        //UNSAFE: The context outlives the call, see `with_invoke_context()`
+        Some(context) => unsafe { &*context }.invoke(instruction, keyed_accounts),
+        None => Err(InstructionError::InvokeUnavailable),
+    }
+}
+
+/// The compute budget of the running transaction, or the default outside of one
+pub fn compute_budget() -> ComputeBudget {
+    match CONTEXT.with(Cell::get) {
+        //UNSAFE: The context outlives the call, see `with_invoke_context()`
+        Some(context) => *unsafe { &*context }.compute_budget(),
+        None => ComputeBudget::default(),
+    }
+}
+
+/// Charge `units` against the running transaction's compute budget
+pub fn consume_compute_units(units: u64) -> Result<(), InstructionError> {
+    match CONTEXT.with(Cell::get) {
+        //UNSAFE: The context outlives the call, see `with_invoke_context()`
+        Some(context) => unsafe { &*context }.consume_compute_units(units),
+        None => Ok(()),
+    }
+}
+
+/// Units left in the running transaction's compute budget, `None` outside of a transaction
+pub fn remaining_compute_units() -> Option<u64> {
+    //UNSAFE: The context outlives the call, see `with_invoke_context()`
+    CONTEXT
+        .with(Cell::get)
+        .map(|context| unsafe { &*context }.remaining_compute_units())
+}
+
+/// Record `message` with the running transaction's log messages, if any
+pub fn log(message: &str) {
+    if let Some(context) = CONTEXT.with(Cell::get) {
+        //UNSAFE: The context outlives the call, see `with_invoke_context()`
+        unsafe { &*context }.log(message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account::Account;
+    use crate::pubkey::Pubkey;
+    use std::cell::RefCell;
+
+    struct CreditContext(ComputeBudget, RefCell<Vec<String>>);
+
+    impl InvokeContext for CreditContext {
+        fn invoke(
+            &self,
+            _instruction: &Instruction,
+            keyed_accounts: &mut [KeyedAccount],
+        ) -> Result<(), InstructionError> {
+            keyed_accounts[0].account.lamports += 1;
+            Ok(())
+        }
+
+        fn compute_budget(&self) -> &ComputeBudget {
+            &self.0
+        }
+
+        fn consume_compute_units(&self, _units: u64) -> Result<(), InstructionError> {
+            Err(InstructionError::ComputationalBudgetExceeded)
+        }
+
+        fn remaining_compute_units(&self) -> u64 {
+            0
+        }
+
+        fn log(&self, message: &str) {
+            self.1.borrow_mut().push(message.to_string());
+        }
+    }
+
+    #[test]
+    fn test_invoke_context() {
+        let key = Pubkey::new_rand();
+        let mut account = Account::new(1, 0, &Pubkey::default());
+        let instruction = Instruction::new(Pubkey::new_rand(), &(), vec![]);
+        let mut keyed_accounts = vec![KeyedAccount::new(&key, false, &mut account)];
+
+        assert_eq!(
+            invoke(&instruction, &mut keyed_accounts),
+            Err(InstructionError::InvokeUnavailable)
+        );
+        assert_eq!(consume_compute_units(1), Ok(()));
+        assert_eq!(remaining_compute_units(), None);
+        log("dropped");
+
+        let budget = ComputeBudget {
+            max_units: 0,
+            ..ComputeBudget::default()
+        };
+        let context = CreditContext(budget, RefCell::new(vec![]));
+        with_invoke_context(&context, || {
+            assert_eq!(invoke(&instruction, &mut keyed_accounts), Ok(()));
+            assert_eq!(compute_budget(), budget);
+            assert_eq!(
+                consume_compute_units(1),
+                Err(InstructionError::ComputationalBudgetExceeded)
+            );
+            assert_eq!(remaining_compute_units(), Some(0));
+            log("logged");
+        });
+        assert_eq!(context.1.into_inner(), vec!["logged".to_string()]);
+        assert_eq!(
+            invoke(&instruction, &mut keyed_accounts),
+            Err(InstructionError::InvokeUnavailable)
+        );
+        assert_eq!(account.lamports, 2);
+    }
+
+    #[test]
+    fn test_invoke_context_panic() {
+        let key = Pubkey::new_rand();
+        let mut account = Account::new(1, 0, &Pubkey::default());
+        let instruction = Instruction::new(Pubkey::new_rand(), &(), vec![]);
+        let mut keyed_accounts = vec![KeyedAccount::new(&key, false, &mut account)];
+
+        let result = std::panic::catch_unwind(|| {
+            let context = CreditContext(ComputeBudget::default(), RefCell::new(vec![]));
+            with_invoke_context(&context, || panic!())
+        });
+        assert!(result.is_err());
+        // The context doesn't outlive the panic
+        assert_eq!(
+            invoke(&instruction, &mut keyed_accounts),
+            Err(InstructionError::InvokeUnavailable)
+        );
+    }
+}
@@ -2,15 +2,20 @@ pub mod account;
 pub mod account_utils;
 pub mod bpf_loader;
 pub mod client;
+pub mod commitment_config;
+pub mod compute_budget;
+pub mod epoch_schedule;
 pub mod fee_calculator;
 pub mod genesis_block;
 pub mod hash;
 pub mod inflation;
 pub mod instruction;
 pub mod instruction_processor_utils;
+pub mod invoke_context;
 pub mod loader_instruction;
 pub mod message;
 pub mod native_loader;
+pub mod nonce_state;
 pub mod packet;
 pub mod poh_config;
 pub mod pubkey;
@@ -23,6 +28,7 @@ pub mod system_program;
 pub mod system_transaction;
 pub mod timing;
 pub mod transaction;
+pub mod transaction_status;
 pub mod transport;
 
 #[macro_use]
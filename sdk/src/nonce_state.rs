@@ -0,0 +1,71 @@
+//! The `nonce_state` module defines the state of a durable transaction nonce account.
+//!
+//! A nonce account is a system account that stores a blockhash.  A transaction may name
+//! that stored blockhash as its `recent_blockhash` long after the hash has aged out of the
+//! Bank's queue, provided its first instruction advances the nonce, which replaces the
+//! stored blockhash and so prevents the transaction from being replayed.
+
+use crate::account::Account;
+use crate::account_utils::State;
+use crate::hash::Hash;
+use crate::pubkey::Pubkey;
+use bincode::serialized_size;
+
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq, Clone, Copy)]
+pub struct Meta {
+    /// The key that must sign to advance, withdraw from or reauthorize the nonce
+    pub nonce_authority: Pubkey,
+}
+
+impl Meta {
+    pub fn new(nonce_authority: &Pubkey) -> Self {
+        Self {
+            nonce_authority: *nonce_authority,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+pub enum NonceState {
+    Uninitialized,
+    Initialized(Meta, Hash),
+}
+
+impl Default for NonceState {
+    fn default() -> Self {
+        NonceState::Uninitialized
+    }
+}
+
+impl NonceState {
+    /// Account data length needed to hold any nonce state
+    pub fn size() -> usize {
+        serialized_size(&NonceState::Initialized(Meta::default(), Hash::default())).unwrap()
+            as usize
+    }
+
+    /// The state of `account`, `None` if it doesn't hold a nonce
+    pub fn from(account: &Account) -> Option<Self> {
+        account.state().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::system_program;
+
+    #[test]
+    fn test_nonce_state() {
+        assert_eq!(NonceState::default(), NonceState::Uninitialized);
+
+        let mut account = Account::new(1, NonceState::size(), &system_program::id());
+        assert_eq!(NonceState::from(&account), Some(NonceState::Uninitialized));
+
+        let state = NonceState::Initialized(Meta::new(&Pubkey::new_rand()), Hash::new(&[1; 32]));
+        account.set_state(&state).unwrap();
+        assert_eq!(NonceState::from(&account), Some(state));
+
+        assert_eq!(NonceState::from(&Account::default()), None);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::hash::hashv;
 use generic_array::typenum::U32;
 use generic_array::GenericArray;
 use std::error;
@@ -8,6 +9,9 @@ use std::mem;
 use std::path::Path;
 use std::str::FromStr;
 
+/// Maximum length of the seed used to derive an address with `Pubkey::create_with_seed()`
+pub const MAX_SEED_LEN: usize = 32;
+
 #[repr(C)]
 #[derive(Serialize, Deserialize, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct Pubkey(GenericArray<u8, U32>);
@@ -26,6 +30,19 @@ impl fmt::Display for ParsePubkeyError {
 
 impl error::Error for ParsePubkeyError {}
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PubkeyError {
+    MaxSeedLengthExceeded,
+}
+
+impl fmt::Display for PubkeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PubkeyError: {:?}", self)
+    }
+}
+
+impl error::Error for PubkeyError {}
+
 impl FromStr for Pubkey {
     type Err = ParsePubkeyError;
 
@@ -49,6 +66,21 @@ impl Pubkey {
     pub fn new_rand() -> Self {
         Self::new(&rand::random::<[u8; 32]>())
     }
+
+    /// Derive the address of an account owned by `program_id` from a `base` key and a `seed`.
+    /// No keypair exists for the address; instructions that use it require `base` to sign instead
+    pub fn create_with_seed(
+        base: &Pubkey,
+        seed: &str,
+        program_id: &Pubkey,
+    ) -> Result<Pubkey, PubkeyError> {
+        if seed.len() > MAX_SEED_LEN {
+            return Err(PubkeyError::MaxSeedLengthExceeded);
+        }
+        Ok(Pubkey::new(
+            hashv(&[base.as_ref(), seed.as_ref(), program_id.as_ref()]).as_ref(),
+        ))
+    }
 }
 
 impl AsRef<[u8]> for Pubkey {
@@ -165,6 +197,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_create_with_seed() {
+        let base = Pubkey::new_rand();
+        let program_id = Pubkey::new_rand();
+        let address = Pubkey::create_with_seed(&base, "seed", &program_id).unwrap();
+
+        assert_eq!(
+            Pubkey::create_with_seed(&base, "seed", &program_id),
+            Ok(address)
+        );
+        assert_ne!(
+            Pubkey::create_with_seed(&base, "seed2", &program_id),
+            Ok(address)
+        );
+        assert_ne!(
+            Pubkey::create_with_seed(&Pubkey::new_rand(), "seed", &program_id),
+            Ok(address)
+        );
+        assert_ne!(
+            Pubkey::create_with_seed(&base, "seed", &Pubkey::new_rand()),
+            Ok(address)
+        );
+
+        let max_seed = "x".repeat(MAX_SEED_LEN);
+        assert!(Pubkey::create_with_seed(&base, &max_seed, &program_id).is_ok());
+        assert_eq!(
+            Pubkey::create_with_seed(&base, &format!("{}x", max_seed), &program_id),
+            Err(PubkeyError::MaxSeedLengthExceeded)
+        );
+    }
+
     #[test]
     fn test_read_write_pubkey() -> Result<(), Box<error::Error>> {
         let filename = "test_pubkey.json";
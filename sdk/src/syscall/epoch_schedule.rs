@@ -0,0 +1,55 @@
+//! This account contains the cluster's epoch schedule
+//!
+use crate::account::Account;
+use crate::syscall;
+use bincode::serialized_size;
+
+pub use crate::epoch_schedule::EpochSchedule;
+
+///  epoch schedule account pubkey
+const ID: [u8; 32] = [
+    6, 167, 211, 138, 69, 218, 77, 47, 235, 19, 149, 245, 60, 12, 74, 210, 36, 238, 134, 139, 165,
+    32, 72, 109, 253, 81, 246, 153, 132, 128, 0, 0,
+];
+
+crate::solana_name_id!(ID, "Sysca11EpochSchedu1e11111111111111111111111");
+
+impl EpochSchedule {
+    pub fn from(account: &Account) -> Option<Self> {
+        account.deserialize_data().ok()
+    }
+    pub fn to(&self, account: &mut Account) -> Option<()> {
+        account.serialize_data(self).ok()
+    }
+
+    pub fn size_of() -> usize {
+        serialized_size(&Self::default()).unwrap() as usize
+    }
+}
+
+pub fn create_account(lamports: u64, epoch_schedule: &EpochSchedule) -> Account {
+    Account::new_data(lamports, epoch_schedule, &syscall::id()).unwrap()
+}
+
+use crate::account::KeyedAccount;
+use crate::instruction::InstructionError;
+pub fn from_keyed_account(account: &KeyedAccount) -> Result<EpochSchedule, InstructionError> {
+    if !check_id(account.unsigned_key()) {
+        return Err(InstructionError::InvalidArgument);
+    }
+    EpochSchedule::from(account.account).ok_or(InstructionError::InvalidArgument)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::epoch_schedule::MINIMUM_SLOTS_PER_EPOCH;
+
+    #[test]
+    fn test_create_account() {
+        let epoch_schedule = EpochSchedule::new(MINIMUM_SLOTS_PER_EPOCH * 4, 16, true);
+        let account = create_account(42, &epoch_schedule);
+        assert_eq!(account.data.len(), EpochSchedule::size_of());
+        assert_eq!(EpochSchedule::from(&account), Some(epoch_schedule));
+    }
+}
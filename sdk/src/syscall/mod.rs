@@ -3,15 +3,23 @@
 use crate::pubkey::Pubkey;
 
 pub mod current;
+pub mod epoch_schedule;
 pub mod fees;
+pub mod recent_blockhashes;
+pub mod rent;
 pub mod rewards;
 pub mod slot_hashes;
+pub mod stake_history;
 pub mod tick_height;
 
 pub fn is_syscall_id(id: &Pubkey) -> bool {
     current::check_id(id)
+        || epoch_schedule::check_id(id)
         || fees::check_id(id)
+        || recent_blockhashes::check_id(id)
+        || rent::check_id(id)
         || slot_hashes::check_id(id)
+        || stake_history::check_id(id)
         || tick_height::check_id(id)
 }
 
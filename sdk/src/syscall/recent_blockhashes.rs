@@ -0,0 +1,94 @@
+//! named accounts for synthesized data accounts for bank state, etc.
+//!
+//! this account carries the most recent blockhashes registered by the Bank, newest first
+//!
+use crate::account::Account;
+use crate::hash::Hash;
+use crate::syscall;
+use bincode::serialized_size;
+use std::ops::Deref;
+
+///  recent blockhashes account pubkey
+const ID: [u8; 32] = [
+    6, 167, 211, 138, 69, 219, 174, 174, 71, 44, 205, 174, 17, 227, 196, 187, 100, 212, 62, 245,
+    160, 64, 182, 182, 156, 3, 233, 5, 197, 32, 0, 0,
+];
+
+crate::solana_name_id!(ID, "Sysca11RecentB1ockHashes1111111111111111111");
+
+pub const MAX_ENTRIES: usize = 32;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct RecentBlockhashes {
+    // non-pub to keep control of size
+    inner: Vec<Hash>,
+}
+
+impl RecentBlockhashes {
+    pub fn from(account: &Account) -> Option<Self> {
+        account.deserialize_data().ok()
+    }
+    pub fn to(&self, account: &mut Account) -> Option<()> {
+        account.serialize_data(self).ok()
+    }
+
+    pub fn size_of() -> usize {
+        serialized_size(&RecentBlockhashes {
+            inner: vec![Hash::default(); MAX_ENTRIES],
+        })
+        .unwrap() as usize
+    }
+    /// `blockhashes` are expected newest first; only the first `MAX_ENTRIES` are kept
+    pub fn new(blockhashes: &[Hash]) -> Self {
+        Self {
+            inner: blockhashes.iter().take(MAX_ENTRIES).cloned().collect(),
+        }
+    }
+}
+
+impl Deref for RecentBlockhashes {
+    type Target = Vec<Hash>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+pub fn create_account(lamports: u64, blockhashes: &[Hash]) -> Account {
+    let mut account = Account::new(lamports, RecentBlockhashes::size_of(), &syscall::id());
+    RecentBlockhashes::new(blockhashes)
+        .to(&mut account)
+        .unwrap();
+    account
+}
+
+use crate::account::KeyedAccount;
+use crate::instruction::InstructionError;
+pub fn from_keyed_account(account: &KeyedAccount) -> Result<RecentBlockhashes, InstructionError> {
+    if !check_id(account.unsigned_key()) {
+        return Err(InstructionError::InvalidArgument);
+    }
+    RecentBlockhashes::from(account.account).ok_or(InstructionError::InvalidArgument)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::hash;
+
+    #[test]
+    fn test_create_account() {
+        let account = create_account(42, &[]);
+        assert_eq!(account.data.len(), RecentBlockhashes::size_of());
+        assert_eq!(
+            RecentBlockhashes::from(&account),
+            Some(RecentBlockhashes::default())
+        );
+
+        let blockhashes: Vec<_> = (0..MAX_ENTRIES + 1)
+            .map(|i| hash(&[(i >> 8) as u8, i as u8]))
+            .collect();
+        let account = create_account(42, &blockhashes);
+        let recent_blockhashes = RecentBlockhashes::from(&account).unwrap();
+        assert_eq!(*recent_blockhashes, blockhashes[..MAX_ENTRIES].to_vec());
+    }
+}
@@ -0,0 +1,120 @@
+//! This account contains the current cluster rent parameters
+//!
+use crate::account::Account;
+use crate::syscall;
+use bincode::serialized_size;
+
+///  rent account pubkey
+const ID: [u8; 32] = [
+    6, 167, 211, 138, 69, 219, 174, 199, 178, 58, 21, 125, 47, 216, 154, 37, 41, 105, 158, 41, 227,
+    240, 252, 211, 154, 87, 214, 153, 0, 0, 0, 0,
+];
+
+crate::solana_name_id!(ID, "Sysca11Rent11111111111111111111111111111111");
+
+/// Bytes of storage each account is charged for in addition to its data
+pub const ACCOUNT_STORAGE_OVERHEAD: u64 = 128;
+
+/// Epochs of rent an account must hold to be exempt, unless configured otherwise
+pub const DEFAULT_EXEMPTION_THRESHOLD: u64 = 100;
+
+/// The default `Rent` charges nothing; clusters opt in through their genesis block
+#[repr(C)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Rent {
+    /// Lamports charged per byte of storage per epoch
+    pub lamports_per_byte_epoch: u64,
+    /// Accounts holding at least this many epochs of rent are exempt from paying it
+    pub exemption_threshold: u64,
+}
+
+impl Default for Rent {
+    fn default() -> Self {
+        Self {
+            lamports_per_byte_epoch: 0,
+            exemption_threshold: DEFAULT_EXEMPTION_THRESHOLD,
+        }
+    }
+}
+
+impl Rent {
+    pub fn new(lamports_per_byte_epoch: u64, exemption_threshold: u64) -> Self {
+        Self {
+            lamports_per_byte_epoch,
+            exemption_threshold,
+        }
+    }
+
+    pub fn from(account: &Account) -> Option<Self> {
+        account.deserialize_data().ok()
+    }
+    pub fn to(&self, account: &mut Account) -> Option<()> {
+        account.serialize_data(self).ok()
+    }
+
+    pub fn size_of() -> usize {
+        serialized_size(&Self::default()).unwrap() as usize
+    }
+
+    fn lamports_per_epoch(&self, data_len: usize) -> u64 {
+        (ACCOUNT_STORAGE_OVERHEAD + data_len as u64).saturating_mul(self.lamports_per_byte_epoch)
+    }
+
+    /// The balance an account with `data_len` bytes of data needs to be exempt from rent
+    pub fn minimum_balance(&self, data_len: usize) -> u64 {
+        self.lamports_per_epoch(data_len)
+            .saturating_mul(self.exemption_threshold)
+    }
+
+    pub fn is_exempt(&self, lamports: u64, data_len: usize) -> bool {
+        lamports >= self.minimum_balance(data_len)
+    }
+
+    /// The rent an account owes for `epochs` epochs of storage
+    pub fn due(&self, lamports: u64, data_len: usize, epochs: u64) -> u64 {
+        if self.is_exempt(lamports, data_len) {
+            0
+        } else {
+            self.lamports_per_epoch(data_len).saturating_mul(epochs)
+        }
+    }
+}
+
+pub fn create_account(lamports: u64, rent: &Rent) -> Account {
+    Account::new_data(lamports, rent, &syscall::id()).unwrap()
+}
+
+use crate::account::KeyedAccount;
+use crate::instruction::InstructionError;
+pub fn from_keyed_account(account: &KeyedAccount) -> Result<Rent, InstructionError> {
+    if !check_id(account.unsigned_key()) {
+        return Err(InstructionError::InvalidArgument);
+    }
+    Rent::from(account.account).ok_or(InstructionError::InvalidArgument)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rent_create_account() {
+        let rent = Rent::new(2, 10);
+        let account = create_account(42, &rent);
+        assert_eq!(Rent::from(&account), Some(rent));
+    }
+
+    #[test]
+    fn test_rent_due() {
+        let rent = Rent::new(2, 10);
+        let per_epoch = (ACCOUNT_STORAGE_OVERHEAD + 10) * 2;
+        assert_eq!(rent.minimum_balance(10), per_epoch * 10);
+        assert_eq!(rent.due(0, 10, 3), per_epoch * 3);
+        assert_eq!(rent.due(per_epoch * 10 - 1, 10, 3), per_epoch * 3);
+        assert_eq!(rent.due(per_epoch * 10, 10, 3), 0);
+
+        let rent = Rent::default();
+        assert_eq!(rent.minimum_balance(10), 0);
+        assert_eq!(rent.due(0, 10, 3), 0);
+    }
+}
@@ -0,0 +1,119 @@
+//! This account contains the history of stake activation and deactivation, one entry per
+//! epoch, most recent first
+//!
+use crate::account::Account;
+use crate::syscall;
+use bincode::serialized_size;
+use std::ops::Deref;
+
+pub use crate::timing::Epoch;
+
+///  stake history account pubkey
+const ID: [u8; 32] = [
+    6, 167, 211, 138, 69, 219, 215, 51, 212, 240, 184, 3, 96, 127, 137, 17, 222, 180, 212, 157, 49,
+    45, 128, 13, 72, 215, 114, 178, 194, 0, 0, 0,
+];
+
+crate::solana_name_id!(ID, "Sysca11StakeHistory111111111111111111111111");
+
+pub const MAX_STAKE_HISTORY: usize = 512; // it should never take as many as 512 epochs to warm up
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct StakeHistoryEntry {
+    /// Stake that counted toward its vote account in the epoch
+    pub effective: u64,
+    /// Delegated stake still warming up
+    pub activating: u64,
+    /// Effective stake that is cooling down
+    pub deactivating: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct StakeHistory {
+    // non-pub to keep control of size
+    inner: Vec<(Epoch, StakeHistoryEntry)>,
+}
+
+impl StakeHistory {
+    pub fn from(account: &Account) -> Option<Self> {
+        account.deserialize_data().ok()
+    }
+    pub fn to(&self, account: &mut Account) -> Option<()> {
+        account.serialize_data(self).ok()
+    }
+
+    pub fn size_of() -> usize {
+        serialized_size(&StakeHistory {
+            inner: vec![(0, StakeHistoryEntry::default()); MAX_STAKE_HISTORY],
+        })
+        .unwrap() as usize
+    }
+
+    pub fn get(&self, epoch: Epoch) -> Option<&StakeHistoryEntry> {
+        self.inner
+            .iter()
+            .find(|(entry_epoch, _)| *entry_epoch == epoch)
+            .map(|(_, entry)| entry)
+    }
+
+    pub fn add(&mut self, epoch: Epoch, entry: StakeHistoryEntry) {
+        self.inner.insert(0, (epoch, entry));
+        self.inner.truncate(MAX_STAKE_HISTORY);
+    }
+}
+
+impl Deref for StakeHistory {
+    type Target = Vec<(Epoch, StakeHistoryEntry)>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+pub fn create_account(lamports: u64, stake_history: &StakeHistory) -> Account {
+    let mut account = Account::new(lamports, StakeHistory::size_of(), &syscall::id());
+    stake_history.to(&mut account).unwrap();
+    account
+}
+
+use crate::account::KeyedAccount;
+use crate::instruction::InstructionError;
+pub fn from_keyed_account(account: &KeyedAccount) -> Result<StakeHistory, InstructionError> {
+    if !check_id(account.unsigned_key()) {
+        return Err(InstructionError::InvalidArgument);
+    }
+    StakeHistory::from(account.account).ok_or(InstructionError::InvalidArgument)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_account() {
+        let account = create_account(42, &StakeHistory::default());
+        assert_eq!(account.data.len(), StakeHistory::size_of());
+        let stake_history = StakeHistory::from(&account);
+        assert_eq!(stake_history, Some(StakeHistory::default()));
+
+        let mut stake_history = stake_history.unwrap();
+        for i in 0..MAX_STAKE_HISTORY as u64 + 1 {
+            stake_history.add(
+                i,
+                StakeHistoryEntry {
+                    effective: i,
+                    ..StakeHistoryEntry::default()
+                },
+            );
+        }
+        assert_eq!(stake_history.len(), MAX_STAKE_HISTORY);
+        assert_eq!(stake_history[0].0, MAX_STAKE_HISTORY as u64);
+        assert_eq!(stake_history.get(0), None);
+        assert_eq!(
+            stake_history.get(1),
+            Some(&StakeHistoryEntry {
+                effective: 1,
+                ..StakeHistoryEntry::default()
+            })
+        );
+    }
+}
@@ -1,6 +1,8 @@
 use crate::instruction::{AccountMeta, Instruction};
 use crate::instruction_processor_utils::DecodeError;
-use crate::pubkey::Pubkey;
+use crate::nonce_state::NonceState;
+use crate::pubkey::{Pubkey, PubkeyError};
+use crate::syscall::{recent_blockhashes, rent};
 use crate::system_program;
 use num_derive::FromPrimitive;
 
@@ -11,6 +13,11 @@ pub enum SystemError {
     SourceNotSystemAccount,
     InvalidProgramId,
     InvalidAccountId,
+    AddressWithSeedMismatch,
+    MaxSeedLengthExceeded,
+    NonceNoRecentBlockhashes,
+    NonceBlockhashNotExpired,
+    SourceHasData,
 }
 
 impl<T> DecodeError<T> for SystemError {
@@ -26,6 +33,14 @@ impl std::fmt::Display for SystemError {
 }
 impl std::error::Error for SystemError {}
 
+impl From<PubkeyError> for SystemError {
+    fn from(err: PubkeyError) -> Self {
+        match err {
+            PubkeyError::MaxSeedLengthExceeded => SystemError::MaxSeedLengthExceeded,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub enum SystemInstruction {
     /// Create a new account
@@ -46,6 +61,57 @@ pub enum SystemInstruction {
     /// * Transaction::keys[0] - source
     /// * Transaction::keys[1] - destination
     Transfer { lamports: u64 },
+    /// Create a new account at an address derived from a base key and a seed
+    /// * Transaction::keys[0] - source
+    /// * Transaction::keys[1] - new account key, `Pubkey::create_with_seed(base, seed, program_id)`
+    /// * Transaction::keys[2] - base, unless it is the source
+    /// * lamports - number of lamports to transfer to the new account
+    /// * space - memory to allocate if greater then zero
+    /// * program_id - the program id of the new account
+    CreateAccountWithSeed {
+        base: Pubkey,
+        seed: String,
+        lamports: u64,
+        space: u64,
+        program_id: Pubkey,
+    },
+    /// Assign an account at a derived address to a program
+    /// * Transaction::keys[0] - account to assign, `Pubkey::create_with_seed(base, seed, program_id)`
+    /// * Transaction::keys[1] - base
+    AssignWithSeed { seed: String, program_id: Pubkey },
+    /// Transfer lamports from an account at a derived address
+    /// * Transaction::keys[0] - source, `Pubkey::create_with_seed(base, from_seed, from_owner)`
+    /// * Transaction::keys[1] - base
+    /// * Transaction::keys[2] - destination
+    TransferWithSeed {
+        lamports: u64,
+        from_seed: String,
+        from_owner: Pubkey,
+    },
+    /// Replace the blockhash stored in a nonce account with the most recent blockhash.  A
+    /// transaction whose first instruction is `NonceAdvance` may use the stored blockhash
+    /// as its recent blockhash.
+    /// * Transaction::keys[0] - nonce account
+    /// * Transaction::keys[1] - RecentBlockhashes syscall
+    /// * Transaction::keys[2] - nonce authority
+    NonceAdvance,
+    /// Withdraw lamports from a nonce account, leaving at least the rent exempt minimum
+    /// unless the nonce account is emptied
+    /// * Transaction::keys[0] - nonce account
+    /// * Transaction::keys[1] - destination
+    /// * Transaction::keys[2] - RecentBlockhashes syscall
+    /// * Transaction::keys[3] - Rent syscall
+    /// * Transaction::keys[4] - nonce authority, or the nonce account if uninitialized
+    NonceWithdraw { lamports: u64 },
+    /// Store the most recent blockhash in a rent exempt, uninitialized nonce account
+    /// * Transaction::keys[0] - nonce account
+    /// * Transaction::keys[1] - RecentBlockhashes syscall
+    /// * Transaction::keys[2] - Rent syscall
+    NonceInitialize { nonce_authority: Pubkey },
+    /// Change the key entitled to operate a nonce account
+    /// * Transaction::keys[0] - nonce account
+    /// * Transaction::keys[1] - current nonce authority
+    NonceAuthorize { nonce_authority: Pubkey },
 }
 
 pub fn create_account(
@@ -87,6 +153,79 @@ pub fn assign(from_pubkey: &Pubkey, program_id: &Pubkey) -> Instruction {
     )
 }
 
+pub fn create_account_with_seed(
+    from_pubkey: &Pubkey,
+    to_pubkey: &Pubkey, // must match create_with_seed(base, seed, program_id)
+    base: &Pubkey,
+    seed: &str,
+    lamports: u64,
+    space: u64,
+    program_id: &Pubkey,
+) -> Instruction {
+    let mut account_metas = vec![
+        AccountMeta::new(*from_pubkey, true),
+        AccountMeta::new(*to_pubkey, false),
+    ];
+    if base != from_pubkey {
+        account_metas.push(AccountMeta::new_credit_only(*base, true));
+    }
+    Instruction::new(
+        system_program::id(),
+        &SystemInstruction::CreateAccountWithSeed {
+            base: *base,
+            seed: seed.to_string(),
+            lamports,
+            space,
+            program_id: *program_id,
+        },
+        account_metas,
+    )
+}
+
+pub fn assign_with_seed(
+    address: &Pubkey, // must match create_with_seed(base, seed, program_id)
+    base: &Pubkey,
+    seed: &str,
+    program_id: &Pubkey,
+) -> Instruction {
+    let account_metas = vec![
+        AccountMeta::new(*address, false),
+        AccountMeta::new_credit_only(*base, true),
+    ];
+    Instruction::new(
+        system_program::id(),
+        &SystemInstruction::AssignWithSeed {
+            seed: seed.to_string(),
+            program_id: *program_id,
+        },
+        account_metas,
+    )
+}
+
+pub fn transfer_with_seed(
+    from_pubkey: &Pubkey, // must match create_with_seed(base, from_seed, from_owner)
+    from_base: &Pubkey,
+    from_seed: &str,
+    from_owner: &Pubkey,
+    to_pubkey: &Pubkey,
+    lamports: u64,
+) -> Instruction {
+    let account_metas = vec![
+        AccountMeta::new(*from_pubkey, false),
+        AccountMeta::new_credit_only(*from_base, true),
+        AccountMeta::new(*to_pubkey, false),
+    ];
+    Instruction::new(
+        system_program::id(),
+        &SystemInstruction::TransferWithSeed {
+            lamports,
+            from_seed: from_seed.to_string(),
+            from_owner: *from_owner,
+        },
+        account_metas,
+    )
+}
+
 pub fn transfer(from_pubkey: &Pubkey, to_pubkey: &Pubkey, lamports: u64) -> Instruction {
     let account_metas = vec![
         AccountMeta::new(*from_pubkey, true),
@@ -99,6 +238,86 @@ pub fn transfer(from_pubkey: &Pubkey, to_pubkey: &Pubkey, lamports: u64) -> Inst
     )
 }
 
+/// Create a nonce account at `nonce_pubkey` funded by `from_pubkey` and initialize it
+pub fn create_nonce_account(
+    from_pubkey: &Pubkey,
+    nonce_pubkey: &Pubkey,
+    nonce_authority: &Pubkey,
+    lamports: u64,
+) -> Vec<Instruction> {
+    vec![
+        create_account(
+            from_pubkey,
+            nonce_pubkey,
+            lamports,
+            NonceState::size() as u64,
+            &system_program::id(),
+        ),
+        Instruction::new(
+            system_program::id(),
+            &SystemInstruction::NonceInitialize {
+                nonce_authority: *nonce_authority,
+            },
+            vec![
+                AccountMeta::new(*nonce_pubkey, false),
+                AccountMeta::new_credit_only(recent_blockhashes::id(), false),
+                AccountMeta::new_credit_only(rent::id(), false),
+            ],
+        ),
+    ]
+}
+
+pub fn nonce_advance(nonce_pubkey: &Pubkey, nonce_authority: &Pubkey) -> Instruction {
+    let account_metas = vec![
+        AccountMeta::new(*nonce_pubkey, false),
+        AccountMeta::new_credit_only(recent_blockhashes::id(), false),
+        AccountMeta::new_credit_only(*nonce_authority, true),
+    ];
+    Instruction::new(
+        system_program::id(),
+        &SystemInstruction::NonceAdvance,
+        account_metas,
+    )
+}
+
+pub fn nonce_withdraw(
+    nonce_pubkey: &Pubkey,
+    nonce_authority: &Pubkey,
+    to_pubkey: &Pubkey,
+    lamports: u64,
+) -> Instruction {
+    let account_metas = vec![
+        AccountMeta::new(*nonce_pubkey, false),
+        AccountMeta::new(*to_pubkey, false),
+        AccountMeta::new_credit_only(recent_blockhashes::id(), false),
+        AccountMeta::new_credit_only(rent::id(), false),
+        AccountMeta::new_credit_only(*nonce_authority, true),
+    ];
+    Instruction::new(
+        system_program::id(),
+        &SystemInstruction::NonceWithdraw { lamports },
+        account_metas,
+    )
+}
+
+pub fn nonce_authorize(
+    nonce_pubkey: &Pubkey,
+    nonce_authority: &Pubkey,
+    new_nonce_authority: &Pubkey,
+) -> Instruction {
+    let account_metas = vec![
+        AccountMeta::new(*nonce_pubkey, false),
+        AccountMeta::new_credit_only(*nonce_authority, true),
+    ];
+    Instruction::new(
+        system_program::id(),
+        &SystemInstruction::NonceAuthorize {
+            nonce_authority: *new_nonce_authority,
+        },
+        account_metas,
+    )
+}
+
 /// Create and sign new SystemInstruction::Transfer transaction to many destinations
 pub fn transfer_many(from_pubkey: &Pubkey, to_lamports: &[(Pubkey, u64)]) -> Vec<Instruction> {
     to_lamports
@@ -127,4 +346,51 @@ mod tests {
         assert_eq!(get_keys(&instructions[0]), vec![alice_pubkey, bob_pubkey]);
         assert_eq!(get_keys(&instructions[1]), vec![alice_pubkey, carol_pubkey]);
     }
+
+    #[test]
+    fn test_create_account_with_seed_keys() {
+        let from_pubkey = Pubkey::new_rand();
+        let base = Pubkey::new_rand();
+        let program_id = Pubkey::new_rand();
+        let to_pubkey = Pubkey::create_with_seed(&base, "seed", &program_id).unwrap();
+
+        let instruction =
+            create_account_with_seed(&from_pubkey, &to_pubkey, &base, "seed", 1, 0, &program_id);
+        assert_eq!(get_keys(&instruction), vec![from_pubkey, to_pubkey, base]);
+
+        // The source doubles as the base
+        let to_pubkey = Pubkey::create_with_seed(&from_pubkey, "seed", &program_id).unwrap();
+        let instruction = create_account_with_seed(
+            &from_pubkey,
+            &to_pubkey,
+            &from_pubkey,
+            "seed",
+            1,
+            0,
+            &program_id,
+        );
+        assert_eq!(get_keys(&instruction), vec![from_pubkey, to_pubkey]);
+    }
+
+    #[test]
+    fn test_nonce_keys() {
+        let from_pubkey = Pubkey::new_rand();
+        let nonce_pubkey = Pubkey::new_rand();
+        let authority = Pubkey::new_rand();
+
+        let instructions = create_nonce_account(&from_pubkey, &nonce_pubkey, &authority, 42);
+        assert_eq!(instructions.len(), 2);
+        assert_eq!(get_keys(&instructions[0]), vec![from_pubkey, nonce_pubkey]);
+        assert_eq!(
+            get_keys(&instructions[1]),
+            vec![nonce_pubkey, recent_blockhashes::id(), rent::id()]
+        );
+
+        let instruction = nonce_advance(&nonce_pubkey, &authority);
+        assert_eq!(
+            get_keys(&instruction),
+            vec![nonce_pubkey, recent_blockhashes::id(), authority]
+        );
+        assert!(instruction.accounts[2].is_signer);
+    }
 }
@@ -51,6 +51,9 @@ pub enum TransactionError {
 
     /// Transaction contains an invalid account reference
     InvalidAccountIndex,
+
+    /// Transaction is missing a signature or one of its signatures does not verify
+    SignatureFailure,
 }
 
 pub type Result<T> = result::Result<T, TransactionError>;
@@ -220,6 +223,27 @@ impl Transaction {
             .all(|signature| *signature != Signature::default())
     }
 
+    /// Verify that every key required to sign the message has signed it
+    pub fn verify(&self) -> Result<()> {
+        let num_required_signatures = self.message.header.num_required_signatures as usize;
+        if self.signatures.len() != num_required_signatures
+            || self.message.account_keys.len() < num_required_signatures
+        {
+            return Err(TransactionError::SignatureFailure);
+        }
+        let message_data = self.message_data();
+        let verified = self
+            .signatures
+            .iter()
+            .zip(&self.message.account_keys)
+            .all(|(signature, pubkey)| signature.verify(pubkey.as_ref(), &message_data));
+        if verified {
+            Ok(())
+        } else {
+            Err(TransactionError::SignatureFailure)
+        }
+    }
+
     /// Verify that references in the instructions are valid
     pub fn verify_refs(&self) -> bool {
         let message = self.message();
@@ -473,6 +497,32 @@ mod tests {
         assert!(tx.is_signed());
     }
 
+    #[test]
+    fn test_verify() {
+        let keypair0 = Keypair::new();
+        let keypair1 = Keypair::new();
+        let ix = Instruction::new(
+            Pubkey::default(),
+            &0,
+            vec![
+                AccountMeta::new(keypair0.pubkey(), true),
+                AccountMeta::new(keypair1.pubkey(), true),
+            ],
+        );
+        let mut tx = Transaction::new_unsigned_instructions(vec![ix]);
+        assert_eq!(tx.verify(), Err(TransactionError::SignatureFailure));
+        tx.partial_sign(&[&keypair0], Hash::default());
+        assert_eq!(tx.verify(), Err(TransactionError::SignatureFailure));
+        tx.partial_sign(&[&keypair1], Hash::default());
+        assert_eq!(tx.verify(), Ok(()));
+
+        // A signature over another message does not verify
+        tx.signatures.swap(0, 1);
+        assert_eq!(tx.verify(), Err(TransactionError::SignatureFailure));
+        tx.signatures.pop();
+        assert_eq!(tx.verify(), Err(TransactionError::SignatureFailure));
+    }
+
     #[test]
     #[should_panic]
     fn test_transaction_missing_keypair() {
@@ -0,0 +1,21 @@
+//! The `transaction_status` module defines what is recorded about each transaction a Bank
+//! commits, beyond whether it succeeded.
+
+use crate::transaction;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionStatusMeta {
+    /// The result of executing the transaction
+    pub status: transaction::Result<()>,
+    /// Lamports charged to the fee payer
+    pub fee: u64,
+    /// Lamports held by each of the message's account keys before the transaction
+    pub pre_balances: Vec<u64>,
+    /// Lamports held by each of the message's account keys after the transaction and its fee
+    pub post_balances: Vec<u64>,
+    /// Messages logged by the transaction's programs
+    pub log_messages: Vec<String>,
+    /// Units the transaction's instructions consumed from its compute budget
+    pub compute_units_consumed: u64,
+}
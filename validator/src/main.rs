@@ -2,14 +2,17 @@ use clap::{crate_description, crate_name, crate_version, App, Arg};
 use log::*;
 use solana::cluster_info::{Node, FULLNODE_PORT_RANGE};
 use solana::contact_info::ContactInfo;
+use solana::ledger_cleanup_service::DEFAULT_MAX_LEDGER_SLOTS;
 use solana::local_vote_signer_service::LocalVoteSignerService;
 use solana::service::Service;
+use solana::snapshot_utils;
 use solana::socketaddr;
 use solana::validator::{Validator, ValidatorConfig};
 use solana_netutil::parse_port_range;
 use solana_sdk::signature::{read_keypair, Keypair, KeypairUtil};
 use std::fs::File;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::process::exit;
 use std::sync::Arc;
 
@@ -27,6 +30,11 @@ fn main() {
 
     let default_dynamic_port_range =
         &format!("{}-{}", FULLNODE_PORT_RANGE.0, FULLNODE_PORT_RANGE.1);
+    let limit_ledger_size_help = &format!(
+        "Purge the ledger of slots that fall more than SLOTS behind the root, keeping those \
+         from the latest snapshot archive on [default SLOTS: {}]",
+        DEFAULT_MAX_LEDGER_SLOTS
+    );
 
     let matches = App::new(crate_name!()).about(crate_description!())
         .version(crate_version!())
@@ -124,6 +132,44 @@ fn main() {
                 .takes_value(true)
                 .help("Enable the JSON RPC 'requestAirdrop' API with this drone address."),
         )
+        .arg(
+            Arg::with_name("rpc_max_requests_per_second")
+                .long("rpc-max-requests-per-second")
+                .value_name("NUMBER")
+                .takes_value(true)
+                .help("Limit each client IP, as named by the X-Forwarded-For header of the reverse proxy in front of the RPC port, to this many JSON RPC calls per second"),
+        )
+        .arg(
+            Arg::with_name("rpc_trusted_proxy")
+                .long("rpc-trusted-proxy")
+                .value_name("IP")
+                .takes_value(true)
+                .multiple(true)
+                .help("Skip the hops this reverse proxy adds to the X-Forwarded-For header of JSON RPC requests when looking for the client IP"),
+        )
+        .arg(
+            Arg::with_name("rpc_allow_method")
+                .long("rpc-allow-method")
+                .value_name("METHOD")
+                .takes_value(true)
+                .multiple(true)
+                .help("Only serve these JSON RPC methods on the public RPC port"),
+        )
+        .arg(
+            Arg::with_name("rpc_deny_method")
+                .long("rpc-deny-method")
+                .value_name("METHOD")
+                .takes_value(true)
+                .multiple(true)
+                .help("Never serve these JSON RPC methods on the public RPC port"),
+        )
+        .arg(
+            Arg::with_name("admin_rpc_path")
+                .long("admin-rpc-path")
+                .value_name("PATH")
+                .takes_value(true)
+                .help("Serve every JSON RPC method on a Unix socket at this path, which only the validator's user can connect to, and stop serving privileged methods such as 'fullnodeExit' on the public RPC port"),
+        )
         .arg(
             Arg::with_name("signer")
                 .short("s")
@@ -162,6 +208,48 @@ fn main() {
                 .value_name("PATHS")
                 .takes_value(true)
                 .help("Snapshot path"),
+        )
+        .arg(
+            clap::Arg::with_name("snapshot_interval_slots")
+                .long("snapshot-interval-slots")
+                .value_name("SLOTS")
+                .takes_value(true)
+                .requires("snapshot_path")
+                .help("Number of slots the root advances between snapshot archives"),
+        )
+        .arg(
+            clap::Arg::with_name("limit_ledger_size")
+                .long("limit-ledger-size")
+                .value_name("SLOTS")
+                .takes_value(true)
+                .min_values(0)
+                .max_values(1)
+                .requires("snapshot_path")
+                .help(limit_ledger_size_help),
+        )
+        .arg(
+            clap::Arg::with_name("download_snapshot_from")
+                .long("download-snapshot-from")
+                .value_name("HOST:PORT")
+                .takes_value(true)
+                .requires("snapshot_path")
+                .requires("expected_bank_hash")
+                .help("Download a snapshot archive from this RPC address before starting"),
+        )
+        .arg(
+            clap::Arg::with_name("expected_bank_hash")
+                .long("expected-bank-hash")
+                .value_name("HASH")
+                .takes_value(true)
+                .requires("download_snapshot_from")
+                .help("Bank hash the downloaded snapshot archive must have"),
+        )
+        .arg(
+            clap::Arg::with_name("enable_rpc_snapshot_download")
+                .long("enable-rpc-snapshot-download")
+                .takes_value(false)
+                .requires("snapshot_path")
+                .help("Serve the latest snapshot archive to other validators over RPC"),
         )
          .get_matches();
 
@@ -209,6 +297,26 @@ fn main() {
     validator_config.rpc_config.drone_addr = matches.value_of("rpc_drone_address").map(|address| {
         solana_netutil::parse_host_port(address).expect("failed to parse drone address")
     });
+    validator_config.rpc_config.max_requests_per_second_per_ip = matches
+        .value_of("rpc_max_requests_per_second")
+        .map(|count| {
+            count
+                .parse()
+                .expect("failed to parse rpc_max_requests_per_second")
+        });
+    if let Some(proxies) = matches.values_of("rpc_trusted_proxy") {
+        validator_config.rpc_config.trusted_proxies = proxies
+            .map(|proxy| proxy.parse().expect("failed to parse rpc_trusted_proxy"))
+            .collect();
+    }
+    validator_config.rpc_config.allowed_methods = matches
+        .values_of("rpc_allow_method")
+        .map(|methods| methods.map(ToString::to_string).collect());
+    if let Some(methods) = matches.values_of("rpc_deny_method") {
+        validator_config.rpc_config.denied_methods = methods.map(ToString::to_string).collect();
+    }
+    validator_config.rpc_config.admin_rpc_path =
+        matches.value_of("admin_rpc_path").map(PathBuf::from);
 
     let dynamic_port_range = parse_port_range(matches.value_of("dynamic_port_range").unwrap())
         .expect("invalid dynamic_port_range");
@@ -232,6 +340,40 @@ fn main() {
     } else {
         validator_config.snapshot_path = None;
     }
+    if let Some(slots) = matches.value_of("snapshot_interval_slots") {
+        validator_config.snapshot_interval_slots = slots
+            .parse()
+            .expect("failed to parse snapshot_interval_slots");
+    }
+    if matches.is_present("limit_ledger_size") {
+        validator_config.max_ledger_slots = Some(
+            matches
+                .value_of("limit_ledger_size")
+                .map_or(DEFAULT_MAX_LEDGER_SLOTS, |slots| {
+                    slots.parse().expect("failed to parse limit_ledger_size")
+                }),
+        );
+    }
+    validator_config.rpc_config.enable_snapshot_download =
+        matches.is_present("enable_rpc_snapshot_download");
+    if let Some(address) = matches.value_of("download_snapshot_from") {
+        let rpc_addr =
+            solana_netutil::parse_host_port(address).expect("failed to parse snapshot address");
+        let archive_path = snapshot_utils::get_snapshot_archive_path(
+            validator_config.snapshot_path.as_ref().unwrap(),
+        );
+        snapshot_utils::download_snapshot(&rpc_addr, &archive_path).unwrap_or_else(|err| {
+            eprintln!("Unable to download snapshot from {}: {}", rpc_addr, err);
+            exit(1);
+        });
+        validator_config.expected_bank_hash = Some(
+            matches
+                .value_of("expected_bank_hash")
+                .unwrap()
+                .parse()
+                .expect("failed to parse expected_bank_hash"),
+        );
+    }
     let cluster_entrypoint = matches.value_of("entrypoint").map(|entrypoint| {
         let entrypoint_addr = solana_netutil::parse_host_port(entrypoint)
             .expect("failed to parse entrypoint address");
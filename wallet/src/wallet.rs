@@ -21,9 +21,12 @@ use solana_sdk::instruction::InstructionError;
 use solana_sdk::instruction_processor_utils::DecodeError;
 use solana_sdk::loader_instruction;
 use solana_sdk::message::Message;
+use solana_sdk::nonce_state::NonceState;
 use solana_sdk::pubkey::Pubkey;
+use solana_sdk::pubkey::MAX_SEED_LEN;
 use solana_sdk::signature::{read_keypair, Keypair, KeypairUtil, Signature};
-use solana_sdk::system_instruction::SystemError;
+use solana_sdk::system_instruction::{self, SystemError};
+use solana_sdk::system_program;
 use solana_sdk::system_transaction;
 use solana_sdk::transaction::{Transaction, TransactionError};
 use solana_stake_api::stake_instruction;
@@ -47,6 +50,8 @@ pub enum WalletCommand {
     Balance(Pubkey),
     Cancel(Pubkey),
     Confirm(Signature),
+    // CreateAddressWithSeed(base, seed, program_id)
+    CreateAddressWithSeed(Pubkey, String, Pubkey),
     AuthorizeVoter(Pubkey, Keypair, Pubkey),
     CreateVoteAccount(Pubkey, Pubkey, u32, u64),
     ShowVoteAccount(Pubkey),
@@ -75,6 +80,15 @@ pub enum WalletCommand {
     TimeElapsed(Pubkey, Pubkey, DateTime<Utc>),
     // Witness(to, process_id)
     Witness(Pubkey, Pubkey),
+    // TransferWithSeed(from_seed, to, lamports)
+    TransferWithSeed(String, Pubkey, u64),
+    // CreateNonceAccount(nonce_account_pubkey, nonce_authority, lamports)
+    CreateNonceAccount(Pubkey, Pubkey, u64),
+    GetNonce(Pubkey),
+    NewNonce(Pubkey),
+    ShowNonceAccount(Pubkey),
+    // WithdrawFromNonceAccount(nonce_account_pubkey, destination_account_pubkey, lamports)
+    WithdrawFromNonceAccount(Pubkey, Pubkey, u64),
 }
 
 #[derive(Debug, Clone)]
@@ -197,6 +211,12 @@ pub fn parse_command(
                 }
             }
         }
+        ("create-address-with-seed", Some(matches)) => {
+            let base = value_of(matches, "from").unwrap_or(*pubkey);
+            let seed = matches.value_of("seed").unwrap().to_string();
+            let program_id = value_of(matches, "program_id").unwrap_or_else(system_program::id);
+            Ok(WalletCommand::CreateAddressWithSeed(base, seed, program_id))
+        }
         ("create-vote-account", Some(matches)) => {
             let voting_account_pubkey = value_of(matches, "voting_account_pubkey").unwrap();
             let node_pubkey = value_of(matches, "node_pubkey").unwrap();
@@ -369,6 +389,45 @@ pub fn parse_command(
             };
             Ok(WalletCommand::TimeElapsed(to, process_id, dt))
         }
+        ("transfer-with-seed", Some(matches)) => {
+            let seed = matches.value_of("seed").unwrap().to_string();
+            let to = value_of(matches, "to").unwrap();
+            let lamports = matches.value_of("lamports").unwrap().parse()?;
+            Ok(WalletCommand::TransferWithSeed(seed, to, lamports))
+        }
+        ("create-nonce-account", Some(matches)) => {
+            let nonce_account_pubkey = value_of(matches, "nonce_account_pubkey").unwrap();
+            let lamports = matches.value_of("lamports").unwrap().parse()?;
+            let nonce_authority = value_of(matches, "nonce_authority").unwrap_or(*pubkey);
+            Ok(WalletCommand::CreateNonceAccount(
+                nonce_account_pubkey,
+                nonce_authority,
+                lamports,
+            ))
+        }
+        ("get-nonce", Some(matches)) => {
+            let nonce_account_pubkey = value_of(matches, "nonce_account_pubkey").unwrap();
+            Ok(WalletCommand::GetNonce(nonce_account_pubkey))
+        }
+        ("new-nonce", Some(matches)) => {
+            let nonce_account_pubkey = value_of(matches, "nonce_account_pubkey").unwrap();
+            Ok(WalletCommand::NewNonce(nonce_account_pubkey))
+        }
+        ("show-nonce-account", Some(matches)) => {
+            let nonce_account_pubkey = value_of(matches, "nonce_account_pubkey").unwrap();
+            Ok(WalletCommand::ShowNonceAccount(nonce_account_pubkey))
+        }
+        ("withdraw-from-nonce-account", Some(matches)) => {
+            let nonce_account_pubkey = value_of(matches, "nonce_account_pubkey").unwrap();
+            let destination_account_pubkey =
+                value_of(matches, "destination_account_pubkey").unwrap();
+            let lamports = matches.value_of("lamports").unwrap().parse()?;
+            Ok(WalletCommand::WithdrawFromNonceAccount(
+                nonce_account_pubkey,
+                destination_account_pubkey,
+                lamports,
+            ))
+        }
         ("", None) => {
             eprintln!("{}", matches.usage());
             Err(WalletError::CommandNotRecognized(
@@ -461,6 +520,15 @@ fn process_confirm(rpc_client: &RpcClient, signature: &Signature) -> ProcessResu
     }
 }
 
+fn process_create_address_with_seed(
+    base: &Pubkey,
+    seed: &str,
+    program_id: &Pubkey,
+) -> ProcessResult {
+    let address = Pubkey::create_with_seed(base, seed, program_id)?;
+    Ok(address.to_string())
+}
+
 fn process_create_vote_account(
     rpc_client: &RpcClient,
     config: &WalletConfig,
@@ -913,6 +981,134 @@ fn process_pay(
     }
 }
 
+fn process_transfer_with_seed(
+    rpc_client: &RpcClient,
+    config: &WalletConfig,
+    from_seed: &str,
+    to: &Pubkey,
+    lamports: u64,
+) -> ProcessResult {
+    let base = config.keypair.pubkey();
+    let from_owner = system_program::id();
+    let from = Pubkey::create_with_seed(&base, from_seed, &from_owner)?;
+    let (blockhash, _fee_calculator) = rpc_client.get_recent_blockhash()?;
+    let ix =
+        system_instruction::transfer_with_seed(&from, &base, from_seed, &from_owner, to, lamports);
+    let mut tx = Transaction::new_signed_instructions(&[&config.keypair], vec![ix], blockhash);
+    let result = rpc_client.send_and_confirm_transaction(&mut tx, &[&config.keypair]);
+    let signature_str = log_instruction_custom_error::<SystemError>(result)?;
+    Ok(signature_str.to_string())
+}
+
+fn process_create_nonce_account(
+    rpc_client: &RpcClient,
+    config: &WalletConfig,
+    nonce_account_pubkey: &Pubkey,
+    nonce_authority: &Pubkey,
+    lamports: u64,
+) -> ProcessResult {
+    let minimum_balance = rpc_client.get_minimum_balance_for_rent_exemption(NonceState::size())?;
+    if lamports < minimum_balance {
+        Err(WalletError::BadParameter(format!(
+            "need at least {} lamports for the nonce account to be rent exempt, provided lamports: {}",
+            minimum_balance, lamports
+        )))?;
+    }
+
+    let (recent_blockhash, _fee_calculator) = rpc_client.get_recent_blockhash()?;
+    let ixs = system_instruction::create_nonce_account(
+        &config.keypair.pubkey(),
+        nonce_account_pubkey,
+        nonce_authority,
+        lamports,
+    );
+    let mut tx = Transaction::new_signed_instructions(&[&config.keypair], ixs, recent_blockhash);
+    let result = rpc_client.send_and_confirm_transaction(&mut tx, &[&config.keypair]);
+    let signature_str = log_instruction_custom_error::<SystemError>(result)?;
+    Ok(signature_str.to_string())
+}
+
+fn get_nonce_state(
+    rpc_client: &RpcClient,
+    nonce_account_pubkey: &Pubkey,
+) -> Result<(u64, NonceState), Box<dyn error::Error>> {
+    let nonce_account = rpc_client.get_account(nonce_account_pubkey)?;
+    if nonce_account.owner != system_program::id() {
+        Err(WalletError::RpcRequestError(format!(
+            "{} is not a nonce account",
+            nonce_account_pubkey
+        )))?;
+    }
+    match nonce_account.state() {
+        Ok(nonce_state) => Ok((nonce_account.lamports, nonce_state)),
+        Err(_) => Err(WalletError::RpcRequestError(
+            "Account data could not be deserialized to nonce state".to_string(),
+        ))?,
+    }
+}
+
+fn process_get_nonce(rpc_client: &RpcClient, nonce_account_pubkey: &Pubkey) -> ProcessResult {
+    match get_nonce_state(rpc_client, nonce_account_pubkey)? {
+        (_, NonceState::Initialized(_, hash)) => Ok(hash.to_string()),
+        (_, NonceState::Uninitialized) => Err(WalletError::RpcRequestError(
+            "Nonce account is uninitialized".to_string(),
+        ))?,
+    }
+}
+
+fn process_new_nonce(
+    rpc_client: &RpcClient,
+    config: &WalletConfig,
+    nonce_account_pubkey: &Pubkey,
+) -> ProcessResult {
+    let (recent_blockhash, _fee_calculator) = rpc_client.get_recent_blockhash()?;
+    let ix = system_instruction::nonce_advance(nonce_account_pubkey, &config.keypair.pubkey());
+    let mut tx =
+        Transaction::new_signed_instructions(&[&config.keypair], vec![ix], recent_blockhash);
+    let result = rpc_client.send_and_confirm_transaction(&mut tx, &[&config.keypair]);
+    let signature_str = log_instruction_custom_error::<SystemError>(result)?;
+    Ok(signature_str.to_string())
+}
+
+fn process_show_nonce_account(
+    rpc_client: &RpcClient,
+    nonce_account_pubkey: &Pubkey,
+) -> ProcessResult {
+    let (lamports, nonce_state) = get_nonce_state(rpc_client, nonce_account_pubkey)?;
+    println!("balance: {}", lamports);
+    match nonce_state {
+        NonceState::Initialized(meta, hash) => {
+            println!("nonce: {}", hash);
+            println!("nonce authority: {}", meta.nonce_authority);
+        }
+        NonceState::Uninitialized => {
+            println!("nonce: uninitialized");
+        }
+    }
+    Ok("".to_string())
+}
+
+fn process_withdraw_from_nonce_account(
+    rpc_client: &RpcClient,
+    config: &WalletConfig,
+    nonce_account_pubkey: &Pubkey,
+    destination_account_pubkey: &Pubkey,
+    lamports: u64,
+) -> ProcessResult {
+    let (recent_blockhash, _fee_calculator) = rpc_client.get_recent_blockhash()?;
+    let ix = system_instruction::nonce_withdraw(
+        nonce_account_pubkey,
+        &config.keypair.pubkey(),
+        destination_account_pubkey,
+        lamports,
+    );
+    let mut tx =
+        Transaction::new_signed_instructions(&[&config.keypair], vec![ix], recent_blockhash);
+    let result = rpc_client.send_and_confirm_transaction(&mut tx, &[&config.keypair]);
+    let signature_str = log_instruction_custom_error::<SystemError>(result)?;
+    Ok(signature_str.to_string())
+}
+
 fn process_cancel(rpc_client: &RpcClient, config: &WalletConfig, pubkey: &Pubkey) -> ProcessResult {
     let (blockhash, _fee_calculator) = rpc_client.get_recent_blockhash()?;
     let ix = budget_instruction::apply_signature(
@@ -982,6 +1178,10 @@ pub fn process_command(config: &WalletConfig) -> ProcessResult {
         // Get address of this client
         return Ok(format!("{}", config.keypair.pubkey()));
     }
+    if let WalletCommand::CreateAddressWithSeed(base, seed, program_id) = &config.command {
+        // Derive an address without contacting the cluster
+        return process_create_address_with_seed(base, seed, program_id);
+    }
 
     let drone_addr = config.drone_addr();
 
@@ -998,6 +1198,9 @@ pub fn process_command(config: &WalletConfig) -> ProcessResult {
         // Get address of this client
         WalletCommand::Address => unreachable!(),
 
+        // Derive an address from a base pubkey and a seed
+        WalletCommand::CreateAddressWithSeed(_, _, _) => unreachable!(),
+
         WalletCommand::Fees => process_fees(&rpc_client),
 
         // Request an airdrop from Solana Drone;
@@ -1157,6 +1360,47 @@ pub fn process_command(config: &WalletConfig) -> ProcessResult {
         WalletCommand::Witness(to, pubkey) => {
             process_witness(&rpc_client, config, drone_addr, &to, &pubkey)
         }
+
+        // Transfer lamports out of an account at an address derived from this client's pubkey
+        WalletCommand::TransferWithSeed(from_seed, to, lamports) => {
+            process_transfer_with_seed(&rpc_client, config, from_seed, to, *lamports)
+        }
+
+        // Create a nonce account whose blockhash can stand in for a recent blockhash
+        WalletCommand::CreateNonceAccount(nonce_account_pubkey, nonce_authority, lamports) => {
+            process_create_nonce_account(
+                &rpc_client,
+                config,
+                &nonce_account_pubkey,
+                &nonce_authority,
+                *lamports,
+            )
+        }
+
+        WalletCommand::GetNonce(nonce_account_pubkey) => {
+            process_get_nonce(&rpc_client, &nonce_account_pubkey)
+        }
+
+        // Advance a nonce account to a new blockhash
+        WalletCommand::NewNonce(nonce_account_pubkey) => {
+            process_new_nonce(&rpc_client, config, &nonce_account_pubkey)
+        }
+
+        WalletCommand::ShowNonceAccount(nonce_account_pubkey) => {
+            process_show_nonce_account(&rpc_client, &nonce_account_pubkey)
+        }
+
+        WalletCommand::WithdrawFromNonceAccount(
+            nonce_account_pubkey,
+            destination_account_pubkey,
+            lamports,
+        ) => process_withdraw_from_nonce_account(
+            &rpc_client,
+            config,
+            &nonce_account_pubkey,
+            &destination_account_pubkey,
+            *lamports,
+        ),
     }
 }
 
@@ -1258,6 +1502,18 @@ fn is_pubkey(string: String) -> Result<(), String> {
     }
 }
 
+// Return an error if a seed is too long to derive an address with.
+fn is_seed(string: String) -> Result<(), String> {
+    if string.len() > MAX_SEED_LEN {
+        Err(format!(
+            "seed is longer than {} bytes: {}",
+            MAX_SEED_LEN, string
+        ))
+    } else {
+        Ok(())
+    }
+}
+
 pub fn app<'ab, 'v>(name: &str, about: &'ab str, version: &'v str) -> App<'ab, 'v> {
     App::new(name)
         .about(about)
@@ -1314,6 +1570,35 @@ pub fn app<'ab, 'v>(name: &str, about: &'ab str, version: &'v str) -> App<'ab, '
                         .help("The transaction signature to confirm"),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("create-address-with-seed")
+                .about("Derive an account address from a base pubkey and a seed")
+                .arg(
+                    Arg::with_name("seed")
+                        .index(1)
+                        .value_name("SEED")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_seed)
+                        .help("The seed to derive the address with"),
+                )
+                .arg(
+                    Arg::with_name("program_id")
+                        .index(2)
+                        .value_name("PROGRAM_ID")
+                        .takes_value(true)
+                        .validator(is_pubkey)
+                        .help("The program that will own the account [default: the system program]"),
+                )
+                .arg(
+                    Arg::with_name("from")
+                        .long("from")
+                        .value_name("PUBKEY")
+                        .takes_value(true)
+                        .validator(is_pubkey)
+                        .help("The base pubkey [default: your public key]"),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("authorize-voter")
                 .about("Authorize a new vote signing keypair for the given vote account")
@@ -1714,6 +1999,134 @@ pub fn app<'ab, 'v>(name: &str, about: &'ab str, version: &'v str) -> App<'ab, '
                         .help("The process id of the transfer to authorize"),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("transfer-with-seed")
+                .about("Send lamports from an account at an address derived from your public key")
+                .arg(
+                    Arg::with_name("seed")
+                        .index(1)
+                        .value_name("SEED")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_seed)
+                        .help("The seed the source address was derived with"),
+                )
+                .arg(
+                    Arg::with_name("to")
+                        .index(2)
+                        .value_name("PUBKEY")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_pubkey)
+                        .help("The pubkey of recipient"),
+                )
+                .arg(
+                    Arg::with_name("lamports")
+                        .index(3)
+                        .value_name("NUM")
+                        .takes_value(true)
+                        .required(true)
+                        .help("The number of lamports to send"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("create-nonce-account")
+                .about("Create a nonce account, whose blockhash can stand in for a recent blockhash")
+                .arg(
+                    Arg::with_name("nonce_account_pubkey")
+                        .index(1)
+                        .value_name("PUBKEY")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_pubkey)
+                        .help("Nonce account address to fund"),
+                )
+                .arg(
+                    Arg::with_name("lamports")
+                        .index(2)
+                        .value_name("NUM")
+                        .takes_value(true)
+                        .required(true)
+                        .help("The number of lamports to send to the nonce account, at least its rent exempt minimum"),
+                )
+                .arg(
+                    Arg::with_name("nonce_authority")
+                        .long("nonce-authority")
+                        .value_name("PUBKEY")
+                        .takes_value(true)
+                        .validator(is_pubkey)
+                        .help("The key allowed to advance and withdraw from the nonce, defaults to your public key"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("get-nonce")
+                .about("Get the current nonce of a nonce account")
+                .arg(
+                    Arg::with_name("nonce_account_pubkey")
+                        .index(1)
+                        .value_name("PUBKEY")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_pubkey)
+                        .help("Nonce account pubkey"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("new-nonce")
+                .about("Advance a nonce account to a new nonce, signing as its nonce authority")
+                .arg(
+                    Arg::with_name("nonce_account_pubkey")
+                        .index(1)
+                        .value_name("PUBKEY")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_pubkey)
+                        .help("Nonce account pubkey"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("show-nonce-account")
+                .about("Show the contents of a nonce account")
+                .arg(
+                    Arg::with_name("nonce_account_pubkey")
+                        .index(1)
+                        .value_name("PUBKEY")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_pubkey)
+                        .help("Nonce account pubkey"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("withdraw-from-nonce-account")
+                .about("Withdraw lamports from a nonce account, signing as its nonce authority")
+                .arg(
+                    Arg::with_name("nonce_account_pubkey")
+                        .index(1)
+                        .value_name("PUBKEY")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_pubkey)
+                        .help("Nonce account pubkey"),
+                )
+                .arg(
+                    Arg::with_name("destination_account_pubkey")
+                        .index(2)
+                        .value_name("PUBKEY")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_pubkey)
+                        .help("The account where the lamports should be transfered"),
+                )
+                .arg(
+                    Arg::with_name("lamports")
+                        .index(3)
+                        .value_name("NUM")
+                        .takes_value(true)
+                        .required(true)
+                        .help("The number of lamports to withdraw from the nonce account"),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("send-timestamp")
                 .about("Send a timestamp to unlock a transfer")
@@ -1825,6 +2238,116 @@ mod tests {
             .get_matches_from(vec!["test", "confirm", "deadbeef"]);
         assert!(parse_command(&pubkey, &test_bad_signature).is_err());
 
+        // Test CreateAddressWithSeed Subcommand
+        let program_id = Pubkey::new_rand();
+        let program_id_string = format!("{}", program_id);
+        let test_create_address_with_seed = test_commands.clone().get_matches_from(vec![
+            "test",
+            "create-address-with-seed",
+            "seed",
+            &program_id_string,
+        ]);
+        assert_eq!(
+            parse_command(&pubkey, &test_create_address_with_seed).unwrap(),
+            WalletCommand::CreateAddressWithSeed(pubkey, "seed".to_string(), program_id)
+        );
+        let base = Pubkey::new_rand();
+        let base_string = format!("{}", base);
+        let test_create_address_with_seed = test_commands.clone().get_matches_from(vec![
+            "test",
+            "create-address-with-seed",
+            "seed",
+            "--from",
+            &base_string,
+        ]);
+        assert_eq!(
+            parse_command(&pubkey, &test_create_address_with_seed).unwrap(),
+            WalletCommand::CreateAddressWithSeed(base, "seed".to_string(), system_program::id())
+        );
+        let long_seed = "x".repeat(MAX_SEED_LEN + 1);
+        assert!(test_commands
+            .clone()
+            .get_matches_from_safe(vec!["test", "create-address-with-seed", &long_seed])
+            .is_err());
+
+        // Test TransferWithSeed Subcommand
+        let test_transfer_with_seed = test_commands.clone().get_matches_from(vec![
+            "test",
+            "transfer-with-seed",
+            "seed",
+            &pubkey_string,
+            "50",
+        ]);
+        assert_eq!(
+            parse_command(&pubkey, &test_transfer_with_seed).unwrap(),
+            WalletCommand::TransferWithSeed("seed".to_string(), pubkey, 50)
+        );
+
+        // Test CreateNonceAccount Subcommand
+        let test_create_nonce_account = test_commands.clone().get_matches_from(vec![
+            "test",
+            "create-nonce-account",
+            &pubkey_string,
+            "50",
+        ]);
+        assert_eq!(
+            parse_command(&pubkey, &test_create_nonce_account).unwrap(),
+            WalletCommand::CreateNonceAccount(pubkey, pubkey, 50)
+        );
+        let nonce_authority = Pubkey::new_rand();
+        let test_create_nonce_account = test_commands.clone().get_matches_from(vec![
+            "test",
+            "create-nonce-account",
+            &pubkey_string,
+            "50",
+            "--nonce-authority",
+            &nonce_authority.to_string(),
+        ]);
+        assert_eq!(
+            parse_command(&pubkey, &test_create_nonce_account).unwrap(),
+            WalletCommand::CreateNonceAccount(pubkey, nonce_authority, 50)
+        );
+
+        // Test GetNonce, NewNonce and ShowNonceAccount Subcommands
+        let test_get_nonce =
+            test_commands
+                .clone()
+                .get_matches_from(vec!["test", "get-nonce", &pubkey_string]);
+        assert_eq!(
+            parse_command(&pubkey, &test_get_nonce).unwrap(),
+            WalletCommand::GetNonce(pubkey)
+        );
+        let test_new_nonce =
+            test_commands
+                .clone()
+                .get_matches_from(vec!["test", "new-nonce", &pubkey_string]);
+        assert_eq!(
+            parse_command(&pubkey, &test_new_nonce).unwrap(),
+            WalletCommand::NewNonce(pubkey)
+        );
+        let test_show_nonce_account = test_commands.clone().get_matches_from(vec![
+            "test",
+            "show-nonce-account",
+            &pubkey_string,
+        ]);
+        assert_eq!(
+            parse_command(&pubkey, &test_show_nonce_account).unwrap(),
+            WalletCommand::ShowNonceAccount(pubkey)
+        );
+
+        // Test WithdrawFromNonceAccount Subcommand
+        let test_withdraw_from_nonce_account = test_commands.clone().get_matches_from(vec![
+            "test",
+            "withdraw-from-nonce-account",
+            &pubkey_string,
+            &nonce_authority.to_string(),
+            "42",
+        ]);
+        assert_eq!(
+            parse_command(&pubkey, &test_withdraw_from_nonce_account).unwrap(),
+            WalletCommand::WithdrawFromNonceAccount(pubkey, nonce_authority, 42)
+        );
+
         // Test AuthorizeVoter Subcommand
         let keypair_file = make_tmp_path("keypair_file");
         gen_keypair_file(&keypair_file).unwrap();
@@ -2078,6 +2601,19 @@ mod tests {
         config.command = WalletCommand::Address;
         assert_eq!(process_command(&config).unwrap(), pubkey);
 
+        let program_id = Pubkey::new_rand();
+        config.command = WalletCommand::CreateAddressWithSeed(
+            config.keypair.pubkey(),
+            "seed".to_string(),
+            program_id,
+        );
+        assert_eq!(
+            process_command(&config).unwrap(),
+            Pubkey::create_with_seed(&config.keypair.pubkey(), "seed", &program_id)
+                .unwrap()
+                .to_string()
+        );
+
         config.command = WalletCommand::Balance(config.keypair.pubkey());
         assert_eq!(process_command(&config).unwrap(), "50 lamports");
 
@@ -2128,6 +2664,24 @@ mod tests {
         let signature = process_command(&config);
         assert_eq!(signature.unwrap(), SIGNATURE.to_string());
 
+        config.command = WalletCommand::TransferWithSeed("seed".to_string(), bob_pubkey, 10);
+        let signature = process_command(&config);
+        assert_eq!(signature.unwrap(), SIGNATURE.to_string());
+
+        config.command = WalletCommand::CreateNonceAccount(bob_pubkey, bob_pubkey, 2000);
+        let signature = process_command(&config);
+        assert_eq!(signature.unwrap(), SIGNATURE.to_string());
+        config.command = WalletCommand::CreateNonceAccount(bob_pubkey, bob_pubkey, 50);
+        assert!(process_command(&config).is_err());
+
+        config.command = WalletCommand::NewNonce(bob_pubkey);
+        let signature = process_command(&config);
+        assert_eq!(signature.unwrap(), SIGNATURE.to_string());
+
+        config.command = WalletCommand::WithdrawFromNonceAccount(bob_pubkey, bob_pubkey, 10);
+        let signature = process_command(&config);
+        assert_eq!(signature.unwrap(), SIGNATURE.to_string());
+
         let date_string = "\"2018-09-19T17:30:59Z\"";
         let dt: DateTime<Utc> = serde_json::from_str(&date_string).unwrap();
         config.command = WalletCommand::Pay(